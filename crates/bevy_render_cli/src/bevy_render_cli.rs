@@ -1,8 +1,14 @@
 use bevy::prelude::*;
 use bevy_render_core::render_core;
-use raytracing_core::Scene;
-pub fn render_cli(scene: Scene, results: Vec<Vec<Vec3>>) -> Result<(), Box<dyn std::error::Error>> {
+pub use bevy_render_core::OverlayOptions;
+use raytracing_core::{DetailedPath, LengthUnit, Scene};
+pub fn render_cli(
+    scene: Scene,
+    results: Vec<DetailedPath>,
+    length_unit: Option<LengthUnit>,
+    overlay: OverlayOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
     println!("レンダー起動");
-    render_core(scene, results);
+    render_core(scene, results, length_unit, overlay);
     Ok(())
 }
@@ -1,46 +1,251 @@
+use bevy::asset::RenderAssetUsages;
 use bevy::pbr::{DirectionalLight, StandardMaterial};
 use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
 use bevy_flycam::prelude::*;
 use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin};
 use csgrs::traits::CSG;
-use rand::{self, Rng};
-use raytracing_core::{Hittable, InfiniteCone, Scene};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use raytracing_core::{
+    DetailedPath, Hittable, InfiniteCone, LengthUnit, Material as OpticalMaterial, Scene,
+};
+use std::collections::BTreeMap;
 type Csmesh = csgrs::mesh::Mesh<()>;
+// 途中の点が少ない光路だけを選ぶ（光路やオブジェクトの Visibility と重ならないように除く）
+type ShortPathFilter = (
+    With<ShortPathEntity>,
+    Without<RayPathEntity>,
+    Without<SceneObjectEntity>,
+);
 #[derive(Resource)]
 pub struct RenderScene(pub Scene);
 #[derive(Resource)]
-pub struct PathData(pub Vec<Vec<Vec3>>);
+pub struct PathData(pub Vec<DetailedPath>);
+
+// 光路の矢印の目印（表示の切り替えに使う）
+#[derive(Component, Clone, Copy)]
+pub struct RayPathEntity;
+
+// シーンのオブジェクトの目印（表示の切り替えに使う）
+#[derive(Component, Clone, Copy)]
+pub struct SceneObjectEntity;
+
+// 途中の点が少ない（ほとんど何にも当たらずに飛び去った）光路の矢印の目印（RayPathEntity とは別に切り替える）
+#[derive(Component, Clone, Copy)]
+pub struct ShortPathEntity;
+
+// 表示を切り替えるキー
+const TOGGLE_PATHS_KEY: KeyCode = KeyCode::KeyP;
+const TOGGLE_OBJECTS_KEY: KeyCode = KeyCode::KeyO;
+const TOGGLE_SHORT_PATHS_KEY: KeyCode = KeyCode::KeyE;
+
+// 位置を読み取るための補助表示（XYZ軸と方眼）と、光路の色の決め方
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct OverlayOptions {
+    pub axes: bool,
+    pub grid: bool,
+    pub color_seed: u64, // 同じ値なら、同じシーンの光路は毎回同じ色で描かれる
+    pub min_interior_points: usize, // 途中の点がこれより少ない光路を最初は隠す（0 なら全て表示）
+    pub aggregate_sources: bool, // 光路を1本ずつの矢印ではなく、光源ごとの半透明の線の束で描く
+}
+
+// 矢印の軸の太さと先端の大きさ
+#[derive(Debug, Clone, Copy)]
+pub struct ArrowStyle {
+    pub shaft_radius: f32,
+    pub head_radius: f32,
+    pub head_length: f32,
+}
+
+// 飛び去った区間の矢印の不透明度
+const ESCAPED_ALPHA: f32 = 0.25;
+// ガラス面の部分反射によるゴーストの光路の色（主光線と区別できる暗い赤）
+const GHOST_COLOR: Color = Color::srgb(0.45, 0.08, 0.08);
+
+impl ArrowStyle {
+    // 飛び去った区間用の細い矢印
+    pub fn escaped(self) -> Self {
+        Self {
+            shaft_radius: self.shaft_radius * 0.5,
+            ..self
+        }
+    }
+
+    // 光路全体の広がりに比例した寸法にする（広がり100で従来の寸法）
+    // 飛び去った区間の終点は広がりに含めない
+    pub fn from_paths(paths: &[DetailedPath]) -> Self {
+        let mut min = Vec3::splat(f32::INFINITY);
+        let mut max = Vec3::splat(f32::NEG_INFINITY);
+        for point in paths.iter().flat_map(bounded_points) {
+            min = min.min(*point);
+            max = max.max(*point);
+        }
+        let extent = (max - min).length();
+        let scale = if extent.is_finite() && extent > 0.0 {
+            extent / 100.0
+        } else {
+            1.0
+        };
+        Self {
+            shaft_radius: 0.02 * scale,
+            head_radius: 0.08 * scale,
+            head_length: 0.2 * scale,
+        }
+    }
+}
+
+// 単位が分かっている場合のカメラの移動速度（毎秒0.5m相当）
+const CAMERA_SPEED_METERS_PER_SEC: f32 = 0.5;
+
+// 単位からフライカメラの移動速度を決める。単位が無ければbevy_flycamの既定値
+pub fn camera_speed(length_unit: Option<LengthUnit>) -> f32 {
+    match length_unit {
+        Some(unit) => CAMERA_SPEED_METERS_PER_SEC / unit.meters(),
+        None => MovementSettings::default().speed,
+    }
+}
+
+// 始点と終点を除いた途中の点（反射・屈折などが起きた点）が min_interior_points より少ない光路か
+// 何にも当たらずに飛び去った光路は途中の点が 0 個
+pub fn is_short_path(path: &DetailedPath, min_interior_points: usize) -> bool {
+    path.points.len().saturating_sub(2) < min_interior_points
+}
+
+// 光路を光源の番号 (DetailedPath::source) ごとにまとめる。光源の番号順に並べ、各光源の中は元の順序を保つ
+pub fn group_by_source<'a>(
+    paths: &[(usize, &'a DetailedPath)],
+) -> Vec<(usize, Vec<&'a DetailedPath>)> {
+    let mut groups: BTreeMap<usize, Vec<&DetailedPath>> = BTreeMap::new();
+    for &(_, path) in paths {
+        groups.entry(path.source).or_default().push(path);
+    }
+    groups.into_iter().collect()
+}
+
+// 束の不透明度。本数が多いほど1本ずつを薄くし、光線が重なる所ほど濃く見えるようにする
+const BUNDLE_OPACITY_BUDGET: f32 = 8.0;
+const BUNDLE_MIN_ALPHA: f32 = 0.02;
+const BUNDLE_MAX_ALPHA: f32 = 0.6;
+
+pub fn bundle_alpha(path_count: usize) -> f32 {
+    (BUNDLE_OPACITY_BUDGET / path_count.max(1) as f32).clamp(BUNDLE_MIN_ALPHA, BUNDLE_MAX_ALPHA)
+}
+
+// 光路の点のうち、飛び去った区間の終点を除いたもの
+fn bounded_points(path: &DetailedPath) -> &[Vec3] {
+    if path.escaped {
+        &path.points[..path.points.len().saturating_sub(1)]
+    } else {
+        &path.points
+    }
+}
 
 pub fn render_core(
     scene: Scene,
-    results: Vec<Vec<Vec3>>,
+    results: Vec<DetailedPath>,
+    length_unit: Option<LengthUnit>,
+    overlay: OverlayOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    render_main(scene, results);
+    render_main(scene, results, length_unit, overlay);
     Ok(())
 }
 
-fn render_main(scene: Scene, results: Vec<Vec<Vec3>>) {
+fn render_main(
+    scene: Scene,
+    results: Vec<DetailedPath>,
+    length_unit: Option<LengthUnit>,
+    overlay: OverlayOptions,
+) {
+    // ウィンドウのタイトルに単位を表示する
+    let title = match length_unit {
+        Some(unit) => format!("RayTracing [{}]", unit.symbol()),
+        None => "RayTracing".to_string(),
+    };
     App::new()
-        .add_plugins(DefaultPlugins)
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window { title, ..default() }),
+            ..default()
+        }))
         .add_plugins(PanOrbitCameraPlugin)
         .add_plugins(PlayerPlugin)
+        .insert_resource(MovementSettings {
+            speed: camera_speed(length_unit),
+            ..default()
+        })
         .insert_resource(RenderScene(scene))
         .insert_resource(PathData(results))
+        .insert_resource(overlay)
         .add_systems(Startup, setup)
+        .add_systems(Update, toggle_visibility)
         .run();
 }
 
 fn setup(
     render_scene: Res<RenderScene>,
     path_data: Res<PathData>,
+    overlay: Res<OverlayOptions>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
     let scene = &render_scene.0;
     let results = &path_data.0;
+    // オブジェクトの描画
+    spawn_scene_objects(scene, &mut materials, &mut meshes, &mut commands);
     // 光の軌跡の描画
-    spawn_arrows(&mut commands, &mut meshes, &mut materials, results);
+    // 途中の点が少ない光路は隠した状態で描き、E キーで表示できるようにする
+    let arrow_style = ArrowStyle::from_paths(results);
+    let (short, long): (Vec<_>, Vec<_>) = results
+        .iter()
+        .enumerate()
+        .partition(|(_, path)| is_short_path(path, overlay.min_interior_points));
+    if overlay.aggregate_sources {
+        spawn_bundles(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &long,
+            overlay.color_seed,
+            RayPathEntity,
+        );
+        spawn_bundles(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &short,
+            overlay.color_seed,
+            (ShortPathEntity, Visibility::Hidden),
+        );
+    } else {
+        spawn_arrows(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &long,
+            arrow_style,
+            overlay.color_seed,
+            RayPathEntity,
+        );
+        spawn_arrows(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &short,
+            arrow_style,
+            overlay.color_seed,
+            (ShortPathEntity, Visibility::Hidden),
+        );
+    }
+    // 軸と方眼の描画
+    spawn_overlay(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        *overlay,
+        scene_half_extent(scene, results),
+        arrow_style.shaft_radius,
+    );
     commands.spawn(DirectionalLight {
         shadows_enabled: true,
         ..default()
@@ -48,60 +253,306 @@ fn setup(
     //commands.spawn((Camera3d::default(),));
 }
 
-fn spawn_object(
+// P キーで光路、E キーで途中の点が少ない光路、O キーでオブジェクトの表示を切り替える
+fn toggle_visibility(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut paths: Query<&mut Visibility, (With<RayPathEntity>, Without<SceneObjectEntity>)>,
+    mut short_paths: Query<&mut Visibility, ShortPathFilter>,
+    mut objects: Query<&mut Visibility, (With<SceneObjectEntity>, Without<RayPathEntity>)>,
+) {
+    if keys.just_pressed(TOGGLE_PATHS_KEY) {
+        paths
+            .iter_mut()
+            .for_each(|mut visibility| toggle(&mut visibility));
+    }
+    if keys.just_pressed(TOGGLE_SHORT_PATHS_KEY) {
+        short_paths
+            .iter_mut()
+            .for_each(|mut visibility| toggle(&mut visibility));
+    }
+    if keys.just_pressed(TOGGLE_OBJECTS_KEY) {
+        objects
+            .iter_mut()
+            .for_each(|mut visibility| toggle(&mut visibility));
+    }
+}
+
+fn toggle(visibility: &mut Visibility) {
+    *visibility = match *visibility {
+        Visibility::Hidden => Visibility::Inherited,
+        _ => Visibility::Hidden,
+    };
+}
+
+// 軸の色（X:赤 Y:緑 Z:青）と方眼の色
+const AXIS_COLORS: [(Vec3, Color); 3] = [
+    (Vec3::X, Color::srgb(0.9, 0.2, 0.2)),
+    (Vec3::Y, Color::srgb(0.2, 0.8, 0.2)),
+    (Vec3::Z, Color::srgb(0.2, 0.4, 0.9)),
+];
+const GRID_COLOR: Color = Color::srgba(0.5, 0.5, 0.5, 0.5);
+
+// 原点から見て、有限なオブジェクトと光路（飛び去った区間の終点を除く）が収まる範囲の半径
+// 何も無ければ 1
+pub fn scene_half_extent(scene: &Scene, paths: &[DetailedPath]) -> f32 {
+    let object_corners = scene
+        .bounding_box()
+        .into_iter()
+        .flat_map(|bbox| [bbox.min, bbox.max]);
+    let path_points = paths.iter().flat_map(bounded_points).copied();
+    let extent = object_corners
+        .chain(path_points)
+        .map(|point| point.abs().max_element())
+        .fold(0.0, f32::max);
+    if extent > 0.0 {
+        extent
+    } else {
+        1.0
+    }
+}
+
+// 方眼の間隔。半径の 1/10 程度になる 10 のべき乗
+pub fn grid_spacing(half_extent: f32) -> f32 {
+    10f32.powf((half_extent / 10.0).log10().floor())
+}
+
+// 原点の XYZ 軸と、XZ 平面（y = 0）の方眼を描く
+fn spawn_overlay(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    options: OverlayOptions,
+    half_extent: f32,
+    line_radius: f32,
+) {
+    if options.axes {
+        // 矢印の先端の色で軸を見分ける
+        let style = ArrowStyle {
+            shaft_radius: line_radius * 2.0,
+            head_radius: line_radius * 8.0,
+            head_length: half_extent * 0.05,
+        };
+        for (axis, color) in AXIS_COLORS {
+            let material = materials.add(StandardMaterial {
+                base_color: color,
+                unlit: true,
+                ..default()
+            });
+            spawn_arrow(
+                commands,
+                meshes,
+                material,
+                Vec3::ZERO,
+                axis * half_extent,
+                style,
+                (),
+            );
+        }
+    }
+
+    if options.grid {
+        let spacing = grid_spacing(half_extent);
+        let count = (half_extent / spacing).ceil() as i32;
+        let size = count as f32 * spacing;
+        let material = materials.add(StandardMaterial {
+            base_color: GRID_COLOR,
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        });
+        let line = meshes.add(Cylinder {
+            radius: line_radius * 0.5,
+            half_height: size,
+        });
+        for i in -count..=count {
+            let offset = i as f32 * spacing;
+            // X 方向の線と Z 方向の線
+            for (translation, direction) in [
+                (Vec3::new(0.0, 0.0, offset), Vec3::X),
+                (Vec3::new(offset, 0.0, 0.0), Vec3::Z),
+            ] {
+                commands.spawn((
+                    Mesh3d(line.clone()),
+                    MeshMaterial3d(material.clone()),
+                    Transform {
+                        translation,
+                        rotation: arrow_rotation(direction),
+                        ..default()
+                    },
+                ));
+            }
+        }
+    }
+}
+
+// 材質が分からない形状の色
+const DEFAULT_OBJECT_COLOR: Color = Color::srgb(0.8, 0.7, 0.6);
+
+// 材質から表示用のマテリアルを作る（ガラスは半透明、鏡は金属光沢、吸収体は暗色）
+pub fn display_material(material: Option<&OpticalMaterial>) -> StandardMaterial {
+    let Some(material) = material else {
+        return StandardMaterial::from(DEFAULT_OBJECT_COLOR);
+    };
+    let [r, g, b, a] = material.display_color();
+    let (metallic, perceptual_roughness) = match material {
+        OpticalMaterial::Mirror
+        | OpticalMaterial::OneSidedMirror
+        | OpticalMaterial::MetalMirror { .. }
+        | OpticalMaterial::HalfMirror { .. } => (1.0, 0.1),
+        OpticalMaterial::Absorber => (0.0, 1.0),
+        _ => (0.0, 0.3),
+    };
+    StandardMaterial {
+        base_color: Color::srgba(r, g, b, a),
+        metallic,
+        perceptual_roughness,
+        alpha_mode: if a < 1.0 {
+            AlphaMode::Blend
+        } else {
+            AlphaMode::Opaque
+        },
+        ..default()
+    }
+}
+
+// 各オブジェクトを材質ごとの色で描く（O キーで表示を切り替えられる）
+// 形状は Scene::renderable_triangles で三角形に分けたもので、無限に広がる形状は描かない
+fn spawn_scene_objects(
     scene: &Scene,
     materials: &mut ResMut<Assets<StandardMaterial>>,
     meshes: &mut ResMut<Assets<Mesh>>,
     commands: &mut Commands,
 ) {
-    let object_material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+    for (object, (vertices, triangles)) in scene.objects.iter().zip(scene.renderable_triangles()) {
+        if triangles.is_empty() {
+            continue;
+        }
+        let mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vertices)
+        .with_inserted_indices(Indices::U32(triangles.concat()))
+        .with_computed_smooth_normals();
+        commands.spawn((
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(materials.add(display_material(object.material()))),
+            SceneObjectEntity,
+        ));
+    }
 }
 
+// (光路の番号, 光路) の組を矢印で描く。marker は全ての矢印に付ける
 fn spawn_arrows(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
-    results: &Vec<Vec<Vec3>>,
+    results: &[(usize, &DetailedPath)],
+    style: ArrowStyle,
+    color_seed: u64,
+    marker: impl Bundle + Clone,
 ) {
     let mut arrow_material = materials.add(Color::srgb(0.1, 0.1, 0.1));
 
-    for arrows in results {
-        let mut rng = rand::rng();
-        let r: f32 = rng.random::<f32>();
-        let g: f32 = rng.random::<f32>();
-        let b: f32 = rng.random::<f32>();
-        let random_color = Color::srgb(r, g, b);
+    for &(index, path) in results {
+        // ゴーストは色を揃えて、主光線（光路ごとに別の色）と見分けられるようにする
+        let random_color = if path.branch_label().is_ghost() {
+            GHOST_COLOR
+        } else {
+            path_color(color_seed, index)
+        };
         arrow_material = materials.add(random_color);
-        for pair in arrows.windows(2) {
+        let segment_count = path.points.len().saturating_sub(1);
+        for (i, pair) in path.points.windows(2).enumerate() {
+            // 飛び去った最後の区間は、半透明の細い矢印で描く
+            let (material, style) = if path.escaped && i + 1 == segment_count {
+                let faded = materials.add(StandardMaterial {
+                    base_color: random_color.with_alpha(ESCAPED_ALPHA),
+                    alpha_mode: AlphaMode::Blend,
+                    ..default()
+                });
+                (faded, style.escaped())
+            } else {
+                (arrow_material.clone(), style)
+            };
             spawn_arrow(
                 commands,
                 meshes,
-                arrow_material.clone(),
-                pair[0].into(),
-                pair[1].into(),
+                material,
+                pair[0],
+                pair[1],
+                style,
+                marker.clone(),
             );
         }
     }
 }
 
+// (光路の番号, 光路) の組を光源ごとにまとめ、光源ごとに1つの半透明の線の束で描く（矢印の先端は描かない）
+// 束の色は光源の番号で決める。marker は全ての束に付ける
+fn spawn_bundles(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    results: &[(usize, &DetailedPath)],
+    color_seed: u64,
+    marker: impl Bundle + Clone,
+) {
+    for (source, paths) in group_by_source(results) {
+        let positions: Vec<Vec3> = paths
+            .iter()
+            .flat_map(|path| path.points.windows(2))
+            .flat_map(|pair| [pair[0], pair[1]])
+            .collect();
+        if positions.is_empty() {
+            continue;
+        }
+        let mesh = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        let material = materials.add(StandardMaterial {
+            base_color: path_color(color_seed, source).with_alpha(bundle_alpha(paths.len())),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        });
+        commands.spawn((
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(material),
+            marker.clone(),
+        ));
+    }
+}
+
+// index 番目の光路の色。seed と index だけで決まるので、描き直しても色が変わらない
+pub fn path_color(seed: u64, index: usize) -> Color {
+    let mut rng = StdRng::seed_from_u64(seed ^ (index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    let r: f32 = rng.random::<f32>();
+    let g: f32 = rng.random::<f32>();
+    let b: f32 = rng.random::<f32>();
+    Color::srgb(r, g, b)
+}
+
+// marker は軸と先端の両方のエンティティに付ける
 fn spawn_arrow(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     material: Handle<StandardMaterial>,
     start: Vec3,
     end: Vec3,
+    style: ArrowStyle,
+    marker: impl Bundle + Clone,
 ) {
     let direction = end - start;
     let half_length = direction.length() / 2.0;
-    if half_length < 0.001 {
+    if half_length < style.head_length * 0.005 {
         return;
     }
 
     let mid_point = start + direction / 2.0;
-    let rotation = Quat::from_rotation_arc(Vec3::Y, direction.normalize());
+    let rotation = arrow_rotation(direction);
     commands.spawn((
         Mesh3d(meshes.add(Cylinder {
-            radius: 0.02,
+            radius: style.shaft_radius,
             half_height: half_length,
         })),
         MeshMaterial3d(material.clone()),
@@ -110,13 +561,14 @@ fn spawn_arrow(
             rotation,
             ..default()
         },
+        marker.clone(),
     ));
 
     // 矢印の先端
     commands.spawn((
         Mesh3d(meshes.add(Cone {
-            radius: 0.08,
-            height: 0.2,
+            radius: style.head_radius,
+            height: style.head_length,
         })),
         MeshMaterial3d(material),
         Transform {
@@ -124,5 +576,17 @@ fn spawn_arrow(
             rotation,
             ..default()
         },
+        marker,
     ));
 }
+
+// +Y 向きのメッシュを direction の向きに回す回転
+// 真下 (-Y) 向きでは回転軸が定まらないので、X軸まわりの180°回転を明示的に使う
+pub fn arrow_rotation(direction: Vec3) -> Quat {
+    let dir = direction.normalize();
+    if dir.dot(Vec3::Y) < -1.0 + 1e-6 {
+        Quat::from_rotation_x(std::f32::consts::PI)
+    } else {
+        Quat::from_rotation_arc(Vec3::Y, dir)
+    }
+}
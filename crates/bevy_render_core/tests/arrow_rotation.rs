@@ -0,0 +1,29 @@
+// 矢印の回転 (arrow_rotation) が真下向きでも有限で、+Y を指定した向きに回すことの確認
+use bevy::prelude::Vec3;
+use bevy_render_core::arrow_rotation;
+
+#[test]
+fn arrow_pointing_down_gets_a_finite_rotation() {
+    let rotation = arrow_rotation(Vec3::NEG_Y * 3.0);
+    assert!(rotation.is_finite());
+    assert!(rotation.is_normalized());
+    assert!((rotation * Vec3::Y - Vec3::NEG_Y).length() < 1e-5);
+}
+
+#[test]
+fn arrow_rotation_points_y_along_direction() {
+    for direction in [
+        Vec3::Y,
+        Vec3::X,
+        Vec3::new(0.3, -0.9, 0.1),
+        Vec3::new(1e-4, -1.0, 0.0),
+    ] {
+        let rotation = arrow_rotation(direction);
+        assert!(rotation.is_finite(), "{direction}");
+        let rotated = rotation * Vec3::Y;
+        assert!(
+            (rotated - direction.normalize()).length() < 1e-3,
+            "{direction}: {rotated}"
+        );
+    }
+}
@@ -0,0 +1,15 @@
+// 光路の色が乱数の種と光路の番号だけで決まることの確認
+use bevy_render_core::path_color;
+
+#[test]
+fn same_seed_gives_same_colors() {
+    let first: Vec<_> = (0..16).map(|i| path_color(7, i)).collect();
+    let second: Vec<_> = (0..16).map(|i| path_color(7, i)).collect();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn paths_and_seeds_get_different_colors() {
+    assert_ne!(path_color(0, 0), path_color(0, 1));
+    assert_ne!(path_color(0, 3), path_color(1, 3));
+}
@@ -0,0 +1,38 @@
+// 途中の点が少ない光路を見分ける is_short_path の確認
+use bevy_render_core::is_short_path;
+use glam::Vec3;
+use raytracing_core::DetailedPath;
+
+fn path(point_count: usize, escaped: bool) -> DetailedPath {
+    DetailedPath {
+        points: (0..point_count).map(|i| Vec3::X * i as f32).collect(),
+        optical_lengths: vec![0.0; point_count],
+        interactions: Vec::new(),
+        intensity: 1.0,
+        escaped,
+        stuck: false,
+        rehit: false,
+        truncated_hits: 0,
+        source: 0,
+    }
+}
+
+#[test]
+fn straight_shot_is_short_when_filtering() {
+    // 始点と飛び去った先の終点だけの光路
+    let straight = path(2, true);
+    assert!(is_short_path(&straight, 1));
+    assert!(!is_short_path(&straight, 0));
+}
+
+#[test]
+fn counts_only_interior_points() {
+    // 途中の点が 2 個
+    let bounced = path(4, true);
+    assert!(!is_short_path(&bounced, 1));
+    assert!(!is_short_path(&bounced, 2));
+    assert!(is_short_path(&bounced, 3));
+    // 点が 1 個以下でも途中の点は 0 個として扱う
+    assert!(is_short_path(&path(1, false), 1));
+    assert!(is_short_path(&path(0, false), 1));
+}
@@ -0,0 +1,57 @@
+// 光源ごとの束で描く前に、光路を光源の番号でまとめる group_by_source の確認
+use bevy_render_core::{bundle_alpha, group_by_source};
+use glam::Vec3;
+use raytracing_core::DetailedPath;
+
+fn path(source: usize, x: f32) -> DetailedPath {
+    DetailedPath {
+        points: vec![Vec3::new(x, 0.0, 0.0), Vec3::new(x, 0.0, 1.0)],
+        optical_lengths: vec![0.0, 1.0],
+        interactions: Vec::new(),
+        intensity: 1.0,
+        escaped: false,
+        stuck: false,
+        rehit: false,
+        truncated_hits: 0,
+        source,
+    }
+}
+
+#[test]
+fn groups_interleaved_paths_by_source() {
+    // 分岐などで光源の違う光路が混ざって並んでいても、光源ごとにまとまる
+    let paths = [
+        path(2, 0.0),
+        path(0, 1.0),
+        path(2, 2.0),
+        path(0, 3.0),
+        path(5, 4.0),
+    ];
+    let indexed: Vec<_> = paths.iter().enumerate().collect();
+    let groups = group_by_source(&indexed);
+
+    let sources: Vec<usize> = groups.iter().map(|(source, _)| *source).collect();
+    assert_eq!(sources, [0, 2, 5]);
+    // 各光源の中は元の順序のまま
+    let starts = |group: &[&DetailedPath]| -> Vec<f32> {
+        group.iter().map(|path| path.points[0].x).collect()
+    };
+    assert_eq!(starts(&groups[0].1), [1.0, 3.0]);
+    assert_eq!(starts(&groups[1].1), [0.0, 2.0]);
+    assert_eq!(starts(&groups[2].1), [4.0]);
+    assert!(groups
+        .iter()
+        .all(|(source, group)| group.iter().all(|path| path.source == *source)));
+}
+
+#[test]
+fn empty_input_has_no_groups() {
+    assert!(group_by_source(&[]).is_empty());
+}
+
+#[test]
+fn dense_bundles_are_fainter() {
+    assert!(bundle_alpha(1000) < bundle_alpha(10));
+    assert!(bundle_alpha(1_000_000) > 0.0);
+    assert!(bundle_alpha(0) <= 1.0);
+}
@@ -0,0 +1,282 @@
+use std::iter::Peekable;
+use std::path::PathBuf;
+
+// 光路の出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OutputFormat {
+    /// 光路ごとに path_{i}.csv を出力する
+    #[default]
+    Csv,
+    /// 全光路をまとめて paths.bin に出力する
+    Bin,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(OutputFormat::Csv),
+            "bin" => Ok(OutputFormat::Bin),
+            _ => Err(()),
+        }
+    }
+}
+
+// コマンドライン引数の解析
+#[derive(Debug, Default)]
+pub struct CliArgs {
+    pub incidence_object: Option<usize>, // 入射角ヒストグラムを取るオブジェクトの添字
+    pub incidence_bins: usize,
+    pub format: OutputFormat,
+    pub near_target: Option<[f32; 4]>, // x,y,z,半径: 最終点がこの球に入る光路を数える
+    pub detector: Option<[f32; 7]>,    // 点x,y,z,法線x,y,z,半径: 平面上の円形検出器
+    pub reverse: bool,                 // レイを目標側から光源側へ逆向きに追跡する
+    pub precision: Option<usize>,      // CSVに書く座標の小数点以下の桁数（省略時は全桁）
+    pub sweep: Option<SweepArgs>,      // パラメータを変えながら繰り返し追跡する
+    pub tolerance: Option<ToleranceArgs>, // パラメータを公差の範囲でランダムにずらしながら繰り返し追跡する
+    pub dump_expanded: bool,              // ジェネレータを展開した設定ファイルを書き出して終了する
+    pub yes: bool,                        // 見積もりが大きくても確認せずに追跡する
+    pub voxels: Option<[usize; 3]>, // nx,ny,nz: 光路の長さをボクセル格子に集計して voxels.npy に出力する
+    pub checkpoint: Option<usize>,  // このレイ数ごとに追跡済みの光路を checkpoint.bin に書き出す
+    pub resume: bool,               // checkpoint.bin に残った続きから追跡する
+    pub detector_image: Option<[usize; 2]>, // nu,nv: --detector に当たった強度を格子に集計して detector.pgm に出力する
+    pub hit_logs: bool,                     // 名前を付けた面ごとの衝突を hits_<名前>.csv に出力する
+    pub export_ply: Option<PathBuf>,        // 形状の頂点と光路をこの名前の PLY ファイルに出力する
+}
+
+// --sweep object=0 field=transform.position.z from=10 to=20 steps=11
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepArgs {
+    pub object: usize, // [[scene.objects]] の番号
+    pub field: String, // オブジェクト内の点区切りの場所
+    pub from: f32,
+    pub to: f32,
+    pub steps: usize, // from と to を含む値の個数
+}
+
+impl SweepArgs {
+    // 次のフラグ（-- で始まる引数）の手前までの key=value を読み取る
+    fn parse<I: Iterator<Item = String>>(args: &mut Peekable<I>) -> Result<SweepArgs, String> {
+        let (mut object, mut field, mut from, mut to, mut steps) = (None, None, None, None, None);
+        while let Some(arg) = args.next_if(|arg| !arg.starts_with("--")) {
+            let (key, value) = arg.split_once('=').ok_or_else(|| {
+                format!("--sweep の引数は key=value の形で指定してください: {}", arg)
+            })?;
+            let flag = format!("--sweep {}", key);
+            let value = Some(value.to_string());
+            match key {
+                "object" => object = Some(parse_value(&flag, value)?),
+                "field" => field = value,
+                "from" => from = Some(parse_value(&flag, value)?),
+                "to" => to = Some(parse_value(&flag, value)?),
+                "steps" => steps = Some(parse_value(&flag, value)?),
+                _ => return Err(format!("--sweep の不明なキーです: {}", key)),
+            }
+        }
+        let missing = |key: &str| format!("--sweep には {}= が必要です", key);
+        let steps = steps.ok_or_else(|| missing("steps"))?;
+        if steps == 0 {
+            return Err("--sweep の steps は 1 以上にしてください".to_string());
+        }
+        Ok(SweepArgs {
+            object: object.ok_or_else(|| missing("object"))?,
+            field: field.ok_or_else(|| missing("field"))?,
+            from: from.ok_or_else(|| missing("from"))?,
+            to: to.ok_or_else(|| missing("to"))?,
+            steps,
+        })
+    }
+
+    // i 番目の値（from から to まで等間隔）
+    pub fn value(&self, i: usize) -> f32 {
+        if self.steps == 1 {
+            return self.from;
+        }
+        self.from + (self.to - self.from) * i as f32 / (self.steps - 1) as f32
+    }
+}
+
+// --tolerance trials=100 seed=1 param=0:material.ior:0.001 param=0:transform.position.z:0.05
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToleranceArgs {
+    pub trials: usize,
+    pub seed: u64, // 同じ値なら毎回同じずらし方になる（省略時は 0）
+    pub params: Vec<ToleranceSpec>,
+}
+
+// 1つのパラメータの公差。設定ファイルの値（公称値）から ±tolerance の範囲で一様にずらす
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToleranceSpec {
+    pub object: usize, // [[scene.objects]] の番号
+    pub field: String, // オブジェクト内の点区切りの場所
+    pub tolerance: f32,
+}
+
+impl ToleranceArgs {
+    // 次のフラグ（-- で始まる引数）の手前までの key=value を読み取る
+    fn parse<I: Iterator<Item = String>>(args: &mut Peekable<I>) -> Result<ToleranceArgs, String> {
+        let (mut trials, mut seed, mut params) = (None, 0, Vec::new());
+        while let Some(arg) = args.next_if(|arg| !arg.starts_with("--")) {
+            let (key, value) = arg.split_once('=').ok_or_else(|| {
+                format!(
+                    "--tolerance の引数は key=value の形で指定してください: {}",
+                    arg
+                )
+            })?;
+            let flag = format!("--tolerance {}", key);
+            match key {
+                "trials" => trials = Some(parse_value(&flag, Some(value.to_string()))?),
+                "seed" => seed = parse_value(&flag, Some(value.to_string()))?,
+                "param" => params.push(ToleranceSpec::parse(value)?),
+                _ => return Err(format!("--tolerance の不明なキーです: {}", key)),
+            }
+        }
+        let trials = trials.ok_or("--tolerance には trials= が必要です")?;
+        if trials == 0 {
+            return Err("--tolerance の trials は 1 以上にしてください".to_string());
+        }
+        if params.is_empty() {
+            return Err("--tolerance には param= が1つ以上必要です".to_string());
+        }
+        Ok(ToleranceArgs {
+            trials,
+            seed,
+            params,
+        })
+    }
+}
+
+impl ToleranceSpec {
+    // オブジェクトの番号:場所:公差（例: 0:material.ior:0.001）
+    fn parse(value: &str) -> Result<ToleranceSpec, String> {
+        let invalid = || {
+            format!(
+                "--tolerance param は オブジェクトの番号:場所:公差 の形で指定してください: {}",
+                value
+            )
+        };
+        let [object, field, tolerance] = value
+            .splitn(3, ':')
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|_| invalid())?;
+        let tolerance: f32 = tolerance.parse().map_err(|_| invalid())?;
+        if !(tolerance >= 0.0 && tolerance.is_finite()) {
+            return Err(format!(
+                "--tolerance param の公差は 0 以上にしてください: {}",
+                value
+            ));
+        }
+        Ok(ToleranceSpec {
+            object: object.parse().map_err(|_| invalid())?,
+            field: field.to_string(),
+            tolerance,
+        })
+    }
+}
+
+impl CliArgs {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<CliArgs, String> {
+        let mut cli_args = CliArgs {
+            incidence_bins: 18, // 5°刻み
+            ..Default::default()
+        };
+
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--incidence" => cli_args.incidence_object = Some(parse_value(&arg, args.next())?),
+                "--bins" => cli_args.incidence_bins = parse_value(&arg, args.next())?,
+                "--format" => cli_args.format = parse_value(&arg, args.next())?,
+                "--near" => cli_args.near_target = Some(parse_floats(&arg, args.next())?),
+                "--detector" => cli_args.detector = Some(parse_floats(&arg, args.next())?),
+                "--reverse" => cli_args.reverse = true,
+                "--precision" => cli_args.precision = Some(parse_value(&arg, args.next())?),
+                "--sweep" => cli_args.sweep = Some(SweepArgs::parse(&mut args)?),
+                "--tolerance" => cli_args.tolerance = Some(ToleranceArgs::parse(&mut args)?),
+                "--dump-expanded" => cli_args.dump_expanded = true,
+                "--yes" => cli_args.yes = true,
+                "--voxels" => cli_args.voxels = Some(parse_counts(&arg, args.next())?),
+                "--detector-image" => {
+                    cli_args.detector_image = Some(parse_counts(&arg, args.next())?)
+                }
+                "--checkpoint" => cli_args.checkpoint = Some(parse_value(&arg, args.next())?),
+                "--resume" => cli_args.resume = true,
+                "--hit-logs" => cli_args.hit_logs = true,
+                "--export-ply" => cli_args.export_ply = Some(parse_value(&arg, args.next())?),
+                _ => return Err(format!("不明な引数です: {}", arg)),
+            }
+        }
+        cli_args.check_combinations()?;
+        Ok(cli_args)
+    }
+
+    // 一緒に指定する必要のあるフラグと、同時に使えないフラグを調べる
+    // チェックポイントには光路の点しか残らないので、衝突の情報を使う解析とは組み合わせられない
+    fn check_combinations(&self) -> Result<(), String> {
+        if self.detector_image.is_some() && self.detector.is_none() {
+            return Err("--detector-image には --detector が必要です".to_string());
+        }
+        if self.resume && self.checkpoint.is_none() {
+            return Err("--resume には --checkpoint が必要です".to_string());
+        }
+        if self.checkpoint == Some(0) {
+            return Err("--checkpoint は 1 以上にしてください".to_string());
+        }
+        let analyses = [
+            ("--incidence", self.incidence_object.is_some()),
+            ("--near", self.near_target.is_some()),
+            ("--detector", self.detector.is_some()),
+            ("--voxels", self.voxels.is_some()),
+            ("--hit-logs", self.hit_logs),
+        ];
+        if let (Some(_), Some((flag, _))) =
+            (self.checkpoint, analyses.iter().find(|(_, used)| *used))
+        {
+            return Err(format!("--checkpoint と {} は同時に使えません", flag));
+        }
+        Ok(())
+    }
+}
+
+// フラグの値を読み取る
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} には値が必要です", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("{} の値が不正です: {}", flag, value))
+}
+
+// カンマ区切りの N 個の数値を読み取る
+fn parse_floats<const N: usize>(flag: &str, value: Option<String>) -> Result<[f32; N], String> {
+    let value = value.ok_or_else(|| format!("{} には値が必要です", flag))?;
+    let numbers: Vec<f32> = value
+        .split(',')
+        .map(|part| part.trim().parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("{} の値が不正です: {}", flag, value))?;
+    numbers.try_into().map_err(|_| {
+        format!(
+            "{} には {} 個の数値をカンマ区切りで指定してください",
+            flag, N
+        )
+    })
+}
+
+// カンマ区切りの N 個の格子の分割数を読み取る（いずれも 1 以上）
+fn parse_counts<const N: usize>(flag: &str, value: Option<String>) -> Result<[usize; N], String> {
+    let value = value.ok_or_else(|| format!("{} には値が必要です", flag))?;
+    let counts: Vec<usize> = value
+        .split(',')
+        .map(|part| part.trim().parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("{} の値が不正です: {}", flag, value))?;
+    match <[usize; N]>::try_from(counts) {
+        Ok(counts) if counts.iter().all(|&count| count > 0) => Ok(counts),
+        _ => Err(format!(
+            "{} には 1 以上の整数を {} 個カンマ区切りで指定してください",
+            flag, N
+        )),
+    }
+}
@@ -0,0 +1,75 @@
+// 光路のバイナリ形式での書き出しと読み込み
+//
+// 形式（すべてリトルエンディアン）:
+//   "RTPB" | バージョン u32 | 光路数 u32 | 光路ごとに [点の数 u32 | (x, y, z) f32 × 点の数]
+use std::io::{self, Read, Write};
+
+use glam::Vec3;
+
+const MAGIC: &[u8; 4] = b"RTPB";
+const VERSION: u32 = 1;
+
+pub fn write_paths_binary<W: Write>(writer: &mut W, paths: &[Vec<Vec3>]) -> io::Result<()> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&len_to_u32(paths.len())?.to_le_bytes())?;
+    for path in paths {
+        writer.write_all(&len_to_u32(path.len())?.to_le_bytes())?;
+        for point in path {
+            for value in point.to_array() {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn read_paths_binary<R: Read>(reader: &mut R) -> io::Result<Vec<Vec<Vec3>>> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid_data("光路のバイナリファイルではありません"));
+    }
+    let version = read_u32(reader)?;
+    if version != VERSION {
+        return Err(invalid_data(&format!(
+            "対応していないバージョンです: {}",
+            version
+        )));
+    }
+
+    let path_count = read_u32(reader)?;
+    let mut paths = Vec::new();
+    for _ in 0..path_count {
+        let point_count = read_u32(reader)?;
+        let mut path = Vec::new();
+        for _ in 0..point_count {
+            let x = read_f32(reader)?;
+            let y = read_f32(reader)?;
+            let z = read_f32(reader)?;
+            path.push(Vec3::new(x, y, z));
+        }
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_f32<R: Read>(reader: &mut R) -> io::Result<f32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(f32::from_le_bytes(bytes))
+}
+
+fn len_to_u32(len: usize) -> io::Result<u32> {
+    u32::try_from(len).map_err(|_| invalid_data("光路または点の数が多すぎます"))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
@@ -0,0 +1,107 @@
+// 長い追跡を途中から再開するためのチェックポイント
+//
+// レイは互いに独立なので、番号順に一定数ずつ追跡し、そのたびに追跡済みの光路をファイルに書き出す
+// 形式（リトルエンディアン）: 次に追跡するレイの番号 u32 | 以降は paths.bin と同じ形式の光路
+// 書き出しは一時ファイルに書いてから名前を変えるので、途中で止まっても前回の内容が残る
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use glam::Vec3;
+use raytracing_core::{DetailedPath, Scene, SceneError, SimulationSettingsConfig};
+
+use crate::{read_paths_binary, write_paths_binary};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Checkpoint {
+    pub next_ray: usize,       // これより前の番号のレイは追跡済み
+    pub paths: Vec<Vec<Vec3>>, // 追跡済みの光路の点（ワールド座標）
+}
+
+impl Checkpoint {
+    // ファイルが無ければ最初から始める
+    pub fn load(path: &Path) -> io::Result<Checkpoint> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Checkpoint::default()),
+            Err(e) => return Err(e),
+        };
+        let mut reader = BufReader::new(file);
+        let mut bytes = [0u8; 4];
+        reader.read_exact(&mut bytes)?;
+        let next_ray = u32::from_le_bytes(bytes) as usize;
+        let paths = read_paths_binary(&mut reader)?;
+        Ok(Checkpoint { next_ray, paths })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let next_ray = u32::try_from(self.next_ray)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "レイの数が多すぎます"))?;
+        let temporary = path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&temporary)?);
+        writer.write_all(&next_ray.to_le_bytes())?;
+        write_paths_binary(&mut writer, &self.paths)?;
+        writer.flush()?;
+        drop(writer);
+        fs::rename(&temporary, path)
+    }
+
+    pub fn is_complete(&self, scene: &Scene) -> bool {
+        self.next_ray >= scene.rays.len()
+    }
+
+    // 続きの chunk_size 本のレイを追跡して追加する（reverse なら光路を逆向きに並べ替える）
+    pub fn advance(
+        &mut self,
+        scene: &Scene,
+        setting: SimulationSettingsConfig,
+        chunk_size: usize,
+        reverse: bool,
+    ) -> Result<(), SceneError> {
+        let end = (self.next_ray + chunk_size.max(1)).min(scene.rays.len());
+        let indices: Vec<usize> = (self.next_ray..end).collect();
+        let traced = scene.simulate_ray_indices(&indices, setting)?;
+        self.paths.extend(traced.into_iter().map(|path| {
+            let path: DetailedPath = if reverse { path.reversed() } else { path };
+            path.points
+        }));
+        self.next_ray = end;
+        Ok(())
+    }
+}
+
+// チェックポイントを書きながら全レイを追跡し、光路の点を返す
+// resume なら checkpoint_path の続きから追跡する。ファイルは結果を書き出した後で呼び出し側が消す
+pub fn trace_with_checkpoints(
+    scene: &Scene,
+    setting: SimulationSettingsConfig,
+    chunk_size: usize,
+    reverse: bool,
+    resume: bool,
+    checkpoint_path: &Path,
+) -> io::Result<Vec<Vec<Vec3>>> {
+    let mut checkpoint = if resume {
+        let checkpoint = Checkpoint::load(checkpoint_path)?;
+        println!(
+            "チェックポイント '{}' から再開します（{} / {} 本のレイを追跡済み）",
+            checkpoint_path.display(),
+            checkpoint.next_ray.min(scene.rays.len()),
+            scene.rays.len()
+        );
+        checkpoint
+    } else {
+        Checkpoint::default()
+    };
+    while !checkpoint.is_complete(scene) {
+        checkpoint
+            .advance(scene, setting, chunk_size, reverse)
+            .map_err(io::Error::other)?;
+        checkpoint.save(checkpoint_path)?;
+        println!(
+            "チェックポイント: {} / {} 本のレイを追跡しました",
+            checkpoint.next_ray,
+            scene.rays.len()
+        );
+    }
+    Ok(checkpoint.paths)
+}
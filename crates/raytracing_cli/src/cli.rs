@@ -1,33 +1,473 @@
-use bevy_render_cli::render_cli;
+use bevy_render_cli::{render_cli, OverlayOptions};
 use csv::Writer;
-use raytracing_config::simulation_config::SimulationConfig;
-use raytracing_core::Scene;
+use glam::{Mat4, Vec2, Vec3};
+use raytracing_config::{scene_writer::dump_expanded, simulation_config::SimulationConfig};
+use raytracing_core::{
+    analysis, set_geometric_epsilon, DetailedPath, LengthUnit, Material, Plane, Scene,
+    SimulationSettingsConfig, DEFAULT_GEOMETRIC_EPSILON,
+};
 use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::{
+    export_ply, focus_spread, run_sweep, run_tolerance, trace_with_checkpoints, write_hit_logs,
+    write_npy_f32, write_paths_binary, write_pgm, CliArgs, OutputFormat,
+};
+
+// 交差判定の回数の見積もりがこれを超えたら、追跡を始める前に確かめる
+const CONFIRM_INTERSECTION_CALLS: u64 = 1_000_000_000;
 
 pub fn cli() -> Result<(), Box<dyn Error>> {
+    let args = CliArgs::parse(std::env::args().skip(1))?;
+
+    if let Some(sweep) = &args.sweep {
+        // 掃引では光路の出力もビューアも使わず、パラメータごとの結果だけを書く
+        let toml_str = std::fs::read_to_string("simulation.toml")?;
+        let file_name = "./dist/sweep.csv";
+        let rows = run_sweep(&toml_str, Path::new(""), sweep, File::create(file_name)?)?;
+        println!("{} 通りの掃引結果を '{}' に出力しました。", rows, file_name);
+        return Ok(());
+    }
+
+    if let Some(tolerance) = &args.tolerance {
+        // 公差解析も、試行ごとの結果だけを書いて終了する
+        let toml_str = std::fs::read_to_string("simulation.toml")?;
+        let file_name = "./dist/tolerance.csv";
+        let foci = run_tolerance(
+            &toml_str,
+            Path::new(""),
+            tolerance,
+            File::create(file_name)?,
+        )?;
+        println!(
+            "{} 回の試行結果を '{}' に出力しました。",
+            foci.len(),
+            file_name
+        );
+        match focus_spread(&foci) {
+            Some((mean, std_dev)) => println!(
+                "集光点: 平均 ({:.4}, {:.4}, {:.4})、標準偏差 ({:.4}, {:.4}, {:.4})",
+                mean.x, mean.y, mean.z, std_dev.x, std_dev.y, std_dev.z
+            ),
+            None => println!("集光点が求まった試行がありません"),
+        }
+        return Ok(());
+    }
+
+    if args.dump_expanded {
+        let toml_str = std::fs::read_to_string("simulation.toml")?;
+        let file_name = "./dist/expanded.toml";
+        std::fs::write(file_name, dump_expanded(&toml_str, Path::new(""))?)?;
+        println!("展開した設定ファイルを '{}' に出力しました。", file_name);
+        return Ok(());
+    }
+
     println!("設定ファイル simulation.toml を読み込んでいます...");
+    let runs = SimulationConfig::load_all("simulation.toml")?;
+    if runs.len() == 1 {
+        let config = runs.into_iter().next().expect("要素は1つある");
+        return run_simulation(config, &args, Path::new("./dist"), true);
+    }
+    // 複数のシーンは ./dist/run_0/ のように別々のディレクトリに出力する
+    // ビューアは1つのプロセスで1度しか開けないので、表示せずに出力だけを行う
+    println!(
+        "{} 個のシーンを順に追跡します（ビューアは表示しません）",
+        runs.len()
+    );
+    for (i, config) in runs.into_iter().enumerate() {
+        let out_dir = Path::new("./dist").join(format!("run_{}", i));
+        std::fs::create_dir_all(&out_dir)?;
+        println!("--- シーン {} ---", i);
+        run_simulation(config, &args, &out_dir, false)?;
+    }
+
+    Ok(())
+}
+
+// 1つのシーンを追跡し、結果を out_dir に出力する
+fn run_simulation(
+    config: SimulationConfig,
+    args: &CliArgs,
+    out_dir: &Path,
+    show_viewer: bool,
+) -> Result<(), Box<dyn Error>> {
     let SimulationConfig {
         scene,
         simulation_settings,
-    } = SimulationConfig::load_from_path("simulation.toml")?;
-    let scene: Scene = scene.into();
-    let results = scene.simulate_rays(simulation_settings.into());
-    render_cli(scene, results.clone());
+        units,
+        render,
+        output,
+        ..
+    } = config;
+    let length_unit: Option<LengthUnit> = units.map(|units| units.length.into());
+    if let Some(unit) = length_unit {
+        println!("長さの単位: {}", unit.symbol());
+    }
+    // [[runs]] で前のシーンの値が残らないよう、省略時も既定値に戻す
+    set_geometric_epsilon(
+        simulation_settings
+            .geometric_epsilon
+            .unwrap_or(DEFAULT_GEOMETRIC_EPSILON),
+    );
+    let stop_index = scene.stop_indices().first().copied();
+    let merged = scene.merged_object_count();
+    if merged > 0 {
+        println!("重複したオブジェクトを {} 個まとめました", merged);
+    }
+    let scene: Scene = scene.try_into()?;
+    // 設定の検査をすり抜けた退化した形状や NaN のレイは、追跡の前に止める
+    scene.validate()?;
+    if let Some(stop_index) = stop_index {
+        report_entrance_pupil(&scene, stop_index);
+    }
+    let setting: SimulationSettingsConfig = simulation_settings.into();
+    if !confirm_cost(&scene, setting, args.yes)? {
+        println!("追跡を中止しました。");
+        return Ok(());
+    }
+    // デバッグビルドでは、CSGの内外判定が食い違う形状を警告する
+    if cfg!(debug_assertions) {
+        for issue in scene.check_csg_consistency() {
+            eprintln!("警告: {}", issue);
+        }
+    }
+    if let Some(chunk_size) = args.checkpoint {
+        // 光路の点だけを残しながら追跡する（解析とビューアは使わない）
+        let checkpoint_path = out_dir.join("checkpoint.bin");
+        let mut results = trace_with_checkpoints(
+            &scene,
+            setting,
+            chunk_size,
+            args.reverse,
+            args.resume,
+            &checkpoint_path,
+        )?;
+        if let Some(file_name) = &args.export_ply {
+            export_ply(&scene, &results, &out_dir.join(file_name))?;
+        }
+        if let Some(matrix) = output.transform_matrix() {
+            apply_output_transform(&mut results, matrix);
+        }
+        write_results(results, args, out_dir)?;
+        // 結果を書き出せたので、再開用のファイルはもう要らない
+        std::fs::remove_file(&checkpoint_path)?;
+        return Ok(());
+    }
+    let detailed_paths = if args.reverse {
+        println!("レイを目標側から逆向きに追跡します");
+        scene.simulate_rays_from_targets(setting)
+    } else {
+        scene.simulate_rays_detailed(setting)
+    };
+    let ghosts = detailed_paths
+        .iter()
+        .filter(|path| path.branch_label().is_ghost())
+        .count();
+    if ghosts > 0 {
+        println!(
+            "ガラス面で部分反射したゴーストの光路: {} / {}",
+            ghosts,
+            detailed_paths.len()
+        );
+    }
+    let stuck = detailed_paths.iter().filter(|path| path.stuck).count();
+    if stuck > 0 {
+        println!(
+            "ほとんど進まずに往復し続けたため途中で打ち切った光路: {} / {}",
+            stuck,
+            detailed_paths.len()
+        );
+    }
+    let rehit = detailed_paths.iter().filter(|path| path.rehit).count();
+    if rehit > 0 {
+        println!(
+            "同じ面に再衝突したため途中で打ち切った光路: {} / {}",
+            rehit,
+            detailed_paths.len()
+        );
+    }
+    let truncated_hits: usize = detailed_paths.iter().map(|path| path.truncated_hits).sum();
+    if truncated_hits > 0 {
+        println!(
+            "交差判定のヒット数が max_intersection_hits を超えたため切り詰めた回数: {}",
+            truncated_hits
+        );
+    }
+    let tir_suppressed = detailed_paths
+        .iter()
+        .flat_map(|path| &path.interactions)
+        .filter(|interaction| interaction.tir_suppressed)
+        .count();
+    if tir_suppressed > 0 {
+        println!(
+            "全反射しないガラスのため、屈折できない光をそのまま直進させた衝突: {}",
+            tir_suppressed
+        );
+    }
+    if !args.reverse {
+        report_stray_light(&analysis::stray_light_budget(&detailed_paths));
+    }
+    let mut results: Vec<_> = detailed_paths
+        .iter()
+        .map(|path| path.points.clone())
+        .collect();
+    // PLY には形状と同じワールド座標で書く
+    if let Some(file_name) = &args.export_ply {
+        export_ply(&scene, &results, &out_dir.join(file_name))?;
+    }
+    // 書き出す光路だけを指定の座標系に直す（解析とビューアはワールド座標のまま）
+    if let Some(matrix) = output.transform_matrix() {
+        apply_output_transform(&mut results, matrix);
+    }
+    if let Some(object_index) = args.incidence_object {
+        write_incidence_histogram(&detailed_paths, object_index, args.incidence_bins, out_dir)?;
+    }
+    if let Some([x, y, z, radius]) = args.near_target {
+        let count = analysis::count_hits_near(&detailed_paths, Vec3::new(x, y, z), radius);
+        print_hit_count("目標点", count, detailed_paths.len());
+    }
+    if let Some([px, py, pz, nx, ny, nz, radius]) = args.detector {
+        let plane = Plane {
+            point: Vec3::new(px, py, pz),
+            normal: Vec3::new(nx, ny, nz),
+            material: Material::Mirror, // 判定には使わない
+        };
+        let count = analysis::hits_on_plane_within(&detailed_paths, &plane, Vec2::ZERO, radius);
+        print_hit_count("検出器", count, detailed_paths.len());
+        if let Some(resolution) = args.detector_image {
+            let shape = analysis::DetectorShape::Disk { radius };
+            write_detector_image(&detailed_paths, &plane, shape, resolution, out_dir)?;
+        }
+    }
+    if let Some(resolution) = args.voxels {
+        write_voxels(&scene, &detailed_paths, resolution, out_dir)?;
+    }
+    if args.hit_logs {
+        if scene.object_names.is_empty() {
+            eprintln!("警告: 名前を付けたオブジェクトが無いため、衝突の記録を出力しません");
+        }
+        write_hit_logs(&scene.object_names, &detailed_paths, out_dir)?;
+    }
+    let overlay = OverlayOptions {
+        axes: render.show_axes,
+        grid: render.show_grid,
+        color_seed: render.color_seed,
+        min_interior_points: render.min_interior_points,
+        aggregate_sources: render.aggregate_sources,
+    };
+    if show_viewer {
+        render_cli(scene, detailed_paths.clone(), length_unit, overlay);
+    }
+    write_results(results, args, out_dir)
+}
+
+// is_stop を付けた絞りから求めた近軸の入射瞳を表示する
+fn report_entrance_pupil(scene: &Scene, stop_index: usize) {
+    match analysis::entrance_pupil(scene, stop_index) {
+        Some(pupil) => println!(
+            "入射瞳: 中心 ({:.4}, {:.4}, {:.4})、直径 {:.4}",
+            pupil.position.x, pupil.position.y, pupil.position.z, pupil.diameter
+        ),
+        None => println!("絞りから入射瞳を求められませんでした（無限遠にあるか、光が抜けません）"),
+    }
+}
+
+// 迷光の内訳を表示する（どれも 0 なら何も表示しない）
+fn report_stray_light(budget: &analysis::StrayLightBudget) {
+    if *budget == analysis::StrayLightBudget::default() {
+        return;
+    }
+    println!("迷光の内訳（強度の合計）:");
+    println!("  フレネル反射: {:.6}", budget.fresnel_reflection);
+    println!("  金属鏡での吸収: {:.6}", budget.mirror_absorption);
+    println!("  全反射: {:.6}", budget.total_internal_reflection);
+    println!("  吸収体: {:.6}", budget.absorbed);
+}
+
+// 光路を --format の形式で out_dir に書き出す
+fn write_results(
+    results: Vec<Vec<Vec3>>,
+    args: &CliArgs,
+    out_dir: &Path,
+) -> Result<(), Box<dyn Error>> {
+    match args.format {
+        OutputFormat::Csv => write_paths_csv(results, args.precision, out_dir)?,
+        OutputFormat::Bin => {
+            let file_name = out_dir.join("paths.bin");
+            let mut writer = BufWriter::new(File::create(&file_name)?);
+            write_paths_binary(&mut writer, &results)?;
+            writer.flush()?;
+            println!(
+                "{} 本の光路を '{}' に出力しました。",
+                results.len(),
+                file_name.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// 物体とレイの始点を囲む範囲をボクセルに分け、光路の長さの分布を voxels.npy に書き出す
+// 配列の形は (nz, ny, nx)
+fn write_voxels(
+    scene: &Scene,
+    detailed_paths: &[DetailedPath],
+    resolution: [usize; 3],
+    out_dir: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let Some(bounds) = scene.bounding_box_with_rays() else {
+        eprintln!("警告: 範囲の決まる物体もレイも無いため、ボクセルを出力しません");
+        return Ok(());
+    };
+    let grid = analysis::voxelize(detailed_paths, bounds, resolution);
+
+    let file_name = out_dir.join("voxels.npy");
+    let mut writer = BufWriter::new(File::create(&file_name)?);
+    let [nx, ny, nz] = resolution;
+    write_npy_f32(&mut writer, &[nz, ny, nx], &grid)?;
+    writer.flush()?;
+    println!(
+        "{}×{}×{} のボクセル（範囲 {} 〜 {}）を '{}' に出力しました。",
+        nx,
+        ny,
+        nz,
+        bounds.min,
+        bounds.max,
+        file_name.display()
+    );
+    Ok(())
+}
+
+// 検出器に当たった強度の分布を detector.pgm に書き出す
+// 画像の右が検出器の平面内の u 方向、上が v 方向（analysis::plane_basis）
+fn write_detector_image(
+    detailed_paths: &[DetailedPath],
+    plane: &Plane,
+    shape: analysis::DetectorShape,
+    resolution: [usize; 2],
+    out_dir: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let image = analysis::detector_image(detailed_paths, plane, shape, resolution);
+    let [nu, nv] = resolution;
+    // 格子は v の小さい行から並んでいるので、画像では下から上へ積む
+    let rows: Vec<f32> = image.chunks(nu).rev().flatten().copied().collect();
+
+    let file_name = out_dir.join("detector.pgm");
+    let mut writer = BufWriter::new(File::create(&file_name)?);
+    write_pgm(&mut writer, nu, nv, &rows)?;
+    writer.flush()?;
+    let total: f32 = image.iter().sum();
+    println!(
+        "検出器の強度分布（{}×{}、合計 {}）を '{}' に出力しました。",
+        nu,
+        nv,
+        total,
+        file_name.display()
+    );
+    Ok(())
+}
+
+// 書き出す前に、光路の各点に変換行列を掛ける
+pub fn apply_output_transform(results: &mut [Vec<Vec3>], matrix: Mat4) {
+    for point in results.iter_mut().flatten() {
+        *point = matrix.transform_point3(*point);
+    }
+}
+
+pub fn write_paths_csv(
+    results: Vec<Vec<Vec3>>,
+    precision: Option<usize>,
+    out_dir: &Path,
+) -> Result<(), Box<dyn Error>> {
     // --- 3c. 結果を光路ごとに別々のCSVファイルに出力 ---
     for (i, result) in results.into_iter().enumerate() {
-        let file_name = format!("./dist/path_{}.csv", i);
+        let file_name = out_dir.join(format!("path_{}.csv", i));
         let mut wtr = Writer::from_path(&file_name)?;
         wtr.write_record(&["x", "y", "z"])?;
         for point in result {
             wtr.write_record(&[
-                point.x.to_string(),
-                point.y.to_string(),
-                point.z.to_string(),
+                format_coordinate(point.x, precision),
+                format_coordinate(point.y, precision),
+                format_coordinate(point.z, precision),
             ])?;
         }
         wtr.flush()?;
-        println!("光路 {} を '{}' に出力しました。", i, file_name);
+        println!("光路 {} を '{}' に出力しました。", i, file_name.display());
     }
 
     Ok(())
 }
+
+// 桁数の指定があれば小数点以下をその桁数に丸める（指数表記にはならない）
+pub fn format_coordinate(value: f32, precision: Option<usize>) -> String {
+    match precision {
+        Some(precision) => format!("{:.*}", precision, value),
+        None => value.to_string(),
+    }
+}
+
+// 見積もりを表示し、大きければ続けるか確かめる（--yes なら確かめずに続ける）
+fn confirm_cost(
+    scene: &Scene,
+    setting: SimulationSettingsConfig,
+    yes: bool,
+) -> Result<bool, Box<dyn Error>> {
+    let cost = scene.estimated_cost(setting);
+    println!(
+        "見積もり: レイ {} 本、交差判定 最大 {} 回",
+        cost.rays, cost.max_intersection_calls
+    );
+    if yes || cost.max_intersection_calls <= CONFIRM_INTERSECTION_CALLS {
+        return Ok(true);
+    }
+    print!("交差判定の回数が多いため時間がかかる可能性があります。続行しますか？ [y/N] ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+// 到達した光路の数と割合を表示
+fn print_hit_count(target: &str, count: usize, total: usize) {
+    let fraction = if total > 0 {
+        count as f32 / total as f32 * 100.0
+    } else {
+        0.0
+    };
+    println!(
+        "{}に到達した光路: {} / {} ({:.1}%)",
+        target, count, total, fraction
+    );
+}
+
+// 入射角ヒストグラムを incidence.csv に出力
+fn write_incidence_histogram(
+    detailed_paths: &[DetailedPath],
+    object_index: usize,
+    bins: usize,
+    out_dir: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let histogram = analysis::incidence_histogram(detailed_paths, object_index, bins);
+    let bin_width = 90.0 / bins as f32;
+
+    let file_name = out_dir.join("incidence.csv");
+    let mut wtr = Writer::from_path(&file_name)?;
+    wtr.write_record(["angle_min_deg", "angle_max_deg", "count"])?;
+    for (i, count) in histogram.iter().enumerate() {
+        wtr.write_record(&[
+            (i as f32 * bin_width).to_string(),
+            ((i + 1) as f32 * bin_width).to_string(),
+            count.to_string(),
+        ])?;
+    }
+    wtr.flush()?;
+    println!(
+        "オブジェクト {} の入射角分布を '{}' に出力しました。",
+        object_index,
+        file_name.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,80 @@
+// 名前を付けた面ごとの衝突の記録を hits_<名前>.csv に書き出す
+//
+// 列: path（光路の番号）, x,y,z（衝突点）, dx,dy,dz（衝突前の進行方向）, intensity（衝突直前の強度）, incidence_deg（入射角）
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use csv::Writer;
+use raytracing_core::{
+    analysis::{self, SurfaceHit},
+    DetailedPath,
+};
+
+pub fn write_hit_log<W: Write>(writer: W, hits: &[SurfaceHit]) -> Result<(), Box<dyn Error>> {
+    let mut wtr = Writer::from_writer(writer);
+    wtr.write_record([
+        "path",
+        "x",
+        "y",
+        "z",
+        "dx",
+        "dy",
+        "dz",
+        "intensity",
+        "incidence_deg",
+    ])?;
+    for hit in hits {
+        wtr.write_record(&[
+            hit.path_index.to_string(),
+            hit.position.x.to_string(),
+            hit.position.y.to_string(),
+            hit.position.z.to_string(),
+            hit.direction.x.to_string(),
+            hit.direction.y.to_string(),
+            hit.direction.z.to_string(),
+            hit.intensity.to_string(),
+            hit.incidence_deg.to_string(),
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+// object_names の面ごとに out_dir/hits_<名前>.csv を書き出し、書き出したファイルを名前順に返す
+// 名前のうちファイル名に使えない文字は _ に置き換える
+pub fn write_hit_logs(
+    object_names: &HashMap<String, usize>,
+    detailed_paths: &[DetailedPath],
+    out_dir: &Path,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut names: Vec<_> = object_names.iter().collect();
+    names.sort();
+    let mut file_names = Vec::with_capacity(names.len());
+    for (name, &object_index) in names {
+        let hits = analysis::surface_hits(detailed_paths, object_index);
+        let file_name = out_dir.join(format!("hits_{}.csv", file_stem(name)));
+        write_hit_log(std::fs::File::create(&file_name)?, &hits)?;
+        println!(
+            "面 '{}' への {} 回の衝突を '{}' に出力しました。",
+            name,
+            hits.len(),
+            file_name.display()
+        );
+        file_names.push(file_name);
+    }
+    Ok(file_names)
+}
+
+fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
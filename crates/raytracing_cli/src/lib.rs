@@ -1,3 +1,21 @@
+pub mod args;
+pub mod binary;
+pub mod checkpoint;
 pub mod cli;
+pub mod hit_log;
+pub mod npy;
+pub mod pgm;
+pub mod ply;
+pub mod sweep;
+pub mod tolerance;
 
+pub use args::*;
+pub use binary::*;
+pub use checkpoint::*;
 pub use cli::*;
+pub use hit_log::*;
+pub use npy::*;
+pub use pgm::*;
+pub use ply::*;
+pub use sweep::*;
+pub use tolerance::*;
@@ -0,0 +1,44 @@
+// 数値配列を NumPy の .npy 形式（バージョン 1.0）で書き出す
+//
+// 形式: "\x93NUMPY" | 1 | 0 | ヘッダ長 u16 | ヘッダ（Python の辞書リテラル）| データ
+// ヘッダは空白で埋め、データの先頭が 64 バイト境界に来るようにする
+use std::io::{self, Write};
+
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+const ALIGNMENT: usize = 64;
+
+// shape は C 順（最後の軸が最も速く変わる）。data の長さは shape の積と一致すること
+pub fn write_npy_f32<W: Write>(writer: &mut W, shape: &[usize], data: &[f32]) -> io::Result<()> {
+    if shape.iter().product::<usize>() != data.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "配列の形と要素数が一致しません",
+        ));
+    }
+    let dims: Vec<String> = shape.iter().map(|n| n.to_string()).collect();
+    // 1次元のときはタプルの末尾にカンマが要る
+    let shape_str = if dims.len() == 1 {
+        format!("({},)", dims[0])
+    } else {
+        format!("({})", dims.join(", "))
+    };
+    let mut header = format!(
+        "{{'descr': '<f4', 'fortran_order': False, 'shape': {}, }}",
+        shape_str
+    );
+    let prefix_len = MAGIC.len() + 2 + 2;
+    let padding = ALIGNMENT - (prefix_len + header.len() + 1) % ALIGNMENT;
+    header.push_str(&" ".repeat(padding % ALIGNMENT));
+    header.push('\n');
+    let header_len = u16::try_from(header.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "ヘッダが長すぎます"))?;
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[1, 0])?;
+    writer.write_all(&header_len.to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    for value in data {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+// 2次元の値の格子を、グレースケールの PGM 画像（バイナリ形式 P5、8ビット）で書き出す
+//
+// 形式: "P5\n幅 高さ\n255\n" | 画素（1バイト × 幅 × 高さ、上の行から）
+// 値は最大値が 255 になるように比例させる（すべて 0 なら真っ黒）
+use std::io::{self, Write};
+
+const MAX_LEVEL: f32 = 255.0;
+
+// rows は上の行から順に並んだ width × height 個の値
+pub fn write_pgm<W: Write>(
+    writer: &mut W,
+    width: usize,
+    height: usize,
+    rows: &[f32],
+) -> io::Result<()> {
+    if width * height != rows.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "画像の大きさと画素数が一致しません",
+        ));
+    }
+    let max = rows.iter().copied().fold(0.0_f32, f32::max);
+    let scale = if max > 0.0 { MAX_LEVEL / max } else { 0.0 };
+    write!(writer, "P5\n{} {}\n{}\n", width, height, MAX_LEVEL as u32)?;
+    let pixels: Vec<u8> = rows
+        .iter()
+        .map(|&value| (value.max(0.0) * scale).round().min(MAX_LEVEL) as u8)
+        .collect();
+    writer.write_all(&pixels)
+}
@@ -0,0 +1,77 @@
+// シーンの形状の頂点と光路を、MeshLab などで確かめるための1つの PLY ファイル（ASCII 形式）に書き出す
+//
+// 頂点: x y z red green blue（オブジェクトの頂点の後に、光路の点を光路ごとに並べる）
+// 辺: vertex1 vertex2（光路の隣り合う点を結ぶ区間）
+// 形状は Scene::renderable_triangles の頂点だけを点として書き、無限に広がる形状は書かない
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use glam::Vec3;
+use raytracing_core::Scene;
+
+// オブジェクトの頂点と光路の点の色
+const OBJECT_COLOR: [u8; 3] = [204, 179, 153];
+const PATH_COLOR: [u8; 3] = [230, 40, 40];
+
+pub fn write_ply<W: Write>(writer: &mut W, scene: &Scene, paths: &[Vec<Vec3>]) -> io::Result<()> {
+    let object_vertices: Vec<Vec3> = scene
+        .renderable_triangles()
+        .into_iter()
+        .flat_map(|(vertices, _)| vertices)
+        .collect();
+    let path_vertex_count: usize = paths.iter().map(Vec::len).sum();
+    let edge_count: usize = paths.iter().map(|path| path.len().saturating_sub(1)).sum();
+
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format ascii 1.0")?;
+    writeln!(
+        writer,
+        "element vertex {}",
+        object_vertices.len() + path_vertex_count
+    )?;
+    for property in ["float x", "float y", "float z"] {
+        writeln!(writer, "property {}", property)?;
+    }
+    for property in ["uchar red", "uchar green", "uchar blue"] {
+        writeln!(writer, "property {}", property)?;
+    }
+    writeln!(writer, "element edge {}", edge_count)?;
+    writeln!(writer, "property int vertex1")?;
+    writeln!(writer, "property int vertex2")?;
+    writeln!(writer, "end_header")?;
+
+    let write_vertex = |writer: &mut W, point: Vec3, [r, g, b]: [u8; 3]| {
+        writeln!(
+            writer,
+            "{} {} {} {} {} {}",
+            point.x, point.y, point.z, r, g, b
+        )
+    };
+    for &point in &object_vertices {
+        write_vertex(writer, point, OBJECT_COLOR)?;
+    }
+    for &point in paths.iter().flatten() {
+        write_vertex(writer, point, PATH_COLOR)?;
+    }
+    let mut start = object_vertices.len();
+    for path in paths {
+        for i in 1..path.len() {
+            writeln!(writer, "{} {}", start + i - 1, start + i)?;
+        }
+        start += path.len();
+    }
+    Ok(())
+}
+
+pub fn export_ply(scene: &Scene, paths: &[Vec<Vec3>], path: &Path) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_ply(&mut writer, scene, paths)?;
+    writer.flush()?;
+    println!(
+        "形状と {} 本の光路を '{}' に出力しました。",
+        paths.len(),
+        path.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+
+use csv::Writer;
+use raytracing_config::parameter_path::set_object_parameter;
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::{analysis, Scene};
+
+use crate::SweepArgs;
+
+// パラメータを1つずつ変えてシーンを作り直し、追跡結果の集光点を CSV に書く
+// 集光点は、何かに当たった光路の最後の区間を延ばした直線から求める（決まらなければ空欄）
+// 書いた行数（ヘッダーを除く）を返す
+pub fn run_sweep<W: Write>(
+    toml_str: &str,
+    base_dir: &Path,
+    sweep: &SweepArgs,
+    writer: W,
+) -> Result<usize, Box<dyn Error>> {
+    let document: toml::Table = toml_str.parse()?;
+    let mut wtr = Writer::from_writer(writer);
+    wtr.write_record(["value", "focus_x", "focus_y", "focus_z", "paths"])?;
+
+    for i in 0..sweep.steps {
+        let value = sweep.value(i);
+        let mut step_document = document.clone();
+        set_object_parameter(&mut step_document, sweep.object, &sweep.field, value)?;
+        let config = SimulationConfig::from_toml_table_in(&step_document, base_dir)?;
+        let scene: Scene = config.scene.try_into()?;
+        let paths = scene.simulate_rays_detailed(config.simulation_settings.into());
+
+        let focus = analysis::exit_focus(&paths)
+            .map(|focus| focus.to_array().map(|c| c.to_string()))
+            .unwrap_or_default();
+        wtr.write_record([
+            value.to_string(),
+            focus[0].clone(),
+            focus[1].clone(),
+            focus[2].clone(),
+            paths.len().to_string(),
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(sweep.steps)
+}
@@ -0,0 +1,87 @@
+// モンテカルロ法による公差解析
+//
+// 各試行で、指定したパラメータを設定ファイルの値（公称値）から ±公差 の範囲で一様な乱数だけずらしてシーンを作り直し、
+// 追跡結果の集光点を求める。試行ごとの集光点の散らばりが、製造誤差による性能のばらつきの目安になる
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+
+use csv::Writer;
+use glam::Vec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use raytracing_config::parameter_path::{get_object_parameter, set_object_parameter};
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::{analysis, Scene};
+
+use crate::ToleranceArgs;
+
+// 試行ごとに、ずらした値と集光点を CSV に書き、集光点（決まらなければ None）を試行の順に返す
+// 集光点は --sweep と同じく、何かに当たった光路の最後の区間を延ばした直線から求める
+pub fn run_tolerance<W: Write>(
+    toml_str: &str,
+    base_dir: &Path,
+    tolerance: &ToleranceArgs,
+    writer: W,
+) -> Result<Vec<Option<Vec3>>, Box<dyn Error>> {
+    let document: toml::Table = toml_str.parse()?;
+    let nominal: Vec<f32> = tolerance
+        .params
+        .iter()
+        .map(|param| get_object_parameter(&document, param.object, &param.field))
+        .collect::<Result<_, _>>()?;
+
+    let mut wtr = Writer::from_writer(writer);
+    let mut header = vec!["trial".to_string()];
+    header.extend(
+        tolerance
+            .params
+            .iter()
+            .map(|param| format!("{}:{}", param.object, param.field)),
+    );
+    header.extend(["focus_x", "focus_y", "focus_z", "paths"].map(str::to_string));
+    wtr.write_record(&header)?;
+
+    let mut rng = StdRng::seed_from_u64(tolerance.seed);
+    let mut foci = Vec::with_capacity(tolerance.trials);
+    for trial in 0..tolerance.trials {
+        let mut trial_document = document.clone();
+        let mut record = vec![trial.to_string()];
+        for (param, nominal) in tolerance.params.iter().zip(&nominal) {
+            // 公差が 0 なら公称値そのもの
+            let value = nominal + param.tolerance * rng.gen_range(-1.0..=1.0f32);
+            set_object_parameter(&mut trial_document, param.object, &param.field, value)?;
+            record.push(value.to_string());
+        }
+        let config = SimulationConfig::from_toml_table_in(&trial_document, base_dir)?;
+        let scene: Scene = config.scene.try_into()?;
+        let paths = scene.simulate_rays_detailed(config.simulation_settings.into());
+
+        let focus = analysis::exit_focus(&paths);
+        let columns = focus
+            .map(|focus| focus.to_array().map(|c| c.to_string()))
+            .unwrap_or_default();
+        record.extend(columns);
+        record.push(paths.len().to_string());
+        wtr.write_record(&record)?;
+        foci.push(focus);
+    }
+    wtr.flush()?;
+    Ok(foci)
+}
+
+// 集光点が決まった試行についての、集光点の平均と標準偏差（1つも無ければ None）
+pub fn focus_spread(foci: &[Option<Vec3>]) -> Option<(Vec3, Vec3)> {
+    let found: Vec<Vec3> = foci.iter().flatten().copied().collect();
+    if found.is_empty() {
+        return None;
+    }
+    let count = found.len() as f32;
+    let mean = found.iter().sum::<Vec3>() / count;
+    let variance = found
+        .iter()
+        .map(|focus| (*focus - mean) * (*focus - mean))
+        .sum::<Vec3>()
+        / count;
+    Some((mean, variance.powf(0.5)))
+}
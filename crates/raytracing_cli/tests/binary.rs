@@ -0,0 +1,43 @@
+// 光路のバイナリ形式が、書き出して読み戻したときに浮動小数点の値をそのまま再現することの確認
+use glam::Vec3;
+use raytracing_cli::{read_paths_binary, write_paths_binary};
+
+#[test]
+fn round_trip_reproduces_exact_floats() {
+    let paths = vec![
+        vec![
+            Vec3::new(0.1, -0.2, 1.0 / 3.0),
+            Vec3::new(f32::MIN_POSITIVE, f32::MAX, -0.0),
+            Vec3::new(1e-30, 123456.79, std::f32::consts::PI),
+        ],
+        Vec::new(),
+        vec![Vec3::new(-7.5, 2.0e7, 1.0e-7)],
+    ];
+    let mut bytes = Vec::new();
+    write_paths_binary(&mut bytes, &paths).unwrap();
+    // 見出し 12 バイトと、光路ごとに点の数 4 バイト、点ごとに 12 バイト
+    assert_eq!(bytes.len(), 12 + 3 * 4 + 4 * 12);
+
+    let loaded = read_paths_binary(&mut bytes.as_slice()).unwrap();
+    assert_eq!(loaded.len(), paths.len());
+    for (loaded, original) in loaded.iter().zip(&paths) {
+        assert_eq!(loaded.len(), original.len());
+        for (a, b) in loaded.iter().zip(original) {
+            // -0.0 と 0.0 も区別するため、ビット列で比べる
+            assert_eq!(
+                a.to_array().map(f32::to_bits),
+                b.to_array().map(f32::to_bits)
+            );
+        }
+    }
+}
+
+#[test]
+fn other_files_are_rejected() {
+    assert!(read_paths_binary(&mut b"RTPX\x01\x00\x00\x00".as_slice()).is_err());
+    // 途中で切れたファイル
+    let mut bytes = Vec::new();
+    write_paths_binary(&mut bytes, &[vec![Vec3::ONE]]).unwrap();
+    bytes.truncate(bytes.len() - 2);
+    assert!(read_paths_binary(&mut bytes.as_slice()).is_err());
+}
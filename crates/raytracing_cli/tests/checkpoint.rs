@@ -0,0 +1,65 @@
+// 途中で止めたチェックポイントから再開した結果が、通しで追跡した結果と一致することの確認
+use std::fs;
+
+use glam::Vec3;
+use raytracing_cli::{trace_with_checkpoints, Checkpoint};
+use raytracing_core::{FresnelMode, Material, Ray, Scene, SimulationSettingsConfig, Sphere};
+
+// ガラス球に向けて高さの違う平行光を並べる（Split で部分反射の分岐も生じる）
+fn scene() -> Scene {
+    let rays = (0..10)
+        .map(|i| Ray::new(Vec3::new(0.0, -0.9 + 0.2 * i as f32, -5.0), Vec3::Z, 1.0))
+        .collect();
+    Scene {
+        objects: vec![Box::new(Sphere {
+            center: Vec3::ZERO,
+            radius: 1.0,
+            material: Material::Glass { ior: 1.5 },
+        })],
+        rays,
+        object_names: Default::default(),
+    }
+}
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        infinity_distance: 20.0,
+        max_bounces: 10,
+        max_reflections: 10,
+        max_refractions: 10,
+        fresnel_mode: FresnelMode::Split,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn resumed_run_matches_uninterrupted_run() {
+    let scene = scene();
+    let expected: Vec<Vec<Vec3>> = scene
+        .simulate_rays_detailed(setting())
+        .into_iter()
+        .map(|path| path.points)
+        .collect();
+
+    let dir = std::env::temp_dir().join("raytracing_checkpoint");
+    fs::create_dir_all(&dir).unwrap();
+    let checkpoint_path = dir.join("checkpoint.bin");
+
+    // 3本追跡したところで止まったことにする
+    let mut interrupted = Checkpoint::default();
+    interrupted.advance(&scene, setting(), 3, false).unwrap();
+    interrupted.save(&checkpoint_path).unwrap();
+    assert_eq!(Checkpoint::load(&checkpoint_path).unwrap(), interrupted);
+
+    let resumed =
+        trace_with_checkpoints(&scene, setting(), 3, false, true, &checkpoint_path).unwrap();
+    assert_eq!(resumed, expected);
+    assert_eq!(Checkpoint::load(&checkpoint_path).unwrap().next_ray, 10);
+}
+
+#[test]
+fn missing_checkpoint_starts_from_the_first_ray() {
+    let path = std::env::temp_dir().join("raytracing_checkpoint_missing.bin");
+    let _ = fs::remove_file(&path);
+    assert_eq!(Checkpoint::load(&path).unwrap(), Checkpoint::default());
+}
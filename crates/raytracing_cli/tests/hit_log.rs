@@ -0,0 +1,86 @@
+// 名前を付けた面ごとの衝突の記録に、その面への衝突だけが書かれることの確認
+use std::collections::HashMap;
+use std::fs;
+
+use glam::Vec3;
+use raytracing_cli::write_hit_logs;
+use raytracing_core::{Material, Ray, Scene, SimulationSettingsConfig, Sphere};
+
+// x = -2 の球 "left" と x = 2 の球 "right" に、それぞれ1本ずつと、どちらにも当たらない1本のレイを飛ばす
+// left はガラスなので、通り抜ける間に2回当たる
+fn scene() -> Scene {
+    let sphere = |x: f32, material: Material| {
+        Box::new(Sphere {
+            center: Vec3::new(x, 0.0, 0.0),
+            radius: 1.0,
+            material,
+        })
+    };
+    Scene {
+        objects: vec![
+            sphere(-2.0, Material::Glass { ior: 1.5 }),
+            sphere(2.0, Material::Absorber),
+        ],
+        rays: vec![
+            Ray::new(Vec3::new(2.0, 0.0, -5.0), Vec3::Z, 1.0),
+            Ray::new(Vec3::new(-2.0, 0.0, -5.0), Vec3::Z, 1.0),
+            Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::Z, 1.0),
+        ],
+        object_names: HashMap::from([("left".to_string(), 0), ("right".to_string(), 1)]),
+    }
+}
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        infinity_distance: 20.0,
+        max_bounces: 10,
+        max_reflections: 10,
+        max_refractions: 10,
+        ..Default::default()
+    }
+}
+
+// ヘッダを除いた各行を列に分ける
+fn read_rows(path: &std::path::Path) -> Vec<Vec<f32>> {
+    let text = fs::read_to_string(path).unwrap();
+    let mut lines = text.lines();
+    assert_eq!(
+        lines.next(),
+        Some("path,x,y,z,dx,dy,dz,intensity,incidence_deg")
+    );
+    lines
+        .map(|line| {
+            line.split(',')
+                .map(|value| value.parse().unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn each_file_lists_only_hits_on_its_surface() {
+    let scene = scene();
+    let detailed_paths = scene.simulate_rays_detailed(setting());
+
+    let dir = std::env::temp_dir().join("raytracing_hit_log");
+    fs::create_dir_all(&dir).unwrap();
+    let files = write_hit_logs(&scene.object_names, &detailed_paths, &dir).unwrap();
+    assert_eq!(
+        files,
+        vec![dir.join("hits_left.csv"), dir.join("hits_right.csv")]
+    );
+
+    let left = read_rows(&files[0]);
+    assert_eq!(left.len(), 2);
+    assert!(left.iter().all(|row| row[0] == 1.0 && row[1] == -2.0));
+    assert_eq!(left[0][3], -1.0); // 入る面
+    assert!((left[1][3] - 1.0).abs() < 1e-4); // 出る面
+
+    let right = read_rows(&files[1]);
+    assert_eq!(right.len(), 1);
+    let row = &right[0];
+    assert_eq!(row[0], 0.0);
+    assert_eq!(&row[1..7], &[2.0, 0.0, -1.0, 0.0, 0.0, 1.0]);
+    assert_eq!(row[7], 1.0); // 強度
+    assert!(row[8].abs() < 1e-3); // 正面から当たる
+}
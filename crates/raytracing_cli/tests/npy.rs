@@ -0,0 +1,28 @@
+// .npy 形式の書き出しの確認
+use raytracing_cli::write_npy_f32;
+
+#[test]
+fn header_is_aligned_and_data_follows() {
+    let data: Vec<f32> = (0..24).map(|i| i as f32).collect();
+    let mut bytes = Vec::new();
+    write_npy_f32(&mut bytes, &[2, 3, 4], &data).unwrap();
+
+    assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+    let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    let data_start = 10 + header_len;
+    assert_eq!(data_start % 64, 0);
+    let header = std::str::from_utf8(&bytes[10..data_start]).unwrap();
+    assert!(header.contains("'descr': '<f4'"), "{header}");
+    assert!(header.contains("'shape': (2, 3, 4)"), "{header}");
+    assert!(header.ends_with('\n'));
+
+    assert_eq!(bytes.len(), data_start + 24 * 4);
+    let last = f32::from_le_bytes(bytes[bytes.len() - 4..].try_into().unwrap());
+    assert_eq!(last, 23.0);
+}
+
+#[test]
+fn mismatched_shape_is_rejected() {
+    let mut bytes = Vec::new();
+    assert!(write_npy_f32(&mut bytes, &[2, 2], &[0.0; 3]).is_err());
+}
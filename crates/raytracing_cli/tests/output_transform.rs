@@ -0,0 +1,60 @@
+// [output] の変換を掛けてから光路をCSVに書き出すことの確認
+use std::fs;
+
+use glam::Vec3;
+use raytracing_cli::{apply_output_transform, write_paths_csv};
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::Scene;
+
+const SCENE: &str = r#"
+[simulation_settings]
+infinity_distance = 20.0
+max_bounces = 10
+
+[output]
+transform = { position = [1.5, -2.0, 10.0] }
+
+[[scene.objects]]
+shape = { type = "Plane", normal = [-1.0, 0.0, 1.0] }
+material = { type = "Mirror" }
+transform = { position = [0.0, 0.0, 5.0] }
+
+[[scene.rays]]
+origin = [0.0, 0.0, 0.0]
+direction = [0.0, 0.0, 1.0]
+"#;
+
+// path_0.csv の各行を点として読む
+fn read_points(path: &std::path::Path) -> Vec<Vec3> {
+    let mut reader = csv::Reader::from_path(path).unwrap();
+    reader
+        .records()
+        .map(|record| {
+            let record = record.unwrap();
+            let value = |i: usize| record[i].parse::<f32>().unwrap();
+            Vec3::new(value(0), value(1), value(2))
+        })
+        .collect()
+}
+
+#[test]
+fn translation_shifts_written_points() {
+    let config = SimulationConfig::from_toml_str(SCENE).unwrap();
+    let matrix = config.output.transform_matrix().unwrap();
+    let scene: Scene = config.scene.try_into().unwrap();
+    let world = scene.simulate_rays(config.simulation_settings.into());
+
+    let mut results = world.clone();
+    apply_output_transform(&mut results, matrix);
+
+    let dir = std::env::temp_dir().join("raytracing_output_transform");
+    fs::create_dir_all(&dir).unwrap();
+    write_paths_csv(results, None, &dir).unwrap();
+
+    let written = read_points(&dir.join("path_0.csv"));
+    assert_eq!(written.len(), world[0].len());
+    let shift = Vec3::new(1.5, -2.0, 10.0);
+    for (written, original) in written.iter().zip(&world[0]) {
+        assert_eq!(*written, *original + shift);
+    }
+}
@@ -0,0 +1,17 @@
+// PGM 画像の書き出しの確認
+use raytracing_cli::write_pgm;
+
+#[test]
+fn values_are_scaled_to_the_brightest_pixel() {
+    let mut bytes = Vec::new();
+    write_pgm(&mut bytes, 3, 2, &[0.0, 1.0, 2.0, 4.0, 0.5, -1.0]).unwrap();
+    let header = b"P5\n3 2\n255\n";
+    assert_eq!(&bytes[..header.len()], header);
+    assert_eq!(&bytes[header.len()..], &[0, 64, 128, 255, 32, 0]);
+}
+
+#[test]
+fn mismatched_size_is_rejected() {
+    let mut bytes = Vec::new();
+    assert!(write_pgm(&mut bytes, 2, 2, &[0.0; 3]).is_err());
+}
@@ -0,0 +1,65 @@
+// PLY の書き出しで、ヘッダの頂点数と辺の数が形状と光路に合うことの確認
+use glam::Vec3;
+use raytracing_cli::write_ply;
+use raytracing_core::{AxisAlignedBox, Material, Plane, Scene};
+
+fn header_count(text: &str, element: &str) -> usize {
+    let prefix = format!("element {} ", element);
+    text.lines()
+        .find_map(|line| line.strip_prefix(&prefix))
+        .expect("要素の行がある")
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn header_counts_object_and_path_vertices_and_edges() {
+    let cube = AxisAlignedBox {
+        min: Vec3::splat(-1.0),
+        max: Vec3::splat(1.0),
+        material: Material::Absorber,
+    };
+    // 無限に広がる平面は書かない
+    let plane = Plane {
+        point: Vec3::new(0.0, -5.0, 0.0),
+        normal: Vec3::Y,
+        material: Material::Mirror,
+    };
+    let scene = Scene {
+        objects: vec![Box::new(cube), Box::new(plane)],
+        rays: Vec::new(),
+        object_names: Default::default(),
+    };
+    let object_vertices: usize = scene
+        .renderable_triangles()
+        .iter()
+        .map(|(vertices, _)| vertices.len())
+        .sum();
+    assert!(object_vertices > 0);
+    let paths = vec![
+        vec![Vec3::ZERO, Vec3::X, Vec3::Y],
+        vec![Vec3::Z],
+        vec![Vec3::ZERO, Vec3::NEG_X],
+    ];
+
+    let mut bytes = Vec::new();
+    write_ply(&mut bytes, &scene, &paths).unwrap();
+    let text = String::from_utf8(bytes).unwrap();
+
+    assert!(text.starts_with("ply\nformat ascii 1.0\n"));
+    assert_eq!(header_count(&text, "vertex"), object_vertices + 6);
+    assert_eq!(header_count(&text, "edge"), 3);
+    // ヘッダの後に頂点と辺が1行ずつ並ぶ
+    let body: Vec<&str> = text.split("end_header\n").nth(1).unwrap().lines().collect();
+    assert_eq!(body.len(), object_vertices + 6 + 3);
+    // 辺は光路の点の番号（オブジェクトの頂点の後ろから数える）を結ぶ
+    let first_path = object_vertices;
+    assert_eq!(
+        &body[object_vertices + 6..],
+        [
+            format!("{} {}", first_path, first_path + 1),
+            format!("{} {}", first_path + 1, first_path + 2),
+            format!("{} {}", first_path + 4, first_path + 5),
+        ]
+    );
+}
@@ -0,0 +1,40 @@
+// --precision で指定した桁数に丸めて CSV に座標が書かれることの確認
+use std::fs;
+
+use glam::Vec3;
+use raytracing_cli::{format_coordinate, write_paths_csv, CliArgs};
+
+#[test]
+fn coordinates_round_to_requested_decimals() {
+    assert_eq!(format_coordinate(1.23456, Some(2)), "1.23");
+    assert_eq!(format_coordinate(1.235, Some(0)), "1");
+    assert_eq!(format_coordinate(-0.98765, Some(3)), "-0.988");
+    // 桁数を指定すれば、小さな値でも指数表記にならない
+    assert_eq!(format_coordinate(1e-7, Some(3)), "0.000");
+    assert_eq!(format_coordinate(1.5, Some(3)), "1.500");
+    // 省略時は f32 の全桁
+    assert_eq!(format_coordinate(1.23456, None), "1.23456");
+}
+
+#[test]
+fn precision_flag_is_parsed() {
+    let args = ["--precision", "4"].map(str::to_string);
+    assert_eq!(CliArgs::parse(args).unwrap().precision, Some(4));
+    assert_eq!(CliArgs::parse(Vec::new()).unwrap().precision, None);
+    assert!(CliArgs::parse(["--precision", "x"].map(str::to_string)).is_err());
+}
+
+#[test]
+fn written_csv_uses_precision() {
+    let dir = std::env::temp_dir().join("raytracing_precision");
+    fs::create_dir_all(&dir).unwrap();
+    let paths = vec![vec![
+        Vec3::new(1.23456, -2.0, 1e-8),
+        Vec3::new(1.0 / 3.0, 123.45679, -0.0049),
+    ]];
+    write_paths_csv(paths, Some(2), &dir).unwrap();
+
+    let written = fs::read_to_string(dir.join("path_0.csv")).unwrap();
+    let rows: Vec<&str> = written.lines().collect();
+    assert_eq!(rows, ["x,y,z", "1.23,-2.00,0.00", "0.33,123.46,-0.00"]);
+}
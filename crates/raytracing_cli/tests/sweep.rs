@@ -0,0 +1,98 @@
+// --sweep でオブジェクトの位置を掃引し、値ごとに1行の結果が書かれることの確認
+use std::path::Path;
+
+use raytracing_cli::{run_sweep, CliArgs, SweepArgs};
+
+// +Z 方向の平行光をボールレンズで集める
+const SCENE: &str = r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[[scene.rays]]
+origin = [0.0, 0.5, -20.0]
+direction = [0.0, 0.0, 1.0]
+
+[[scene.rays]]
+origin = [0.5, 0.0, -20.0]
+direction = [0.0, 0.0, 1.0]
+
+[[scene.rays]]
+origin = [0.0, -0.5, -20.0]
+direction = [0.0, 0.0, 1.0]
+
+[[scene.objects]]
+shape = { type = "Sphere", radius = 5.0 }
+material = { type = "Glass", ior = 1.5 }
+transform = { position = [0.0, 0.0, 0.0] }
+"#;
+
+fn args(list: &str) -> Vec<String> {
+    list.split_whitespace().map(str::to_string).collect()
+}
+
+#[test]
+fn sweep_arguments_are_parsed_up_to_the_next_flag() {
+    let cli_args = CliArgs::parse(args(
+        "--sweep object=0 field=transform.position.z from=10 to=20 steps=11 --reverse",
+    ))
+    .unwrap();
+    assert_eq!(
+        cli_args.sweep,
+        Some(SweepArgs {
+            object: 0,
+            field: "transform.position.z".to_string(),
+            from: 10.0,
+            to: 20.0,
+            steps: 11,
+        })
+    );
+    assert!(cli_args.reverse);
+    assert!(CliArgs::parse(args("--sweep object=0 field=transform.position.z")).is_err());
+}
+
+#[test]
+fn sweeping_position_writes_one_row_per_step() {
+    let sweep = SweepArgs {
+        object: 0,
+        field: "transform.position.z".to_string(),
+        from: 0.0,
+        to: 4.0,
+        steps: 5,
+    };
+    let mut output = Vec::new();
+    let rows = run_sweep(SCENE, Path::new(""), &sweep, &mut output).unwrap();
+    assert_eq!(rows, 5);
+
+    let text = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 1 + 5);
+    assert_eq!(lines[0], "value,focus_x,focus_y,focus_z,paths");
+
+    // レンズと一緒に集光点も動く
+    let focus_offsets: Vec<f32> = lines[1..]
+        .iter()
+        .map(|line| {
+            let columns: Vec<f32> = line.split(',').map(|c| c.parse().unwrap()).collect();
+            columns[3] - columns[0]
+        })
+        .collect();
+    for offset in &focus_offsets {
+        assert!(
+            (offset - focus_offsets[0]).abs() < 1e-3,
+            "{focus_offsets:?}"
+        );
+    }
+}
+
+#[test]
+fn unknown_parameter_is_an_error() {
+    let sweep = SweepArgs {
+        object: 3,
+        field: "transform.position.z".to_string(),
+        from: 0.0,
+        to: 1.0,
+        steps: 2,
+    };
+    assert!(run_sweep(SCENE, Path::new(""), &sweep, Vec::new()).is_err());
+}
@@ -0,0 +1,143 @@
+// --tolerance の公差解析で、公差 0 なら毎回公称値と同じ結果になり、公差があれば結果が散らばることの確認
+use std::path::Path;
+
+use raytracing_cli::{
+    focus_spread, run_sweep, run_tolerance, CliArgs, SweepArgs, ToleranceArgs, ToleranceSpec,
+};
+
+// +Z 方向の平行光をボールレンズで集める
+const SCENE: &str = r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[[scene.rays]]
+origin = [0.0, 0.5, -20.0]
+direction = [0.0, 0.0, 1.0]
+
+[[scene.rays]]
+origin = [0.5, 0.0, -20.0]
+direction = [0.0, 0.0, 1.0]
+
+[[scene.rays]]
+origin = [0.0, -0.5, -20.0]
+direction = [0.0, 0.0, 1.0]
+
+[[scene.objects]]
+shape = { type = "Sphere", radius = 5.0 }
+material = { type = "Glass", ior = 1.5 }
+transform = { position = [0.0, 0.0, 0.0] }
+"#;
+
+fn tolerance(ior: f32, position_z: f32) -> ToleranceArgs {
+    ToleranceArgs {
+        trials: 8,
+        seed: 3,
+        params: vec![
+            ToleranceSpec {
+                object: 0,
+                field: "material.ior".to_string(),
+                tolerance: ior,
+            },
+            ToleranceSpec {
+                object: 0,
+                field: "shape.radius".to_string(),
+                tolerance: 0.0,
+            },
+            ToleranceSpec {
+                object: 0,
+                field: "transform.position.z".to_string(),
+                tolerance: position_z,
+            },
+        ],
+    }
+}
+
+// 公称値のシーンの集光点（1 段の掃引で、位置を設定ファイルと同じ値にする）
+fn nominal_focus_z() -> f32 {
+    let sweep = SweepArgs {
+        object: 0,
+        field: "transform.position.z".to_string(),
+        from: 0.0,
+        to: 0.0,
+        steps: 1,
+    };
+    let mut output = Vec::new();
+    run_sweep(SCENE, Path::new(""), &sweep, &mut output).unwrap();
+    let text = String::from_utf8(output).unwrap();
+    let row = text.lines().nth(1).unwrap();
+    row.split(',').nth(3).unwrap().parse().unwrap()
+}
+
+#[test]
+fn zero_tolerances_reproduce_nominal_every_trial() {
+    let mut output = Vec::new();
+    let foci = run_tolerance(SCENE, Path::new(""), &tolerance(0.0, 0.0), &mut output).unwrap();
+    assert_eq!(foci.len(), 8);
+    let nominal = nominal_focus_z();
+    for focus in &foci {
+        assert_eq!(focus.unwrap().z, nominal);
+    }
+    let (_, std_dev) = focus_spread(&foci).unwrap();
+    assert!(std_dev.z < 1e-5);
+
+    let text = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 1 + 8);
+    assert_eq!(
+        lines[0],
+        "trial,0:material.ior,0:shape.radius,0:transform.position.z,focus_x,focus_y,focus_z,paths"
+    );
+    // ずらした値の列には公称値がそのまま入る
+    assert!(lines[1..].iter().all(|line| line.contains(",1.5,5,0,")));
+}
+
+#[test]
+fn tolerances_spread_focus_within_range() {
+    let foci = run_tolerance(SCENE, Path::new(""), &tolerance(0.0, 0.5), Vec::new()).unwrap();
+    let nominal = nominal_focus_z();
+    // レンズの位置だけをずらすので、集光点も ±0.5 の範囲で動く
+    for focus in &foci {
+        let offset = focus.unwrap().z - nominal;
+        assert!(offset.abs() <= 0.5 + 1e-3, "{offset}");
+    }
+    let (_, std_dev) = focus_spread(&foci).unwrap();
+    assert!(std_dev.z > 0.0);
+    // 同じ seed なら同じ結果
+    let again = run_tolerance(SCENE, Path::new(""), &tolerance(0.0, 0.5), Vec::new()).unwrap();
+    assert_eq!(foci, again);
+}
+
+#[test]
+fn tolerance_arguments_are_parsed_up_to_the_next_flag() {
+    let args: Vec<String> =
+        "--tolerance trials=20 seed=7 param=0:material.ior:0.001 param=1:transform.position.z:0.05 --yes"
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+    let cli_args = CliArgs::parse(args).unwrap();
+    let parsed = cli_args.tolerance.unwrap();
+    assert_eq!(parsed.trials, 20);
+    assert_eq!(parsed.seed, 7);
+    assert_eq!(
+        parsed.params[1],
+        ToleranceSpec {
+            object: 1,
+            field: "transform.position.z".to_string(),
+            tolerance: 0.05,
+        }
+    );
+    assert!(cli_args.yes);
+    assert!(CliArgs::parse(["--tolerance".to_string(), "trials=5".to_string()]).is_err());
+    assert!(CliArgs::parse(
+        ["--tolerance", "trials=5", "param=0:material.ior:-1"].map(str::to_string)
+    )
+    .is_err());
+}
+
+#[test]
+fn missing_parameter_is_an_error() {
+    let mut spec = tolerance(0.1, 0.0);
+    spec.params[0].field = "material.abbe".to_string();
+    assert!(run_tolerance(SCENE, Path::new(""), &spec, Vec::new()).is_err());
+}
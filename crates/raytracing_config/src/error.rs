@@ -0,0 +1,204 @@
+use std::{error::Error, fmt};
+
+// 設定ファイルの読み込み・変換で発生するエラー
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    // 未知のキー（タイプミスなど）
+    UnknownField {
+        section: String, // 該当する [テーブル] 名とキー
+        field: String,
+        line: usize,
+        message: String,
+    },
+    Parse(toml::de::Error),
+    // material を省略したオブジェクトがあるのに、[scene] に default_material が無い
+    MissingMaterial,
+    // [[scene.rays]] の向きが direction と角度の両方で指定されている、またはどちらも無い
+    RayDirection {
+        index: usize,
+    },
+    // ParallelGrid の本数が count_u/count_v と density の両方で指定されている、またはどちらも無い
+    RayCount {
+        index: usize, // [[scene.ray_generators]] の番号
+    },
+    // [[scene.rays]] の frame に当たるオブジェクトが無い（名前の誤りや範囲外の番号）
+    UnknownFrame {
+        index: usize,  // [[scene.rays]] の番号
+        frame: String, // 指定された名前か番号
+    },
+    // レイのジェネレータの total_power が正の値でない
+    TotalPower {
+        index: usize, // [[scene.ray_generators]] の番号
+    },
+    // is_stop を付けたオブジェクトが2つ以上ある
+    MultipleStops {
+        count: usize,
+    },
+    // 名前で指定された材質が材質ライブラリに無い（ライブラリが指定されていない場合も含む）
+    UnknownMaterial {
+        name: String,
+    },
+    // 材質ライブラリの中で、材質を別の名前で指定している
+    NestedNamedMaterial {
+        name: String,
+    },
+    // 掃引するパラメータの場所が設定ファイルに無い
+    ParameterPath {
+        object: usize,
+        field: String,
+    },
+    // [[runs]] の要素の読み込みに失敗した
+    Run {
+        index: usize,
+        source: Box<ConfigError>,
+    },
+    // [scene.optical_axis] の向きが0ベクトル、または axial を使えない所で使っている
+    OpticalAxis {
+        reason: String,
+    },
+    // 形状の寸法が立体にならない（半径が0以下、法線が0ベクトルなど）
+    DegenerateShape {
+        shape: &'static str, // 形状の type 名
+        reason: String,
+    },
+}
+
+impl ConfigError {
+    // TOMLの解析エラーを、未知のキーであれば場所と名前付きのエラーに変換する
+    pub fn from_toml(error: toml::de::Error, source: &str) -> ConfigError {
+        let field = error
+            .message()
+            .strip_prefix("unknown field `")
+            .and_then(|rest| rest.split('`').next())
+            .map(str::to_string);
+        match (field, error.span()) {
+            (Some(field), Some(span)) => {
+                // タグ付きenumではスパンがテーブル全体を指すので、その中からキーを探す
+                let span_end = span.end.min(source.len());
+                let location = find_key(&source[span.start..span_end], &field)
+                    .map_or(span.start, |offset| span.start + offset);
+                ConfigError::UnknownField {
+                    section: describe_section(source, location, &field),
+                    line: source[..location].matches('\n').count() + 1,
+                    field,
+                    message: error.message().to_string(),
+                }
+            }
+            _ => ConfigError::Parse(error),
+        }
+    }
+}
+
+// `key =` の形で現れるキーの位置を探す
+fn find_key(text: &str, key: &str) -> Option<usize> {
+    text.match_indices(key).map(|(i, _)| i).find(|&i| {
+        let preceded_by_word = text[..i]
+            .chars()
+            .last()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        !preceded_by_word && text[i + key.len()..].trim_start().starts_with('=')
+    })
+}
+
+// 指定位置の直前のテーブル見出しと、その行のキーを "[[scene.objects]] shape" のように返す
+fn describe_section(source: &str, location: usize, field: &str) -> String {
+    let line_start = source[..location].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[location..]
+        .find('\n')
+        .map_or(source.len(), |i| location + i);
+    let header = source[..line_end]
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| line.starts_with('['))
+        .unwrap_or("(トップレベル)");
+    let current_line = source[line_start..line_end].trim();
+    match current_line.split_once('=') {
+        Some((key, _)) if !current_line.starts_with('[') && key.trim() != field => {
+            format!("{} {}", header, key.trim())
+        }
+        _ => header.to_string(),
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "設定ファイルを読み込めません: {}", e),
+            ConfigError::UnknownField {
+                section,
+                field,
+                line,
+                message,
+            } => write!(
+                f,
+                "{} 行目: {} に未知のフィールド `{}` があります ({})",
+                line, section, field, message
+            ),
+            ConfigError::Parse(e) => write!(f, "設定ファイルの解析に失敗しました: {}", e),
+            ConfigError::MissingMaterial => write!(
+                f,
+                "material を省略したオブジェクトがありますが、[scene] に default_material がありません"
+            ),
+            ConfigError::RayDirection { index } => write!(
+                f,
+                "{} 番目の [[scene.rays]] は direction か azimuth_deg/elevation_deg のどちらか一方で向きを指定してください",
+                index + 1
+            ),
+            ConfigError::RayCount { index } => write!(
+                f,
+                "{} 番目の [[scene.ray_generators]] は count_u/count_v か正の density のどちらか一方で本数を指定してください",
+                index + 1
+            ),
+            ConfigError::UnknownFrame { index, frame } => write!(
+                f,
+                "{} 番目の [[scene.rays]] の frame {} に当たるオブジェクトがありません",
+                index + 1,
+                frame
+            ),
+            ConfigError::TotalPower { index } => write!(
+                f,
+                "{} 番目の [[scene.ray_generators]] の total_power は正の値にしてください",
+                index + 1
+            ),
+            ConfigError::MultipleStops { count } => write!(
+                f,
+                "is_stop を付けたオブジェクトが {} 個あります。絞りは1つだけにしてください",
+                count
+            ),
+            ConfigError::UnknownMaterial { name } => write!(
+                f,
+                "材質 `{}` が材質ライブラリ (material_library) にありません",
+                name
+            ),
+            ConfigError::NestedNamedMaterial { name } => write!(
+                f,
+                "材質ライブラリの `{}` は Named ではなく材質の定義を直接書いてください",
+                name
+            ),
+            ConfigError::ParameterPath { object, field } => write!(
+                f,
+                "{} 番目の [[scene.objects]] にパラメータ `{}` がありません",
+                object, field
+            ),
+            ConfigError::Run { index, source } => {
+                write!(f, "{} 番目の [[runs]]: {}", index + 1, source)
+            }
+            ConfigError::OpticalAxis { reason } => {
+                write!(f, "光軸に沿った配置の指定が正しくありません: {}", reason)
+            }
+            ConfigError::DegenerateShape { shape, reason } => {
+                write!(f, "形状 {} の指定が正しくありません: {}", shape, reason)
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
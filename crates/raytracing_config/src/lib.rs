@@ -1,3 +1,5 @@
+pub mod error;
 pub mod model;
 
+pub use error::*;
 pub use model::*;
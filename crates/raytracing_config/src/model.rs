@@ -1,9 +1,19 @@
+pub mod group_config;
 pub mod material_config;
+pub mod material_library_config;
 pub mod object_config;
 pub mod object_generator_config;
+pub mod optical_axis_config;
+pub mod output_config;
+pub mod parameter_path;
+pub mod prescription_config;
 pub mod ray_config;
+pub mod render_config;
 pub mod scene_config;
+pub mod scene_writer;
 pub mod shape_config;
 pub mod simulation_config;
 pub mod simulation_settings_config;
+pub mod spectrum_config;
 pub mod transform_config;
+pub mod units_config;
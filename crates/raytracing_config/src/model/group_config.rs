@@ -0,0 +1,93 @@
+use glam::Mat4;
+use raytracing_core::Hittable;
+use serde::Deserialize;
+
+use crate::{
+    error::ConfigError, material_config::MaterialConfig,
+    material_library_config::MaterialLibraryConfig, object_config::ObjectConfig,
+    transform_config::TransformConfig,
+};
+
+// 1つのTransformを共有するオブジェクトの集まり（剛体として一緒に動かす）
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GroupConfig {
+    pub transform: TransformConfig,
+    #[serde(default)]
+    pub objects: Vec<ObjectConfig>,
+    #[serde(default)]
+    pub groups: Vec<GroupConfig>, // 入れ子のグループ
+}
+
+impl GroupConfig {
+    // 入れ子のグループも含め、材質が省略されたオブジェクトに既定の材質を使う
+    pub fn fill_default_material(&mut self, default: &MaterialConfig) {
+        for obj in &mut self.objects {
+            obj.fill_default_material(default);
+        }
+        for group in &mut self.groups {
+            group.fill_default_material(default);
+        }
+    }
+
+    // 入れ子のグループも含め、名前で指定された材質をライブラリの定義に置き換える
+    pub fn resolve_named_materials(
+        &mut self,
+        library: &MaterialLibraryConfig,
+    ) -> Result<(), ConfigError> {
+        for obj in &mut self.objects {
+            obj.resolve_named_material(library)?;
+        }
+        for group in &mut self.groups {
+            group.resolve_named_materials(library)?;
+        }
+        Ok(())
+    }
+
+    // 入れ子のグループも含め、材質が決まっていないオブジェクトがあるか
+    pub fn has_missing_material(&self) -> bool {
+        self.objects.iter().any(|obj| obj.material.is_none())
+            || self.groups.iter().any(GroupConfig::has_missing_material)
+    }
+
+    // 入れ子のグループも含め、axial で光軸に沿って置いたオブジェクトがあるか
+    pub fn has_axial_placement(&self) -> bool {
+        self.objects.iter().any(|obj| obj.axial.is_some())
+            || self.groups.iter().any(GroupConfig::has_axial_placement)
+    }
+
+    // 入れ子のグループも含め、有効なオブジェクトの形状を確かめる
+    pub fn validate_shapes(&self) -> Result<(), ConfigError> {
+        for obj in self.objects.iter().filter(|obj| obj.enabled) {
+            obj.shape.validate()?;
+        }
+        for group in &self.groups {
+            group.validate_shapes()?;
+        }
+        Ok(())
+    }
+
+    // 親の変換行列にグループの変換を掛け合わせ、子オブジェクトに適用する
+    pub fn into_hittables(self, parent: Mat4) -> Result<Vec<Box<dyn Hittable>>, ConfigError> {
+        self.into_placed_objects(parent)
+            .into_iter()
+            .map(|(obj, parent)| obj.into_with_parent(parent))
+            .collect()
+    }
+
+    // 入れ子のグループも含めた有効なオブジェクトを、それぞれの親の変換行列と組にして返す
+    pub fn into_placed_objects(self, parent: Mat4) -> Vec<(ObjectConfig, Mat4)> {
+        let group_matrix = parent * self.transform.to_matrix();
+
+        let mut placed: Vec<(ObjectConfig, Mat4)> = self
+            .objects
+            .into_iter()
+            .filter(|obj| obj.enabled)
+            .map(|obj| (obj, group_matrix))
+            .collect();
+        for group in self.groups {
+            placed.extend(group.into_placed_objects(group_matrix));
+        }
+        placed
+    }
+}
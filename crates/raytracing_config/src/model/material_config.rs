@@ -1,21 +1,121 @@
 use serde::Deserialize;
 
-use raytracing_core::Material;
+use raytracing_core::{Material, Reflectance, ThinFilmStack};
 
-#[derive(Deserialize, Clone, Copy)] // 材質はコピーするのでClone, Copyも
-#[serde(tag = "type")]
+use crate::error::ConfigError;
+
+#[derive(Deserialize, Clone)] // 材質は形状ごとに複製するのでClone
+#[serde(tag = "type", deny_unknown_fields)]
 pub enum MaterialConfig {
-    Glass { ior: f32 },
-    HalfMirror { reflectance: f32 },
+    Glass {
+        ior: f32,
+        #[serde(default)]
+        no_tir: bool, // true にすると全反射せず、屈折できない光は向きを変えずに抜ける理想化したガラスになる（省略時は false）
+    },
+    GlassByAbbe {
+        nd: f32,
+        vd: f32,
+    },
+    HalfMirror {
+        reflectance: ReflectanceConfig,
+    },
     Mirror,
+    OneSidedMirror, // 表（平面では法線の向いている側）から当たった光だけを反射し、反対側からの光は素通りさせる
+    Retroreflector,
+    Absorber,
+    MetalMirror {
+        reflectance: ReflectanceConfig,
+    }, // 表は [波長(nm), 反射率]
+    Detector {
+        #[serde(default = "default_acceptance_half_angle_deg")]
+        acceptance_half_angle_deg: f32, // 受光角の半角[度]（省略時は90度で、当たった光をすべて記録する）
+    },
+    MultilayerFilter {
+        layers: Vec<(f32, f32)>, // 表側から順に [屈折率, 厚さ(nm)]
+        #[serde(default = "default_incident_ior")]
+        incident_ior: f32, // 表側の媒質の屈折率（省略時は空気の1.0）
+        substrate_ior: f32,      // 裏側の基板の屈折率
+    },
+    Named {
+        name: String,
+    }, // 材質ライブラリの名前で指定する（読み込み時に定義へ置き換える）
 }
 
-impl Into<Material> for MaterialConfig {
-    fn into(self) -> Material {
-        match self {
-            MaterialConfig::Mirror => Material::Mirror,
-            MaterialConfig::Glass { ior } => Material::Glass { ior },
-            MaterialConfig::HalfMirror { reflectance } => Material::HalfMirror { reflectance },
+fn default_acceptance_half_angle_deg() -> f32 {
+    90.0
+}
+
+fn default_incident_ior() -> f32 {
+    1.0
+}
+
+// 反射率は定数か、[入射角(度), 反射率] の表で指定する（金属鏡では [波長(nm), 反射率]）
+// 例: reflectance = 0.5
+//     reflectance = [[0.0, 0.3], [45.0, 0.5], [85.0, 0.9]]
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+pub enum ReflectanceConfig {
+    Constant(f32),
+    Table(Vec<(f32, f32)>),
+}
+
+impl From<ReflectanceConfig> for Reflectance {
+    fn from(config: ReflectanceConfig) -> Self {
+        match config {
+            ReflectanceConfig::Constant(reflectance) => Reflectance::Constant(reflectance),
+            ReflectanceConfig::Table(mut table) => {
+                // 補間のため昇順に並べておく
+                table.sort_by(|a, b| a.0.total_cmp(&b.0));
+                Reflectance::Table(table.into())
+            }
         }
     }
 }
+
+// Named はライブラリの定義に置き換えてから変換する（置き換えていなければ UnknownMaterial）
+impl TryFrom<MaterialConfig> for Material {
+    type Error = ConfigError;
+
+    fn try_from(config: MaterialConfig) -> Result<Self, ConfigError> {
+        let material = match config {
+            MaterialConfig::Mirror => Material::Mirror,
+            MaterialConfig::OneSidedMirror => Material::OneSidedMirror,
+            MaterialConfig::Glass { ior, no_tir } => {
+                if no_tir {
+                    Material::IdealGlass { ior }
+                } else {
+                    Material::Glass { ior }
+                }
+            }
+            MaterialConfig::GlassByAbbe { nd, vd } => Material::GlassByAbbe { nd, vd },
+            MaterialConfig::HalfMirror { reflectance } => Material::HalfMirror {
+                reflectance: reflectance.into(),
+            },
+            MaterialConfig::Retroreflector => Material::Retroreflector,
+            MaterialConfig::Absorber => Material::Absorber,
+            MaterialConfig::MetalMirror { reflectance } => Material::MetalMirror {
+                reflectance: reflectance.into(),
+            },
+            MaterialConfig::Detector {
+                acceptance_half_angle_deg,
+            } => Material::Detector {
+                acceptance_half_angle_deg,
+            },
+            MaterialConfig::MultilayerFilter {
+                layers,
+                incident_ior,
+                substrate_ior,
+            } => Material::MultilayerFilter {
+                stack: ThinFilmStack {
+                    layers: layers.into(),
+                    incident_ior,
+                    substrate_ior,
+                },
+            },
+            MaterialConfig::Named { name } => {
+                return Err(ConfigError::UnknownMaterial { name });
+            }
+        };
+        Ok(material)
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::{error::ConfigError, material_config::MaterialConfig};
+
+// 名前で参照できる材質の一覧（別ファイルに書いて複数のシーンで使い回す）
+// 例:
+//   [materials.BK7]
+//   type = "GlassByAbbe"
+//   nd = 1.5168
+//   vd = 64.17
+#[derive(Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MaterialLibraryConfig {
+    #[serde(default)]
+    pub materials: HashMap<String, MaterialConfig>,
+}
+
+impl MaterialLibraryConfig {
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<MaterialLibraryConfig, ConfigError> {
+        let toml_str = std::fs::read_to_string(path)?;
+        Self::from_toml_str(&toml_str)
+    }
+
+    pub fn from_toml_str(toml_str: &str) -> Result<MaterialLibraryConfig, ConfigError> {
+        let library: MaterialLibraryConfig =
+            toml::from_str(toml_str).map_err(|e| ConfigError::from_toml(e, toml_str))?;
+        // ライブラリの中で別の名前を参照することはできない
+        if let Some(name) = library
+            .materials
+            .iter()
+            .find(|(_, material)| matches!(material, MaterialConfig::Named { .. }))
+            .map(|(name, _)| name.clone())
+        {
+            return Err(ConfigError::NestedNamedMaterial { name });
+        }
+        Ok(library)
+    }
+
+    // 名前で指定された材質を、ライブラリの定義に置き換える
+    pub fn resolve(&self, material: &mut MaterialConfig) -> Result<(), ConfigError> {
+        if let MaterialConfig::Named { name } = material {
+            *material = self
+                .materials
+                .get(name.as_str())
+                .cloned()
+                .ok_or_else(|| ConfigError::UnknownMaterial { name: name.clone() })?;
+        }
+        Ok(())
+    }
+}
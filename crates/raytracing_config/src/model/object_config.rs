@@ -1,30 +1,121 @@
-use glam::{Mat4, Vec3};
-use raytracing_core::{Hittable, Material, Transform};
+use glam::Mat4;
+use raytracing_core::{Hittable, Material, NonOccluding, Transform};
 use serde::Deserialize;
 
 use crate::{
-    material_config::MaterialConfig, shape_config::ShapeConfig, transform_config::TransformConfig,
+    error::ConfigError, material_config::MaterialConfig,
+    material_library_config::MaterialLibraryConfig, optical_axis_config::AxialPlacementConfig,
+    shape_config::ShapeConfig, transform_config::TransformConfig,
 };
 
 #[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct ObjectConfig {
+    #[serde(default)]
+    pub name: Option<String>, // 解析でオブジェクトを指定するための名前（省略可）
     pub shape: ShapeConfig,
-    pub material: MaterialConfig,
-    pub transform: TransformConfig,
+    #[serde(default)]
+    pub material: Option<MaterialConfig>, // 省略時はシーンの default_material
+    #[serde(default)]
+    pub transform: TransformConfig, // axial を指定したときは光軸上の位置からの相対（省略時は移動も回転もしない）
+    #[serde(default)]
+    pub axial: Option<AxialPlacementConfig>, // [scene.optical_axis] に沿った位置と傾き（[[scene.objects]] の直下だけで使える。省略時は transform だけで置く）
+    #[serde(default = "default_enabled")]
+    pub enabled: bool, // false にするとシーンから除外される
+    #[serde(default)]
+    pub non_occluding: bool, // true にすると通過を記録するだけで後ろの物体を隠さない計測用の面になる（省略時は false）
+    #[serde(default)]
+    pub is_stop: bool, // true にすると開口絞りとして入射瞳の計算に使う（シーンに1つまで。省略時は false）
 }
 
-impl Into<Box<dyn Hittable>> for ObjectConfig {
-    fn into(self) -> Box<dyn Hittable> {
-        let material: Material = self.material.into();
+fn default_enabled() -> bool {
+    true
+}
+
+impl ObjectConfig {
+    // 材質が省略されていれば既定の材質を使う
+    pub fn fill_default_material(&mut self, default: &MaterialConfig) {
+        if self.material.is_none() {
+            self.material = Some(default.clone());
+        }
+    }
+
+    // 名前で指定された材質をライブラリの定義に置き換える
+    pub fn resolve_named_material(
+        &mut self,
+        library: &MaterialLibraryConfig,
+    ) -> Result<(), ConfigError> {
+        match &mut self.material {
+            Some(material) => library.resolve(material),
+            None => Ok(()),
+        }
+    }
+
+    // 形状の寸法と、材質が決まっている（ライブラリの定義に置き換え済み）かを確かめる
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.shape.validate()?;
+        match &self.material {
+            None => Err(ConfigError::MissingMaterial),
+            Some(MaterialConfig::Named { name }) => {
+                Err(ConfigError::UnknownMaterial { name: name.clone() })
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    // 同じ形状が同じ位置・姿勢に置かれているか（変換行列の各成分の差が epsilon 以内）
+    pub fn duplicates(
+        &self,
+        parent: Mat4,
+        other: &ObjectConfig,
+        other_parent: Mat4,
+        epsilon: f32,
+    ) -> bool {
+        let matrix = self.world_matrix(parent);
+        let other_matrix = other.world_matrix(other_parent);
+        matrix.abs_diff_eq(other_matrix, epsilon)
+            && self.shape == other.shape
+            && self.non_occluding == other.non_occluding
+    }
+
+    // 親（グループ）の変換行列を合成した、ローカル座標からワールド座標への変換
+    pub fn world_matrix(&self, parent: Mat4) -> Mat4 {
+        parent * self.transform.to_matrix()
+    }
+
+    // 親（グループ）の変換行列を合成してHittableにする
+    // 材質が省略されたまま（既定の材質で埋めていない）なら MissingMaterial を返す
+    pub fn into_with_parent(self, parent: Mat4) -> Result<Box<dyn Hittable>, ConfigError> {
+        let material: Material = self
+            .material
+            .ok_or(ConfigError::MissingMaterial)?
+            .try_into()?;
 
         let primitive = self.shape.into_with(material);
 
         // Transformを適用
-        let transform_config = self.transform;
-        let translation = Mat4::from_translation(Vec3::from_array(transform_config.position));
-        let rotation = Mat4::from_rotation_y(transform_config.rotation_y_deg.to_radians());
-        let transform_matrix = translation * rotation;
+        let transform_matrix = parent * self.transform.to_matrix();
+
+        let transformed = Box::new(Transform::new(primitive, transform_matrix));
+        if self.non_occluding {
+            Ok(Box::new(NonOccluding::new(transformed)))
+        } else {
+            Ok(transformed)
+        }
+    }
+}
+
+// 読み込み時の確認を経ていない設定も変換できるよう、形状と材質をここで確かめる
+impl TryFrom<ObjectConfig> for Box<dyn Hittable> {
+    type Error = ConfigError;
 
-        Box::new(Transform::new(primitive, transform_matrix))
+    fn try_from(config: ObjectConfig) -> Result<Self, ConfigError> {
+        config.validate()?;
+        if config.axial.is_some() {
+            return Err(ConfigError::OpticalAxis {
+                reason: "光軸が無いので axial は使えません".to_string(),
+            });
+        }
+        config.into_with_parent(Mat4::IDENTITY)
     }
 }
@@ -1,24 +1,37 @@
 use glam::Vec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use raytracing_core::{Hittable, Ray};
 use serde::Deserialize;
 
 use crate::{
-    material_config::MaterialConfig, object_config::ObjectConfig, shape_config::ShapeConfig,
+    error::ConfigError, material_config::MaterialConfig, object_config::ObjectConfig,
+    shape_config::ShapeConfig, spectrum_config::SpectrumConfig,
 };
 
 // --- ジェネレータの定義 ---
 
 #[derive(Deserialize, Debug)]
-#[serde(tag = "type")]
+#[serde(tag = "type", deny_unknown_fields)]
 pub enum RayGeneratorConfig {
     ParallelGrid {
         origin_corner: [f32; 3],
         vec_u: [f32; 3],
         vec_v: [f32; 3],
-        count_u: u32,
-        count_v: u32,
+        #[serde(default)]
+        count_u: Option<u32>, // density で指定する場合は count_u/count_v を省略する
+        #[serde(default)]
+        count_v: Option<u32>,
+        #[serde(default)]
+        density: Option<f32>, // 単位長さあたりのレイの本数（u, v 共通）。本数は vec_u/vec_v の長さから決める
         direction: [f32; 3],
         current_ior: f32,
+        #[serde(default)]
+        jitter: f32, // 始点をセル内でずらす幅（セルの大きさに対する割合 0〜1、省略時は0で格子点のまま）
+        #[serde(default)]
+        seed: u64, // ずらし量の乱数の種
+        #[serde(default)]
+        total_power: Option<f32>, // 生成した全レイの強度の合計（省略時はどのレイも強度1.0）
     },
     Projector {
         origin: [f32; 3],
@@ -28,11 +41,132 @@ pub enum RayGeneratorConfig {
         count_u: u32,
         count_v: u32,
         current_ior: f32,
+        #[serde(default)]
+        jitter: f32, // 投影面上の目標点をセル内でずらす幅（割合 0〜1、省略時は0）
+        #[serde(default)]
+        seed: u64, // ずらし量の乱数の種
+        #[serde(default)]
+        total_power: Option<f32>,
+    },
+    // Projectorと同じ配置で、各レイの波長を分光分布に比例する確率で選ぶ
+    // 分布に従って波長を選ぶので、強度はどのレイも同じ（total_power を省略すれば1.0）
+    SpectralSource {
+        origin: [f32; 3],
+        target_corner: [f32; 3],
+        target_u: [f32; 3],
+        target_v: [f32; 3],
+        count_u: u32,
+        count_v: u32,
+        current_ior: f32,
+        spectrum: SpectrumConfig,
+        #[serde(default)]
+        total_power: Option<f32>,
     },
 }
 
+impl RayGeneratorConfig {
+    // ParallelGrid の本数の指定が、count_u/count_v の組と正の density のちょうど一方だけか
+    pub fn has_valid_count(&self) -> bool {
+        match *self {
+            RayGeneratorConfig::ParallelGrid {
+                count_u,
+                count_v,
+                density,
+                ..
+            } => match density {
+                Some(density) => density > 0.0 && count_u.is_none() && count_v.is_none(),
+                None => count_u.is_some() && count_v.is_some(),
+            },
+            _ => true,
+        }
+    }
+
+    pub fn total_power(&self) -> Option<f32> {
+        match *self {
+            RayGeneratorConfig::ParallelGrid { total_power, .. }
+            | RayGeneratorConfig::Projector { total_power, .. }
+            | RayGeneratorConfig::SpectralSource { total_power, .. } => total_power,
+        }
+    }
+}
+
+// ParallelGrid の u, v 方向の本数。density の指定があれば、辺の長さ × density を丸めた本数（1本以上）にする
+pub fn grid_counts(
+    vec_u: Vec3,
+    vec_v: Vec3,
+    count_u: Option<u32>,
+    count_v: Option<u32>,
+    density: Option<f32>,
+) -> (u32, u32) {
+    let count = |count: Option<u32>, side: Vec3| match density {
+        Some(density) => ((side.length() * density).round() as u32).max(1),
+        None => count.unwrap_or(1),
+    };
+    (count(count_u, vec_u), count(count_v, vec_v))
+}
+
+// 1つのジェネレータが生成した rays の強度を、合計が total_power になるように等分する（None ならそのまま）
+// 検出器に届いた強度の合計が、光源の全パワーに対する絶対的な値になる
+pub fn normalize_power(rays: &mut [Ray], total_power: Option<f32>) {
+    let Some(total_power) = total_power else {
+        return;
+    };
+    let intensity = total_power / rays.len().max(1) as f32;
+    for ray in rays {
+        ray.intensity = intensity;
+    }
+}
+
+// 1つのジェネレータが生成した rays に光源の番号を付ける（ビューアで光源ごとにまとめて描く用）
+pub fn assign_source(rays: &mut [Ray], source: usize) {
+    for ray in rays {
+        ray.source = source;
+    }
+}
+
+// 格子点からセル内でずらす量（u_step, v_step はセルの辺）
+// jitter が0なら乱数を使わずにゼロを返す
+pub fn jitter_offset(rng: &mut StdRng, jitter: f32, u_step: Vec3, v_step: Vec3) -> Vec3 {
+    let jitter = jitter.clamp(0.0, 1.0);
+    if jitter == 0.0 {
+        return Vec3::ZERO;
+    }
+    // 0以上1未満なので、隣のセルにははみ出さない
+    let a: f32 = rng.r#gen::<f32>() * jitter;
+    let b: f32 = rng.r#gen::<f32>() * jitter;
+    a * u_step + b * v_step
+}
+
+// SpectralSource のレイ。投影面の格子点へ向け、波長は spectrum に比例する確率で rng から選ぶ
+// target_uv は投影面の u, v 方向の辺、counts はその方向の本数
+pub fn spectral_source_rays(
+    origin: Vec3,
+    target_corner: Vec3,
+    target_uv: (Vec3, Vec3),
+    counts: (u32, u32),
+    current_ior: f32,
+    spectrum: &SpectrumConfig,
+    rng: &mut impl Rng,
+) -> Vec<Ray> {
+    let sampler = spectrum.sampler();
+    let (count_u, count_v) = counts;
+    let target_u_step = target_uv.0 / (count_u as f32);
+    let target_v_step = target_uv.1 / (count_v as f32);
+    let mut rays = Vec::with_capacity((count_u * count_v) as usize);
+    for i in 0..count_u {
+        for j in 0..count_v {
+            let target_point =
+                target_corner + (i as f32 * target_u_step) + (j as f32 * target_v_step);
+            let mut ray = Ray::new(origin, (target_point - origin).normalize(), current_ior);
+            ray.wavelength = sampler.sample(rng.r#gen());
+            rays.push(ray);
+        }
+    }
+    rays
+}
+
 #[derive(Deserialize)]
-#[serde(tag = "type")]
+#[serde(tag = "type", deny_unknown_fields)]
 pub enum ObjectGeneratorConfig {
     ObjectGrid {
         count_x: u32,
@@ -45,6 +179,7 @@ pub enum ObjectGeneratorConfig {
 }
 
 #[derive(Deserialize, Clone)] // テンプレートはクローン可能にする
+#[serde(deny_unknown_fields)]
 pub struct ObjectTemplateConfig {
     pub shape: ShapeConfig,
     pub material: MaterialConfig,
@@ -54,6 +189,7 @@ pub struct ObjectTemplateConfig {
 // (ShapeConfig, MaterialConfig, ObjectConfig などは以前のものを使用)
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SceneDefinition {
     // defaultを追加して、TOMLにキーが無くてもエラーにならないようにする
     //#[serde(default)]
@@ -66,13 +202,19 @@ pub struct SceneDefinition {
     pub objects: Vec<ObjectConfig>,
 }
 
+// 設定から生成したレイとオブジェクト
+pub type BuiltScene = (Vec<Ray>, Vec<Box<dyn Hittable>>);
+
 // この関数でConfigから実行時に使うオブジェクトを生成する
-pub fn build_scene_from_config(config: SceneDefinition) -> (Vec<Ray>, Vec<Box<dyn Hittable>>) {
+pub fn build_scene_from_config(config: SceneDefinition) -> Result<BuiltScene, ConfigError> {
     let mut rays: Vec<Ray> = Vec::new();
     let mut hittables: Vec<Box<dyn Hittable>> = Vec::new();
 
     // === レイの生成 ===
-    for generator in config.ray_generators {
+    // 光源の番号は生成器の順番
+    for (source, generator) in config.ray_generators.into_iter().enumerate() {
+        let total_power = generator.total_power();
+        let start = rays.len();
         match generator {
             RayGeneratorConfig::ParallelGrid {
                 origin_corner,
@@ -80,22 +222,28 @@ pub fn build_scene_from_config(config: SceneDefinition) -> (Vec<Ray>, Vec<Box<dy
                 vec_v,
                 count_u,
                 count_v,
+                density,
                 direction,
                 current_ior,
+                jitter,
+                seed,
+                ..
             } => {
+                let (vec_u, vec_v) = (Vec3::from(vec_u), Vec3::from(vec_v));
+                let (count_u, count_v) = grid_counts(vec_u, vec_v, count_u, count_v, density);
                 let corner = Vec3::from(origin_corner);
-                let u_step = Vec3::from(vec_u) / (count_u as f32);
-                let v_step = Vec3::from(vec_v) / (count_v as f32);
+                let u_step = vec_u / (count_u as f32);
+                let v_step = vec_v / (count_v as f32);
                 let dir = Vec3::from(direction).normalize();
+                let mut rng = StdRng::seed_from_u64(seed);
 
                 for i in 0..count_u {
                     for j in 0..count_v {
-                        let origin = corner + (i as f32 * u_step) + (j as f32 * v_step);
-                        rays.push(Ray {
-                            origin,
-                            direction: dir,
-                            current_ior, //..Default::default()
-                        });
+                        let origin = corner
+                            + (i as f32 * u_step)
+                            + (j as f32 * v_step)
+                            + jitter_offset(&mut rng, jitter, u_step, v_step);
+                        rays.push(Ray::new(origin, dir, current_ior));
                     }
                 }
             }
@@ -107,25 +255,54 @@ pub fn build_scene_from_config(config: SceneDefinition) -> (Vec<Ray>, Vec<Box<dy
                 count_u,
                 count_v,
                 current_ior,
+                jitter,
+                seed,
+                ..
             } => {
                 let ray_origin = Vec3::from(origin);
                 let target_c = Vec3::from(target_corner);
                 let target_u_step = Vec3::from(target_u) / (count_u as f32);
                 let target_v_step = Vec3::from(target_v) / (count_v as f32);
+                let mut rng = StdRng::seed_from_u64(seed);
 
                 for i in 0..count_u {
                     for j in 0..count_v {
-                        let target_point =
-                            target_c + (i as f32 * target_u_step) + (j as f32 * target_v_step);
-                        rays.push(Ray {
-                            origin: ray_origin,
-                            direction: (target_point - ray_origin).normalize(),
-                            current_ior, //..Default::default()
-                        });
+                        let target_point = target_c
+                            + (i as f32 * target_u_step)
+                            + (j as f32 * target_v_step)
+                            + jitter_offset(&mut rng, jitter, target_u_step, target_v_step);
+                        rays.push(Ray::new(
+                            ray_origin,
+                            (target_point - ray_origin).normalize(),
+                            current_ior,
+                        ));
                     }
                 }
             }
+            RayGeneratorConfig::SpectralSource {
+                origin,
+                target_corner,
+                target_u,
+                target_v,
+                count_u,
+                count_v,
+                current_ior,
+                spectrum,
+                ..
+            } => {
+                rays.extend(spectral_source_rays(
+                    Vec3::from(origin),
+                    Vec3::from(target_corner),
+                    (Vec3::from(target_u), Vec3::from(target_v)),
+                    (count_u, count_v),
+                    current_ior,
+                    &spectrum,
+                    &mut rand::thread_rng(),
+                ));
+            }
         }
+        normalize_power(&mut rays[start..], total_power);
+        assign_source(&mut rays[start..], source);
     }
 
     // === オブジェクトの生成 ===
@@ -139,6 +316,9 @@ pub fn build_scene_from_config(config: SceneDefinition) -> (Vec<Ray>, Vec<Box<dy
                 step_z,
                 template,
             } => {
+                if !template.enabled {
+                    continue;
+                }
                 let start_pos = Vec3::from(position_start);
                 let x_step = Vec3::from(step_x);
                 let z_step = Vec3::from(step_z);
@@ -151,7 +331,7 @@ pub fn build_scene_from_config(config: SceneDefinition) -> (Vec<Ray>, Vec<Box<dy
                         let mut obj = template.clone();
                         obj.transform.position = pos.to_array();
 
-                        let hittable: Box<dyn Hittable> = obj.into();
+                        let hittable: Box<dyn Hittable> = obj.try_into()?;
                         hittables.push(hittable);
                     }
                 }
@@ -160,14 +340,13 @@ pub fn build_scene_from_config(config: SceneDefinition) -> (Vec<Ray>, Vec<Box<dy
     }
 
     // === 個別オブジェクトの追加 ===
-    for obj_conf in config.objects {
-        let material = obj_conf.material.into();
-        let mut hittable = obj_conf.shape.into_with(material);
-        // ... transformの適用 ...
+    for obj_conf in config.objects.into_iter().filter(|obj| obj.enabled) {
+        // SceneConfig と同じく、ObjectConfig 側で transform を合成する
+        let hittable: Box<dyn Hittable> = obj_conf.try_into()?;
         hittables.push(hittable);
     }
 
-    (rays, hittables)
+    Ok((rays, hittables))
 }
 
 // 以下は仮のヘルパー関数
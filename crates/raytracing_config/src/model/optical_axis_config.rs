@@ -0,0 +1,61 @@
+use glam::{Mat4, Quat, Vec3};
+use serde::Deserialize;
+
+use crate::error::ConfigError;
+
+// シーンの光軸。origin を通って direction の向きに伸びる直線
+// axial を指定したオブジェクトは、ローカル座標の原点を光軸上に、+Z を光軸の向きに合わせて置く
+// 例:
+//   [scene.optical_axis]
+//   origin = [0.0, 0.0, -20.0]
+//   direction = [0.0, 0.0, 1.0]
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OpticalAxisConfig {
+    #[serde(default)]
+    pub origin: [f32; 3], // 光軸上の距離を測る起点（省略時は原点）
+    pub direction: [f32; 3],
+}
+
+// 光軸に沿ったオブジェクトの配置
+#[derive(Deserialize, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AxialPlacementConfig {
+    pub distance: f32, // 光軸の origin から光軸に沿って測った距離
+    #[serde(default)]
+    pub decenter: [f32; 2], // 光軸に垂直な方向のずれ（光軸の座標系の X, Y。省略時は 0）
+    #[serde(default)]
+    pub tilt_x_deg: f32, // 光軸の座標系の X 軸まわりの傾き（省略時は 0）
+    #[serde(default)]
+    pub tilt_y_deg: f32, // 光軸の座標系の Y 軸まわりの傾き（省略時は 0）
+}
+
+impl OpticalAxisConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let direction = Vec3::from(self.direction);
+        if !direction.is_finite() || direction.length_squared() == 0.0 {
+            return Err(ConfigError::OpticalAxis {
+                reason: "direction が0ベクトルです".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    // 光軸の座標系 -> ワールド座標への変換（origin を原点に、direction を +Z に合わせる）
+    // X, Y 軸は +Z を direction へ最短で回したときの向きになる
+    pub fn frame(&self) -> Mat4 {
+        let direction = Vec3::from(self.direction).normalize();
+        Mat4::from_translation(Vec3::from(self.origin))
+            * Mat4::from_quat(Quat::from_rotation_arc(Vec3::Z, direction))
+    }
+
+    // placement の位置・傾きに置いたオブジェクトの親の変換行列（オブジェクトの transform はこの後に掛ける）
+    // 傾きは光軸上の点を中心に、X 軸まわり、Y 軸まわりの順に回す
+    pub fn placement_matrix(&self, placement: &AxialPlacementConfig) -> Mat4 {
+        let [x, y] = placement.decenter;
+        self.frame()
+            * Mat4::from_translation(Vec3::new(x, y, placement.distance))
+            * Mat4::from_rotation_y(placement.tilt_y_deg.to_radians())
+            * Mat4::from_rotation_x(placement.tilt_x_deg.to_radians())
+    }
+}
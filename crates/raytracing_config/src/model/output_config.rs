@@ -0,0 +1,20 @@
+use glam::Mat4;
+use serde::Deserialize;
+
+use crate::transform_config::TransformConfig;
+
+// [output] セクション。光路を書き出すときの設定
+#[derive(Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct OutputConfig {
+    // 書き出す前に各点に掛ける変換（省略時はワールド座標のまま）
+    // 検出器の座標系で書き出すには、検出器の配置の逆の変換を指定する
+    #[serde(default)]
+    pub transform: Option<TransformConfig>,
+}
+
+impl OutputConfig {
+    pub fn transform_matrix(&self) -> Option<Mat4> {
+        self.transform.as_ref().map(TransformConfig::to_matrix)
+    }
+}
@@ -0,0 +1,94 @@
+use toml::{Table, Value};
+
+use crate::error::ConfigError;
+
+// [[scene.objects]] の object 番目の、"transform.position.z" のような点区切りの場所に値を書き込む
+// 配列の要素は番号か x/y/z で指定する。最後のキーが表に無ければ追加する（省略された既定値の上書き）
+pub fn set_object_parameter(
+    document: &mut Table,
+    object: usize,
+    field: &str,
+    value: f32,
+) -> Result<(), ConfigError> {
+    let path_error = || ConfigError::ParameterPath {
+        object,
+        field: field.to_string(),
+    };
+
+    let mut current = document
+        .get_mut("scene")
+        .and_then(|scene| scene.get_mut("objects"))
+        .and_then(|objects| objects.get_mut(object))
+        .ok_or_else(path_error)?;
+
+    let keys: Vec<&str> = field.split('.').collect();
+    let (last, parents) = keys.split_last().ok_or_else(path_error)?;
+    for key in parents {
+        current = step(current, key).ok_or_else(path_error)?;
+    }
+
+    let new_value = Value::Float(f64::from(value));
+    match current {
+        Value::Table(table) => {
+            table.insert(last.to_string(), new_value);
+        }
+        Value::Array(array) => {
+            let slot = array_index(last)
+                .and_then(|index| array.get_mut(index))
+                .ok_or_else(path_error)?;
+            *slot = new_value;
+        }
+        _ => return Err(path_error()),
+    }
+    Ok(())
+}
+
+// [[scene.objects]] の object 番目の、点区切りの場所にある数値を読む（整数も数値として読む）
+// 公差解析で、ずらす前の値を得るのに使う
+pub fn get_object_parameter(
+    document: &Table,
+    object: usize,
+    field: &str,
+) -> Result<f32, ConfigError> {
+    let path_error = || ConfigError::ParameterPath {
+        object,
+        field: field.to_string(),
+    };
+
+    let mut current = document
+        .get("scene")
+        .and_then(|scene| scene.get("objects"))
+        .and_then(|objects| objects.get(object))
+        .ok_or_else(path_error)?;
+    for key in field.split('.') {
+        current = match current {
+            Value::Table(table) => table.get(key),
+            Value::Array(array) => array_index(key).and_then(|index| array.get(index)),
+            _ => None,
+        }
+        .ok_or_else(path_error)?;
+    }
+    match current {
+        Value::Float(value) => Ok(*value as f32),
+        Value::Integer(value) => Ok(*value as f32),
+        _ => Err(path_error()),
+    }
+}
+
+// 表ならキーで、配列なら番号か x/y/z で1段下りる
+fn step<'a>(value: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    match value {
+        Value::Table(table) => table.get_mut(key),
+        Value::Array(array) => array.get_mut(array_index(key)?),
+        _ => None,
+    }
+}
+
+fn array_index(key: &str) -> Option<usize> {
+    match key {
+        "x" => Some(0),
+        "y" => Some(1),
+        "z" => Some(2),
+        _ => key.parse().ok(),
+    }
+}
@@ -0,0 +1,187 @@
+use glam::{Mat4, Vec3};
+use raytracing_core::{
+    CSGObject, CsgOperation, Hittable, InfiniteCylinder, Material, Plane, Sphere, Transform,
+};
+use serde::Deserialize;
+
+use crate::{
+    error::ConfigError, material_config::MaterialConfig,
+    material_library_config::MaterialLibraryConfig, transform_config::TransformConfig,
+};
+
+// レンズの処方表（光学設計で使う面の表）。第1面の頂点を原点に、+Z方向（光軸）へ面を並べる
+// 例:
+//   [[scene.prescriptions]]
+//   [[scene.prescriptions.surfaces]]
+//   radius = 50.0
+//   thickness = 5.0
+//   glass = { type = "Named", name = "BK7" }
+//   semi_diameter = 10.0
+//   [[scene.prescriptions.surfaces]]
+//   radius = -50.0
+//   thickness = 0.0
+//   semi_diameter = 10.0
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PrescriptionConfig {
+    #[serde(default)]
+    pub transform: TransformConfig, // 処方表全体の配置（省略時は原点に置く）
+    pub surfaces: Vec<SurfaceConfig>,
+}
+
+// 処方表の1行
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SurfaceConfig {
+    pub radius: f32,    // 曲率半径。正なら曲率中心が +Z 側。0 か inf なら平面
+    pub thickness: f32, // 次の面の頂点までの距離
+    #[serde(default)]
+    pub glass: Option<MaterialConfig>, // この面の後ろ（次の面まで）の媒質。省略時は空気
+    pub semi_diameter: f32, // 有効半径
+}
+
+impl SurfaceConfig {
+    fn is_flat(&self) -> bool {
+        self.radius == 0.0 || !self.radius.is_finite()
+    }
+
+    // 光軸からの高さ h でのサグ（頂点からの Z 方向のずれ）
+    fn sag(&self, h: f32) -> f32 {
+        if self.is_flat() {
+            return 0.0;
+        }
+        let h = h.min(self.radius.abs());
+        self.radius - self.radius.signum() * (self.radius * self.radius - h * h).sqrt()
+    }
+
+    // 頂点が z にあるこの面の後ろ側（+Z側）の領域。球の外側になる場合は false を返す
+    fn behind(&self, z: f32, material: &Material) -> (Box<dyn Hittable>, bool) {
+        if self.is_flat() {
+            // 平面の法線は立体の内部を向く
+            let plane = Plane {
+                point: Vec3::new(0.0, 0.0, z),
+                normal: Vec3::Z,
+                material: material.clone(),
+            };
+            return (Box::new(plane), true);
+        }
+        let sphere = Sphere {
+            center: Vec3::new(0.0, 0.0, z + self.radius),
+            radius: self.radius.abs(),
+            material: material.clone(),
+        };
+        // 曲率中心が +Z 側なら、頂点の後ろは球の内側
+        (Box::new(sphere), self.radius > 0.0)
+    }
+
+    // 頂点が z にあるこの面の手前側（-Z側）の領域
+    fn in_front(&self, z: f32, material: &Material) -> (Box<dyn Hittable>, bool) {
+        if self.is_flat() {
+            let plane = Plane {
+                point: Vec3::new(0.0, 0.0, z),
+                normal: Vec3::NEG_Z,
+                material: material.clone(),
+            };
+            return (Box::new(plane), true);
+        }
+        let (sphere, inside) = self.behind(z, material);
+        (sphere, !inside)
+    }
+}
+
+// acc と領域の積をとる。領域が球の外側なら球を引く
+fn intersect_region(
+    acc: Box<dyn Hittable>,
+    (region, inside): (Box<dyn Hittable>, bool),
+) -> Box<dyn Hittable> {
+    Box::new(CSGObject {
+        left: acc,
+        right: region,
+        operation: if inside {
+            CsgOperation::Intersection
+        } else {
+            CsgOperation::Difference
+        },
+    })
+}
+
+// 頂点が z1, z2 にある2つの面で挟まれたガラスの立体
+fn build_element(
+    front: &SurfaceConfig,
+    z1: f32,
+    back: &SurfaceConfig,
+    z2: f32,
+    material: Material,
+) -> Box<dyn Hittable> {
+    let semi_diameter = front.semi_diameter.max(back.semi_diameter);
+    for surface in [front, back] {
+        if !surface.is_flat() && semi_diameter > surface.radius.abs() {
+            println!(
+                "警告: 有効半径 {} が曲率半径 {} より大きい面があります",
+                semi_diameter, surface.radius
+            );
+        }
+    }
+
+    // 球の外側を使う面は光軸方向に閉じていないので、両面の縁の高さで挟んでおく
+    let z_min = z1 + front.sag(semi_diameter).min(0.0);
+    let z_max = z2 + back.sag(semi_diameter).max(0.0);
+    let aperture = Box::new(InfiniteCylinder {
+        axis_point: Vec3::ZERO,
+        axis_dir: Vec3::Z,
+        radius: semi_diameter,
+        material: material.clone(),
+    });
+    let slab = [
+        (Vec3::new(0.0, 0.0, z_min), Vec3::Z),
+        (Vec3::new(0.0, 0.0, z_max), Vec3::NEG_Z),
+    ]
+    .into_iter()
+    .fold(aperture as Box<dyn Hittable>, |acc, (point, normal)| {
+        let plane = Plane {
+            point,
+            normal,
+            material: material.clone(),
+        };
+        intersect_region(acc, (Box::new(plane), true))
+    });
+
+    let with_front = intersect_region(slab, front.behind(z1, &material));
+    intersect_region(with_front, back.in_front(z2, &material))
+}
+
+impl PrescriptionConfig {
+    // 名前で指定されたガラスをライブラリの定義に置き換える
+    pub fn resolve_named_materials(
+        &mut self,
+        library: &MaterialLibraryConfig,
+    ) -> Result<(), ConfigError> {
+        for glass in self.surfaces.iter_mut().filter_map(|s| s.glass.as_mut()) {
+            library.resolve(glass)?;
+        }
+        Ok(())
+    }
+
+    // ガラスで満たされた面の間ごとに1つの立体を作る
+    // 出射側の媒質は空気とみなして屈折するので、貼り合わせ面（ガラス同士の境界）は正しく扱えない
+    pub fn into_hittables(self, parent: Mat4) -> Result<Vec<Box<dyn Hittable>>, ConfigError> {
+        let matrix = parent * self.transform.to_matrix();
+        let mut hittables: Vec<Box<dyn Hittable>> = Vec::new();
+        let mut z = 0.0;
+        for (i, surface) in self.surfaces.iter().enumerate() {
+            let next_z = z + surface.thickness;
+            if let Some(glass) = &surface.glass {
+                match self.surfaces.get(i + 1) {
+                    Some(back) => {
+                        let material = glass.clone().try_into()?;
+                        let element = build_element(surface, z, back, next_z, material);
+                        hittables.push(Box::new(Transform::new(element, matrix)));
+                    }
+                    None => println!("警告: 最後の面の後ろのガラスは閉じる面が無いため無視します"),
+                }
+            }
+            z = next_z;
+        }
+        Ok(hittables)
+    }
+}
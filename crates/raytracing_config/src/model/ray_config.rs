@@ -1,20 +1,118 @@
-use glam::Vec3;
+use std::collections::HashMap;
+
+use glam::{Mat4, Vec3};
 use serde::Deserialize;
 
 use raytracing_core::Ray;
 
+use crate::error::ConfigError;
+
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct RayConfig {
     pub origin: [f32; 3],
-    pub direction: [f32; 3],
+    // 向きは direction か、azimuth_deg / elevation_deg のどちらか一方で指定する
+    #[serde(default)]
+    pub direction: Option<[f32; 3]>,
+    #[serde(default)]
+    pub azimuth_deg: Option<f32>, // XZ平面内で +X から +Z へ測る方位角（省略時は0）
+    #[serde(default)]
+    pub elevation_deg: Option<f32>, // XZ平面から +Y へ測る仰角（省略時は0）
+    #[serde(default)]
+    pub wavelength_nm: Option<f32>, // 省略時はd線 (587.6nm)
+    #[serde(default)]
+    pub frame: Option<ObjectRefConfig>, // origin と向きをこのオブジェクトの座標系で表す（省略時はワールド座標）
+}
+
+// オブジェクトの指定。名前 (name) か、[[scene.objects]] とグループなどを並べた順の番号で指定する
+// 番号は enabled = false のオブジェクトと、dedup_objects でまとめた重複を除いて数える（Scene.objects の添字と同じ）
+// 例: frame = "lens2"
+//     frame = 2
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum ObjectRefConfig {
+    Index(usize),
+    Name(String),
+}
+
+impl std::fmt::Display for ObjectRefConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectRefConfig::Index(index) => write!(f, "{}", index),
+            ObjectRefConfig::Name(name) => write!(f, "\"{}\"", name),
+        }
+    }
+}
+
+impl ObjectRefConfig {
+    // 指定されたオブジェクトの、ローカル座標からワールド座標への変換行列
+    fn resolve(&self, frames: &[Mat4], object_names: &HashMap<String, usize>) -> Option<Mat4> {
+        let index = match self {
+            ObjectRefConfig::Index(index) => *index,
+            ObjectRefConfig::Name(name) => *object_names.get(name)?,
+        };
+        frames.get(index).copied()
+    }
+}
+
+impl RayConfig {
+    // 向きの指定が direction と角度のちょうど一方だけか
+    pub fn has_valid_direction(&self) -> bool {
+        let by_angle = self.azimuth_deg.is_some() || self.elevation_deg.is_some();
+        self.direction.is_some() != by_angle
+    }
+
+    // 指定された向きの単位ベクトル
+    pub fn direction_vector(&self) -> Vec3 {
+        match self.direction {
+            Some(direction) => Vec3::from_array(direction).normalize(),
+            None => {
+                let azimuth = self.azimuth_deg.unwrap_or(0.0).to_radians();
+                let elevation = self.elevation_deg.unwrap_or(0.0).to_radians();
+                Vec3::new(
+                    elevation.cos() * azimuth.cos(),
+                    elevation.sin(),
+                    elevation.cos() * azimuth.sin(),
+                )
+            }
+        }
+    }
+
+    // frame を指定したレイは、そのオブジェクトの変換でワールド座標に直す
+    // frames はオブジェクトの番号順の変換行列。index は [[scene.rays]] の番号（エラーの表示用）
+    // frame に当たるオブジェクトが無ければ UnknownFrame を返す
+    pub fn into_ray_in(
+        self,
+        index: usize,
+        frames: &[Mat4],
+        object_names: &HashMap<String, usize>,
+    ) -> Result<Ray, ConfigError> {
+        let Some(frame) = &self.frame else {
+            return Ok(self.into());
+        };
+        let Some(matrix) = frame.resolve(frames, object_names) else {
+            return Err(ConfigError::UnknownFrame {
+                index,
+                frame: frame.to_string(),
+            });
+        };
+        let mut ray: Ray = self.into();
+        ray.origin = matrix.transform_point3(ray.origin);
+        ray.direction = matrix.transform_vector3(ray.direction).normalize();
+        Ok(ray)
+    }
 }
 
-impl Into<Ray> for RayConfig {
-    fn into(self) -> Ray {
-        Ray {
-            origin: Vec3::from_array(self.origin),
-            direction: Vec3::from_array(self.direction).normalize(),
-            current_ior: 1.0,
+impl From<RayConfig> for Ray {
+    fn from(config: RayConfig) -> Self {
+        let mut ray = Ray::new(
+            Vec3::from_array(config.origin),
+            config.direction_vector(),
+            1.0,
+        );
+        if let Some(wavelength) = config.wavelength_nm {
+            ray.wavelength = wavelength;
         }
+        ray
     }
 }
@@ -0,0 +1,17 @@
+use serde::Deserialize;
+
+// [render] セクション。ビューアの補助表示を切り替える
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RenderConfig {
+    #[serde(default)]
+    pub show_axes: bool, // 原点に XYZ 軸を表示する（X:赤 Y:緑 Z:青）
+    #[serde(default)]
+    pub show_grid: bool, // XZ 平面に方眼を表示する
+    #[serde(default)]
+    pub color_seed: u64, // 光路の色を決める乱数の種（省略時は 0）。同じ値なら毎回同じ色になる
+    #[serde(default)]
+    pub min_interior_points: usize, // 途中の点がこれより少ない光路を最初は隠す（省略時は 0 で全て表示）
+    #[serde(default)]
+    pub aggregate_sources: bool, // 光路を光源ごとの半透明の線の束で描く（省略時は1本ずつ矢印で描く）
+}
@@ -1,13 +1,27 @@
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use raytracing_core::{Hittable, Ray, Scene};
 use serde::Deserialize;
 
 use crate::{
-    model::object_generator_config::{ObjectGeneratorConfig, RayGeneratorConfig},
+    error::ConfigError,
+    group_config::GroupConfig,
+    material_config::MaterialConfig,
+    material_library_config::MaterialLibraryConfig,
+    model::object_generator_config::{
+        assign_source, grid_counts, jitter_offset, normalize_power, spectral_source_rays,
+        ObjectGeneratorConfig, RayGeneratorConfig,
+    },
     object_config::ObjectConfig,
+    optical_axis_config::OpticalAxisConfig,
+    prescription_config::PrescriptionConfig,
     ray_config::RayConfig,
 };
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SceneConfig {
     #[serde(default)]
     pub rays: Vec<RayConfig>,
@@ -17,16 +31,189 @@ pub struct SceneConfig {
     pub object_generators: Vec<ObjectGeneratorConfig>,
     #[serde(default)]
     pub objects: Vec<ObjectConfig>,
+    #[serde(default)]
+    pub groups: Vec<GroupConfig>,
+    #[serde(default)]
+    pub prescriptions: Vec<PrescriptionConfig>, // 処方表で並べた面から作るレンズ
+    #[serde(default)]
+    pub default_material: Option<MaterialConfig>, // material を省略したオブジェクトに使う
+    #[serde(default)]
+    pub optical_axis: Option<OpticalAxisConfig>, // axial を指定したオブジェクトを並べる光軸（省略可）
+    #[serde(default)]
+    pub dedup_objects: bool, // 同じ形状が同じ位置に重なったオブジェクトを1つにまとめる（省略時はまとめない）
+}
+
+// 重複とみなす変換行列の成分の差
+const DEDUP_EPSILON: f32 = 1e-5;
+// 重複の候補を探すために位置を丸める格子の幅（DEDUP_EPSILON 以上であること）
+const DEDUP_CELL: f32 = 1e-3;
+
+// ワールド座標での位置を丸めた格子の番号
+fn dedup_cell(obj: &ObjectConfig, parent: glam::Mat4) -> [i64; 3] {
+    let position = obj.world_matrix(parent).w_axis.truncate();
+    (position / DEDUP_CELL).floor().as_i64vec3().to_array()
+}
+
+// 同じ形状・同じ変換のオブジェクトのうち、最初の1つだけを残す
+// 重複なら位置の差も DEDUP_EPSILON 以内なので、同じか隣の格子に残したものとだけ比べる
+fn dedup_placed_objects(
+    placed: Vec<(ObjectConfig, glam::Mat4)>,
+) -> Vec<(ObjectConfig, glam::Mat4)> {
+    let mut kept: Vec<(ObjectConfig, glam::Mat4)> = Vec::with_capacity(placed.len());
+    let mut cells: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+    for (obj, parent) in placed {
+        let [x, y, z] = dedup_cell(&obj, parent);
+        let duplicate = (-1..=1).any(|dx| {
+            (-1..=1).any(|dy| {
+                (-1..=1).any(|dz| {
+                    cells
+                        .get(&[x + dx, y + dy, z + dz])
+                        .into_iter()
+                        .flatten()
+                        .any(|&index| {
+                            let (other, other_parent) = &kept[index];
+                            obj.duplicates(parent, other, *other_parent, DEDUP_EPSILON)
+                        })
+                })
+            })
+        });
+        if !duplicate {
+            cells.entry([x, y, z]).or_default().push(kept.len());
+            kept.push((obj, parent));
+        }
+    }
+    kept
 }
 
-impl Into<Scene> for SceneConfig {
-    fn into(self) -> Scene {
-        // 個別オブジェクト
-        let mut objects: Vec<Box<dyn Hittable>> =
-            self.objects.into_iter().map(Into::into).collect();
+// 名前を付けたオブジェクトの添字を集める。同じ名前があれば最初のものを使う
+fn collect_object_names(placed: &[(ObjectConfig, glam::Mat4)]) -> HashMap<String, usize> {
+    let mut names = HashMap::new();
+    for (index, (obj, _)) in placed.iter().enumerate() {
+        let Some(name) = &obj.name else {
+            continue;
+        };
+        if names.contains_key(name) {
+            println!("警告: オブジェクトの名前 {} が重複しています", name);
+            continue;
+        }
+        names.insert(name.clone(), index);
+    }
+    names
+}
+
+impl SceneConfig {
+    // 材質を省略したオブジェクトに default_material を入れる
+    pub fn fill_default_material(&mut self) {
+        let Some(default) = &self.default_material else {
+            return;
+        };
+        for obj in &mut self.objects {
+            obj.fill_default_material(default);
+        }
+        for group in &mut self.groups {
+            group.fill_default_material(default);
+        }
+        for generator in &mut self.object_generators {
+            match generator {
+                ObjectGeneratorConfig::ObjectGrid { template, .. } => {
+                    template.fill_default_material(default)
+                }
+            }
+        }
+    }
+
+    // 名前で指定された材質を、すべてライブラリの定義に置き換える
+    pub fn resolve_named_materials(
+        &mut self,
+        library: &MaterialLibraryConfig,
+    ) -> Result<(), ConfigError> {
+        if let Some(default) = &mut self.default_material {
+            library.resolve(default)?;
+        }
+        for obj in &mut self.objects {
+            obj.resolve_named_material(library)?;
+        }
+        for group in &mut self.groups {
+            group.resolve_named_materials(library)?;
+        }
+        for prescription in &mut self.prescriptions {
+            prescription.resolve_named_materials(library)?;
+        }
+        for generator in &mut self.object_generators {
+            match generator {
+                ObjectGeneratorConfig::ObjectGrid { template, .. } => {
+                    template.resolve_named_material(library)?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // 材質が決まらないオブジェクトがあるか（default_material を入れた後に確認する）
+    pub fn has_missing_material(&self) -> bool {
+        self.objects.iter().any(|obj| obj.material.is_none())
+            || self.groups.iter().any(GroupConfig::has_missing_material)
+            || self
+                .object_generators
+                .iter()
+                .any(|generator| match generator {
+                    ObjectGeneratorConfig::ObjectGrid { template, .. } => {
+                        template.material.is_none()
+                    }
+                })
+    }
+
+    // 光軸の向きと、axial を使えるのが光軸のあるシーンの個別オブジェクトだけであることを確かめる
+    pub fn validate_optical_axis(&self) -> Result<(), ConfigError> {
+        if let Some(axis) = &self.optical_axis {
+            axis.validate()?;
+        } else if self.objects.iter().any(|obj| obj.axial.is_some()) {
+            return Err(ConfigError::OpticalAxis {
+                reason:
+                    "axial を指定したオブジェクトがありますが、[scene.optical_axis] がありません"
+                        .to_string(),
+            });
+        }
+        let in_generator = self
+            .object_generators
+            .iter()
+            .any(|generator| match generator {
+                ObjectGeneratorConfig::ObjectGrid { template, .. } => template.axial.is_some(),
+            });
+        if in_generator || self.groups.iter().any(GroupConfig::has_axial_placement) {
+            return Err(ConfigError::OpticalAxis {
+                reason: "axial は [[scene.objects]] の直下のオブジェクトにだけ指定できます"
+                    .to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    // シーンに並ぶ順の有効なオブジェクトと、親（グループ）の変換行列の組
+    // 個別オブジェクト、グループ、ジェネレータの順で、dedup_objects なら重複をまとめる
+    // 2つ目はまとめたオブジェクトの数
+    fn placed_objects(&self) -> (Vec<(ObjectConfig, glam::Mat4)>, usize) {
+        // 個別オブジェクト（親の変換行列と組にして集める。axial なら光軸上の配置を親とする）
+        let mut placed: Vec<(ObjectConfig, glam::Mat4)> = self
+            .objects
+            .iter()
+            .filter(|obj| obj.enabled)
+            .map(|obj| {
+                let parent = match (&self.optical_axis, &obj.axial) {
+                    (Some(axis), Some(placement)) => axis.placement_matrix(placement),
+                    _ => glam::Mat4::IDENTITY,
+                };
+                (obj.clone(), parent)
+            })
+            .collect();
+
+        // グループ（子オブジェクトに共通の変換を合成する）
+        for group in &self.groups {
+            placed.extend(group.clone().into_placed_objects(glam::Mat4::IDENTITY));
+        }
 
         // ジェネレータから生成
-        for generator in self.object_generators {
+        for generator in &self.object_generators {
             match generator {
                 ObjectGeneratorConfig::ObjectGrid {
                     count_x,
@@ -36,26 +223,111 @@ impl Into<Scene> for SceneConfig {
                     step_z,
                     template,
                 } => {
-                    let start_pos = glam::Vec3::from(position_start);
-                    let x_step = glam::Vec3::from(step_x);
-                    let z_step = glam::Vec3::from(step_z);
-                    for i in 0..count_x {
-                        for j in 0..count_z {
+                    if !template.enabled {
+                        continue;
+                    }
+                    let start_pos = glam::Vec3::from(*position_start);
+                    let x_step = glam::Vec3::from(*step_x);
+                    let z_step = glam::Vec3::from(*step_z);
+                    for i in 0..*count_x {
+                        for j in 0..*count_z {
                             let pos = start_pos + (i as f32 * x_step) + (j as f32 * z_step);
                             let mut obj = template.clone();
                             obj.transform.position = pos.to_array();
-                            objects.push(obj.into());
+                            placed.push((obj, glam::Mat4::IDENTITY));
                         }
                     }
                 }
             }
         }
 
+        let total = placed.len();
+        if self.dedup_objects {
+            placed = dedup_placed_objects(placed);
+        }
+        let merged = total - placed.len();
+        (placed, merged)
+    }
+
+    // dedup_objects で1つにまとめた、重複したオブジェクトの数
+    pub fn merged_object_count(&self) -> usize {
+        self.placed_objects().1
+    }
+
+    // is_stop を付けたオブジェクトの、シーン内での添字（Scene::objects の添字と同じ）
+    pub fn stop_indices(&self) -> Vec<usize> {
+        self.placed_objects()
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, (obj, _))| obj.is_stop)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    // 有効なオブジェクトの形状を確かめる（処方表の面は対象外）
+    pub fn validate_shapes(&self) -> Result<(), ConfigError> {
+        for obj in self.objects.iter().filter(|obj| obj.enabled) {
+            obj.shape.validate()?;
+        }
+        for group in &self.groups {
+            group.validate_shapes()?;
+        }
+        for generator in &self.object_generators {
+            match generator {
+                ObjectGeneratorConfig::ObjectGrid { template, .. } => {
+                    if template.enabled {
+                        template.shape.validate()?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<SceneConfig> for Scene {
+    type Error = ConfigError;
+
+    fn try_from(mut config: SceneConfig) -> Result<Self, ConfigError> {
+        config.fill_default_material();
+
+        let (placed, _) = config.placed_objects();
+        let object_names = collect_object_names(&placed);
+        // frame を指定したレイのために、オブジェクトごとの座標系を残しておく
+        let frames: Vec<glam::Mat4> = placed
+            .iter()
+            .map(|(obj, parent)| obj.world_matrix(*parent))
+            .collect();
+        let mut objects: Vec<Box<dyn Hittable>> = placed
+            .into_iter()
+            .map(|(obj, parent)| obj.into_with_parent(parent))
+            .collect::<Result<_, _>>()?;
+
+        // 処方表から作るレンズ
+        for prescription in config.prescriptions {
+            objects.extend(prescription.into_hittables(glam::Mat4::IDENTITY)?);
+        }
+
         // 個別レイ
-        let mut rays: Vec<Ray> = self.rays.into_iter().map(Into::into).collect();
+        // 光源の番号は個別レイ1本ずつ、続けて ray_generators の生成器1つずつに振る
+        let mut rays: Vec<Ray> = config
+            .rays
+            .into_iter()
+            .enumerate()
+            .map(|(source, ray)| {
+                Ok(Ray {
+                    source,
+                    ..ray.into_ray_in(source, &frames, &object_names)?
+                })
+            })
+            .collect::<Result<_, ConfigError>>()?;
+        let first_generator_source = rays.len();
 
         // ray_generatorsから生成
-        for generator in self.ray_generators {
+        for (generator_index, generator) in config.ray_generators.into_iter().enumerate() {
+            let total_power = generator.total_power();
+            let start = rays.len();
             match generator {
                 RayGeneratorConfig::ParallelGrid {
                     origin_corner,
@@ -63,21 +335,27 @@ impl Into<Scene> for SceneConfig {
                     vec_v,
                     count_u,
                     count_v,
+                    density,
                     direction,
                     current_ior,
+                    jitter,
+                    seed,
+                    ..
                 } => {
+                    let (vec_u, vec_v) = (glam::Vec3::from(vec_u), glam::Vec3::from(vec_v));
+                    let (count_u, count_v) = grid_counts(vec_u, vec_v, count_u, count_v, density);
                     let corner = glam::Vec3::from(origin_corner);
-                    let u_step = glam::Vec3::from(vec_u) / (count_u as f32);
-                    let v_step = glam::Vec3::from(vec_v) / (count_v as f32);
+                    let u_step = vec_u / (count_u as f32);
+                    let v_step = vec_v / (count_v as f32);
                     let dir = glam::Vec3::from(direction).normalize();
+                    let mut rng = StdRng::seed_from_u64(seed);
                     for i in 0..count_u {
                         for j in 0..count_v {
-                            let origin = corner + (i as f32 * u_step) + (j as f32 * v_step);
-                            rays.push(Ray {
-                                origin,
-                                direction: dir,
-                                current_ior,
-                            });
+                            let origin = corner
+                                + (i as f32 * u_step)
+                                + (j as f32 * v_step)
+                                + jitter_offset(&mut rng, jitter, u_step, v_step);
+                            rays.push(Ray::new(origin, dir, current_ior));
                         }
                     }
                 }
@@ -89,26 +367,59 @@ impl Into<Scene> for SceneConfig {
                     count_u,
                     count_v,
                     current_ior,
+                    jitter,
+                    seed,
+                    ..
                 } => {
                     let ray_origin = glam::Vec3::from(origin);
                     let target_c = glam::Vec3::from(target_corner);
                     let target_u_step = glam::Vec3::from(target_u) / (count_u as f32);
                     let target_v_step = glam::Vec3::from(target_v) / (count_v as f32);
+                    let mut rng = StdRng::seed_from_u64(seed);
                     for i in 0..count_u {
                         for j in 0..count_v {
-                            let target_point =
-                                target_c + (i as f32 * target_u_step) + (j as f32 * target_v_step);
-                            rays.push(Ray {
-                                origin: ray_origin,
-                                direction: (target_point - ray_origin).normalize(),
+                            let target_point = target_c
+                                + (i as f32 * target_u_step)
+                                + (j as f32 * target_v_step)
+                                + jitter_offset(&mut rng, jitter, target_u_step, target_v_step);
+                            rays.push(Ray::new(
+                                ray_origin,
+                                (target_point - ray_origin).normalize(),
                                 current_ior,
-                            });
+                            ));
                         }
                     }
                 }
+                RayGeneratorConfig::SpectralSource {
+                    origin,
+                    target_corner,
+                    target_u,
+                    target_v,
+                    count_u,
+                    count_v,
+                    current_ior,
+                    spectrum,
+                    ..
+                } => {
+                    rays.extend(spectral_source_rays(
+                        glam::Vec3::from(origin),
+                        glam::Vec3::from(target_corner),
+                        (glam::Vec3::from(target_u), glam::Vec3::from(target_v)),
+                        (count_u, count_v),
+                        current_ior,
+                        &spectrum,
+                        &mut rand::thread_rng(),
+                    ));
+                }
             }
+            normalize_power(&mut rays[start..], total_power);
+            assign_source(&mut rays[start..], first_generator_source + generator_index);
         }
 
-        Scene { objects, rays }
+        Ok(Scene {
+            objects,
+            rays,
+            object_names,
+        })
     }
 }
@@ -0,0 +1,204 @@
+use std::path::Path;
+
+use toml_edit::{value, Array, ArrayOfTables, Decor, DocumentMut, InlineTable, Item, Table};
+
+use crate::{
+    error::ConfigError, model::object_generator_config::ObjectGeneratorConfig,
+    simulation_config::SimulationConfig,
+};
+
+// 書き出したファイルの先頭に付けるコメント
+const HEADER: [&str; 2] = [
+    "# dump_expanded で書き出したシーン",
+    "# object_generators は末尾のグループに展開済み（元のコメントは可能な範囲で残している）",
+];
+
+// ジェネレータから展開したオブジェクトを入れるグループの見出し
+const GENERATED_LABEL: &str = "ジェネレータから展開したオブジェクト";
+
+// 節の見出し（キーの場所と表示名）
+const SECTION_LABELS: [(&str, &str); 10] = [
+    ("simulation_settings", "シミュレーション設定"),
+    ("units", "単位"),
+    ("render", "表示"),
+    ("output", "出力"),
+    ("scene", "シーン"),
+    ("scene.rays", "レイ"),
+    ("scene.ray_generators", "レイのジェネレータ"),
+    ("scene.objects", "オブジェクト"),
+    ("scene.groups", "グループ"),
+    ("scene.prescriptions", "処方表"),
+];
+
+// 設定ファイルを読み込み、オブジェクトのジェネレータを展開したTOMLを返す
+// 展開したオブジェクトは恒等変換のグループとして最後に置くので、シーン内の順番は変わらない
+// 先頭のコメントと節の見出しを付け、それ以外のコメントや書式は toml_edit が保てる範囲で残す
+pub fn dump_expanded(toml_str: &str, base_dir: &Path) -> Result<String, ConfigError> {
+    // 展開の前に、設定として読み込めることを確かめる
+    let config = SimulationConfig::from_toml_str_in(toml_str, base_dir)?;
+    let mut document: DocumentMut = toml_str
+        .parse()
+        .expect("toml で読み込めたファイルは toml_edit でも読み込める");
+
+    if let Some(scene) = document.get_mut("scene").and_then(Item::as_table_mut) {
+        let templates = take_array_of_tables(scene, "object_generators");
+        let mut expanded = ArrayOfTables::new();
+        for (generator, template) in config.scene.object_generators.iter().zip(templates.iter()) {
+            expand_generator(generator, template, &mut expanded);
+        }
+        if !expanded.is_empty() {
+            let mut group = Table::new();
+            group.insert("transform", value(InlineTable::new()));
+            group.insert("objects", Item::ArrayOfTables(expanded));
+            add_label(group.decor_mut(), GENERATED_LABEL);
+
+            let mut groups = take_array_of_tables(scene, "groups");
+            groups.push(group);
+            scene.insert("groups", Item::ArrayOfTables(groups));
+        }
+    }
+
+    remove_header(&mut document);
+    label_sections(&mut document);
+    document
+        .decor_mut()
+        .set_prefix(format!("{}\n", HEADER.join("\n")));
+    Ok(document.to_string())
+}
+
+// ObjectGrid のテンプレートを並べ、位置だけを書き換えたオブジェクトを追加する
+fn expand_generator(generator: &ObjectGeneratorConfig, raw: &Table, expanded: &mut ArrayOfTables) {
+    match generator {
+        ObjectGeneratorConfig::ObjectGrid {
+            count_x,
+            count_z,
+            position_start,
+            step_x,
+            step_z,
+            template,
+        } => {
+            if !template.enabled {
+                return;
+            }
+            let Some(raw_template) = raw.get("template").and_then(Item::as_table_like) else {
+                return;
+            };
+            // SceneConfig と同じ計算で位置を求め、読み込み直したときに同じ値になるようにする
+            let start_pos = glam::Vec3::from(*position_start);
+            let x_step = glam::Vec3::from(*step_x);
+            let z_step = glam::Vec3::from(*step_z);
+            for i in 0..*count_x {
+                for j in 0..*count_z {
+                    let pos = start_pos + (i as f32 * x_step) + (j as f32 * z_step);
+                    let mut object = Table::new();
+                    for (key, item) in raw_template.iter() {
+                        // 入れ子の表はインライン表にして、1つの [[...]] にまとめる
+                        if let Ok(item_value) = item.clone().into_value() {
+                            object.insert(key, value(item_value));
+                        }
+                    }
+                    object["transform"]["position"] = value(f32_array(pos.to_array()));
+                    if let Some(transform) = object["transform"].as_inline_table_mut() {
+                        transform.fmt();
+                    }
+                    expanded.push(object);
+                }
+            }
+        }
+    }
+}
+
+// f32 として最短の表記で書く（f64 に広げた値をそのまま書くと 4.099999904632568 のようになる）
+fn f32_array(values: [f32; 3]) -> Array {
+    values
+        .iter()
+        .map(|v| {
+            v.to_string()
+                .parse::<f64>()
+                .expect("f32 の表記は f64 として読める")
+        })
+        .collect()
+}
+
+// 表の配列を取り出す（インラインの配列も [[...]] の形に直す。無ければ空）
+fn take_array_of_tables(table: &mut Table, key: &str) -> ArrayOfTables {
+    table
+        .remove(key)
+        .and_then(|item| item.into_array_of_tables().ok())
+        .unwrap_or_default()
+}
+
+// 主な表と、[scene] 内の表の配列の最初の要素に見出しを付ける
+fn label_sections(document: &mut DocumentMut) {
+    for (path, label) in SECTION_LABELS {
+        let mut keys = path.split('.');
+        let root = keys.next().and_then(|key| document.get_mut(key));
+        let item = keys.fold(root, |item, key| item.and_then(|item| item.get_mut(key)));
+        let table = match item {
+            Some(Item::Table(table)) if !table.is_implicit() => Some(table),
+            Some(Item::ArrayOfTables(array)) => array.get_mut(0),
+            _ => None,
+        };
+        if let Some(table) = table {
+            add_label(table.decor_mut(), label);
+        }
+    }
+}
+
+fn label_line(label: &str) -> String {
+    format!("# --- {} ---", label)
+}
+
+// 見出しを付ける。以前に書き出したファイルを読み直した場合は、同じ見出しを重ねない
+// 表の直前のコメント（最後の空行より後）は表の説明とみなし、見出しをその手前に入れる
+fn add_label(decor: &mut Decor, label: &str) {
+    let line = label_line(label);
+    strip_lines(decor, &[line.as_str()]);
+    let existing = decor
+        .prefix()
+        .and_then(|prefix| prefix.as_str())
+        .unwrap_or("");
+    let lines: Vec<&str> = existing.split_inclusive('\n').collect();
+    let prefix = match lines.iter().rposition(|line| line.trim().is_empty()) {
+        Some(blank) => format!(
+            "{}{}\n{}",
+            lines[..=blank].concat(),
+            line,
+            lines[blank + 1..].concat()
+        ),
+        None => format!("\n{}\n{}", line, existing),
+    };
+    decor.set_prefix(prefix);
+}
+
+// 以前に書き出したファイルの先頭コメントを取り除く（最初のキーや表の前に残っている）
+fn remove_header(document: &mut DocumentMut) {
+    let root = document.as_table_mut();
+    let keys: Vec<String> = root.iter().map(|(key, _)| key.to_string()).collect();
+    for key in keys {
+        if let Some(mut key_mut) = root.key_mut(&key) {
+            strip_lines(key_mut.leaf_decor_mut(), &HEADER);
+        }
+        match root.get_mut(&key) {
+            Some(Item::Table(table)) => strip_lines(table.decor_mut(), &HEADER),
+            Some(Item::ArrayOfTables(array)) => {
+                for table in array.iter_mut() {
+                    strip_lines(table.decor_mut(), &HEADER);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// 前置きのコメントから指定した行を除く（前置きが無ければ既定の書式のまま）
+fn strip_lines(decor: &mut Decor, lines: &[&str]) {
+    let Some(prefix) = decor.prefix().and_then(|prefix| prefix.as_str()) else {
+        return;
+    };
+    let stripped: String = prefix
+        .split_inclusive('\n')
+        .filter(|line| !lines.contains(&line.trim_end()))
+        .collect();
+    decor.set_prefix(stripped);
+}
@@ -1,22 +1,49 @@
 use glam::Vec3;
 use raytracing_core::{
-    AxisAlignedBox, CSGObject, CsgOperation, Hittable, InfiniteCone, InfiniteCylinder, Lens,
-    Material, Plane, Sphere, Wedge,
+    Annulus, AsphericSurface, AxisAlignedBox, CSGObject, CsgOperation, Hittable, Hyperboloid,
+    HyperboloidSheets, InfiniteCone, InfiniteCylinder, KnifeEdge, KnifeEdgeSide, Lens, Material,
+    Plane, Rectangle, Sphere, SphericalCap, TriangleMesh, Wedge,
 };
 use serde::Deserialize;
 
-#[derive(Deserialize, Clone)]
-#[serde(tag = "type")]
+use crate::error::ConfigError;
+
+#[derive(Deserialize, Clone, PartialEq)] // 重複したオブジェクトの検出で比較する
+#[serde(tag = "type", deny_unknown_fields)]
 pub enum ShapeConfig {
     Sphere {
         radius: f32,
     },
+    // 原点を中心とする球面のうち、axis_dir から見て min_cos_angle 以上の部分（ドーム）
+    SphericalCap {
+        radius: f32,
+        axis_dir: [f32; 3],
+        min_cos_angle: f32,
+    },
     Box {
         size: [f32; 3],
     },
     Plane {
         normal: [f32; 3],
     },
+    // 原点を中心とする XY 平面上の、幅 width（X 方向）・高さ height（Y 方向）の長方形（法線は +Z）
+    // 縁より外は素通りするので、鏡の材質と組み合わせて折り返し鏡に使う（rotation_y_deg などで傾ける）
+    FoldMirror {
+        width: f32,
+        height: f32,
+    },
+    // 原点を通るエッジで区切られた半平面。blocking_side 側だけが光を遮る
+    KnifeEdge {
+        normal: [f32; 3],
+        edge_dir: [f32; 3],
+        blocking_side: KnifeEdgeSideConfig,
+    },
+    // 原点を中心とし normal に垂直な平面上の、内径 inner_radius から外径 outer_radius までの輪
+    Annulus {
+        normal: [f32; 3],
+        inner_radius: f32,
+        outer_radius: f32,
+    },
     Cylinder {
         height: f32,
         radius: f32,
@@ -29,12 +56,32 @@ pub enum ShapeConfig {
         size: [f32; 3],
         angle_deg: f32,
     },
+    // Y軸方向の無限双曲面。二葉では原点が+Y側の葉の頂点、一葉ではくびれの中心
+    Hyperboloid {
+        a: f32,
+        c: f32,
+        #[serde(default)]
+        sheets: HyperboloidSheetsConfig, // 省略時は二葉
+    },
     Lens {
         thickness: f32,
         diameter: f32,
         r1: f32,
         r2: f32,
     },
+    // 原点を頂点とし、+Z方向（光軸）の側を内部とする非球面。coeffs は r⁴ から始まる偶数次の係数
+    Asphere {
+        curvature: f32,
+        #[serde(default)]
+        conic: f32,
+        #[serde(default)]
+        coeffs: Vec<f32>,
+    },
+    // 三角形メッシュ。CSGに使う場合は閉じたメッシュで、外側から見て反時計回りに頂点を並べる
+    Mesh {
+        vertices: Vec<[f32; 3]>,
+        triangles: Vec<[usize; 3]>,
+    },
     // CSG（Constructive Solid Geometry）オブジェクトの定義
     Union {
         a: Box<ShapeConfig>,
@@ -50,7 +97,199 @@ pub enum ShapeConfig {
     },
 }
 
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+pub enum KnifeEdgeSideConfig {
+    Left,
+    Right,
+}
+
+impl From<KnifeEdgeSideConfig> for KnifeEdgeSide {
+    fn from(config: KnifeEdgeSideConfig) -> Self {
+        match config {
+            KnifeEdgeSideConfig::Left => KnifeEdgeSide::Left,
+            KnifeEdgeSideConfig::Right => KnifeEdgeSide::Right,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum HyperboloidSheetsConfig {
+    One,
+    #[default]
+    Two,
+}
+
+impl From<HyperboloidSheetsConfig> for HyperboloidSheets {
+    fn from(config: HyperboloidSheetsConfig) -> Self {
+        match config {
+            HyperboloidSheetsConfig::One => HyperboloidSheets::One,
+            HyperboloidSheetsConfig::Two => HyperboloidSheets::Two,
+        }
+    }
+}
+
 impl ShapeConfig {
+    // 設定ファイルの type 名
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ShapeConfig::Sphere { .. } => "Sphere",
+            ShapeConfig::SphericalCap { .. } => "SphericalCap",
+            ShapeConfig::Box { .. } => "Box",
+            ShapeConfig::Plane { .. } => "Plane",
+            ShapeConfig::FoldMirror { .. } => "FoldMirror",
+            ShapeConfig::KnifeEdge { .. } => "KnifeEdge",
+            ShapeConfig::Annulus { .. } => "Annulus",
+            ShapeConfig::Cylinder { .. } => "Cylinder",
+            ShapeConfig::Cone { .. } => "Cone",
+            ShapeConfig::Wedge { .. } => "Wedge",
+            ShapeConfig::Hyperboloid { .. } => "Hyperboloid",
+            ShapeConfig::Lens { .. } => "Lens",
+            ShapeConfig::Asphere { .. } => "Asphere",
+            ShapeConfig::Mesh { .. } => "Mesh",
+            ShapeConfig::Union { .. } => "Union",
+            ShapeConfig::Intersection { .. } => "Intersection",
+            ShapeConfig::Difference { .. } => "Difference",
+        }
+    }
+
+    // 寸法が立体（面）にならない形状をエラーにする。CSGは両方の形状を確かめる
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let shape = self.type_name();
+        match self {
+            ShapeConfig::Sphere { radius } => positive(shape, "radius", *radius),
+            ShapeConfig::SphericalCap {
+                radius,
+                axis_dir,
+                min_cos_angle,
+            } => {
+                positive(shape, "radius", *radius)?;
+                nonzero_vector(shape, "axis_dir", *axis_dir)?;
+                if !(-1.0..=1.0).contains(min_cos_angle) {
+                    return Err(degenerate(
+                        shape,
+                        format!(
+                            "min_cos_angle ({}) は -1 以上 1 以下にしてください",
+                            min_cos_angle
+                        ),
+                    ));
+                }
+                Ok(())
+            }
+            ShapeConfig::Box { size } => {
+                for value in size {
+                    positive(shape, "size", *value)?;
+                }
+                Ok(())
+            }
+            ShapeConfig::Plane { normal } => nonzero_vector(shape, "normal", *normal),
+            ShapeConfig::FoldMirror { width, height } => {
+                positive(shape, "width", *width)?;
+                positive(shape, "height", *height)
+            }
+            ShapeConfig::KnifeEdge {
+                normal, edge_dir, ..
+            } => {
+                nonzero_vector(shape, "normal", *normal)?;
+                nonzero_vector(shape, "edge_dir", *edge_dir)
+            }
+            ShapeConfig::Annulus {
+                normal,
+                inner_radius,
+                outer_radius,
+            } => {
+                nonzero_vector(shape, "normal", *normal)?;
+                positive(shape, "outer_radius", *outer_radius)?;
+                if !(0.0..*outer_radius).contains(inner_radius) {
+                    return Err(degenerate(
+                        shape,
+                        format!(
+                            "inner_radius ({}) は 0 以上 outer_radius ({}) 未満にしてください",
+                            inner_radius, outer_radius
+                        ),
+                    ));
+                }
+                Ok(())
+            }
+            ShapeConfig::Cylinder { height, radius } => {
+                positive(shape, "height", *height)?;
+                positive(shape, "radius", *radius)
+            }
+            ShapeConfig::Cone { angle_deg, height } => {
+                positive(shape, "height", *height)?;
+                if !(*angle_deg > 0.0 && *angle_deg < 90.0) {
+                    return Err(degenerate(
+                        shape,
+                        format!(
+                            "angle_deg ({}) は 0 より大きく 90 未満にしてください",
+                            angle_deg
+                        ),
+                    ));
+                }
+                Ok(())
+            }
+            ShapeConfig::Wedge { size, .. } => {
+                for value in size {
+                    positive(shape, "size", *value)?;
+                }
+                Ok(())
+            }
+            ShapeConfig::Hyperboloid { a, c, .. } => {
+                positive(shape, "a", *a)?;
+                positive(shape, "c", *c)
+            }
+            ShapeConfig::Lens {
+                thickness,
+                diameter,
+                r1,
+                r2,
+            } => {
+                positive(shape, "thickness", *thickness)?;
+                positive(shape, "diameter", *diameter)?;
+                // 曲率半径は無限大（平面）でもよいが、有限なら開口の半径以上が必要
+                for (name, radius) in [("r1", *r1), ("r2", *r2)] {
+                    if radius.is_finite() && radius.abs() < diameter / 2.0 {
+                        return Err(degenerate(
+                            shape,
+                            format!(
+                                "{} ({}) の絶対値が開口の半径 ({}) より小さいため、球面が開口を覆いません",
+                                name,
+                                radius,
+                                diameter / 2.0
+                            ),
+                        ));
+                    }
+                }
+                Ok(())
+            }
+            ShapeConfig::Mesh {
+                vertices,
+                triangles,
+            } => {
+                for (i, triangle) in triangles.iter().enumerate() {
+                    if let Some(index) = triangle.iter().find(|&&index| index >= vertices.len()) {
+                        return Err(degenerate(
+                            shape,
+                            format!(
+                                "三角形 {} の頂点の添字 {} が頂点の数 {} を超えています",
+                                i,
+                                index,
+                                vertices.len()
+                            ),
+                        ));
+                    }
+                }
+                Ok(())
+            }
+            ShapeConfig::Asphere { .. } => Ok(()),
+            ShapeConfig::Union { a, b }
+            | ShapeConfig::Intersection { a, b }
+            | ShapeConfig::Difference { a, b } => {
+                a.validate()?;
+                b.validate()
+            }
+        }
+    }
+
     pub fn into_with(self, material: Material) -> Box<dyn Hittable> {
         match self {
             ShapeConfig::Sphere { radius } => Box::new(Sphere {
@@ -58,6 +297,17 @@ impl ShapeConfig {
                 radius,
                 material,
             }),
+            ShapeConfig::SphericalCap {
+                radius,
+                axis_dir,
+                min_cos_angle,
+            } => Box::new(SphericalCap {
+                center: Vec3::ZERO,
+                radius,
+                axis_dir: Vec3::from_array(axis_dir).normalize(),
+                min_cos_angle,
+                material,
+            }),
             ShapeConfig::Box { size } => {
                 let s = Vec3::from_array(size) / 2.0;
                 Box::new(AxisAlignedBox {
@@ -71,18 +321,48 @@ impl ShapeConfig {
                 normal: Vec3::from_array(normal),
                 material,
             }),
+            ShapeConfig::FoldMirror { width, height } => Box::new(Rectangle {
+                center: Vec3::ZERO,
+                normal: Vec3::Z,
+                width_dir: Vec3::X,
+                half_width: width / 2.0,
+                half_height: height / 2.0,
+                material,
+            }),
+            ShapeConfig::KnifeEdge {
+                normal,
+                edge_dir,
+                blocking_side,
+            } => Box::new(KnifeEdge {
+                plane_point: Vec3::ZERO,
+                normal: Vec3::from_array(normal),
+                edge_dir: Vec3::from_array(edge_dir),
+                blocking_side: blocking_side.into(),
+                material,
+            }),
+            ShapeConfig::Annulus {
+                normal,
+                inner_radius,
+                outer_radius,
+            } => Box::new(Annulus {
+                center: Vec3::ZERO,
+                normal: Vec3::from_array(normal),
+                inner_radius,
+                outer_radius,
+                material,
+            }),
             ShapeConfig::Cylinder { height, radius } => {
                 let half_height = height / 2.0;
                 let body = Box::new(InfiniteCylinder {
                     axis_point: Vec3::ZERO,
                     axis_dir: Vec3::Y,
                     radius,
-                    material,
+                    material: material.clone(),
                 });
                 let cap_top = Box::new(Plane {
                     point: Vec3::new(0.0, half_height, 0.0),
                     normal: Vec3::NEG_Y,
-                    material,
+                    material: material.clone(),
                 });
                 let cap_bottom = Box::new(Plane {
                     point: Vec3::new(0.0, -half_height, 0.0),
@@ -105,7 +385,7 @@ impl ShapeConfig {
                     Vec3::ZERO,
                     Vec3::Y,
                     angle_deg.to_radians(),
-                    material,
+                    material.clone(),
                 ));
                 let cap = Box::new(Plane {
                     point: Vec3::new(0.0, height, 0.0),
@@ -123,12 +403,40 @@ impl ShapeConfig {
                 angle_deg.to_radians(),
                 material,
             )),
+            ShapeConfig::Hyperboloid { a, c, sheets } => Box::new(Hyperboloid::new(
+                Vec3::ZERO,
+                Vec3::Y,
+                a,
+                c,
+                sheets.into(),
+                material,
+            )),
             ShapeConfig::Lens {
                 thickness,
                 diameter,
                 r1,
                 r2,
             } => Box::new(Lens::new(thickness, diameter, r1, r2, material)),
+            ShapeConfig::Asphere {
+                curvature,
+                conic,
+                coeffs,
+            } => Box::new(AsphericSurface {
+                vertex: Vec3::ZERO,
+                axis: Vec3::Z,
+                curvature,
+                conic,
+                coeffs,
+                material,
+            }),
+            ShapeConfig::Mesh {
+                vertices,
+                triangles,
+            } => Box::new(TriangleMesh::new(
+                vertices.into_iter().map(Vec3::from_array).collect(),
+                triangles,
+                material,
+            )),
             ShapeConfig::Union { a, b } => Box::new(CSGObject {
                 left: a.into_with(material.clone()),
                 right: b.into_with(material),
@@ -147,3 +455,32 @@ impl ShapeConfig {
         }
     }
 }
+
+fn degenerate(shape: &'static str, reason: String) -> ConfigError {
+    ConfigError::DegenerateShape { shape, reason }
+}
+
+// 正の有限な値か（NaN も弾く）
+fn positive(shape: &'static str, name: &str, value: f32) -> Result<(), ConfigError> {
+    if value > 0.0 && value.is_finite() {
+        Ok(())
+    } else {
+        Err(degenerate(
+            shape,
+            format!("{} ({}) は正の値にしてください", name, value),
+        ))
+    }
+}
+
+// 向きとして使えるベクトルか（0ベクトルは正規化できない）
+fn nonzero_vector(shape: &'static str, name: &str, value: [f32; 3]) -> Result<(), ConfigError> {
+    let vector = Vec3::from_array(value);
+    if vector.is_finite() && vector.length_squared() > 0.0 {
+        Ok(())
+    } else {
+        Err(degenerate(
+            shape,
+            format!("{} ({:?}) は 0 でないベクトルにしてください", name, value),
+        ))
+    }
+}
@@ -1,20 +1,136 @@
-use std::{error::Error, path::Path};
+use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 
-use crate::{scene_config::SceneConfig, simulation_settings_config::SimulationSettingsConfig};
+use crate::{
+    error::ConfigError, material_library_config::MaterialLibraryConfig,
+    output_config::OutputConfig, render_config::RenderConfig, scene_config::SceneConfig,
+    simulation_settings_config::SimulationSettingsConfig, units_config::UnitsConfig,
+};
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SimulationConfig {
     pub simulation_settings: SimulationSettingsConfig,
     pub scene: SceneConfig,
+    #[serde(default)]
+    pub units: Option<UnitsConfig>, // 省略時は単位を記録しない
+    #[serde(default)]
+    pub render: RenderConfig, // 省略時は補助表示なし
+    #[serde(default)]
+    pub output: OutputConfig, // 省略時はワールド座標のまま書き出す
+    #[serde(default)]
+    pub material_library: Option<PathBuf>, // 材質ライブラリのファイル（相対パスは設定ファイルの場所から）
+}
+
+// 複数のシーンをまとめたファイル。[[runs]] 以外のキーは置けない
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RunsConfig {
+    runs: Vec<toml::Table>,
 }
 
 impl SimulationConfig {
-    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<SimulationConfig, Box<dyn Error>> {
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<SimulationConfig, ConfigError> {
+        let path = path.as_ref();
         let toml_str = std::fs::read_to_string(path)?;
-        let simulation_config = toml::from_str(&toml_str)?;
+        Self::from_toml_str_in(&toml_str, path.parent().unwrap_or(Path::new("")))
+    }
+
+    // 1つのファイルに [[runs]] で並べた複数のシーンを読み込む
+    // [[runs]] が無ければ、ファイル全体を1つのシーンとして読み込む
+    pub fn load_all<P: AsRef<Path>>(path: P) -> Result<Vec<SimulationConfig>, ConfigError> {
+        let path = path.as_ref();
+        let toml_str = std::fs::read_to_string(path)?;
+        Self::from_toml_str_all_in(&toml_str, path.parent().unwrap_or(Path::new("")))
+    }
+
+    // [[runs]] の各要素は、それだけで完結した設定（[simulation_settings] と [scene] を含む）
+    pub fn from_toml_str_all_in(
+        toml_str: &str,
+        base_dir: &Path,
+    ) -> Result<Vec<SimulationConfig>, ConfigError> {
+        let table: toml::Table =
+            toml::from_str(toml_str).map_err(|e| ConfigError::from_toml(e, toml_str))?;
+        if !table.contains_key("runs") {
+            return Ok(vec![Self::from_toml_str_in(toml_str, base_dir)?]);
+        }
+        let RunsConfig { runs } =
+            toml::from_str(toml_str).map_err(|e| ConfigError::from_toml(e, toml_str))?;
+        runs.iter()
+            .enumerate()
+            .map(|(index, run)| {
+                Self::from_toml_table_in(run, base_dir).map_err(|e| ConfigError::Run {
+                    index,
+                    source: Box::new(e),
+                })
+            })
+            .collect()
+    }
+
+    // 材質ライブラリの相対パスはカレントディレクトリから探す
+    pub fn from_toml_str(toml_str: &str) -> Result<SimulationConfig, ConfigError> {
+        Self::from_toml_str_in(toml_str, Path::new(""))
+    }
+
+    // 書き換えた設定（パラメータ掃引など）を読み込み直す
+    pub fn from_toml_table_in(
+        table: &toml::Table,
+        base_dir: &Path,
+    ) -> Result<SimulationConfig, ConfigError> {
+        let toml_str = toml::to_string(table).expect("TOMLの表は常に文字列にできる");
+        Self::from_toml_str_in(&toml_str, base_dir)
+    }
 
-        Ok(simulation_config)
+    // 材質ライブラリの相対パスは base_dir から探す
+    pub fn from_toml_str_in(
+        toml_str: &str,
+        base_dir: &Path,
+    ) -> Result<SimulationConfig, ConfigError> {
+        let mut config: SimulationConfig =
+            toml::from_str(toml_str).map_err(|e| ConfigError::from_toml(e, toml_str))?;
+        // ライブラリが無ければ、名前で指定された材質はすべて未知の材質になる
+        let library = match &config.material_library {
+            Some(library_path) => {
+                MaterialLibraryConfig::load_from_path(base_dir.join(library_path))?
+            }
+            None => MaterialLibraryConfig::default(),
+        };
+        config.scene.resolve_named_materials(&library)?;
+        config.scene.fill_default_material();
+        if config.scene.has_missing_material() {
+            return Err(ConfigError::MissingMaterial);
+        }
+        if let Some(index) = config
+            .scene
+            .rays
+            .iter()
+            .position(|ray| !ray.has_valid_direction())
+        {
+            return Err(ConfigError::RayDirection { index });
+        }
+        if let Some(index) = config
+            .scene
+            .ray_generators
+            .iter()
+            .position(|generator| !generator.has_valid_count())
+        {
+            return Err(ConfigError::RayCount { index });
+        }
+        if let Some(index) = config
+            .scene
+            .ray_generators
+            .iter()
+            .position(|generator| generator.total_power().is_some_and(|power| power <= 0.0))
+        {
+            return Err(ConfigError::TotalPower { index });
+        }
+        config.scene.validate_shapes()?;
+        config.scene.validate_optical_axis()?;
+        let stops = config.scene.stop_indices();
+        if stops.len() > 1 {
+            return Err(ConfigError::MultipleStops { count: stops.len() });
+        }
+        Ok(config)
     }
 }
@@ -1,17 +1,103 @@
-use raytracing_core::SimulationSettingsConfig as CoreSimulationSettingsConfig;
+use raytracing_core::{
+    AdaptiveBounces, FresnelMode, RehitMode,
+    SimulationSettingsConfig as CoreSimulationSettingsConfig, DEFAULT_MAX_INTERSECTION_HITS,
+};
 use serde::Deserialize;
 
+#[derive(Deserialize, Clone, Copy, Default)]
+pub enum FresnelModeConfig {
+    Stochastic,
+    #[default]
+    AlwaysRefract,
+    Deterministic,
+    Split,
+}
+
+impl From<FresnelModeConfig> for FresnelMode {
+    fn from(config: FresnelModeConfig) -> Self {
+        match config {
+            FresnelModeConfig::Stochastic => FresnelMode::Stochastic,
+            FresnelModeConfig::AlwaysRefract => FresnelMode::AlwaysRefract,
+            FresnelModeConfig::Deterministic => FresnelMode::Deterministic,
+            FresnelModeConfig::Split => FresnelMode::Split,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Default)]
+pub enum RehitModeConfig {
+    #[default]
+    Nudge,
+    Terminate,
+}
+
+impl From<RehitModeConfig> for RehitMode {
+    fn from(config: RehitModeConfig) -> Self {
+        match config {
+            RehitModeConfig::Nudge => RehitMode::Nudge,
+            RehitModeConfig::Terminate => RehitMode::Terminate,
+        }
+    }
+}
+
+// [simulation_settings.adaptive]。max_bounces の代わりに、強度が残る限り hard_cap 回まで追跡する
+#[derive(Deserialize, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct AdaptiveBouncesConfig {
+    pub min_intensity: f32,
+    pub hard_cap: u32,
+}
+
+impl From<AdaptiveBouncesConfig> for AdaptiveBounces {
+    fn from(config: AdaptiveBouncesConfig) -> Self {
+        AdaptiveBounces {
+            min_intensity: config.min_intensity,
+            hard_cap: config.hard_cap,
+        }
+    }
+}
+
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SimulationSettingsConfig {
     pub infinity_distance: f32,
     pub max_bounces: u32,
+    #[serde(default)]
+    pub max_reflections: Option<u32>, // 省略時は max_bounces（adaptive があれば hard_cap）と同じ
+    #[serde(default)]
+    pub max_refractions: Option<u32>, // 省略時は max_bounces（adaptive があれば hard_cap）と同じ
+    #[serde(default)]
+    pub fresnel_mode: FresnelModeConfig, // 省略時は従来通り常に屈折
+    #[serde(default)]
+    pub rehit_mode: RehitModeConfig, // 省略時は始点をずらして続行
+    #[serde(default)]
+    pub max_intersection_hits: Option<usize>, // 1回の交差判定で返すヒット数の上限（省略時は1024）
+    #[serde(default)]
+    pub geometric_epsilon: Option<f32>, // 長さの許容誤差（省略時は1e-4）。シーンの大きさに合わせて変える
+    #[serde(default)]
+    pub adaptive: Option<AdaptiveBouncesConfig>, // 省略時は max_bounces で打ち切る
+    #[serde(default)]
+    pub gap_decay_length: Option<f32>, // 省略時は隙間があれば常に全反射する
 }
 
-impl Into<CoreSimulationSettingsConfig> for SimulationSettingsConfig {
-    fn into(self) -> CoreSimulationSettingsConfig {
+impl From<SimulationSettingsConfig> for CoreSimulationSettingsConfig {
+    fn from(config: SimulationSettingsConfig) -> Self {
+        // 反射・屈折の回数の上限は、省略時は進める回数の上限に揃える
+        let bounce_limit = config
+            .adaptive
+            .map_or(config.max_bounces, |adaptive| adaptive.hard_cap);
         CoreSimulationSettingsConfig {
-            infinity_distance: self.infinity_distance,
-            max_bounces: self.max_bounces,
+            infinity_distance: config.infinity_distance,
+            max_bounces: config.max_bounces,
+            max_reflections: config.max_reflections.unwrap_or(bounce_limit),
+            max_refractions: config.max_refractions.unwrap_or(bounce_limit),
+            fresnel_mode: config.fresnel_mode.into(),
+            rehit_mode: config.rehit_mode.into(),
+            adaptive: config.adaptive.map(Into::into),
+            gap_decay_length: config.gap_decay_length,
+            max_intersection_hits: config
+                .max_intersection_hits
+                .unwrap_or(DEFAULT_MAX_INTERSECTION_HITS),
         }
     }
 }
@@ -0,0 +1,117 @@
+use raytracing_core::D_LINE_NM;
+use serde::Deserialize;
+
+// 黒体放射の波長範囲を省略したときの値（可視域）[nm]
+const DEFAULT_BLACKBODY_RANGE_NM: [f32; 2] = [380.0, 780.0];
+// 黒体放射を表にするときの波長の刻み[nm]
+const BLACKBODY_STEP_NM: f32 = 1.0;
+// 放射の第2定数 hc/k [nm·K]
+const PLANCK_C2_NM_K: f64 = 1.438_777e7;
+
+// 光源の分光分布（波長ごとの相対的な強さ）
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "type", deny_unknown_fields)]
+pub enum SpectrumConfig {
+    // 温度 temp_k [K] の黒体放射
+    Blackbody {
+        temp_k: f32,
+        #[serde(default)]
+        range_nm: Option<[f32; 2]>, // 省略時は380〜780nm
+    },
+    // [波長[nm], 重み] の表。間は線形補間する
+    Table {
+        samples: Vec<(f32, f32)>,
+    },
+}
+
+impl SpectrumConfig {
+    // 波長の昇順に並んだ (波長[nm], 重み) の表にする
+    fn samples(&self) -> Vec<(f32, f32)> {
+        match self {
+            SpectrumConfig::Blackbody { temp_k, range_nm } => {
+                let [start, end] = range_nm.unwrap_or(DEFAULT_BLACKBODY_RANGE_NM);
+                let steps = ((end - start) / BLACKBODY_STEP_NM).ceil().max(1.0) as usize;
+                (0..=steps)
+                    .map(|i| {
+                        let wavelength = start + (end - start) * i as f32 / steps as f32;
+                        (wavelength, planck(wavelength, *temp_k))
+                    })
+                    .collect()
+            }
+            SpectrumConfig::Table { samples } => {
+                let mut samples = samples.clone();
+                samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+                samples
+            }
+        }
+    }
+
+    pub fn sampler(&self) -> WavelengthSampler {
+        WavelengthSampler::new(self.samples())
+    }
+}
+
+// 黒体放射の分光放射輝度（定数倍を除く）
+fn planck(wavelength_nm: f32, temp_k: f32) -> f32 {
+    let wavelength = wavelength_nm as f64;
+    let x = PLANCK_C2_NM_K / (wavelength * temp_k as f64);
+    // 最大値が1程度になるよう λ^-5 を 1000nm で割ってから計算する
+    ((wavelength / 1000.0).powi(-5) / x.exp_m1()) as f32
+}
+
+// 分光分布に比例する確率で波長を選ぶ（区間ごとの累積分布を逆算する）
+pub struct WavelengthSampler {
+    samples: Vec<(f32, f32)>,
+    cumulative: Vec<f32>, // 各区間の終わりまでの面積
+}
+
+impl WavelengthSampler {
+    fn new(samples: Vec<(f32, f32)>) -> Self {
+        let mut total = 0.0;
+        let cumulative = samples
+            .windows(2)
+            .map(|pair| {
+                let (x0, w0) = pair[0];
+                let (x1, w1) = pair[1];
+                total += (x1 - x0) * (w0.max(0.0) + w1.max(0.0)) / 2.0;
+                total
+            })
+            .collect();
+        Self {
+            samples,
+            cumulative,
+        }
+    }
+
+    // u は 0.0〜1.0 の一様乱数。分布の面積が無ければ表の最初の波長（表も空ならd線）を返す
+    pub fn sample(&self, u: f32) -> f32 {
+        let total = self.cumulative.last().copied().unwrap_or(0.0);
+        if total <= 0.0 {
+            return self.samples.first().map_or(D_LINE_NM, |sample| sample.0);
+        }
+        let target = u.clamp(0.0, 1.0) * total;
+        let segment = self
+            .cumulative
+            .partition_point(|&area| area < target)
+            .min(self.cumulative.len() - 1);
+        let before = if segment == 0 {
+            0.0
+        } else {
+            self.cumulative[segment - 1]
+        };
+        let (x0, w0) = self.samples[segment];
+        let (x1, w1) = self.samples[segment + 1];
+        let (w0, w1) = (w0.max(0.0), w1.max(0.0));
+        let width = x1 - x0;
+        let area = target - before;
+        // 区間内の重みは w0 + k t で増減するので、面積 w0 t + k t²/2 = area を t について解く
+        let k = (w1 - w0) / width;
+        let t = if k.abs() * width <= 1e-6 * (w0 + w1) {
+            // ほぼ平坦な区間
+            area / w0.max(f32::MIN_POSITIVE)
+        } else {
+            (-w0 + (w0 * w0 + 2.0 * k * area).max(0.0).sqrt()) / k
+        };
+        x0 + t.clamp(0.0, width)
+    }
+}
@@ -1,7 +1,47 @@
-use serde::Deserialize;
+use glam::{Mat4, Vec3};
+use serde::{Deserialize, Deserializer};
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
 pub struct TransformConfig {
+    #[serde(default)]
     pub position: [f32; 3],
+    #[serde(default)]
     pub rotation_y_deg: f32,
+    // CADなどから持ってきた4x4行列（行優先）。指定時は rotation_y_deg の代わりに使い、
+    // position はその後の平行移動として合成する（省略時は行列がそのまま使われる）
+    #[serde(default, deserialize_with = "deserialize_matrix")]
+    pub matrix: Option<Mat4>,
+}
+
+impl TransformConfig {
+    // ローカル空間 -> 親空間への変換行列
+    pub fn to_matrix(&self) -> Mat4 {
+        let translation = Mat4::from_translation(Vec3::from_array(self.position));
+        match self.matrix {
+            Some(matrix) => translation * matrix,
+            None => {
+                let rotation = Mat4::from_rotation_y(self.rotation_y_deg.to_radians());
+                translation * rotation
+            }
+        }
+    }
+}
+
+// 行優先の [[f32; 4]; 4] を読み込み、Transform::new で逆行列を取れるか確かめる
+fn deserialize_matrix<'de, D>(deserializer: D) -> Result<Option<Mat4>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let rows = <[[f32; 4]; 4]>::deserialize(deserializer)?;
+    // glamは列優先なので転置する
+    let matrix = Mat4::from_cols_array_2d(&rows).transpose();
+    let determinant = matrix.determinant();
+    if !determinant.is_finite() || determinant.abs() < 1e-6 {
+        return Err(serde::de::Error::custom(format!(
+            "matrix が逆行列を持ちません (行列式 = {})",
+            determinant
+        )));
+    }
+    Ok(Some(matrix))
 }
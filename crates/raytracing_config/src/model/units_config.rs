@@ -0,0 +1,38 @@
+use raytracing_core::LengthUnit;
+use serde::Deserialize;
+
+// [units] セクション。シーン全体の長さの単位を記録する
+#[derive(Deserialize, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct UnitsConfig {
+    pub length: LengthUnitConfig,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum LengthUnitConfig {
+    #[serde(rename = "nm")]
+    Nanometer,
+    #[serde(rename = "um")]
+    Micrometer,
+    #[serde(rename = "mm")]
+    Millimeter,
+    #[serde(rename = "cm")]
+    Centimeter,
+    #[serde(rename = "m")]
+    Meter,
+    #[serde(rename = "in")]
+    Inch,
+}
+
+impl From<LengthUnitConfig> for LengthUnit {
+    fn from(config: LengthUnitConfig) -> Self {
+        match config {
+            LengthUnitConfig::Nanometer => LengthUnit::Nanometer,
+            LengthUnitConfig::Micrometer => LengthUnit::Micrometer,
+            LengthUnitConfig::Millimeter => LengthUnit::Millimeter,
+            LengthUnitConfig::Centimeter => LengthUnit::Centimeter,
+            LengthUnitConfig::Meter => LengthUnit::Meter,
+            LengthUnitConfig::Inch => LengthUnit::Inch,
+        }
+    }
+}
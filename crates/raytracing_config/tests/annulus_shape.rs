@@ -0,0 +1,43 @@
+// ShapeConfig::Annulus の読み込みと寸法の確認
+use glam::Vec3;
+use raytracing_config::{error::ConfigError, object_config::ObjectConfig};
+use raytracing_core::{Hittable, Ray};
+
+fn object(inner_radius: f32, outer_radius: f32) -> ObjectConfig {
+    toml::from_str(&format!(
+        r#"
+        shape = {{ type = "Annulus", normal = [0.0, 0.0, -1.0], inner_radius = {inner_radius}, outer_radius = {outer_radius} }}
+        material = {{ type = "Absorber" }}
+        transform = {{ position = [0.0, 0.0, 5.0] }}
+        "#
+    ))
+    .unwrap()
+}
+
+#[test]
+fn annulus_is_placed_by_transform() {
+    let annulus = Box::<dyn Hittable>::try_from(object(1.0, 2.0)).unwrap();
+    let hit_at = |x: f32| {
+        let ray = Ray::new(Vec3::new(x, 0.0, 0.0), Vec3::Z, 1.0);
+        annulus
+            .intersect_all(&ray, 1e-4, f32::INFINITY)
+            .map(|hits| hits[0].point)
+    };
+    assert!(hit_at(0.0).is_none());
+    assert!(hit_at(2.5).is_none());
+    let point = hit_at(1.5).unwrap();
+    assert!((point - Vec3::new(1.5, 0.0, 5.0)).length() < 1e-4);
+}
+
+#[test]
+fn inner_radius_not_below_outer_is_rejected() {
+    let result: Result<Box<dyn Hittable>, ConfigError> = object(2.0, 2.0).try_into();
+    match result {
+        Err(ConfigError::DegenerateShape { shape, reason }) => {
+            assert_eq!(shape, "Annulus");
+            assert!(reason.contains("inner_radius"), "{reason}");
+        }
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("degenerate annulus was accepted"),
+    }
+}
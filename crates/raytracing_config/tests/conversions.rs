@@ -0,0 +1,145 @@
+// 設定から実行時の型への From/TryFrom 変換を確かめる
+use glam::Vec3;
+use raytracing_config::{
+    error::ConfigError, material_config::MaterialConfig, object_config::ObjectConfig,
+    ray_config::RayConfig, simulation_config::SimulationConfig,
+};
+use raytracing_core::{Hittable, Material, Ray, Scene};
+
+fn object(toml_str: &str) -> ObjectConfig {
+    toml::from_str(toml_str).unwrap()
+}
+
+#[test]
+fn valid_object_converts_with_try_from() {
+    let config = object(
+        r#"
+        shape = { type = "Lens", thickness = 2.0, diameter = 10.0, r1 = 20.0, r2 = -20.0 }
+        material = { type = "Glass", ior = 1.5 }
+        transform = { position = [0.0, 0.0, 5.0] }
+        "#,
+    );
+    let lens = Box::<dyn Hittable>::try_from(config).unwrap();
+    assert!(lens.contains(Vec3::new(0.0, 0.0, 5.0)));
+    assert!(!lens.contains(Vec3::ZERO));
+}
+
+#[test]
+fn lens_smaller_than_aperture_is_rejected() {
+    // 曲率半径 3 の球面では直径 10 の開口を覆えない
+    let config = object(
+        r#"
+        shape = { type = "Lens", thickness = 2.0, diameter = 10.0, r1 = 3.0, r2 = -20.0 }
+        material = { type = "Glass", ior = 1.5 }
+        transform = {}
+        "#,
+    );
+    let result: Result<Box<dyn Hittable>, ConfigError> = config.try_into();
+    match result {
+        Err(ConfigError::DegenerateShape { shape, reason }) => {
+            assert_eq!(shape, "Lens");
+            assert!(reason.contains("r1"), "{reason}");
+        }
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("degenerate lens was accepted"),
+    }
+}
+
+#[test]
+fn object_without_material_is_rejected() {
+    let config = object(
+        r#"
+        shape = { type = "Sphere", radius = 1.0 }
+        transform = {}
+        "#,
+    );
+    assert!(matches!(
+        Box::<dyn Hittable>::try_from(config),
+        Err(ConfigError::MissingMaterial)
+    ));
+}
+
+#[test]
+fn degenerate_shape_inside_csg_is_rejected_on_load() {
+    let toml_str = r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[[scene.objects]]
+shape = { type = "Difference", a = { type = "Box", size = [2.0, 2.0, 2.0] }, b = { type = "Plane", normal = [0.0, 0.0, 0.0] } }
+material = { type = "Absorber" }
+transform = {}
+"#;
+    match SimulationConfig::from_toml_str(toml_str) {
+        Err(ConfigError::DegenerateShape { shape, .. }) => assert_eq!(shape, "Plane"),
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("zero normal was accepted"),
+    }
+}
+
+#[test]
+fn infallible_conversions_use_from() {
+    let ray_config: RayConfig = toml::from_str(
+        r#"
+        origin = [1.0, 2.0, 3.0]
+        direction = [0.0, 0.0, 2.0]
+        "#,
+    )
+    .unwrap();
+    let ray = Ray::from(ray_config);
+    assert_eq!(ray.origin, Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(ray.direction, Vec3::Z);
+
+    let config = SimulationConfig::from_toml_str(
+        r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[[scene.objects]]
+shape = { type = "Sphere", radius = 1.0 }
+material = { type = "Mirror" }
+transform = {}
+"#,
+    )
+    .unwrap();
+    let scene = Scene::try_from(config.scene).unwrap();
+    assert_eq!(scene.objects.len(), 1);
+}
+
+#[test]
+fn glass_with_no_tir_becomes_ideal_glass() {
+    let material = |toml_str: &str| {
+        let config: MaterialConfig = toml::from_str(toml_str).unwrap();
+        Material::try_from(config).unwrap()
+    };
+    assert_eq!(
+        material("type = 'Glass'\nior = 1.5"),
+        Material::Glass { ior: 1.5 }
+    );
+    assert_eq!(
+        material("type = 'Glass'\nior = 1.5\nno_tir = true"),
+        Material::IdealGlass { ior: 1.5 }
+    );
+}
+
+#[test]
+fn mesh_with_out_of_range_index_is_rejected() {
+    let config = object(
+        r#"
+        shape = { type = "Mesh", vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], triangles = [[0, 1, 2], [0, 2, 3]] }
+        material = { type = "Absorber" }
+        transform = {}
+        "#,
+    );
+    match Box::<dyn Hittable>::try_from(config) {
+        Err(ConfigError::DegenerateShape { shape, reason }) => {
+            assert_eq!(shape, "Mesh");
+            assert!(reason.contains("三角形 1"), "{reason}");
+            assert!(reason.contains("添字 3"), "{reason}");
+        }
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("out-of-range triangle index was accepted"),
+    }
+}
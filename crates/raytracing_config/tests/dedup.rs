@@ -0,0 +1,85 @@
+// dedup_objects が同じ形状・同じ配置のオブジェクトだけを1つにまとめることの確認
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::Scene;
+
+// 半径 1 の球を transform の一覧どおりに並べたシーン
+fn config(transforms: &[&str], dedup: bool) -> SimulationConfig {
+    let objects: String = transforms
+        .iter()
+        .map(|transform| {
+            format!(
+                r#"
+[[scene.objects]]
+shape = {{ type = "Sphere", radius = 1.0 }}
+material = {{ type = "Mirror" }}
+transform = {transform}
+"#
+            )
+        })
+        .collect();
+    SimulationConfig::from_toml_str(&format!(
+        r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[scene]
+dedup_objects = {dedup}
+{objects}
+"#
+    ))
+    .unwrap()
+}
+
+fn object_count(transforms: &[&str]) -> usize {
+    let config = config(transforms, true);
+    let merged = config.scene.merged_object_count();
+    let scene: Scene = config.scene.try_into().unwrap();
+    assert_eq!(scene.objects.len() + merged, transforms.len());
+    scene.objects.len()
+}
+
+#[test]
+fn identical_objects_collapse() {
+    let same = "{ position = [1.0, 2.0, 3.0] }";
+    assert_eq!(object_count(&[same, same, same]), 1);
+    // 誤差程度の違いはまとめる
+    assert_eq!(
+        object_count(&[same, "{ position = [1.000001, 2.0, 3.0] }"]),
+        1
+    );
+}
+
+#[test]
+fn duplicates_across_cell_boundary_collapse() {
+    // 位置を丸める格子の境目 (0.001) をまたいでいても、差が小さければまとめる
+    assert_eq!(
+        object_count(&[
+            "{ position = [0.000998, 0.0, 0.0] }",
+            "{ position = [0.001002, 0.0, 0.0] }",
+        ]),
+        1
+    );
+}
+
+#[test]
+fn different_transforms_stay_distinct() {
+    let transforms = [
+        "{ position = [0.0, 0.0, 0.0] }",
+        "{ position = [0.0, 0.0, 0.01] }",
+        "{ position = [5.0, 0.0, 0.0] }",
+        // 同じ位置でも回転が違えば別のオブジェクト
+        "{ position = [0.0, 0.0, 0.0], rotation_y_deg = 30.0 }",
+        "{ position = [0.0, 0.0, 0.0], rotation_y_deg = 90.0 }",
+    ];
+    assert_eq!(object_count(&transforms), transforms.len());
+}
+
+#[test]
+fn nothing_is_merged_without_dedup() {
+    let same = "{ position = [1.0, 2.0, 3.0] }";
+    let config = config(&[same, same], false);
+    assert_eq!(config.scene.merged_object_count(), 0);
+    let scene: Scene = config.scene.try_into().unwrap();
+    assert_eq!(scene.objects.len(), 2);
+}
@@ -0,0 +1,79 @@
+// material を省略したオブジェクトが [scene] の default_material を受け継ぐことの確認
+use raytracing_config::error::ConfigError;
+use raytracing_config::scene_config::SceneConfig;
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::{InteractionKind, Scene};
+
+// x = 0 の球へ -x 側から当てるレイと、default_material を鏡にしたシーン
+const SCENE: &str = r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[scene]
+default_material = { type = "Mirror" }
+
+[[scene.rays]]
+origin = [-10.0, 0.0, 0.0]
+direction = [1.0, 0.0, 0.0]
+
+[[scene.rays]]
+origin = [-10.0, 0.0, 5.0]
+direction = [1.0, 0.0, 0.0]
+
+[[scene.objects]]
+shape = { type = "Sphere", radius = 1.0 }
+
+[[scene.objects]]
+shape = { type = "Sphere", radius = 1.0 }
+material = { type = "Glass", ior = 1.5 }
+transform = { position = [0.0, 0.0, 5.0] }
+"#;
+
+fn first_kinds(toml_str: &str) -> Vec<InteractionKind> {
+    let config = SimulationConfig::from_toml_str(toml_str).unwrap();
+    let scene: Scene = config.scene.try_into().unwrap();
+    scene
+        .simulate_rays_detailed(config.simulation_settings.into())
+        .iter()
+        .map(|path| path.interactions[0].kind)
+        .collect()
+}
+
+#[test]
+fn object_without_material_inherits_default() {
+    // 材質を省略した球は鏡になり、指定した球はガラスのまま
+    assert_eq!(
+        first_kinds(SCENE),
+        [InteractionKind::Reflection, InteractionKind::Refraction]
+    );
+}
+
+#[test]
+fn group_object_inherits_default() {
+    let toml_str = SCENE.replace(
+        "[[scene.objects]]\nshape = { type = \"Sphere\", radius = 1.0 }\n\n",
+        "[[scene.groups]]\ntransform = {}\n\n[[scene.groups.objects]]\nshape = { type = \"Sphere\", radius = 1.0 }\n\n",
+    );
+    assert!(toml_str.contains("scene.groups.objects"));
+    assert_eq!(
+        first_kinds(&toml_str),
+        [InteractionKind::Reflection, InteractionKind::Refraction]
+    );
+}
+
+#[test]
+fn unfilled_material_is_an_error() {
+    // 読み込み時の確認を通さずに変換しても、材質が無ければパニックせずにエラーになる
+    let config: SceneConfig = toml::from_str(
+        r#"
+[[objects]]
+shape = { type = "Sphere", radius = 1.0 }
+"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        Scene::try_from(config),
+        Err(ConfigError::MissingMaterial)
+    ));
+}
@@ -0,0 +1,106 @@
+// dump_expanded で書き出したTOMLに見出しが付き、読み込み直すと同じシーンになるか確かめる
+use std::path::Path;
+
+use raytracing_config::scene_writer::dump_expanded;
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::{Scene, SimulationSettingsConfig};
+
+const SCENE: &str = r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[scene]
+default_material = { type = "Glass", ior = 1.5 }
+
+[[scene.rays]]
+origin = [-20.0, 0.0, 0.0]
+direction = [1.0, 0.0, 0.0]
+
+[[scene.rays]]
+origin = [-20.0, 0.0, 5.0]
+direction = [1.0, 0.0, 0.01]
+
+# 手で調整したレンズ
+[[scene.objects]]
+name = "lens"
+shape = { type = "Sphere", radius = 2.0 }
+transform = { position = [-10.0, 0.0, 0.0] }
+
+[[scene.groups]]
+transform = { position = [0.0, 0.0, 5.0] }
+
+[[scene.groups.objects]]
+shape = { type = "Sphere", radius = 1.0 }
+transform = { position = [0.0, 0.0, 0.0] }
+
+[[scene.object_generators]]
+type = "ObjectGrid"
+count_x = 3
+count_z = 2
+position_start = [0.0, 0.0, 0.0]
+step_x = [4.1, 0.0, 0.0]
+step_z = [0.0, 0.0, 5.0]
+template.shape = { type = "Sphere", radius = 1.2 }
+template.transform = { rotation_y_deg = 30.0 }
+"#;
+
+fn load_scene(toml_str: &str) -> Scene {
+    SimulationConfig::from_toml_str(toml_str)
+        .unwrap()
+        .scene
+        .try_into()
+        .unwrap()
+}
+
+fn trace(scene: Scene) -> Vec<Vec<glam::Vec3>> {
+    let setting = SimulationSettingsConfig {
+        max_bounces: 10,
+        max_reflections: 10,
+        max_refractions: 10,
+        ..Default::default()
+    };
+    scene
+        .simulate_rays_detailed(setting)
+        .into_iter()
+        .map(|path| path.points)
+        .collect()
+}
+
+#[test]
+fn dumped_file_has_header_and_section_labels() {
+    let dumped = dump_expanded(SCENE, Path::new("")).unwrap();
+
+    assert!(dumped.starts_with("# dump_expanded で書き出したシーン\n"));
+    for label in [
+        "# --- シミュレーション設定 ---\n[simulation_settings]",
+        "# --- シーン ---\n[scene]",
+        "# --- レイ ---\n[[scene.rays]]",
+        "# --- オブジェクト ---\n# 手で調整したレンズ\n[[scene.objects]]",
+        "# --- グループ ---\n[[scene.groups]]",
+        "# --- ジェネレータから展開したオブジェクト ---\n[[scene.groups]]",
+    ] {
+        assert!(dumped.contains(label), "{label} が無い:\n{dumped}");
+    }
+    assert!(!dumped.contains("[[scene.object_generators]]"));
+    assert_eq!(dumped.matches("[[scene.groups.objects]]").count(), 1 + 6);
+}
+
+#[test]
+fn dumped_file_reparses_into_equivalent_scene() {
+    let dumped = dump_expanded(SCENE, Path::new("")).unwrap();
+    let original = load_scene(SCENE);
+    let reloaded = load_scene(&dumped);
+
+    assert_eq!(reloaded.objects.len(), original.objects.len());
+    assert_eq!(reloaded.rays.len(), original.rays.len());
+    assert_eq!(reloaded.object_names, original.object_names);
+    assert_eq!(trace(reloaded), trace(original));
+}
+
+#[test]
+fn dumping_twice_does_not_repeat_labels() {
+    let dumped = dump_expanded(SCENE, Path::new("")).unwrap();
+    let dumped_again = dump_expanded(&dumped, Path::new("")).unwrap();
+    assert_eq!(dumped_again, dumped);
+}
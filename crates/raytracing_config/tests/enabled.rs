@@ -0,0 +1,52 @@
+// enabled = false のオブジェクトやテンプレートがシーンから除外されることの確認
+use glam::Vec3;
+use raytracing_config::{
+    object_generator_config::{build_scene_from_config, SceneDefinition},
+    simulation_config::SimulationConfig,
+};
+use raytracing_core::Scene;
+
+const OBJECTS: &str = r#"
+[[scene.objects]]
+shape = { type = "Sphere", radius = 1.0 }
+material = { type = "Mirror" }
+transform = { position = [0.0, 0.0, 0.0] }
+
+[[scene.objects]]
+enabled = false
+shape = { type = "Sphere", radius = 1.0 }
+material = { type = "Mirror" }
+transform = { position = [10.0, 0.0, 0.0] }
+
+[[scene.object_generators]]
+type = "ObjectGrid"
+count_x = 2
+count_z = 2
+position_start = [0.0, 0.0, 20.0]
+step_x = [5.0, 0.0, 0.0]
+step_z = [0.0, 0.0, 5.0]
+template.enabled = false
+template.shape = { type = "Sphere", radius = 1.0 }
+template.material = { type = "Mirror" }
+template.transform = {}
+"#;
+
+#[test]
+fn disabled_object_and_template_are_skipped() {
+    let config = SimulationConfig::from_toml_str(&format!(
+        "[simulation_settings]\ninfinity_distance = 100.0\nmax_bounces = 10\n{OBJECTS}"
+    ))
+    .unwrap();
+    let scene: Scene = config.scene.try_into().unwrap();
+    assert_eq!(scene.objects.len(), 1);
+    assert!(scene.objects[0].contains(Vec3::ZERO));
+}
+
+#[test]
+fn disabled_object_and_template_are_skipped_in_scene_definition() {
+    let toml_str = format!("ray_generators = []\n{}", OBJECTS.replace("[[scene.", "[["));
+    let config: SceneDefinition = toml::from_str(&toml_str).unwrap();
+    let (_, hittables) = build_scene_from_config(config).unwrap();
+    assert_eq!(hittables.len(), 1);
+    assert!(hittables[0].contains(Vec3::ZERO));
+}
@@ -0,0 +1,43 @@
+// ShapeConfig::FoldMirror が、傾けて置いても縁の内側だけで光を折り返すことの確認
+use glam::Vec3;
+use raytracing_config::{error::ConfigError, object_config::ObjectConfig};
+use raytracing_core::{Hittable, Ray};
+
+// 幅 4・高さ 2 の鏡を Y 軸まわりに 45° 傾けて原点に置く
+fn fold_mirror(width: f32) -> ObjectConfig {
+    toml::from_str(&format!(
+        r#"
+        shape = {{ type = "FoldMirror", width = {width}, height = 2.0 }}
+        material = {{ type = "Mirror" }}
+        transform = {{ position = [0.0, 0.0, 0.0], rotation_y_deg = 45.0 }}
+        "#
+    ))
+    .unwrap()
+}
+
+#[test]
+fn reflects_only_within_bounds() {
+    let mirror = Box::<dyn Hittable>::try_from(fold_mirror(4.0)).unwrap();
+    let hit = |x: f32| {
+        let ray = Ray::new(Vec3::new(x, 0.0, -10.0), Vec3::Z, 1.0);
+        mirror.intersect_all(&ray, 1e-4, f32::INFINITY)
+    };
+    // 鏡の幅は X 方向に 4 / √2 ≈ 2.83 の範囲を覆う
+    assert!(hit(0.0).is_some());
+    assert!(hit(1.3).is_some());
+    assert!(hit(1.5).is_none());
+    assert!(hit(-1.5).is_none());
+}
+
+#[test]
+fn non_positive_size_is_rejected() {
+    let result: Result<Box<dyn Hittable>, ConfigError> = fold_mirror(0.0).try_into();
+    match result {
+        Err(ConfigError::DegenerateShape { shape, reason }) => {
+            assert_eq!(shape, "FoldMirror");
+            assert!(reason.contains("width"), "{reason}");
+        }
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("degenerate fold mirror was accepted"),
+    }
+}
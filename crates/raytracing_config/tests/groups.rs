@@ -0,0 +1,60 @@
+// [[scene.groups]] の変換が子オブジェクトに合成されることの確認
+use glam::Vec3;
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::{Ray, Scene};
+
+fn load(groups: &str) -> Scene {
+    SimulationConfig::from_toml_str(&format!(
+        "[simulation_settings]\ninfinity_distance = 100.0\nmax_bounces = 10\n{groups}"
+    ))
+    .unwrap()
+    .scene
+    .try_into()
+    .unwrap()
+}
+
+// グループ原点から +Z に 5 離れた半径 1 の球を、グループごと Y 軸まわりに 90° 回して (10, 0, 0) に置く
+const ROTATED_GROUP: &str = r#"
+[[scene.groups]]
+transform = { position = [10.0, 0.0, 0.0], rotation_y_deg = 90.0 }
+
+[[scene.groups.objects]]
+shape = { type = "Sphere", radius = 1.0 }
+material = { type = "Mirror" }
+transform = { position = [0.0, 0.0, 5.0] }
+"#;
+
+#[test]
+fn rotating_a_group_rotates_children_about_the_group_origin() {
+    let scene = load(ROTATED_GROUP);
+    assert_eq!(scene.objects.len(), 1);
+    // 回転後の球の中心は (10, 0, 0) + (5, 0, 0)
+    let sphere = &scene.objects[0];
+    assert!(sphere.contains(Vec3::new(15.0, 0.0, 0.0)));
+    assert!(!sphere.contains(Vec3::new(10.0, 0.0, 5.0)));
+
+    // 上から下ろしたレイは球の頂点 (15, 1, 0) に当たる
+    let ray = Ray::new(Vec3::new(15.0, 10.0, 0.0), Vec3::NEG_Y, 1.0);
+    let hits = sphere.intersect_all(&ray, 1e-4, 100.0).unwrap();
+    assert!((hits[0].point - Vec3::new(15.0, 1.0, 0.0)).length() < 1e-4);
+}
+
+#[test]
+fn nested_group_transforms_compose() {
+    let scene = load(
+        r#"
+[[scene.groups]]
+transform = { position = [0.0, 0.0, 20.0] }
+
+[[scene.groups.groups]]
+transform = { position = [0.0, 3.0, 0.0] }
+
+[[scene.groups.groups.objects]]
+shape = { type = "Sphere", radius = 1.0 }
+material = { type = "Mirror" }
+transform = { position = [1.0, 0.0, 0.0] }
+"#,
+    );
+    assert_eq!(scene.objects.len(), 1);
+    assert!(scene.objects[0].contains(Vec3::new(1.0, 3.0, 20.0)));
+}
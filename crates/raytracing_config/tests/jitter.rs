@@ -0,0 +1,122 @@
+// ParallelGrid と Projector の jitter が、格子点をセル内でだけずらし、同じ seed なら同じ配置になることの確認
+use glam::Vec3;
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::Scene;
+
+// x 方向に 4 本、y 方向に 2 本。セルの辺はどちらも 1
+const COUNT_U: usize = 4;
+const COUNT_V: usize = 2;
+
+fn parallel_grid(extra: &str) -> Vec<Vec3> {
+    load(&format!(
+        r#"
+type = "ParallelGrid"
+origin_corner = [0.0, 0.0, 0.0]
+vec_u = [4.0, 0.0, 0.0]
+vec_v = [0.0, 2.0, 0.0]
+count_u = 4
+count_v = 2
+direction = [0.0, 0.0, 1.0]
+current_ior = 1.0
+{extra}
+"#
+    ))
+    .rays
+    .iter()
+    .map(|ray| ray.origin)
+    .collect()
+}
+
+// 原点から z = 10 の投影面へ向かうレイが、投影面と交わる点
+fn projector(extra: &str) -> Vec<Vec3> {
+    load(&format!(
+        r#"
+type = "Projector"
+origin = [0.0, 0.0, 0.0]
+target_corner = [0.0, 0.0, 10.0]
+target_u = [4.0, 0.0, 0.0]
+target_v = [0.0, 2.0, 0.0]
+count_u = 4
+count_v = 2
+current_ior = 1.0
+{extra}
+"#
+    ))
+    .rays
+    .iter()
+    .map(|ray| ray.origin + ray.direction * (10.0 / ray.direction.z))
+    .collect()
+}
+
+fn load(generator: &str) -> Scene {
+    SimulationConfig::from_toml_str(&format!(
+        r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[[scene.ray_generators]]
+{generator}
+"#
+    ))
+    .unwrap()
+    .scene
+    .try_into()
+    .unwrap()
+}
+
+// u 方向が外側のループなので、k 番目のレイは (k / COUNT_V, k % COUNT_V) の格子点
+fn lattice(z: f32) -> Vec<Vec3> {
+    (0..COUNT_U * COUNT_V)
+        .map(|k| Vec3::new((k / COUNT_V) as f32, (k % COUNT_V) as f32, z))
+        .collect()
+}
+
+// 格子点から u, v 方向に 0 以上 jitter 未満だけずれていて、どれかは格子点から離れている
+fn assert_jittered_within_cells(points: &[Vec3], z: f32, jitter: f32) {
+    let lattice = lattice(z);
+    assert_eq!(points.len(), lattice.len());
+    for (point, grid) in points.iter().zip(&lattice) {
+        let offset = *point - *grid;
+        assert!(
+            (0.0..jitter).contains(&offset.x) && (0.0..jitter).contains(&offset.y),
+            "{point} が格子点 {grid} のセルからはみ出しています"
+        );
+        assert!(offset.z.abs() < 1e-4);
+    }
+    assert!(points
+        .iter()
+        .zip(&lattice)
+        .any(|(point, grid)| !point.abs_diff_eq(*grid, 1e-3)));
+}
+
+#[test]
+fn zero_jitter_keeps_lattice() {
+    assert_eq!(parallel_grid(""), lattice(0.0));
+    assert_eq!(parallel_grid("jitter = 0.0\nseed = 5"), lattice(0.0));
+    for (point, grid) in projector("").iter().zip(lattice(10.0)) {
+        assert!(point.abs_diff_eq(grid, 1e-4), "{point} != {grid}");
+    }
+}
+
+#[test]
+fn jittered_origins_stay_in_cells() {
+    assert_jittered_within_cells(&parallel_grid("jitter = 0.5\nseed = 1"), 0.0, 0.5);
+    assert_jittered_within_cells(&parallel_grid("jitter = 1.0\nseed = 2"), 0.0, 1.0);
+}
+
+#[test]
+fn jittered_targets_stay_in_cells() {
+    assert_jittered_within_cells(&projector("jitter = 0.5\nseed = 1"), 10.0, 0.5);
+}
+
+#[test]
+fn same_seed_gives_same_origins() {
+    let a = parallel_grid("jitter = 0.8\nseed = 42");
+    assert_eq!(a, parallel_grid("jitter = 0.8\nseed = 42"));
+    assert_ne!(a, parallel_grid("jitter = 0.8\nseed = 43"));
+    assert_eq!(
+        projector("jitter = 0.8\nseed = 42"),
+        projector("jitter = 0.8\nseed = 42")
+    );
+}
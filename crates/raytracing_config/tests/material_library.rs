@@ -0,0 +1,80 @@
+// 材質ライブラリ (material_library) から名前で材質を引けることの確認
+use std::fs;
+use std::path::PathBuf;
+
+use raytracing_config::error::ConfigError;
+use raytracing_config::material_config::MaterialConfig;
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::Material;
+
+const LIBRARY: &str = r#"
+[materials.BK7]
+type = "GlassByAbbe"
+nd = 1.5168
+vd = 64.17
+
+[materials.SF11]
+type = "GlassByAbbe"
+nd = 1.7847
+vd = 25.68
+"#;
+
+fn scene(material_library: &str, material_name: &str) -> String {
+    format!(
+        r#"
+material_library = "{material_library}"
+
+[simulation_settings]
+infinity_distance = 50.0
+max_bounces = 10
+
+[[scene.objects]]
+shape = {{ type = "Sphere", radius = 1.0 }}
+material = {{ type = "Named", name = "{material_name}" }}
+transform = {{ position = [0.0, 0.0, 0.0] }}
+"#
+    )
+}
+
+// テストごとに別のディレクトリにライブラリと設定ファイルを書き出す
+fn write_files(test_name: &str, scene_toml: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("raytracing_material_library_{test_name}"));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("materials.toml"), LIBRARY).unwrap();
+    let path = dir.join("simulation.toml");
+    fs::write(&path, scene_toml).unwrap();
+    path
+}
+
+#[test]
+fn named_material_resolves_to_library_definition() {
+    let path = write_files("resolve", &scene("materials.toml", "BK7"));
+    let config = SimulationConfig::load_from_path(path).unwrap();
+    let material = config.scene.objects[0].material.clone();
+    assert!(
+        matches!(material, Some(MaterialConfig::GlassByAbbe { nd, vd }) if nd == 1.5168 && vd == 64.17)
+    );
+}
+
+#[test]
+fn unknown_name_is_an_error() {
+    let path = write_files("unknown", &scene("materials.toml", "N-BK10"));
+    let error = SimulationConfig::load_from_path(path).err().unwrap();
+    assert!(matches!(error, ConfigError::UnknownMaterial { name } if name == "N-BK10"));
+}
+
+#[test]
+fn unresolved_named_material_does_not_convert() {
+    // ライブラリで置き換える前の Named は、変換するとパニックせずにエラーになる
+    let named = MaterialConfig::Named {
+        name: "BK7".to_string(),
+    };
+    assert!(matches!(
+        Material::try_from(named),
+        Err(ConfigError::UnknownMaterial { name }) if name == "BK7"
+    ));
+    assert!(matches!(
+        Material::try_from(MaterialConfig::Mirror),
+        Ok(Material::Mirror)
+    ));
+}
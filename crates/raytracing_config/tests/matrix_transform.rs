@@ -0,0 +1,66 @@
+// transform.matrix で与えた行列が、同じ位置と回転の指定と同じ配置になることの確認
+use glam::Vec3;
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::{Ray, Scene};
+
+fn load(transform: &str) -> Result<Scene, String> {
+    SimulationConfig::from_toml_str(&format!(
+        r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[[scene.objects]]
+shape = {{ type = "Box", size = [4.0, 1.0, 1.0] }}
+material = {{ type = "Mirror" }}
+transform = {transform}
+"#
+    ))
+    .and_then(|config| config.scene.try_into())
+    .map_err(|error| error.to_string())
+}
+
+#[test]
+fn matrix_matches_position_and_rotation() {
+    // Y 軸まわりに 90° 回してから (10, 0, 0) へ動かす行列（行優先）
+    let matrix = load(
+        "{ matrix = [[0.0, 0.0, 1.0, 10.0], [0.0, 1.0, 0.0, 0.0], [-1.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]] }",
+    )
+    .unwrap();
+    let trs = load("{ position = [10.0, 0.0, 0.0], rotation_y_deg = 90.0 }").unwrap();
+
+    // 回転で長辺が Z 方向を向くので、z = 1.5 の上から下ろしたレイも当たる
+    for origin in [
+        Vec3::new(10.0, 10.0, 0.0),
+        Vec3::new(10.2, 10.0, 1.5),
+        Vec3::new(20.0, 0.3, -1.8),
+    ] {
+        let ray = Ray::new(
+            origin,
+            (Vec3::new(10.0, 0.0, 0.0) - origin).normalize(),
+            1.0,
+        );
+        let expected = trs.objects[0].intersect_all(&ray, 1e-4, 100.0).unwrap();
+        let actual = matrix.objects[0].intersect_all(&ray, 1e-4, 100.0).unwrap();
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(&expected) {
+            assert!(
+                (a.point - e.point).length() < 1e-4,
+                "{:?} != {:?}",
+                a.point,
+                e.point
+            );
+            assert!((a.normal - e.normal).length() < 1e-4);
+        }
+    }
+}
+
+#[test]
+fn singular_matrix_is_rejected() {
+    let error = load(
+        "{ matrix = [[1.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]] }",
+    )
+    .err()
+    .unwrap();
+    assert!(error.contains("逆行列"), "{}", error);
+}
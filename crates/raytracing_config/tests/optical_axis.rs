@@ -0,0 +1,102 @@
+// [scene.optical_axis] と axial で、光軸に沿って面を並べられることの確認
+use glam::Vec3;
+use raytracing_config::{error::ConfigError, simulation_config::SimulationConfig};
+use raytracing_core::{Ray, Scene};
+
+fn load(scene: &str) -> Result<SimulationConfig, ConfigError> {
+    SimulationConfig::from_toml_str(&format!(
+        r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+{scene}
+"#
+    ))
+}
+
+// 光軸に垂直な 4×4 の折り返し鏡を、光軸に沿って distance の位置に置く
+fn surface(axial: &str) -> String {
+    format!(
+        r#"
+[[scene.objects]]
+shape = {{ type = "FoldMirror", width = 4.0, height = 4.0 }}
+material = {{ type = "Mirror" }}
+axial = {axial}
+"#
+    )
+}
+
+// 斜めの光軸（origin (1, 2, 3)、向き (1, 0, 1)）
+const AXIS: &str = r#"
+[scene.optical_axis]
+origin = [1.0, 2.0, 3.0]
+direction = [1.0, 0.0, 1.0]
+"#;
+
+// 光軸に沿ったレイが object_index の面に当たる距離
+fn axial_hit_distance(scene: &Scene, object_index: usize, offset: Vec3) -> Option<f32> {
+    let direction = Vec3::new(1.0, 0.0, 1.0).normalize();
+    let ray = Ray::new(Vec3::new(1.0, 2.0, 3.0) + offset, direction, 1.0);
+    let hits = scene.objects[object_index].intersect_all(&ray, 0.0, f32::INFINITY)?;
+    hits.first().map(|hit| hit.t)
+}
+
+#[test]
+fn surfaces_are_separated_by_their_axial_distance() {
+    let config = load(&format!(
+        "{AXIS}{}{}",
+        surface("{ distance = 5.0 }"),
+        surface("{ distance = 12.5 }")
+    ))
+    .unwrap();
+    let scene: Scene = config.scene.try_into().unwrap();
+    let first = axial_hit_distance(&scene, 0, Vec3::ZERO).unwrap();
+    let second = axial_hit_distance(&scene, 1, Vec3::ZERO).unwrap();
+    assert!((first - 5.0).abs() < 1e-4, "{first}");
+    assert!((second - first - 7.5).abs() < 1e-4, "{first} {second}");
+}
+
+#[test]
+fn decenter_moves_the_surface_off_the_axis() {
+    // 幅 4 の鏡を光軸から 3 ずらすと、光軸上のレイは当たらない
+    let config = load(&format!(
+        "{AXIS}{}",
+        surface("{ distance = 5.0, decenter = [3.0, 0.0] }")
+    ))
+    .unwrap();
+    let scene: Scene = config.scene.try_into().unwrap();
+    assert_eq!(axial_hit_distance(&scene, 0, Vec3::ZERO), None);
+}
+
+#[test]
+fn tilt_rotates_the_surface_about_its_axial_point() {
+    // Y 軸まわりに傾けても、光軸との交点は distance の位置のまま
+    let config = load(&format!(
+        "{AXIS}{}",
+        surface("{ distance = 5.0, tilt_y_deg = 30.0 }")
+    ))
+    .unwrap();
+    let scene: Scene = config.scene.try_into().unwrap();
+    let t = axial_hit_distance(&scene, 0, Vec3::ZERO).unwrap();
+    assert!((t - 5.0).abs() < 1e-4, "{t}");
+    // 光軸から Y 方向にずらしたレイも、傾きの回転軸上なので同じ距離で当たる
+    let t = axial_hit_distance(&scene, 0, Vec3::Y).unwrap();
+    assert!((t - 5.0).abs() < 1e-4, "{t}");
+}
+
+#[test]
+fn axial_without_optical_axis_is_rejected() {
+    let result = load(&surface("{ distance = 5.0 }"));
+    assert!(matches!(result, Err(ConfigError::OpticalAxis { .. })));
+}
+
+#[test]
+fn zero_axis_direction_is_rejected() {
+    let result = load(
+        r#"
+[scene.optical_axis]
+direction = [0.0, 0.0, 0.0]
+"#,
+    );
+    assert!(matches!(result, Err(ConfigError::OpticalAxis { .. })));
+}
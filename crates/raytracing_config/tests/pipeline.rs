@@ -0,0 +1,51 @@
+// 設定ファイルの読み込みから光線追跡までを通して確かめる（ウィンドウは開かない）
+use std::fs;
+
+use glam::Vec3;
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::Scene;
+
+// +Z に進むレイを、z = 5 で 45° に傾けた鏡で +X に折り返す
+const SIMULATION: &str = r#"
+[simulation_settings]
+infinity_distance = 20.0
+max_bounces = 10
+
+[[scene.objects]]
+shape = { type = "Plane", normal = [-1.0, 0.0, 1.0] }
+material = { type = "Mirror" }
+transform = { position = [0.0, 0.0, 5.0] }
+
+[[scene.rays]]
+origin = [0.0, 0.0, 0.0]
+direction = [0.0, 0.0, 1.0]
+"#;
+
+#[test]
+fn mirror_at_45_degrees_turns_z_ray_into_x() {
+    let dir = std::env::temp_dir().join("raytracing_pipeline");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("simulation.toml");
+    fs::write(&path, SIMULATION).unwrap();
+
+    let SimulationConfig {
+        simulation_settings,
+        scene,
+        ..
+    } = SimulationConfig::load_from_path(&path).unwrap();
+    let scene: Scene = scene.try_into().unwrap();
+    let paths = scene.simulate_rays(simulation_settings.into());
+
+    assert_eq!(paths.len(), 1);
+    let points = &paths[0];
+    // 始点、鏡での反射点、飛び去った先の3点
+    assert_eq!(points.len(), 3, "points: {points:?}");
+    assert!(points[0].abs_diff_eq(Vec3::ZERO, 1e-5));
+    assert!(
+        points[1].abs_diff_eq(Vec3::new(0.0, 0.0, 5.0), 1e-4),
+        "hit at {}",
+        points[1]
+    );
+    let outgoing = (points[2] - points[1]).normalize();
+    assert!(outgoing.abs_diff_eq(Vec3::X, 1e-5), "outgoing {outgoing}");
+}
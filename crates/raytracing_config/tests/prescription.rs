@@ -0,0 +1,78 @@
+// 処方表 ([[scene.prescriptions]]) から作った単レンズの焦点距離を、厚肉レンズの式と比べる
+use glam::Vec3;
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::{Ray, Scene, SimulationSettingsConfig};
+
+const IOR: f32 = 1.5;
+const THICKNESS: f32 = 5.0;
+
+fn singlet(r1: &str, r2: &str) -> Scene {
+    let toml_str = format!(
+        r#"
+[simulation_settings]
+infinity_distance = 500.0
+max_bounces = 10
+
+[[scene.prescriptions]]
+[[scene.prescriptions.surfaces]]
+radius = {r1}
+thickness = {THICKNESS}
+glass = {{ type = "Glass", ior = {IOR} }}
+semi_diameter = 10.0
+
+[[scene.prescriptions.surfaces]]
+radius = {r2}
+thickness = 0.0
+semi_diameter = 10.0
+"#
+    );
+    SimulationConfig::from_toml_str(&toml_str)
+        .unwrap()
+        .scene
+        .try_into()
+        .unwrap()
+}
+
+// 光軸に平行な近軸光線を通し、出射光の傾きから有効焦点距離を求める
+fn measured_efl(mut scene: Scene) -> f32 {
+    let height = 0.1;
+    scene.rays = vec![Ray::new(Vec3::new(0.0, height, -20.0), Vec3::Z, 1.0)];
+    let setting = SimulationSettingsConfig {
+        infinity_distance: 500.0,
+        max_bounces: 10,
+        max_reflections: 10,
+        max_refractions: 10,
+        ..Default::default()
+    };
+    let path = &scene.simulate_rays_detailed(setting)[0];
+    assert_eq!(path.interactions.len(), 2, "レンズの2面を通っていない");
+    let exit = path.interactions.last().unwrap().outgoing_dir;
+    -height * exit.z / exit.y
+}
+
+// 厚肉レンズの有効焦点距離
+fn theoretical_efl(r1: f32, r2: f32) -> f32 {
+    let power = (IOR - 1.0) * (1.0 / r1 - 1.0 / r2 + (IOR - 1.0) * THICKNESS / (IOR * r1 * r2));
+    1.0 / power
+}
+
+#[test]
+fn biconvex_singlet_matches_thick_lens_formula() {
+    let efl = measured_efl(singlet("50.0", "-50.0"));
+    let expected = theoretical_efl(50.0, -50.0);
+    assert!(
+        (efl - expected).abs() / expected < 1e-2,
+        "EFL {efl} (理論値 {expected})"
+    );
+}
+
+#[test]
+fn plano_concave_singlet_has_negative_focal_length() {
+    // 平面は inf で書ける。第2面は曲率中心が +Z 側なので凹面
+    let efl = measured_efl(singlet("inf", "30.0"));
+    let expected = -30.0 / (IOR - 1.0);
+    assert!(
+        (efl - expected).abs() / expected.abs() < 1e-2,
+        "EFL {efl} (理論値 {expected})"
+    );
+}
@@ -0,0 +1,87 @@
+// [[scene.rays]] の向きを方位角・仰角で指定できること、direction との同時指定が拒否されることの確認
+use glam::Vec3;
+use raytracing_config::{error::ConfigError, simulation_config::SimulationConfig};
+use raytracing_core::Scene;
+
+fn load(rays: &str) -> Result<SimulationConfig, ConfigError> {
+    SimulationConfig::from_toml_str(&format!(
+        r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+{rays}
+"#
+    ))
+}
+
+fn direction(angles: &str) -> Vec3 {
+    let scene: Scene = load(&format!(
+        "[[scene.rays]]\norigin = [0.0, 0.0, 0.0]\n{angles}\n"
+    ))
+    .unwrap()
+    .scene
+    .try_into()
+    .unwrap();
+    scene.rays[0].direction
+}
+
+fn assert_direction(angles: &str, expected: Vec3) {
+    let actual = direction(angles);
+    assert!(
+        actual.abs_diff_eq(expected, 1e-6),
+        "{angles}: {actual} != {expected}"
+    );
+}
+
+#[test]
+fn zero_angles_point_along_x() {
+    assert_direction("azimuth_deg = 0.0\nelevation_deg = 0.0", Vec3::X);
+    // 片方だけでも、省略した方は0になる
+    assert_direction("azimuth_deg = 0.0", Vec3::X);
+    assert_direction("elevation_deg = 0.0", Vec3::X);
+}
+
+#[test]
+fn elevation_90_points_along_y() {
+    assert_direction("elevation_deg = 90.0", Vec3::Y);
+    // 真上では方位角によらない
+    assert_direction("azimuth_deg = 45.0\nelevation_deg = 90.0", Vec3::Y);
+}
+
+#[test]
+fn azimuth_turns_from_x_toward_z() {
+    assert_direction("azimuth_deg = 90.0", Vec3::Z);
+    assert_direction("azimuth_deg = 180.0", Vec3::NEG_X);
+    let diagonal = direction("azimuth_deg = 45.0\nelevation_deg = 30.0");
+    assert!((diagonal.length() - 1.0).abs() < 1e-6);
+    assert!((diagonal.y - 0.5).abs() < 1e-6);
+    assert!((diagonal.x - diagonal.z).abs() < 1e-6);
+}
+
+#[test]
+fn direction_with_angles_is_rejected() {
+    let rays = r#"
+[[scene.rays]]
+origin = [0.0, 0.0, 0.0]
+direction = [1.0, 0.0, 0.0]
+
+[[scene.rays]]
+origin = [0.0, 0.0, 0.0]
+direction = [1.0, 0.0, 0.0]
+azimuth_deg = 10.0
+"#;
+    match load(rays) {
+        Err(ConfigError::RayDirection { index }) => assert_eq!(index, 1),
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("direction together with azimuth_deg was accepted"),
+    }
+}
+
+#[test]
+fn missing_direction_is_rejected() {
+    match load("[[scene.rays]]\norigin = [0.0, 0.0, 0.0]\n") {
+        Err(ConfigError::RayDirection { index }) => assert_eq!(index, 0),
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("ray without direction was accepted"),
+    }
+}
@@ -0,0 +1,98 @@
+// ParallelGrid の本数を density（単位長さあたりの本数）で指定できることの確認
+use raytracing_config::{error::ConfigError, simulation_config::SimulationConfig};
+use raytracing_core::Scene;
+
+fn load(generator: &str) -> Result<SimulationConfig, ConfigError> {
+    let toml_str = format!(
+        r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[[scene.ray_generators]]
+type = "ParallelGrid"
+origin_corner = [0.0, 0.0, 0.0]
+direction = [1.0, 0.0, 0.0]
+current_ior = 1.0
+{generator}
+"#
+    );
+    SimulationConfig::from_toml_str(&toml_str)
+}
+
+fn ray_count(generator: &str) -> usize {
+    let scene: Scene = load(generator).unwrap().scene.try_into().unwrap();
+    scene.rays.len()
+}
+
+#[test]
+fn doubling_vec_u_at_fixed_density_doubles_count_u() {
+    let single = ray_count(
+        r#"
+vec_u = [0.0, 5.0, 0.0]
+vec_v = [0.0, 0.0, 2.0]
+density = 2.0
+"#,
+    );
+    let double = ray_count(
+        r#"
+vec_u = [0.0, 10.0, 0.0]
+vec_v = [0.0, 0.0, 2.0]
+density = 2.0
+"#,
+    );
+    assert_eq!(single, 10 * 4);
+    assert_eq!(double, 20 * 4);
+}
+
+#[test]
+fn density_matches_equivalent_explicit_counts() {
+    let by_density: Scene = load(
+        r#"
+vec_u = [0.0, 3.0, 4.0]
+vec_v = [1.0, 0.0, 0.0]
+density = 0.8
+"#,
+    )
+    .unwrap()
+    .scene
+    .try_into()
+    .unwrap();
+    let by_count: Scene = load(
+        r#"
+vec_u = [0.0, 3.0, 4.0]
+vec_v = [1.0, 0.0, 0.0]
+count_u = 4
+count_v = 1
+"#,
+    )
+    .unwrap()
+    .scene
+    .try_into()
+    .unwrap();
+    let origins = |scene: &Scene| scene.rays.iter().map(|ray| ray.origin).collect::<Vec<_>>();
+    assert_eq!(origins(&by_density), origins(&by_count));
+}
+
+#[test]
+fn count_and_density_together_are_rejected() {
+    let result = load(
+        r#"
+vec_u = [0.0, 5.0, 0.0]
+vec_v = [0.0, 0.0, 2.0]
+count_u = 3
+count_v = 3
+density = 2.0
+"#,
+    );
+    assert!(matches!(result, Err(ConfigError::RayCount { index: 0 })));
+
+    let missing = load(
+        r#"
+vec_u = [0.0, 5.0, 0.0]
+vec_v = [0.0, 0.0, 2.0]
+count_u = 3
+"#,
+    );
+    assert!(matches!(missing, Err(ConfigError::RayCount { index: 0 })));
+}
@@ -0,0 +1,133 @@
+// オブジェクトの座標系で指定したレイが、ワールド座標に直されることの確認
+use glam::Vec3;
+use raytracing_config::{error::ConfigError, simulation_config::SimulationConfig};
+use raytracing_core::Scene;
+
+fn try_scene(rays: &str) -> Result<Scene, ConfigError> {
+    let toml_str = format!(
+        r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[[scene.objects]]
+shape = {{ type = "Sphere", radius = 1.0 }}
+material = {{ type = "Mirror" }}
+transform = {{}}
+
+[[scene.objects]]
+name = "lens"
+shape = {{ type = "Lens", thickness = 2.0, diameter = 10.0, r1 = 20.0, r2 = -20.0 }}
+material = {{ type = "Glass", ior = 1.5 }}
+transform = {{ position = [1.0, 2.0, 3.0], rotation_y_deg = 90.0 }}
+
+{rays}
+"#
+    );
+    SimulationConfig::from_toml_str(&toml_str)?.scene.try_into()
+}
+
+fn scene(rays: &str) -> Scene {
+    try_scene(rays).unwrap()
+}
+
+#[test]
+fn ray_in_translated_object_frame_starts_at_its_center() {
+    let scene = scene(
+        r#"
+[[scene.rays]]
+frame = "lens"
+origin = [0.0, 0.0, 0.0]
+direction = [0.0, 0.0, 1.0]
+
+[[scene.rays]]
+frame = 1
+origin = [0.0, 0.5, 0.0]
+direction = [0.0, 0.0, 1.0]
+"#,
+    );
+    let ray = &scene.rays[0];
+    assert!(
+        ray.origin.abs_diff_eq(Vec3::new(1.0, 2.0, 3.0), 1e-5),
+        "{}",
+        ray.origin
+    );
+    // Y 軸まわりに 90° 回した座標系の +Z は、ワールドの +X
+    assert!(
+        ray.direction.abs_diff_eq(Vec3::X, 1e-5),
+        "{}",
+        ray.direction
+    );
+
+    let by_index = &scene.rays[1];
+    assert!(by_index.origin.abs_diff_eq(Vec3::new(1.0, 2.5, 3.0), 1e-5));
+}
+
+fn unknown_frame(rays: &str) -> (usize, String) {
+    match try_scene(rays) {
+        Err(ConfigError::UnknownFrame { index, frame }) => (index, frame),
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("unknown frame was accepted"),
+    }
+}
+
+#[test]
+fn unknown_frame_name_is_an_error() {
+    let rays = r#"
+[[scene.rays]]
+frame = "lens"
+origin = [0.0, 0.0, 0.0]
+direction = [0.0, 0.0, 1.0]
+
+[[scene.rays]]
+frame = "missing"
+origin = [4.0, 5.0, 6.0]
+direction = [0.0, 1.0, 0.0]
+"#;
+    assert_eq!(unknown_frame(rays), (1, "\"missing\"".to_string()));
+}
+
+#[test]
+fn frame_index_out_of_range_is_an_error() {
+    // オブジェクトは2つなので、番号 2 は範囲外
+    let rays = r#"
+[[scene.rays]]
+frame = 2
+origin = [0.0, 0.0, 0.0]
+direction = [0.0, 0.0, 1.0]
+"#;
+    assert_eq!(unknown_frame(rays), (0, "2".to_string()));
+}
+
+#[test]
+fn frame_index_skips_disabled_objects() {
+    // 番号は enabled = false のオブジェクトを除いて数えるので、0 は2つ目のオブジェクト
+    let toml_str = r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[[scene.objects]]
+shape = { type = "Sphere", radius = 1.0 }
+material = { type = "Mirror" }
+enabled = false
+
+[[scene.objects]]
+shape = { type = "Sphere", radius = 1.0 }
+material = { type = "Mirror" }
+transform = { position = [1.0, 2.0, 3.0] }
+
+[[scene.rays]]
+frame = 0
+origin = [0.0, 0.0, 0.0]
+direction = [0.0, 0.0, 1.0]
+"#;
+    let scene: Scene = SimulationConfig::from_toml_str(toml_str)
+        .unwrap()
+        .scene
+        .try_into()
+        .unwrap();
+    assert!(scene.rays[0]
+        .origin
+        .abs_diff_eq(Vec3::new(1.0, 2.0, 3.0), 1e-5));
+}
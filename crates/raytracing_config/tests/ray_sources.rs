@@ -0,0 +1,50 @@
+// 個別レイと ray_generators の生成器ごとに光源の番号が振られ、追跡した光路に引き継がれることの確認
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::Scene;
+
+const CONFIG: &str = r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[[scene.rays]]
+origin = [0.0, 5.0, 0.0]
+direction = [1.0, 0.0, 0.0]
+
+[[scene.rays]]
+origin = [0.0, 6.0, 0.0]
+direction = [1.0, 0.0, 0.0]
+
+[[scene.ray_generators]]
+type = "ParallelGrid"
+origin_corner = [0.0, 0.0, 0.0]
+vec_u = [0.0, 1.0, 0.0]
+vec_v = [0.0, 0.0, 1.0]
+count_u = 2
+count_v = 2
+direction = [1.0, 0.0, 0.0]
+current_ior = 1.0
+
+[[scene.ray_generators]]
+type = "Projector"
+origin = [-5.0, 0.0, 0.0]
+target_corner = [0.0, -1.0, -1.0]
+target_u = [0.0, 2.0, 0.0]
+target_v = [0.0, 0.0, 2.0]
+count_u = 3
+count_v = 1
+current_ior = 1.0
+"#;
+
+#[test]
+fn sources_follow_rays_then_generators() {
+    let config = SimulationConfig::from_toml_str(CONFIG).unwrap();
+    let setting = config.simulation_settings;
+    let scene: Scene = config.scene.try_into().unwrap();
+    let sources: Vec<usize> = scene.rays.iter().map(|ray| ray.source).collect();
+    assert_eq!(sources, [0, 1, 2, 2, 2, 2, 3, 3, 3]);
+
+    let paths = scene.simulate_rays_detailed(setting.into());
+    let traced: Vec<usize> = paths.iter().map(|path| path.source).collect();
+    assert_eq!(traced, sources);
+}
@@ -0,0 +1,100 @@
+// 1つのファイルに [[runs]] で並べた複数のシーンを読み込めることの確認
+use std::fs;
+
+use raytracing_config::{error::ConfigError, simulation_config::SimulationConfig};
+use raytracing_core::Scene;
+
+const RUNS: &str = r#"
+[[runs]]
+[runs.simulation_settings]
+infinity_distance = 20.0
+max_bounces = 5
+
+[[runs.scene.objects]]
+shape = { type = "Sphere", radius = 1.0 }
+material = { type = "Mirror" }
+transform = { position = [0.0, 0.0, 5.0] }
+
+[[runs.scene.rays]]
+origin = [0.0, 0.0, 0.0]
+direction = [0.0, 0.0, 1.0]
+
+[[runs]]
+[runs.simulation_settings]
+infinity_distance = 30.0
+max_bounces = 8
+
+[[runs.scene.objects]]
+shape = { type = "Box", size = [1.0, 1.0, 1.0] }
+material = { type = "Absorber" }
+transform = { position = [5.0, 0.0, 0.0] }
+
+[[runs.scene.objects]]
+shape = { type = "Box", size = [1.0, 1.0, 1.0] }
+material = { type = "Absorber" }
+transform = { position = [-5.0, 0.0, 0.0] }
+
+[[runs.scene.rays]]
+origin = [0.0, 0.0, 0.0]
+direction = [1.0, 0.0, 0.0]
+
+[[runs.scene.rays]]
+origin = [0.0, 0.0, 0.0]
+direction = [-1.0, 0.0, 0.0]
+"#;
+
+#[test]
+fn two_runs_produce_two_scenes() {
+    let dir = std::env::temp_dir().join("raytracing_runs");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("simulation.toml");
+    fs::write(&path, RUNS).unwrap();
+
+    let runs = SimulationConfig::load_all(&path).unwrap();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0].simulation_settings.max_bounces, 5);
+    assert_eq!(runs[1].simulation_settings.max_bounces, 8);
+
+    let scenes: Vec<Scene> = runs
+        .into_iter()
+        .map(|run| run.scene.try_into().unwrap())
+        .collect();
+    assert_eq!(scenes[0].objects.len(), 1);
+    assert_eq!(scenes[0].rays.len(), 1);
+    assert_eq!(scenes[1].objects.len(), 2);
+    assert_eq!(scenes[1].rays.len(), 2);
+}
+
+#[test]
+fn file_without_runs_is_a_single_scene() {
+    let toml_str = r#"
+[simulation_settings]
+infinity_distance = 20.0
+max_bounces = 5
+
+[[scene.rays]]
+origin = [0.0, 0.0, 0.0]
+direction = [0.0, 0.0, 1.0]
+"#;
+    let runs = SimulationConfig::from_toml_str_all_in(toml_str, std::path::Path::new("")).unwrap();
+    assert_eq!(runs.len(), 1);
+}
+
+#[test]
+fn error_names_the_failing_run() {
+    // 2番目のシーンだけ max_bounces が無い
+    let toml_str = r#"
+[[runs]]
+simulation_settings = { infinity_distance = 20.0, max_bounces = 5 }
+scene = {}
+
+[[runs]]
+simulation_settings = { infinity_distance = 20.0 }
+scene = {}
+"#;
+    match SimulationConfig::from_toml_str_all_in(toml_str, std::path::Path::new("")) {
+        Err(ConfigError::Run { index, .. }) => assert_eq!(index, 1),
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("missing max_bounces was accepted"),
+    }
+}
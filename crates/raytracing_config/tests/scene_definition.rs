@@ -0,0 +1,31 @@
+// build_scene_from_config で個別に追加したオブジェクトにも transform が適用されるか確かめる
+use glam::Vec3;
+use raytracing_config::object_generator_config::{build_scene_from_config, SceneDefinition};
+use raytracing_core::Ray;
+
+const SCENE: &str = r#"
+ray_generators = []
+object_generators = []
+
+[[objects]]
+shape = { type = "Sphere", radius = 1.0 }
+material = { type = "Glass", ior = 1.5 }
+transform = { position = [10.0, 0.0, 0.0] }
+"#;
+
+#[test]
+fn individual_object_is_translated() {
+    let config: SceneDefinition = toml::from_str(SCENE).unwrap();
+    let (_, hittables) = build_scene_from_config(config).unwrap();
+    assert_eq!(hittables.len(), 1);
+    let sphere = &hittables[0];
+
+    assert!(sphere.contains(Vec3::new(10.0, 0.0, 0.0)));
+    assert!(!sphere.contains(Vec3::ZERO));
+
+    // 原点から +X に飛ばしたレイは x = 9 で球に当たる
+    let ray = Ray::new(Vec3::ZERO, Vec3::X, 1.0);
+    let hits = sphere.intersect_all(&ray, 1e-4, 100.0).unwrap();
+    let first = hits.iter().map(|hit| hit.t).fold(f32::INFINITY, f32::min);
+    assert!((first - 9.0).abs() < 1e-4, "first hit at t = {first}");
+}
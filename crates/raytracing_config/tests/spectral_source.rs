@@ -0,0 +1,127 @@
+// SpectralSource の波長の分布が、指定した分光分布に比例することの確認
+use glam::Vec3;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use raytracing_config::object_generator_config::spectral_source_rays;
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_config::spectrum_config::SpectrumConfig;
+use raytracing_core::{Ray, Scene};
+
+const COUNT: u32 = 200;
+const BIN_NM: f32 = 50.0;
+
+fn sample_rays(spectrum: &SpectrumConfig) -> Vec<Ray> {
+    let mut rng = StdRng::seed_from_u64(7);
+    spectral_source_rays(
+        Vec3::ZERO,
+        Vec3::new(-1.0, -1.0, 10.0),
+        (Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 2.0, 0.0)),
+        (COUNT, COUNT),
+        1.0,
+        spectrum,
+        &mut rng,
+    )
+}
+
+// start から BIN_NM ごとの区間に入ったレイの割合
+fn histogram(rays: &[Ray], start: f32, bins: usize) -> Vec<f32> {
+    let mut counts = vec![0usize; bins];
+    for ray in rays {
+        let bin = ((ray.wavelength - start) / BIN_NM) as usize;
+        counts[bin.min(bins - 1)] += 1;
+    }
+    counts
+        .iter()
+        .map(|&count| count as f32 / rays.len() as f32)
+        .collect()
+}
+
+// 分光分布 weight を区間ごとに積分し、合計が1になるように割った値
+fn expected(weight: impl Fn(f32) -> f32, start: f32, bins: usize) -> Vec<f32> {
+    let steps = 1000;
+    let areas: Vec<f32> = (0..bins)
+        .map(|bin| {
+            let lo = start + bin as f32 * BIN_NM;
+            (0..steps)
+                .map(|k| weight(lo + (k as f32 + 0.5) * BIN_NM / steps as f32))
+                .sum::<f32>()
+        })
+        .collect();
+    let total: f32 = areas.iter().sum();
+    areas.iter().map(|area| area / total).collect()
+}
+
+fn assert_matches(actual: &[f32], expected: &[f32]) {
+    for (bin, (a, e)) in actual.iter().zip(expected).enumerate() {
+        assert!(
+            (a - e).abs() < 0.01,
+            "区間 {bin} の割合 {a} が分光分布の {e} と合いません"
+        );
+    }
+}
+
+#[test]
+fn table_histogram_follows_spectrum() {
+    // 400〜500nm で0から1に増え、600〜700nm で0に減る台形
+    let spectrum = SpectrumConfig::Table {
+        samples: vec![(400.0, 0.0), (500.0, 1.0), (600.0, 1.0), (700.0, 0.0)],
+    };
+    let rays = sample_rays(&spectrum);
+    assert_eq!(rays.len(), (COUNT * COUNT) as usize);
+    let trapezoid = |nm: f32| {
+        ((nm - 400.0) / 100.0)
+            .min((700.0 - nm) / 100.0)
+            .clamp(0.0, 1.0)
+    };
+    assert_matches(&histogram(&rays, 400.0, 6), &expected(trapezoid, 400.0, 6));
+}
+
+#[test]
+fn blackbody_histogram_follows_planck() {
+    let temp_k = 3000.0;
+    let spectrum = SpectrumConfig::Blackbody {
+        temp_k,
+        range_nm: Some([400.0, 800.0]),
+    };
+    let rays = sample_rays(&spectrum);
+    assert!(rays
+        .iter()
+        .all(|ray| (400.0..=800.0).contains(&ray.wavelength)));
+    // 3000K では可視域の長波長側ほど強い
+    let planck = |nm: f32| {
+        let nm = nm as f64;
+        (nm.powi(-5) / (1.438_777e7 / (nm * temp_k as f64)).exp_m1()) as f32 * 1e15
+    };
+    let actual = histogram(&rays, 400.0, 8);
+    assert!(actual.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_matches(&actual, &expected(planck, 400.0, 8));
+}
+
+#[test]
+fn config_generator_uses_spectrum() {
+    let config = SimulationConfig::from_toml_str(
+        r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[[scene.ray_generators]]
+type = "SpectralSource"
+origin = [0.0, 0.0, 0.0]
+target_corner = [-1.0, -1.0, 10.0]
+target_u = [2.0, 0.0, 0.0]
+target_v = [0.0, 2.0, 0.0]
+count_u = 4
+count_v = 5
+current_ior = 1.0
+spectrum = { type = "Table", samples = [[500.0, 1.0], [520.0, 1.0]] }
+"#,
+    )
+    .unwrap();
+    let scene: Scene = config.scene.try_into().unwrap();
+    assert_eq!(scene.rays.len(), 20);
+    assert!(scene
+        .rays
+        .iter()
+        .all(|ray| (500.0..=520.0).contains(&ray.wavelength)));
+}
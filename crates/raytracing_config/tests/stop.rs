@@ -0,0 +1,57 @@
+// is_stop を付けた絞りの読み込みと、そこから求める入射瞳の確認
+use raytracing_config::{error::ConfigError, simulation_config::SimulationConfig};
+use raytracing_core::{analysis::entrance_pupil, Scene};
+
+// 半径 2 の穴の空いた薄い吸収板（X 軸まわりに 90° 回して Z 軸向きにする）
+const STOP: &str = r#"
+[[scene.objects]]
+shape = { type = "Difference", a = { type = "Box", size = [16.0, 0.002, 16.0] }, b = { type = "Cylinder", height = 1.0, radius = 2.0 } }
+material = { type = "Absorber" }
+transform = { matrix = [[1.0, 0.0, 0.0, 0.0], [0.0, 0.0, -1.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]] }
+is_stop = true
+"#;
+
+const LENS: &str = r#"
+[[scene.objects]]
+shape = { type = "Lens", thickness = 2.0, diameter = 10.0, r1 = 20.0, r2 = -20.0 }
+material = { type = "Glass", ior = 1.5 }
+transform = { position = [0.0, 0.0, -10.0] }
+"#;
+
+fn load(objects: &str) -> Result<SimulationConfig, ConfigError> {
+    SimulationConfig::from_toml_str(&format!(
+        r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+{objects}
+"#
+    ))
+}
+
+#[test]
+fn stop_index_follows_scene_order() {
+    // レンズの後に置いた絞りは2番目のオブジェクト
+    let config = load(&format!("{LENS}{STOP}")).unwrap();
+    assert_eq!(config.scene.stop_indices(), [1]);
+    let scene: Scene = config.scene.try_into().unwrap();
+    let pupil = entrance_pupil(&scene, 1).unwrap();
+    // レンズが作る絞りの拡大された虚像
+    assert!(pupil.position.z > 0.0);
+    assert!(pupil.diameter > 4.0);
+}
+
+#[test]
+fn scene_without_stop_has_no_index() {
+    let config = load(LENS).unwrap();
+    assert!(config.scene.stop_indices().is_empty());
+}
+
+#[test]
+fn two_stops_are_rejected() {
+    match load(&format!("{STOP}{STOP}")) {
+        Err(ConfigError::MultipleStops { count }) => assert_eq!(count, 2),
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("two stops were accepted"),
+    }
+}
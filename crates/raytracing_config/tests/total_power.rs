@@ -0,0 +1,73 @@
+// レイのジェネレータの total_power で、生成した全レイの強度の合計を光源の全パワーに揃えられることの確認
+use raytracing_config::{error::ConfigError, simulation_config::SimulationConfig};
+use raytracing_core::Scene;
+
+fn load(generators: &str) -> Result<SimulationConfig, ConfigError> {
+    let toml_str = format!(
+        r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+{generators}
+"#
+    );
+    SimulationConfig::from_toml_str(&toml_str)
+}
+
+fn scene(generators: &str) -> Scene {
+    load(generators).unwrap().scene.try_into().unwrap()
+}
+
+const GRID: &str = r#"
+[[scene.ray_generators]]
+type = "ParallelGrid"
+origin_corner = [0.0, 0.0, 0.0]
+vec_u = [0.0, 1.0, 0.0]
+vec_v = [0.0, 0.0, 1.0]
+count_u = 7
+count_v = 3
+direction = [1.0, 0.0, 0.0]
+current_ior = 1.0
+"#;
+
+const PROJECTOR: &str = r#"
+[[scene.ray_generators]]
+type = "Projector"
+origin = [-5.0, 0.0, 0.0]
+target_corner = [0.0, -1.0, -1.0]
+target_u = [0.0, 2.0, 0.0]
+target_v = [0.0, 0.0, 2.0]
+count_u = 4
+count_v = 5
+current_ior = 1.0
+"#;
+
+#[test]
+fn intensities_sum_to_total_power() {
+    let scene = scene(&format!("{GRID}total_power = 2.5\n"));
+    assert_eq!(scene.rays.len(), 21);
+    let total: f32 = scene.rays.iter().map(|ray| ray.intensity).sum();
+    assert!((total - 2.5).abs() < 1e-5, "{total}");
+    assert!(scene
+        .rays
+        .iter()
+        .all(|ray| ray.intensity == scene.rays[0].intensity));
+}
+
+#[test]
+fn each_generator_is_normalized_separately() {
+    // 格子は全体で 3.0、投影光源は指定しないので1本あたり 1.0 のまま
+    let scene = scene(&format!("{GRID}total_power = 3.0\n{PROJECTOR}"));
+    let (grid, projector) = scene.rays.split_at(21);
+    let grid_total: f32 = grid.iter().map(|ray| ray.intensity).sum();
+    assert!((grid_total - 3.0).abs() < 1e-5, "{grid_total}");
+    assert_eq!(projector.len(), 20);
+    assert!(projector.iter().all(|ray| ray.intensity == 1.0));
+}
+
+#[test]
+fn non_positive_total_power_is_rejected() {
+    let error = load(&format!("{PROJECTOR}total_power = 0.0\n")).err();
+    assert!(matches!(error, Some(ConfigError::TotalPower { index: 0 })));
+}
@@ -0,0 +1,35 @@
+// [units] セクションが読み込まれ、SimulationConfig から長さの単位を取り出せることの確認
+use raytracing_config::simulation_config::SimulationConfig;
+use raytracing_core::LengthUnit;
+
+const SETTINGS: &str = r#"
+[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[scene]
+"#;
+
+#[test]
+fn units_field_is_loaded() {
+    let config =
+        SimulationConfig::from_toml_str(&format!("{SETTINGS}\n[units]\nlength = \"mm\"\n"))
+            .unwrap();
+    let unit: LengthUnit = config.units.unwrap().length.into();
+    assert_eq!(unit, LengthUnit::Millimeter);
+    assert_eq!(unit.symbol(), "mm");
+    assert!((unit.meters() - 1e-3).abs() < 1e-9);
+}
+
+#[test]
+fn units_are_optional() {
+    let config = SimulationConfig::from_toml_str(SETTINGS).unwrap();
+    assert!(config.units.is_none());
+}
+
+#[test]
+fn unknown_unit_is_rejected() {
+    let result =
+        SimulationConfig::from_toml_str(&format!("{SETTINGS}\n[units]\nlength = \"furlong\"\n"));
+    assert!(result.is_err());
+}
@@ -0,0 +1,43 @@
+// 設定ファイルの未知のキー（タイプミス）が、場所と名前付きのエラーになることの確認
+use raytracing_config::{error::ConfigError, simulation_config::SimulationConfig};
+
+const SOURCE: &str = r#"[simulation_settings]
+infinity_distance = 100.0
+max_bounces = 10
+
+[[scene.objects]]
+shape = { type = "Sphere", radius = 1.0 }
+material = { type = "Glass", ior = 1.5 }
+transfrom = { position = [0.0, 0.0, 0.0] }
+"#;
+
+#[test]
+fn unknown_key_is_reported_with_its_location() {
+    match SimulationConfig::from_toml_str(SOURCE) {
+        Err(ConfigError::UnknownField {
+            section,
+            field,
+            line,
+            ..
+        }) => {
+            assert_eq!(field, "transfrom");
+            assert_eq!(line, 8);
+            assert!(section.contains("[[scene.objects]]"), "{section}");
+        }
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("unknown key was accepted"),
+    }
+}
+
+#[test]
+fn unknown_key_in_settings_names_the_key() {
+    let source = SOURCE.replace("max_bounces", "max_bounce");
+    let error = match SimulationConfig::from_toml_str(&source) {
+        Err(e) => e,
+        Ok(_) => panic!("unknown key was accepted"),
+    };
+    let message = error.to_string();
+    assert!(message.contains("`max_bounce`"), "{message}");
+    assert!(message.contains("[simulation_settings]"), "{message}");
+    assert!(message.starts_with("3 行目"), "{message}");
+}
@@ -0,0 +1,158 @@
+// 交差判定と光路追跡のベンチマーク（cargo bench -p raytracing_core）
+// BVH・並列化・SIMD などの高速化を比べる基準にするため、レイの集合は乱数を使わず固定する
+use std::collections::HashMap;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use glam::{Mat4, Vec3};
+use raytracing_core::{
+    AxisAlignedBox, CSGObject, CsgOperation, FresnelMode, Hittable, Material, Plane, Ray, Scene,
+    SimulationSettingsConfig, Sphere, Transform,
+};
+
+// z = -10 の平面上の格子から +Z 方向へ、少しずつ傾けて飛ばすレイ（半分ほどが形状に当たる）
+fn ray_grid(count: usize) -> Vec<Ray> {
+    let mut rays = Vec::with_capacity(count * count);
+    for i in 0..count {
+        for j in 0..count {
+            let u = i as f32 / (count - 1) as f32 * 2.0 - 1.0;
+            let v = j as f32 / (count - 1) as f32 * 2.0 - 1.0;
+            let origin = Vec3::new(u * 3.0, v * 3.0, -10.0);
+            let direction = Vec3::new(u * 0.05, v * 0.05, 1.0).normalize();
+            rays.push(Ray::new(origin, direction, 1.0));
+        }
+    }
+    rays
+}
+
+fn glass() -> Material {
+    Material::Glass { ior: 1.5 }
+}
+
+fn sphere(center: Vec3, radius: f32) -> Sphere {
+    Sphere {
+        center,
+        radius,
+        material: glass(),
+    }
+}
+
+// 球から、ずらした球をくり抜いた形状
+fn hollowed_sphere() -> CSGObject {
+    CSGObject {
+        left: Box::new(sphere(Vec3::ZERO, 2.0)),
+        right: Box::new(sphere(Vec3::new(0.0, 0.0, -1.5), 1.5)),
+        operation: CsgOperation::Difference,
+    }
+}
+
+fn rotated_box() -> Transform {
+    let cube = AxisAlignedBox {
+        min: Vec3::splat(-1.5),
+        max: Vec3::splat(1.5),
+        material: glass(),
+    };
+    let matrix = Mat4::from_translation(Vec3::new(0.2, -0.1, 0.0))
+        * Mat4::from_rotation_y(0.4)
+        * Mat4::from_rotation_x(0.3);
+    Transform::new(Box::new(cube), matrix)
+}
+
+fn bench_intersect_all(c: &mut Criterion) {
+    let rays = ray_grid(32);
+    let shapes: Vec<(&str, Box<dyn Hittable>)> = vec![
+        ("sphere", Box::new(sphere(Vec3::ZERO, 2.0))),
+        (
+            "axis_aligned_box",
+            Box::new(AxisAlignedBox {
+                min: Vec3::splat(-1.5),
+                max: Vec3::splat(1.5),
+                material: glass(),
+            }),
+        ),
+        ("csg_difference", Box::new(hollowed_sphere())),
+        ("transform", Box::new(rotated_box())),
+    ];
+
+    let mut group = c.benchmark_group("intersect_all");
+    for (name, shape) in &shapes {
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                for ray in &rays {
+                    black_box(shape.intersect_all(black_box(ray), 0.001, f32::INFINITY));
+                }
+            })
+        });
+    }
+    group.finish();
+
+    // 同じレイの集合を intersect_batch でまとめて判定する
+    let origins: Vec<Vec3> = rays.iter().map(|ray| ray.origin).collect();
+    let directions: Vec<Vec3> = rays.iter().map(|ray| ray.direction).collect();
+    let mut group = c.benchmark_group("intersect_batch");
+    for (name, shape) in &shapes {
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                black_box(shape.intersect_batch(
+                    black_box(&origins),
+                    black_box(&directions),
+                    0.001,
+                    f32::INFINITY,
+                ))
+            })
+        });
+    }
+    group.finish();
+}
+
+// 両凸レンズ・くり抜いた球・傾けた箱・鏡・吸収体を並べたシーン
+fn representative_scene() -> Scene {
+    let lens = CSGObject {
+        left: Box::new(sphere(Vec3::new(0.0, 0.0, 8.0), 10.0)),
+        right: Box::new(sphere(Vec3::new(0.0, 0.0, -8.0), 10.0)),
+        operation: CsgOperation::Intersection,
+    };
+    let hollow = Transform::new(
+        Box::new(hollowed_sphere()),
+        Mat4::from_translation(Vec3::new(0.0, 0.0, 8.0)),
+    );
+    let tilted_box = Transform::new(
+        Box::new(rotated_box()),
+        Mat4::from_translation(Vec3::new(0.0, 0.0, 16.0)),
+    );
+    let mirror = Plane {
+        point: Vec3::new(0.0, -6.0, 0.0),
+        normal: Vec3::Y,
+        material: Material::Mirror,
+    };
+    let screen = Plane {
+        point: Vec3::new(0.0, 0.0, 30.0),
+        normal: Vec3::NEG_Z,
+        material: Material::Absorber,
+    };
+    Scene {
+        objects: vec![
+            Box::new(lens),
+            Box::new(hollow),
+            Box::new(tilted_box),
+            Box::new(mirror),
+            Box::new(screen),
+        ],
+        rays: ray_grid(24),
+        object_names: HashMap::new(),
+    }
+}
+
+fn bench_simulate_rays(c: &mut Criterion) {
+    let scene = representative_scene();
+    let setting = SimulationSettingsConfig {
+        fresnel_mode: FresnelMode::Deterministic,
+        ..Default::default()
+    };
+    c.bench_function("simulate_rays", |b| {
+        b.iter(|| black_box(scene.simulate_rays(black_box(setting))))
+    });
+}
+
+criterion_group!(benches, bench_intersect_all, bench_simulate_rays);
+criterion_main!(benches);
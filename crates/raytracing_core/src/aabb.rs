@@ -0,0 +1,83 @@
+use glam::{Mat4, Vec3};
+
+use crate::Ray;
+
+// 軸並行な境界ボックス（形状の外接範囲を表す）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self {
+            min: min.min(max),
+            max: min.max(max),
+        }
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    // 両方を含む最小のボックス
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    // 共通部分（重ならなければNone）
+    pub fn intersection(&self, other: &Aabb) -> Option<Aabb> {
+        let min = self.min.max(other.min);
+        let max = self.max.min(other.max);
+        if min.cmpgt(max).any() {
+            None
+        } else {
+            Some(Aabb { min, max })
+        }
+    }
+
+    // レイが t_min から t_max の間でボックスの中にある t の範囲（外れればNone）
+    pub fn clip_ray(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<(f32, f32)> {
+        let (mut t_enter, mut t_exit) = (t_min, t_max);
+        for axis in 0..3 {
+            let origin = ray.origin[axis];
+            let direction = ray.direction[axis];
+            if direction == 0.0 {
+                if origin < self.min[axis] || origin > self.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let t0 = (self.min[axis] - origin) / direction;
+            let t1 = (self.max[axis] - origin) / direction;
+            t_enter = t_enter.max(t0.min(t1));
+            t_exit = t_exit.min(t0.max(t1));
+        }
+        (t_enter < t_exit).then_some((t_enter, t_exit))
+    }
+
+    // 8頂点を変換し、それを包むボックスを返す
+    pub fn transformed(&self, matrix: &Mat4) -> Aabb {
+        let mut min = Vec3::splat(f32::INFINITY);
+        let mut max = Vec3::splat(f32::NEG_INFINITY);
+        for i in 0..8 {
+            let corner = Vec3::new(
+                if i & 1 == 0 { self.min.x } else { self.max.x },
+                if i & 2 == 0 { self.min.y } else { self.max.y },
+                if i & 4 == 0 { self.min.z } else { self.max.z },
+            );
+            let p = matrix.transform_point3(corner);
+            min = min.min(p);
+            max = max.max(p);
+        }
+        Aabb { min, max }
+    }
+}
@@ -0,0 +1,630 @@
+// 追跡結果（DetailedPath）を集計する解析関数群
+use std::f32::consts::FRAC_PI_2;
+
+use glam::{Mat3, Vec2, Vec3};
+
+use crate::{
+    geometric_epsilon, Aabb, DetailedPath, FresnelMode, HitRecord, InteractionKind, Material,
+    Plane, Ray, RehitMode, Scene, SimulationSettingsConfig, DEFAULT_MAX_INTERSECTION_HITS,
+};
+
+// 指定したオブジェクトへの入射角 acos(-dir・normal) を 0°〜90° の範囲で bins 個に分けて数える
+pub fn incidence_histogram(
+    detailed_paths: &[DetailedPath],
+    object_index: usize,
+    bins: usize,
+) -> Vec<u32> {
+    let mut histogram = vec![0; bins];
+    if bins == 0 {
+        return histogram;
+    }
+
+    for path in detailed_paths {
+        for interaction in &path.interactions {
+            if interaction.object_index != object_index {
+                continue;
+            }
+            let angle = incidence_angle(&interaction.hit);
+            let bin = (angle / FRAC_PI_2 * bins as f32) as usize;
+            histogram[bin.min(bins - 1)] += 1;
+        }
+    }
+    histogram
+}
+
+// 入射角 acos(-dir・normal)（ラジアン）
+// 法線は常にレイと向かい合っているので、角度は 0〜π/2 に収まる
+fn incidence_angle(hit: &HitRecord) -> f32 {
+    (-hit.incoming.normalize())
+        .dot(hit.normal)
+        .clamp(0.0, 1.0)
+        .acos()
+}
+
+// 1つの面に当たった1回の衝突の記録
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceHit {
+    pub path_index: usize,  // detailed_paths 内での光路の添字
+    pub position: Vec3,     // 衝突点
+    pub direction: Vec3,    // 衝突前の進行方向（単位ベクトル）
+    pub intensity: f32,     // 衝突直前の強度
+    pub incidence_deg: f32, // 入射角（度）
+}
+
+// 指定したオブジェクトへの衝突を、光路の順、光路内では衝突の順に並べる
+pub fn surface_hits(detailed_paths: &[DetailedPath], object_index: usize) -> Vec<SurfaceHit> {
+    detailed_paths
+        .iter()
+        .enumerate()
+        .flat_map(|(path_index, path)| {
+            path.interactions
+                .iter()
+                .filter(move |interaction| interaction.object_index == object_index)
+                .map(move |interaction| SurfaceHit {
+                    path_index,
+                    position: interaction.hit.point,
+                    direction: interaction.hit.incoming.normalize(),
+                    intensity: interaction.incoming_intensity,
+                    incidence_deg: incidence_angle(&interaction.hit).to_degrees(),
+                })
+        })
+        .collect()
+}
+
+// 最終点が point を中心とする半径 radius の球の中にある光路の数
+pub fn count_hits_near(detailed_paths: &[DetailedPath], point: Vec3, radius: f32) -> usize {
+    detailed_paths
+        .iter()
+        .filter_map(|path| path.points.last())
+        .filter(|last| last.distance(point) <= radius)
+        .count()
+}
+
+// 平面上の円形の検出器に当たった光路の数
+// 各光路が最初に平面を横切る点を、平面内の2次元座標 (plane_basis) に直して center2d からの距離を見る
+pub fn hits_on_plane_within(
+    detailed_paths: &[DetailedPath],
+    plane: &Plane,
+    center2d: Vec2,
+    radius: f32,
+) -> usize {
+    let normal = plane.normal.normalize();
+    let (u, v) = plane_basis(normal);
+    detailed_paths
+        .iter()
+        .filter_map(|path| first_plane_crossing(&path.points, plane.point, normal))
+        .filter(|crossing| {
+            let local = *crossing - plane.point;
+            Vec2::new(local.dot(u), local.dot(v)).distance(center2d) <= radius
+        })
+        .count()
+}
+
+// 検出器の形。平面内の2次元座標 (plane_basis) で、平面の基準点 plane.point を中心に置く
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DetectorShape {
+    /// u 方向の幅 2·half_width、v 方向の高さ 2·half_height の長方形
+    Rectangle { half_width: f32, half_height: f32 },
+    /// 半径 radius の円板
+    Disk { radius: f32 },
+}
+
+impl DetectorShape {
+    // 形を囲む長方形の半分の大きさ (u, v)
+    fn half_extent(&self) -> Vec2 {
+        match *self {
+            DetectorShape::Rectangle {
+                half_width,
+                half_height,
+            } => Vec2::new(half_width, half_height),
+            DetectorShape::Disk { radius } => Vec2::splat(radius),
+        }
+    }
+
+    fn contains(&self, local: Vec2) -> bool {
+        match *self {
+            DetectorShape::Rectangle {
+                half_width,
+                half_height,
+            } => local.x.abs() <= half_width && local.y.abs() <= half_height,
+            DetectorShape::Disk { radius } => local.length() <= radius,
+        }
+    }
+}
+
+// 検出器に当たった光の強度を平面内の格子に足し込み、集光模様（コースティクス）の像を作る
+// 各光路が最初に平面を横切る点に、その光路の強度（追跡終了時の値）を置く
+// 格子は検出器を囲む長方形を resolution = [nu, nv] 個に分け、添字 iu + nu * iv の順に並べる
+pub fn detector_image(
+    detailed_paths: &[DetailedPath],
+    plane: &Plane,
+    shape: DetectorShape,
+    resolution: [usize; 2],
+) -> Vec<f32> {
+    let [nu, nv] = resolution;
+    let mut image = vec![0.0; nu * nv];
+    let half = shape.half_extent();
+    if image.is_empty() || half.min_element() <= 0.0 {
+        return image;
+    }
+    let normal = plane.normal.normalize();
+    let (u, v) = plane_basis(normal);
+    for path in detailed_paths {
+        let Some(crossing) = first_plane_crossing(&path.points, plane.point, normal) else {
+            continue;
+        };
+        let offset = crossing - plane.point;
+        let local = Vec2::new(offset.dot(u), offset.dot(v));
+        if !shape.contains(local) {
+            continue;
+        }
+        // 長方形の端 (-half) を 0、反対の端 (+half) を 1 とした位置から格子の添字を決める
+        let cell = (local + half) / (2.0 * half) * Vec2::new(nu as f32, nv as f32);
+        let iu = (cell.x.max(0.0) as usize).min(nu - 1);
+        let iv = (cell.y.max(0.0) as usize).min(nv - 1);
+        image[iu + nu * iv] += path.intensity;
+    }
+    image
+}
+
+// 平面内の2次元座標の軸。u は世界座標のX軸（法線がX軸に近ければY軸）を平面に射影した向き、v = normal × u
+pub fn plane_basis(normal: Vec3) -> (Vec3, Vec3) {
+    let normal = normal.normalize();
+    let reference = if normal.x.abs() < 0.9 {
+        Vec3::X
+    } else {
+        Vec3::Y
+    };
+    let u = (reference - normal * reference.dot(normal)).normalize();
+    (u, normal.cross(u))
+}
+
+// 折れ線が最初に平面を横切る点
+fn first_plane_crossing(points: &[Vec3], plane_point: Vec3, normal: Vec3) -> Option<Vec3> {
+    points.windows(2).find_map(|segment| {
+        let d0 = (segment[0] - plane_point).dot(normal);
+        let d1 = (segment[1] - plane_point).dot(normal);
+        // 両端が同じ側にあれば横切っていない
+        if d0 * d1 > 0.0 || d0 == d1 {
+            return None;
+        }
+        let s = d0 / (d0 - d1);
+        Some(segment[0].lerp(segment[1], s))
+    })
+}
+
+// 波面を測る基準
+#[derive(Debug, Clone, Copy)]
+pub enum WavefrontReference<'a> {
+    /// 平面波の基準。各光路が最初にこの平面を横切る点までの光路長を比べる
+    Plane(&'a Plane),
+    /// 球面波の基準。各光路の最後の区間（を延長した直線）がこの点に最も近づく位置までの光路長を比べる
+    Point(Vec3),
+}
+
+// 基準での光路長のばらつき（平均からの RMS）を長さの単位で返す。波長で割れば波数になる
+// 基準に届かない光路は数えない。1本も届かなければ 0
+pub fn wavefront_rms(detailed_paths: &[DetailedPath], reference: WavefrontReference) -> f32 {
+    let lengths: Vec<f64> = detailed_paths
+        .iter()
+        .filter_map(|path| optical_length_at(path, reference))
+        .map(f64::from)
+        .collect();
+    if lengths.is_empty() {
+        return 0.0;
+    }
+
+    let count = lengths.len() as f64;
+    let mean = lengths.iter().sum::<f64>() / count;
+    let variance = lengths.iter().map(|l| (l - mean).powi(2)).sum::<f64>() / count;
+    variance.sqrt() as f32
+}
+
+// 基準の位置での光路長
+fn optical_length_at(path: &DetailedPath, reference: WavefrontReference) -> Option<f32> {
+    let lengths = &path.optical_lengths;
+    match reference {
+        WavefrontReference::Plane(plane) => {
+            let normal = plane.normal.normalize();
+            path.points.windows(2).enumerate().find_map(|(i, segment)| {
+                let d0 = (segment[0] - plane.point).dot(normal);
+                let d1 = (segment[1] - plane.point).dot(normal);
+                if d0 * d1 > 0.0 || d0 == d1 {
+                    return None;
+                }
+                let s = d0 / (d0 - d1);
+                Some(lengths[i] + (lengths[i + 1] - lengths[i]) * s)
+            })
+        }
+        WavefrontReference::Point(point) => {
+            let n = path.points.len();
+            if n < 2 {
+                return None;
+            }
+            let (start, end) = (path.points[n - 2], path.points[n - 1]);
+            let length = start.distance(end);
+            if length <= 0.0 {
+                return None;
+            }
+            // 区間の屈折率は光路長の増分から求める
+            let ior = (lengths[n - 1] - lengths[n - 2]) / length;
+            let s = (point - start).dot((end - start) / length);
+            Some(lengths[n - 2] + ior * s)
+        }
+    }
+}
+
+// 複数の直線 (点, 方向) に最も近い点を最小二乗法で求める（光線の集光点）
+pub fn focus_point(lines: &[(Vec3, Vec3)]) -> Option<Vec3> {
+    let mut a = Mat3::ZERO;
+    let mut b = Vec3::ZERO;
+    for &(point, direction) in lines {
+        let d = direction.normalize();
+        // 直線に垂直な成分への射影行列 I - d d^T
+        let projection = Mat3::IDENTITY - Mat3::from_cols(d * d.x, d * d.y, d * d.z);
+        a += projection;
+        b += projection * point;
+    }
+    // 全ての直線が平行なら集光点は決まらない
+    if a.determinant().abs() < 1e-6 {
+        return None;
+    }
+    Some(a.inverse() * b)
+}
+
+// 何かに当たった光路の最後の区間を延ばした直線の集光点
+pub fn exit_focus(detailed_paths: &[DetailedPath]) -> Option<Vec3> {
+    let lines: Vec<(Vec3, Vec3)> = detailed_paths
+        .iter()
+        .filter(|path| !path.interactions.is_empty())
+        .filter_map(|path| match path.points.as_slice() {
+            [.., start, end] if start != end => Some((*start, *end - *start)),
+            _ => None,
+        })
+        .collect();
+    focus_point(&lines)
+}
+
+// レンズ（またはレンズ群）の有効焦点距離を測定する
+// 光軸に平行な近軸光線を入射させ、集光点から最終面（後側主平面の近似）までの距離を返す
+// レンズの大きさが決まらない、光線がレンズに当たらない、平行光のまま出るなどで測れなければ NaN
+pub fn measure_efl(scene: &Scene, object_indices: &[usize], probe_direction: Vec3) -> f32 {
+    try_measure_efl(scene, object_indices, probe_direction).unwrap_or(f32::NAN)
+}
+
+fn try_measure_efl(scene: &Scene, object_indices: &[usize], probe_direction: Vec3) -> Option<f32> {
+    let bounds = object_indices
+        .iter()
+        .filter_map(|&index| scene.objects.get(index)?.bounding_box())
+        .reduce(|a, b| a.union(&b))?;
+
+    let axis = probe_direction.normalize();
+    let (u, v) = axis.any_orthonormal_pair();
+    let half_size = bounds.size() / 2.0;
+    let aperture_radius = half_size.dot(u.abs()).min(half_size.dot(v.abs()));
+    let paraxial_height = aperture_radius * 0.02;
+    let distance = bounds.size().length();
+    let start = bounds.center() - axis * distance;
+
+    let setting = SimulationSettingsConfig {
+        infinity_distance: distance,
+        max_bounces: 64,
+        max_reflections: 64,
+        max_refractions: 64,
+        fresnel_mode: FresnelMode::AlwaysRefract,
+        ..Default::default()
+    };
+    // レンズを最後に出た点とその方向
+    let exit_line = |offset: Vec3| {
+        let path = scene.trace_path(Ray::new(start + offset, axis, 1.0), setting);
+        path.interactions
+            .iter()
+            .rev()
+            .find(|interaction| object_indices.contains(&interaction.object_index))
+            .map(|interaction| (interaction.hit.point, interaction.outgoing_dir))
+    };
+
+    // 光軸上の光線の最終面を後側主平面とみなす
+    let (last_surface, _) = exit_line(Vec3::ZERO)?;
+    let lines: Vec<(Vec3, Vec3)> = [u, -u, v, -v]
+        .iter()
+        .filter_map(|&offset| exit_line(offset * paraxial_height))
+        .collect();
+    let focus = focus_point(&lines)?;
+
+    Some((focus - last_surface).dot(axis))
+}
+
+// 主光線と周辺光線を求める割線法の反復回数の上限
+const AIM_MAX_ITERATIONS: usize = 32;
+// 周辺光線は開口の縁のわずかに内側を通す（縁ちょうどでは絞りの壁をかすめて吸収されることがある）
+const MARGINAL_FRACTION: f32 = 0.999;
+
+// 絞り（aperture_index のオブジェクト）を通る主光線と周辺光線を追跡し、(主光線, 周辺光線) の光路を返す
+// 光軸は +Z 方向で、画角 field_deg だけ +Y 側に傾けた平行光のうち、
+// 絞りの中心を通るものを主光線、+Y 側の縁を通るものを周辺光線とする
+// 絞りより前の面で曲がる分は、絞りの面を横切る高さが目標に合うまで入射位置を割線法で調整する
+// 絞りの中心と半径は、外接ボックスの中心と、そこから +Y 方向に最初に当たる面までの距離で決める
+// （穴の空いた板なら穴の半径、レンズなら縁までの距離）
+// 高さは絞りの中心を通る z 一定の面で合わせるので、厚い絞りでは周辺光線が手前の壁に当たることがある
+pub fn chief_and_marginal(
+    scene: &Scene,
+    aperture_index: usize,
+    field_deg: f32,
+) -> Option<(DetailedPath, DetailedPath)> {
+    let (center, semi_aperture) = stop_center_and_radius(scene, aperture_index)?;
+
+    let bounds = scene.bounding_box()?;
+    let distance = bounds.size().length();
+    let start_z = bounds.min.z - distance;
+    let setting = SimulationSettingsConfig {
+        infinity_distance: distance * 4.0,
+        max_bounces: 64,
+        max_reflections: 64,
+        max_refractions: 64,
+        fresnel_mode: FresnelMode::AlwaysRefract,
+        rehit_mode: RehitMode::Nudge,
+        adaptive: None,
+        gap_decay_length: None,
+        max_intersection_hits: DEFAULT_MAX_INTERSECTION_HITS,
+    };
+
+    let field = field_deg.to_radians();
+    let direction = Vec3::new(0.0, field.sin(), field.cos());
+    // 入射位置の高さ h のレイを追跡し、絞りの面を横切る高さとの組を返す
+    let trace = |h: f32| {
+        let path = scene.trace_path(
+            Ray::new(Vec3::new(center.x, h, start_z), direction, 1.0),
+            setting,
+        );
+        // 絞りの手前で遮られた場合は、最後の区間を延ばして絞りの面での高さを求める
+        // （目標から外れたレイでも高さが連続に変わるので、割線法が止まらない）
+        let crossing = first_plane_crossing(&path.points, center, Vec3::Z).or_else(|| {
+            let [.., p0, p1] = path.points.as_slice() else {
+                return None;
+            };
+            let step = *p1 - *p0;
+            (step.z > 0.0).then(|| *p1 + step * ((center.z - p1.z) / step.z))
+        })?;
+        Some((crossing.y, path))
+    };
+    let aim = |target_y: f32| {
+        // 直進するとした場合の高さから始める
+        let mut h0 = target_y - field.tan() * (center.z - start_z);
+        let (mut y0, _) = trace(h0)?;
+        let mut h1 = h0 + semi_aperture * 0.01;
+        for _ in 0..AIM_MAX_ITERATIONS {
+            let (y1, path) = trace(h1)?;
+            if (y1 - target_y).abs() < geometric_epsilon() {
+                return Some(path);
+            }
+            if y1 == y0 {
+                return None;
+            }
+            let h2 = h1 - (y1 - target_y) * (h1 - h0) / (y1 - y0);
+            (h0, y0, h1) = (h1, y1, h2);
+        }
+        None
+    };
+
+    let chief = aim(center.y)?;
+    let marginal = aim(center.y + semi_aperture * MARGINAL_FRACTION)?;
+    Some((chief, marginal))
+}
+
+// 絞りの中心（外接ボックスの中心）と半径（中心から +Y 方向に最初に当たる面までの距離）
+fn stop_center_and_radius(scene: &Scene, stop_index: usize) -> Option<(Vec3, f32)> {
+    let stop = scene.objects.get(stop_index)?;
+    let center = stop.bounding_box()?.center();
+    let radius = stop
+        .intersect_all(&Ray::new(center, Vec3::Y, 1.0), 0.0, f32::INFINITY)?
+        .iter()
+        .map(|hit| hit.t)
+        .reduce(f32::min)?;
+    Some((center, radius))
+}
+
+// 入射瞳（絞りより前の面が作る絞りの像）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntrancePupil {
+    pub position: Vec3, // 光軸上の入射瞳の中心
+    pub diameter: f32,
+}
+
+// 近軸の入射瞳を求めるときの、逆向きに追跡するレイの傾き[rad]と、絞りの半径に対する高さの割合
+const PUPIL_PARAXIAL_ANGLE: f32 = 1e-3;
+const PUPIL_PARAXIAL_FRACTION: f32 = 0.01;
+
+// 絞り（stop_index のオブジェクト）から近軸の入射瞳の位置と直径を求める
+// 光軸は絞りの中心を通る +Z 方向とし、絞りの中心と半径は chief_and_marginal と同じく決める
+// 絞りの中心から光軸にわずかに傾けたレイ（主光線）と、縁の近くから光軸に平行なレイ（周辺光線）を -Z 方向へ逆向きに追跡し、
+// 絞りより前の面を抜けた後の直線を延ばす。主光線が光軸と交わる点が入射瞳の位置、そこでの周辺光線の高さが半径になる
+// 絞りより前に面が無ければ絞りそのもの。抜けた主光線が光軸と平行（入射瞳が無限遠）か、途中で止まった場合は None
+pub fn entrance_pupil(scene: &Scene, stop_index: usize) -> Option<EntrancePupil> {
+    let (center, semi_aperture) = stop_center_and_radius(scene, stop_index)?;
+    let distance = scene.bounding_box()?.size().length();
+    let setting = SimulationSettingsConfig {
+        infinity_distance: distance * 4.0,
+        max_bounces: 64,
+        max_reflections: 64,
+        max_refractions: 64,
+        fresnel_mode: FresnelMode::AlwaysRefract,
+        rehit_mode: RehitMode::Nudge,
+        adaptive: None,
+        gap_decay_length: None,
+        max_intersection_hits: DEFAULT_MAX_INTERSECTION_HITS,
+    };
+    // 絞りより前の面を最後に抜けた点と方向（飛び去らなかった光路は使わない）
+    let object_space_line = |origin: Vec3, direction: Vec3| {
+        let path = scene.trace_path(Ray::new(origin, direction, 1.0), setting);
+        if !path.escaped {
+            return None;
+        }
+        Some(match path.interactions.last() {
+            Some(interaction) => (interaction.hit.point, interaction.outgoing_dir),
+            None => (origin, direction),
+        })
+    };
+
+    let (chief_point, chief_dir) = object_space_line(
+        center,
+        Vec3::new(0.0, PUPIL_PARAXIAL_ANGLE.sin(), -PUPIL_PARAXIAL_ANGLE.cos()),
+    )?;
+    let s = (center.y - chief_point.y) / chief_dir.y;
+    if !s.is_finite() {
+        return None;
+    }
+    let position = chief_point + chief_dir * s;
+
+    let edge = center + Vec3::Y * semi_aperture * PUPIL_PARAXIAL_FRACTION;
+    let (edge_point, edge_dir) = object_space_line(edge, Vec3::NEG_Z)?;
+    let height = edge_point.y + edge_dir.y * (position.z - edge_point.z) / edge_dir.z - center.y;
+    let diameter = 2.0 * (height / PUPIL_PARAXIAL_FRACTION).abs();
+    diameter
+        .is_finite()
+        .then_some(EntrancePupil { position, diameter })
+}
+
+// 迷光の内訳。主な光路から外れた強度を、原因となった衝突の種類ごとに足し合わせたもの
+// 拡散面での散乱は、拡散面の材質が無いので扱わない
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StrayLightBudget {
+    pub fresnel_reflection: f32, // ガラス面・多層膜フィルタで、辿った側と反対側（反射光か透過光）へ分かれた強度
+    pub mirror_absorption: f32,  // 金属鏡で反射されずに吸収された強度
+    pub total_internal_reflection: f32, // ガラス内で全反射した光の強度（向きが変わるだけで失われない）
+    pub absorbed: f32,                  // 吸収体に当たって止まった強度
+}
+
+// 順向きに追跡した光路から迷光の内訳を求める（reversed で並べ替えた光路は渡さない）
+// 各衝突で失った強度は、次の衝突の直前の強度（最後の衝突なら光路の最終的な強度）との差で求める
+// 反射光の分岐が分岐元から引き継いだ衝突は、分岐元の光路で数えるので除く
+pub fn stray_light_budget(detailed_paths: &[DetailedPath]) -> StrayLightBudget {
+    let mut budget = StrayLightBudget::default();
+    for path in detailed_paths {
+        let interactions = &path.interactions;
+        for (i, interaction) in interactions.iter().enumerate() {
+            if interaction.inherited {
+                continue;
+            }
+            let incoming = interaction.incoming_intensity;
+            let outgoing = interactions
+                .get(i + 1)
+                .map_or(path.intensity, |next| next.incoming_intensity);
+            let lost = (incoming - outgoing).max(0.0);
+            match &interaction.hit.material {
+                Material::Glass { .. }
+                | Material::GlassByAbbe { .. }
+                | Material::IdealGlass { .. }
+                    if interaction.kind == InteractionKind::Reflection && !interaction.ghost =>
+                {
+                    budget.total_internal_reflection += incoming;
+                }
+                Material::Glass { .. }
+                | Material::GlassByAbbe { .. }
+                | Material::IdealGlass { .. }
+                | Material::MultilayerFilter { .. } => budget.fresnel_reflection += lost,
+                Material::MetalMirror { .. } => budget.mirror_absorption += lost,
+                Material::Absorber => budget.absorbed += incoming,
+                _ => {}
+            }
+        }
+    }
+    budget
+}
+
+// 光路の各区間の長さを、通過したボクセルに振り分けて足し込む（3次元DDA）。光の通った量の密度になる
+// bounds を resolution = [nx, ny, nz] 個のボクセルに分け、添字 x + nx * (y + ny * z) の順に並べて返す
+// bounds の外にはみ出た部分は数えない
+pub fn voxelize(detailed_paths: &[DetailedPath], bounds: Aabb, resolution: [usize; 3]) -> Vec<f32> {
+    let mut grid = vec![0.0; resolution.iter().product()];
+    let cell = bounds.size() / Vec3::from_array(resolution.map(|n| n as f32));
+    if grid.is_empty() || cell.min_element() <= 0.0 {
+        return grid;
+    }
+    for path in detailed_paths {
+        for segment in path.points.windows(2) {
+            deposit_segment(&mut grid, &bounds, resolution, cell, segment[0], segment[1]);
+        }
+    }
+    grid
+}
+
+// 1つの区間 start → end を、通過するボクセルごとの長さに分けて grid に足す
+fn deposit_segment(
+    grid: &mut [f32],
+    bounds: &Aabb,
+    resolution: [usize; 3],
+    cell: Vec3,
+    start: Vec3,
+    end: Vec3,
+) {
+    let delta = end - start;
+    let length = delta.length();
+    if length == 0.0 {
+        return;
+    }
+    // 区間のうちボックスの中にある範囲（区間の始点を0、終点を1とする）
+    let (mut t_enter, mut t_exit) = (0.0_f32, 1.0_f32);
+    for axis in 0..3 {
+        if delta[axis] == 0.0 {
+            if start[axis] < bounds.min[axis] || start[axis] > bounds.max[axis] {
+                return;
+            }
+            continue;
+        }
+        let t0 = (bounds.min[axis] - start[axis]) / delta[axis];
+        let t1 = (bounds.max[axis] - start[axis]) / delta[axis];
+        t_enter = t_enter.max(t0.min(t1));
+        t_exit = t_exit.min(t0.max(t1));
+    }
+    if t_enter >= t_exit {
+        return;
+    }
+
+    // 入った点のボクセルと、各軸で次のボクセル境界に達する t
+    let entry = start + delta * t_enter;
+    let mut voxel = [0_usize; 3];
+    let mut t_next = [f32::INFINITY; 3];
+    let mut t_step = [f32::INFINITY; 3];
+    for axis in 0..3 {
+        let local = (entry[axis] - bounds.min[axis]) / cell[axis];
+        voxel[axis] = (local.floor().max(0.0) as usize).min(resolution[axis] - 1);
+        if delta[axis] != 0.0 {
+            let boundary_index = if delta[axis] > 0.0 {
+                voxel[axis] + 1
+            } else {
+                voxel[axis]
+            };
+            let boundary = bounds.min[axis] + boundary_index as f32 * cell[axis];
+            t_next[axis] = (boundary - start[axis]) / delta[axis];
+            t_step[axis] = cell[axis] / delta[axis].abs();
+        }
+    }
+
+    let mut t = t_enter;
+    loop {
+        let axis = (0..3)
+            .min_by(|&a, &b| t_next[a].total_cmp(&t_next[b]))
+            .expect("軸は3つある");
+        let t_end = t_next[axis].min(t_exit);
+        let index = voxel[0] + resolution[0] * (voxel[1] + resolution[1] * voxel[2]);
+        grid[index] += (t_end - t).max(0.0) * length;
+        if t_end >= t_exit {
+            break;
+        }
+        t = t_end;
+        // 隣のボクセルへ進む（格子の外に出たら終わり）
+        if delta[axis] > 0.0 {
+            voxel[axis] += 1;
+            if voxel[axis] >= resolution[axis] {
+                break;
+            }
+        } else {
+            if voxel[axis] == 0 {
+                break;
+            }
+            voxel[axis] -= 1;
+        }
+        t_next[axis] += t_step[axis];
+    }
+}
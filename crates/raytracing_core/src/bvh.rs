@@ -0,0 +1,194 @@
+// オブジェクトの外接ボックスの階層（BVH）で、レイに最も近い衝突を探す
+//
+// 外接ボックスを最も長い軸の中央で2つに分けていき、葉には1つのオブジェクトを置く
+// 探索はレイが近くで入る子から辿り、見つかった衝突より奥で入るボックスは調べずに打ち切る
+// 外接ボックスの無い（無限に広がる）オブジェクトは階層に入れず、毎回すべて調べる
+use glam::Vec3;
+
+use crate::{geometric_epsilon, Aabb, HitRecord, Hittable, Ray};
+
+// 外接ボックスを広げる距離（長さの許容誤差に対する倍率）。面がボックスの境界に乗る形状の見落としを防ぐ
+const BOX_PADDING_FACTOR: f32 = 10.0;
+
+#[derive(Debug, Clone)]
+enum BvhNode {
+    Leaf {
+        bounds: Aabb,
+        object: usize,
+    },
+    Branch {
+        bounds: Aabb,
+        left: usize,
+        right: usize,
+    },
+}
+
+impl BvhNode {
+    fn bounds(&self) -> &Aabb {
+        match self {
+            BvhNode::Leaf { bounds, .. } | BvhNode::Branch { bounds, .. } => bounds,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>, // 最後の要素が根
+    unbounded: Vec<usize>,
+    max_hits: Option<usize>, // 1つのオブジェクトとの交差判定で求めるヒット数の上限（None なら無制限）
+}
+
+impl Bvh {
+    // objects の並びの添字で階層を作る（長さの許容誤差を設定した後で作ること）
+    pub fn build(objects: &[Box<dyn Hittable>]) -> Bvh {
+        let padding = Vec3::splat(geometric_epsilon() * BOX_PADDING_FACTOR);
+        let mut bvh = Bvh::default();
+        let mut bounded = Vec::new();
+        for (index, object) in objects.iter().enumerate() {
+            match object.bounding_box() {
+                Some(bounds) => {
+                    bounded.push((index, Aabb::new(bounds.min - padding, bounds.max + padding)))
+                }
+                None => bvh.unbounded.push(index),
+            }
+        }
+        if !bounded.is_empty() {
+            bvh.build_node(&mut bounded);
+        }
+        bvh
+    }
+
+    // 交差判定で求めるヒット数に上限を付ける（入れ子の深いCSGでヒットの列が長くなり過ぎないように）
+    pub fn with_max_hits(mut self, max_hits: usize) -> Bvh {
+        self.max_hits = Some(max_hits.max(1));
+        self
+    }
+
+    // items を葉に持つ部分木を作り、その根の添字を返す
+    fn build_node(&mut self, items: &mut [(usize, Aabb)]) -> usize {
+        if let [(object, bounds)] = *items {
+            self.nodes.push(BvhNode::Leaf { bounds, object });
+            return self.nodes.len() - 1;
+        }
+        let bounds = items
+            .iter()
+            .skip(1)
+            .fold(items[0].1, |bounds, (_, b)| bounds.union(b));
+        let size = bounds.size();
+        let axis = if size.x >= size.y && size.x >= size.z {
+            0
+        } else if size.y >= size.z {
+            1
+        } else {
+            2
+        };
+        items.sort_by(|(_, a), (_, b)| a.center()[axis].total_cmp(&b.center()[axis]));
+        let (left_items, right_items) = items.split_at_mut(items.len() / 2);
+        let left = self.build_node(left_items);
+        let right = self.build_node(right_items);
+        self.nodes.push(BvhNode::Branch {
+            bounds,
+            left,
+            right,
+        });
+        self.nodes.len() - 1
+    }
+
+    // Scene::closest_hit と同じ衝突を返す（同じ t なら添字の小さいオブジェクト）
+    // objects は build に渡したものと同じであること
+    pub fn closest_hit(
+        &self,
+        objects: &[Box<dyn Hittable>],
+        ray: &Ray,
+        t_min: f32,
+        t_max: f32,
+    ) -> Option<(usize, HitRecord)> {
+        self.closest_hit_capped(objects, ray, t_min, t_max).0
+    }
+
+    // closest_hit と同じ衝突と、ヒットの列を max_hits で切り詰めたオブジェクトがあったか
+    pub fn closest_hit_capped(
+        &self,
+        objects: &[Box<dyn Hittable>],
+        ray: &Ray,
+        t_min: f32,
+        t_max: f32,
+    ) -> (Option<(usize, HitRecord)>, bool) {
+        let mut closest: Option<(usize, HitRecord)> = None;
+        let mut t_closest = t_max;
+        let mut truncated = false;
+        let test = |index: usize,
+                    closest: &mut Option<(usize, HitRecord)>,
+                    t_closest: &mut f32,
+                    truncated: &mut bool| {
+            // 見つかった衝突と同じ t の衝突も、添字が小さければ拾えるようにする
+            let ties = closest.as_ref().is_some_and(|(found, _)| index < *found);
+            let t_limit = if ties {
+                t_closest.next_up()
+            } else {
+                *t_closest
+            };
+            let hits = match self.max_hits {
+                // 1つ多く求めて、上限を超えていれば切り詰めたことを記録する
+                Some(max_hits) => objects[index]
+                    .intersect_nearest(ray, t_min, t_limit, max_hits.saturating_add(1))
+                    .map(|mut hits| {
+                        if hits.len() > max_hits {
+                            hits.truncate(max_hits);
+                            *truncated = true;
+                        }
+                        hits
+                    }),
+                None => objects[index].intersect_all(ray, t_min, t_limit),
+            };
+            if let Some(hits) = hits
+                && let Some(first_hit) = hits.first()
+                && (first_hit.t < *t_closest || (ties && first_hit.t == *t_closest))
+            {
+                *t_closest = first_hit.t;
+                *closest = Some((index, first_hit.clone()));
+            }
+        };
+
+        for &index in &self.unbounded {
+            test(index, &mut closest, &mut t_closest, &mut truncated);
+        }
+        let Some(root) = self.nodes.len().checked_sub(1) else {
+            return (closest, truncated);
+        };
+        // (ノード, ボックスに入る t) を、近いものが後ろ（先に取り出される）になるように積む
+        let mut stack = Vec::new();
+        if let Some((t_enter, _)) = self.nodes[root].bounds().clip_ray(ray, t_min, t_closest) {
+            stack.push((root, t_enter));
+        }
+        while let Some((node, t_enter)) = stack.pop() {
+            // 見つかった衝突より奥で入るボックスには、それより近い衝突は無い
+            if t_enter > t_closest {
+                continue;
+            }
+            match self.nodes[node] {
+                BvhNode::Leaf { object, .. } => {
+                    test(object, &mut closest, &mut t_closest, &mut truncated)
+                }
+                BvhNode::Branch { left, right, .. } => {
+                    let enter = |child: usize| {
+                        self.nodes[child]
+                            .bounds()
+                            .clip_ray(ray, t_min, t_closest)
+                            .map(|(t_enter, _)| (child, t_enter))
+                    };
+                    match (enter(left), enter(right)) {
+                        (Some(a), Some(b)) => {
+                            let (near, far) = if a.1 <= b.1 { (a, b) } else { (b, a) };
+                            stack.push(far);
+                            stack.push(near);
+                        }
+                        (Some(only), None) | (None, Some(only)) => stack.push(only),
+                        (None, None) => {}
+                    }
+                }
+            }
+        }
+        (closest, truncated)
+    }
+}
@@ -0,0 +1,142 @@
+use std::fmt;
+
+use glam::Vec3;
+
+use crate::{Hittable, Ray, Scene};
+
+// 外接ボックスを持たない形状（平面など）に試験光線を撃つ範囲の半径
+const UNBOUNDED_TEST_RADIUS: f32 = 10.0;
+// 1つの形状に撃つ試験光線の本数
+const TEST_RAY_COUNT: usize = 256;
+
+// 入射/出射の整合性が取れていない形状の報告
+#[derive(Debug, Clone)]
+pub struct CsgIssue {
+    pub object_index: usize, // Scene.objects 内での添字
+    pub path: String,        // 形状木の中での位置（例: "left.right"）
+    pub failed_rays: usize,  // 不整合が見つかった試験光線の数
+    pub tested_rays: usize,
+}
+
+impl fmt::Display for CsgIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "オブジェクト {} の {} が閉じた立体になっていないか、入射/出射の向きが内外判定と一致しません ({}/{} 本の試験光線)",
+            self.object_index, self.path, self.failed_rays, self.tested_rays
+        )
+    }
+}
+
+impl Scene {
+    // 各オブジェクトの形状木（CSGの子を含む）に試験光線を撃ち、
+    // 閉じているはずの形状のうち、外側から撃った光線で front_face の並びが
+    // contains による内外判定と食い違うものを列挙する
+    pub fn check_csg_consistency(&self) -> Vec<CsgIssue> {
+        let mut issues = Vec::new();
+        for (object_index, object) in self.objects.iter().enumerate() {
+            check_node(
+                object.as_ref(),
+                object_index,
+                "root".to_string(),
+                true,
+                &mut issues,
+            );
+        }
+        issues
+    }
+}
+
+// 閉じているはずの形状は、外接ボックスを持つ形状と、オブジェクトの根にある CSG の組み合わせ。
+// 平面や無限円錐のような開いた面は front_face が法線の向きで決まり、内外判定とは結び付かないので調べない
+// （CSG の途中の半空間の積なども同じ）
+fn check_node(
+    node: &dyn Hittable,
+    object_index: usize,
+    path: String,
+    is_root: bool,
+    issues: &mut Vec<CsgIssue>,
+) {
+    let children = node.children();
+    let is_composite = children.len() >= 2;
+    if node.bounding_box().is_some() || (is_root && is_composite) {
+        let rays = test_rays(node);
+        let failed_rays = rays
+            .iter()
+            .filter(|ray| !closed_consistent(node, ray))
+            .count();
+        if failed_rays > 0 {
+            issues.push(CsgIssue {
+                object_index,
+                path: path.clone(),
+                failed_rays,
+                tested_rays: rays.len(),
+            });
+        }
+    }
+
+    // 子はそれぞれ自分の座標系で試験する（Transformの内側はローカル空間）
+    // Transform などの単体の包みの中身は、根の形状としてそのまま扱う
+    for (name, child) in children.iter() {
+        check_node(
+            *child,
+            object_index,
+            format!("{}.{}", path, name),
+            is_root && !is_composite,
+            issues,
+        );
+    }
+}
+
+// 外側から撃ったレイに沿って、入射と出射が交互に現れ、最後は外部に抜けるかを調べる
+fn closed_consistent(node: &dyn Hittable, ray: &Ray) -> bool {
+    // 試験光線は形状を囲む球の外から撃つので、閉じた立体なら始点は外部にある
+    if node.contains(ray.origin) {
+        return false;
+    }
+    let Some(hits) = node.intersect_all(ray, 0.0, f32::INFINITY) else {
+        return true;
+    };
+
+    let mut inside = false;
+    for hit in &hits {
+        // 接線方向に近い交点は判定が不安定なので数えない
+        if ray.direction.dot(hit.normal).abs() < 1e-3 {
+            return true;
+        }
+        // 外から入るときだけ front_face が立っているはず
+        if hit.front_face == inside {
+            return false;
+        }
+        inside = !inside;
+    }
+    // 内部のまま無限遠へ抜けるなら、形状が閉じていない
+    !inside
+}
+
+// 形状の外接ボックス（無ければ原点周り）を囲む球面から、内側へ向けて試験光線を撃つ
+fn test_rays(node: &dyn Hittable) -> Vec<Ray> {
+    let (center, radius) = match node.bounding_box() {
+        Some(aabb) => (aabb.center(), aabb.size().length().max(1e-3)),
+        None => (Vec3::ZERO, UNBOUNDED_TEST_RADIUS),
+    };
+
+    // フィボナッチ球面で方向を、黄金比の列で中心からのずれを決める（結果を再現可能にするため）
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0_f32.sqrt());
+    (0..TEST_RAY_COUNT)
+        .map(|i| {
+            let y = 1.0 - 2.0 * (i as f32 + 0.5) / TEST_RAY_COUNT as f32;
+            let r = (1.0 - y * y).sqrt();
+            let phi = golden_angle * i as f32;
+            let direction = Vec3::new(r * phi.cos(), y, r * phi.sin());
+
+            let u = direction.any_orthonormal_vector();
+            let v = direction.cross(u);
+            let a = (i as f32 * 0.618_034).fract() * 2.0 - 1.0;
+            let b = (i as f32 * 0.754_878).fract() * 2.0 - 1.0;
+            let offset = (u * a + v * b) * radius * 0.5;
+
+            Ray::new(center + offset - direction * radius * 2.0, direction, 1.0)
+        })
+        .collect()
+}
@@ -1,5 +1,22 @@
+pub mod aabb;
+pub mod analysis;
+pub mod bvh;
+pub mod consistency;
+pub mod pinhole;
 pub mod primitives;
 pub mod scene;
+pub mod tessellate;
+pub mod thin_film;
+pub mod units;
+pub mod validation;
 
+pub use aabb::*;
+pub use bvh::*;
+pub use consistency::*;
+pub use pinhole::*;
 pub use primitives::*;
 pub use scene::*;
+pub use tessellate::*;
+pub use thin_film::*;
+pub use units::*;
+pub use validation::*;
@@ -0,0 +1,67 @@
+// ピンホールカメラから見たシーンの画像（Bevy を使わない簡単な撮像）
+//
+// 画素の中心を通るレイをカメラの位置から飛ばし、最初に当たった形状の材質の表示色 (Material::display_color) で塗る
+// 反射や屈折は辿らない。何にも当たらない画素は BACKGROUND_COLOR にする
+use glam::Vec3;
+
+use crate::{geometric_epsilon, Ray, Scene};
+
+// 何にも当たらなかった画素の色
+pub const BACKGROUND_COLOR: [f32; 3] = [0.0, 0.0, 0.0];
+
+// カメラの位置と向き
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraPose {
+    pub position: Vec3,
+    pub forward: Vec3,         // 画像の中心の向き
+    pub up: Vec3,              // 画像の上の向き（forward と平行でないこと）
+    pub vertical_fov_deg: f32, // 縦の画角（度）。横の画角は画像の縦横比で決まる
+}
+
+// sRGB の RGB（各0.0〜1.0）の画像。pixels は上の行から、各行は左から並ぶ
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[f32; 3]>,
+}
+
+impl Image {
+    pub fn pixel(&self, x: usize, y: usize) -> [f32; 3] {
+        self.pixels[y * self.width + x]
+    }
+}
+
+// resolution は [幅, 高さ]（画素数）
+pub fn render_pinhole(scene: &Scene, camera: &CameraPose, resolution: [usize; 2]) -> Image {
+    let [width, height] = resolution;
+    let forward = camera.forward.normalize();
+    let right = forward.cross(camera.up).normalize();
+    let up = right.cross(forward);
+    let half_height = (camera.vertical_fov_deg.to_radians() / 2.0).tan();
+    let half_width = half_height * width as f32 / height.max(1) as f32;
+
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            // 画素の中心の、画像の中心からの位置（-1〜1）
+            let u = (x as f32 + 0.5) / width as f32 * 2.0 - 1.0;
+            let v = 1.0 - (y as f32 + 0.5) / height as f32 * 2.0;
+            let direction = forward + right * (u * half_width) + up * (v * half_height);
+            let ray = Ray::new(camera.position, direction.normalize(), 1.0);
+            let color = match scene.closest_hit(&ray, geometric_epsilon(), f32::INFINITY) {
+                Some((_, hit)) => {
+                    let [r, g, b, _] = hit.material.display_color();
+                    [r, g, b]
+                }
+                None => BACKGROUND_COLOR,
+            };
+            pixels.push(color);
+        }
+    }
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
@@ -0,0 +1,86 @@
+use crate::validation::{non_finite, nonzero, positive};
+use crate::{Aabb, HitRecord, Hittable, Material, Ray};
+use glam::Vec3;
+
+// 中心 center を通り normal に垂直な平面上の、内径 inner_radius から外径 outer_radius までの輪（ワッシャー）
+// 中心の穴と外周より外は素通りする。吸収体の材質と組み合わせて開口を作る
+// 中心遮蔽のある系（カセグレンなど）では、副鏡の影を内径 0 の輪、外側の絞りを内径が開口の半径の輪で表す
+#[derive(Debug, Clone)]
+pub struct Annulus {
+    pub center: Vec3,
+    pub normal: Vec3,
+    pub inner_radius: f32, // 0 なら穴の無い円板
+    pub outer_radius: f32,
+    pub material: Material,
+}
+
+impl Hittable for Annulus {
+    fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
+        let outward_normal = self.normal.normalize();
+        let denom = outward_normal.dot(ray.direction);
+
+        // レイが平面と平行な場合は衝突しない
+        if denom.abs() < 1e-6 {
+            return None;
+        }
+
+        let t = (self.center - ray.origin).dot(outward_normal) / denom;
+        if t < t_min || t_max < t {
+            return None;
+        }
+
+        // 平面内での中心からの距離が輪の範囲になければ素通りする
+        let point = ray.origin + t * ray.direction;
+        let radius = (point - self.center).length();
+        if radius < self.inner_radius || self.outer_radius < radius {
+            return None;
+        }
+
+        // 厚さがないので、法線側から当たった場合を表面とする
+        let front_face = denom < 0.0;
+        let normal = if front_face {
+            outward_normal
+        } else {
+            -outward_normal
+        };
+
+        Some(vec![HitRecord {
+            t,
+            point,
+            normal,
+            front_face,
+            incoming: ray.direction,
+            material: self.material.clone(),
+        }])
+    }
+
+    fn degeneracy(&self) -> Option<String> {
+        non_finite("center", self.center)
+            .or_else(|| nonzero("normal", self.normal))
+            .or_else(|| positive("outer_radius", self.outer_radius))
+            .or_else(|| {
+                (!(0.0..self.outer_radius).contains(&self.inner_radius)).then(|| {
+                    format!(
+                        "inner_radius は 0 以上 outer_radius ({}) 未満にしてください ({})",
+                        self.outer_radius, self.inner_radius
+                    )
+                })
+            })
+    }
+
+    // 厚さのない面なので内部は存在しない
+    fn contains(&self, _point: Vec3) -> bool {
+        false
+    }
+
+    // 平面内の半径 outer_radius の円を囲むボックス（各軸の広がりは outer_radius × √(1 - n²)）
+    fn bounding_box(&self) -> Option<Aabb> {
+        let n = self.normal.normalize();
+        let extent = self.outer_radius * (Vec3::ONE - n * n).max(Vec3::ZERO).powf(0.5);
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+
+    fn material(&self) -> Option<&Material> {
+        Some(&self.material)
+    }
+}
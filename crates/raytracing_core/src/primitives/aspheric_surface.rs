@@ -0,0 +1,243 @@
+use crate::validation::{non_finite, unit};
+use crate::{geometric_epsilon, HitRecord, Hittable, Material, Ray};
+use glam::Vec3;
+
+// ニュートン法の反復回数の上限と、収束とみなす残差
+const NEWTON_MAX_ITERATIONS: usize = 32;
+// 反復を打ち切る高さの残差と、同じ交点とみなす解の距離（どちらも長さの許容誤差に対する倍率）
+const NEWTON_TOLERANCE_FACTOR: f32 = 0.01;
+const ROOT_MERGE_FACTOR: f32 = 0.1;
+// 根を探す各区間をさらに分ける数（1区間に2つの根があっても見落とさないように）
+const SUBDIVISIONS: usize = 8;
+// t_max が無限のときに根を探す距離
+const FAR_SEARCH_DISTANCE: f32 = 1e4;
+
+// 非球面（円錐係数 + 偶数次の多項式）で区切られた半空間
+// 頂点から軸方向に z、軸からの距離を r として、面のサグ（軸方向の高さ）は
+//   z(r) = c r² / (1 + √(1 - (1+k) c² r²)) + A4 r⁴ + A6 r⁶ + ...
+// c = curvature（曲率 = 1/曲率半径）、k = conic、coeffs = [A4, A6, ...]
+// 面より軸方向の先（z > サグ）を内部とし、レンズの本体とはCSGの積集合で組み合わせる
+// (1+k) c² > 0 ではサグが r < 1/(c√(1+k)) でしか定義されないので、
+// その外側は縁の高さの平らな面で閉じて、内外がどこでも決まるようにする
+#[derive(Debug, Clone)]
+pub struct AsphericSurface {
+    pub vertex: Vec3, // 面の頂点
+    pub axis: Vec3,   // 光軸の方向（正規化されていること）
+    pub curvature: f32,
+    pub conic: f32,
+    pub coeffs: Vec<f32>, // r⁴ から始まる偶数次の係数
+    pub material: Material,
+}
+
+impl AsphericSurface {
+    // サグが定義される（平方根の中が負にならない）範囲か
+    fn in_domain(&self, r_sq: f32) -> bool {
+        1.0 - (1.0 + self.conic) * self.curvature * self.curvature * r_sq >= 0.0
+    }
+
+    // サグが定義される範囲の端の r²（範囲が無限ならNone）
+    fn edge_r_sq(&self) -> Option<f32> {
+        let bound = (1.0 + self.conic) * self.curvature * self.curvature;
+        (bound > 0.0).then(|| 1.0 / bound)
+    }
+
+    // 範囲外を閉じる平らな面の高さ
+    fn edge_height(&self, edge_r_sq: f32) -> f32 {
+        self.sag(edge_r_sq).0
+    }
+
+    // r² に対するサグと、その r² による微分
+    fn sag(&self, r_sq: f32) -> (f32, f32) {
+        let c = self.curvature;
+        let root = (1.0 - (1.0 + self.conic) * c * c * r_sq).max(0.0).sqrt();
+        let mut sag = c * r_sq / (1.0 + root);
+        // dz/d(r²) = c / (2√(1 - (1+k) c² r²))
+        let mut slope = c / (2.0 * root.max(1e-6));
+
+        let mut power = r_sq; // r² の (i+1) 乗
+        for (i, &coeff) in self.coeffs.iter().enumerate() {
+            let order = (i + 2) as f32; // r² の何乗の項か
+            slope += coeff * order * power;
+            power *= r_sq;
+            sag += coeff * power;
+        }
+        (sag, slope)
+    }
+
+    // 頂点からの変位を、軸方向の成分と軸に垂直な成分に分ける
+    fn split(&self, v: Vec3) -> (f32, Vec3) {
+        let z = v.dot(self.axis);
+        (z, v - z * self.axis)
+    }
+
+    // 多項式の項を除いた円錐曲面との交点（ニュートン法の初期値に使う）
+    // 円錐曲面は c (r² + (1+k) z²) - 2z = 0 と書ける
+    fn conic_seeds(&self, ray: &Ray) -> Vec<f32> {
+        let c = self.curvature;
+        let kk = 1.0 + self.conic;
+        let (oz, oq) = self.split(ray.origin - self.vertex);
+        let (dz, dq) = self.split(ray.direction);
+
+        let qa = c * (dq.length_squared() + kk * dz * dz);
+        let qb = 2.0 * c * (oq.dot(dq) + kk * oz * dz) - 2.0 * dz;
+        let qc = c * (oq.length_squared() + kk * oz * oz) - 2.0 * oz;
+
+        if qa.abs() < 1e-12 {
+            if qb.abs() < 1e-12 {
+                return Vec::new();
+            }
+            return vec![-qc / qb];
+        }
+        let discriminant = qb * qb - 4.0 * qa * qc;
+        if discriminant < 0.0 {
+            return Vec::new();
+        }
+        let sqrtd = discriminant.sqrt();
+        vec![(-qb - sqrtd) / (2.0 * qa), (-qb + sqrtd) / (2.0 * qa)]
+    }
+
+    // 定義域の外を縁の高さで延ばしたサグ（どこでも連続になる）
+    fn extended_sag(&self, r_sq: f32) -> (f32, f32) {
+        match self.edge_r_sq() {
+            Some(edge_r_sq) if !self.in_domain(r_sq) => (self.edge_height(edge_r_sq), 0.0),
+            _ => self.sag(r_sq),
+        }
+    }
+
+    // g(t) = z(t) - サグ(r(t)²) とその t による微分。内部で正になる
+    fn signed_height(&self, ray: &Ray, t: f32) -> (f32, f32) {
+        let (z, q) = self.split(ray.origin + t * ray.direction - self.vertex);
+        let (dz, dq) = self.split(ray.direction);
+        let (sag, slope) = self.extended_sag(q.length_squared());
+        (z - sag, dz - slope * 2.0 * q.dot(dq))
+    }
+
+    // 定義域の境界（軸を中心とする円柱）とレイの交点
+    fn domain_wall_crossings(&self, ray: &Ray) -> Vec<f32> {
+        let Some(edge_r_sq) = self.edge_r_sq() else {
+            return Vec::new();
+        };
+        let (_, oq) = self.split(ray.origin - self.vertex);
+        let (_, dq) = self.split(ray.direction);
+        let qa = dq.length_squared();
+        let qb = 2.0 * oq.dot(dq);
+        let qc = oq.length_squared() - edge_r_sq;
+        let discriminant = qb * qb - 4.0 * qa * qc;
+        if qa < 1e-12 || discriminant < 0.0 {
+            return Vec::new();
+        }
+        let sqrtd = discriminant.sqrt();
+        vec![(-qb - sqrtd) / (2.0 * qa), (-qb + sqrtd) / (2.0 * qa)]
+    }
+
+    // 符号が変わる区間 [lo, hi] の中の根を、はみ出すときは二分法に切り替えるニュートン法で求める
+    fn refine(&self, ray: &Ray, mut lo: f32, mut hi: f32) -> f32 {
+        let lo_positive = self.signed_height(ray, lo).0 > 0.0;
+        let mut t = (lo + hi) / 2.0;
+        for _ in 0..NEWTON_MAX_ITERATIONS {
+            let (g, dg) = self.signed_height(ray, t);
+            if g.abs() < geometric_epsilon() * NEWTON_TOLERANCE_FACTOR {
+                break;
+            }
+            if (g > 0.0) == lo_positive {
+                lo = t;
+            } else {
+                hi = t;
+            }
+            let newton = t - g / dg;
+            t = if dg != 0.0 && newton > lo && newton < hi {
+                newton
+            } else {
+                (lo + hi) / 2.0
+            };
+        }
+        t
+    }
+}
+
+impl Hittable for AsphericSurface {
+    fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
+        // 円錐曲面との交点と定義域の境界で区切り、さらに細かく分けた区間ごとに符号の変化を探す
+        let t_end = if t_max.is_finite() {
+            t_max
+        } else {
+            t_min + FAR_SEARCH_DISTANCE
+        };
+        let mut breaks: Vec<f32> = self
+            .conic_seeds(ray)
+            .into_iter()
+            .chain(self.domain_wall_crossings(ray))
+            .filter(|&t| t > t_min && t < t_end)
+            .chain([t_min, t_end])
+            .collect();
+        breaks.sort_by(f32::total_cmp);
+
+        let mut roots = Vec::new();
+        for pair in breaks.windows(2) {
+            for i in 0..SUBDIVISIONS {
+                let lo = pair[0] + (pair[1] - pair[0]) * i as f32 / SUBDIVISIONS as f32;
+                let hi = pair[0] + (pair[1] - pair[0]) * (i + 1) as f32 / SUBDIVISIONS as f32;
+                let g_lo = self.signed_height(ray, lo).0;
+                let g_hi = self.signed_height(ray, hi).0;
+                if (g_lo > 0.0) != (g_hi > 0.0) {
+                    roots.push(self.refine(ray, lo, hi));
+                }
+            }
+        }
+        let merge_distance = geometric_epsilon() * ROOT_MERGE_FACTOR;
+        roots.dedup_by(|a, b| (*a - *b).abs() < merge_distance);
+
+        let hits: Vec<HitRecord> = roots
+            .into_iter()
+            .map(|t| {
+                let point = ray.origin + t * ray.direction;
+                // F(p) = z - サグ(r²) は内部で正。勾配 v - 2 サグ'(r²) q の逆が外向き
+                // （定義域の外の平らな面では -v）
+                let (_, q) = self.split(point - self.vertex);
+                let (_, slope) = self.extended_sag(q.length_squared());
+                let outward_normal = -(self.axis - 2.0 * slope * q).normalize();
+                let front_face = ray.direction.dot(outward_normal) < 0.0;
+                let normal = if front_face {
+                    outward_normal
+                } else {
+                    -outward_normal
+                };
+                HitRecord {
+                    t,
+                    point,
+                    normal,
+                    front_face,
+                    incoming: ray.direction,
+                    material: self.material.clone(),
+                }
+            })
+            .collect();
+
+        if hits.is_empty() {
+            None
+        } else {
+            Some(hits)
+        }
+    }
+
+    fn degeneracy(&self) -> Option<String> {
+        non_finite("vertex", self.vertex)
+            .or_else(|| unit("axis", self.axis))
+            .or_else(|| {
+                let finite = self.curvature.is_finite()
+                    && self.conic.is_finite()
+                    && self.coeffs.iter().all(|coeff| coeff.is_finite());
+                (!finite)
+                    .then(|| "曲率・円錐係数・多項式の係数に有限でない値があります".to_string())
+            })
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        let (z, q) = self.split(point - self.vertex);
+        z > self.extended_sag(q.length_squared()).0
+    }
+
+    fn material(&self) -> Option<&Material> {
+        Some(&self.material)
+    }
+}
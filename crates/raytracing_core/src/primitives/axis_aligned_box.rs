@@ -1,7 +1,8 @@
-use crate::{HitRecord, Hittable, Material, Ray};
+use crate::validation::non_finite;
+use crate::{Aabb, HitRecord, Hittable, Material, Ray};
 use glam::Vec3;
 // 軸並行な直方体 (AABB) 対角の座標を指定
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct AxisAlignedBox {
     pub min: Vec3, // 3つの軸の最小座標 (x_min, y_min, z_min)
     pub max: Vec3, // 3つの軸の最大座標 (x_max, y_max, z_max)
@@ -10,8 +11,13 @@ pub struct AxisAlignedBox {
 // AxisAlignedBox のための Hittable 実装
 impl Hittable for AxisAlignedBox {
     fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
-        let mut tmin = t_min;
-        let mut tmax = t_max;
+        // 範囲で切り詰めずに、直方体への入口と出口を求める
+        // 入口と出口を決めた軸も覚えておき、その軸の面の法線を使う
+        // （辺や角をかすめるレイでは、衝突点から面を選ぶと隣の面と取り違えることがある）
+        let mut t_enter = f32::NEG_INFINITY;
+        let mut t_exit = f32::INFINITY;
+        let mut enter_axis = 0;
+        let mut exit_axis = 0;
 
         // 各軸 (X, Y, Z) に対してSlab Testを実行
         for i in 0..3 {
@@ -26,71 +32,86 @@ impl Hittable for AxisAlignedBox {
             }
 
             // これまで計算された全体の区間と、現在の軸の区間の共通部分を求める
-            tmin = tmin.max(t0);
-            tmax = tmax.min(t1);
+            if t0 > t_enter {
+                t_enter = t0;
+                enter_axis = i;
+            }
+            if t1 < t_exit {
+                t_exit = t1;
+                exit_axis = i;
+            }
 
             // 共通区間がなくなれば、ヒットしない
-            if tmax <= tmin {
+            if t_exit <= t_enter {
                 return None;
             }
         }
 
-        // --- 有効な交差区間 [tmin, tmax] が見つかった ---
+        // --- 交差区間 [t_enter, t_exit] のうち、[t_min, t_max] に入る端だけを返す ---
         let mut hits = Vec::new();
 
-        // 最初のヒット (入口)
-        let point1 = ray.origin + tmin * ray.direction;
-        let normal1 = self.calculate_normal(point1);
-        hits.push(HitRecord {
-            t: tmin,
-            point: point1,
-            normal: normal1,
-            front_face: ray.direction.dot(normal1) < 0.0,
-            material: self.material,
-        });
+        // 入口（始点が内部にあれば範囲外になる）
+        if t_enter > t_min && t_enter < t_max {
+            hits.push(HitRecord {
+                t: t_enter,
+                point: ray.origin + t_enter * ray.direction,
+                normal: Self::facing_normal(ray.direction, enter_axis), // 入口の外向き法線
+                front_face: true,
+                incoming: ray.direction,
+                material: self.material.clone(),
+            });
+        }
 
-        // 2番目のヒット (出口)
-        let point2 = ray.origin + tmax * ray.direction;
-        let normal2 = -self.calculate_normal(point2); // 出口の法線は内側を向く
-        hits.push(HitRecord {
-            t: tmax,
-            point: point2,
-            normal: normal2,
-            front_face: ray.direction.dot(normal2) < 0.0,
-            material: self.material,
-        });
+        // 出口
+        if t_exit > t_min && t_exit < t_max {
+            hits.push(HitRecord {
+                t: t_exit,
+                point: ray.origin + t_exit * ray.direction,
+                normal: Self::facing_normal(ray.direction, exit_axis), // 出口の法線は内側を向く
+                front_face: false,
+                incoming: ray.direction,
+                material: self.material.clone(),
+            });
+        }
 
+        if hits.is_empty() {
+            return None;
+        }
         Some(hits)
     }
+
+    fn degeneracy(&self) -> Option<String> {
+        non_finite("min", self.min)
+            .or_else(|| non_finite("max", self.max))
+            .or_else(|| {
+                (!self.min.cmplt(self.max).all()).then(|| {
+                    format!(
+                        "min {} がすべての成分で max {} より小さくありません",
+                        self.min, self.max
+                    )
+                })
+            })
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        point.cmpgt(self.min).all() && point.cmplt(self.max).all()
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(Aabb::new(self.min, self.max))
+    }
+
+    fn material(&self) -> Option<&Material> {
+        Some(&self.material)
+    }
 }
 
 // AABBのためのヘルパーメソッド
 impl AxisAlignedBox {
-    // 衝突点から、どの面の法線かを計算する
-    fn calculate_normal(&self, point: Vec3) -> Vec3 {
-        let epsilon = 1e-4;
-        let p_minus_min = point - self.min;
-        let p_minus_max = point - self.max;
-
-        if p_minus_min.x.abs() < epsilon {
-            return Vec3::NEG_X;
-        }
-        if p_minus_max.x.abs() < epsilon {
-            return Vec3::X;
-        }
-        if p_minus_min.y.abs() < epsilon {
-            return Vec3::NEG_Y;
-        }
-        if p_minus_max.y.abs() < epsilon {
-            return Vec3::Y;
-        }
-        if p_minus_min.z.abs() < epsilon {
-            return Vec3::NEG_Z;
-        }
-        if p_minus_max.z.abs() < epsilon {
-            return Vec3::Z;
-        }
-
-        Vec3::ZERO // 本来は到達しない
+    // axis 軸の面の法線のうち、向き direction のレイと向かい合うもの
+    fn facing_normal(direction: Vec3, axis: usize) -> Vec3 {
+        let mut normal = Vec3::ZERO;
+        normal[axis] = if direction[axis] < 0.0 { 1.0 } else { -1.0 };
+        normal
     }
 }
@@ -0,0 +1,41 @@
+use glam::Vec3;
+
+// まとめて交差判定を行うレイの本数（f32 を 8 個並べると AVX のレジスタ1本分）
+pub(crate) const BATCH_LANES: usize = 8;
+
+pub(crate) type Lanes = [f32; BATCH_LANES];
+
+// レイの始点と向きを、成分ごとの配列に並べ替えたもの
+// レーンごとに同じ計算を並べておくと、コンパイラがSIMD命令にまとめやすい
+pub(crate) struct RayLanes {
+    pub origin: [Lanes; 3],
+    pub direction: [Lanes; 3],
+}
+
+impl RayLanes {
+    // BATCH_LANES 本以下のレイを読み込む。余ったレーンは原点から +Z 向きのレイで埋める（結果は使わない）
+    pub fn load(origins: &[Vec3], directions: &[Vec3]) -> RayLanes {
+        let mut lanes = RayLanes {
+            origin: [[0.0; BATCH_LANES]; 3],
+            direction: [[0.0; BATCH_LANES]; 3],
+        };
+        lanes.direction[2] = [1.0; BATCH_LANES];
+        for (i, (origin, direction)) in origins.iter().zip(directions).enumerate() {
+            for axis in 0..3 {
+                lanes.origin[axis][i] = origin[axis];
+                lanes.direction[axis][i] = direction[axis];
+            }
+        }
+        lanes
+    }
+}
+
+// レーンごとの内積。Vec3::dot と同じ順で足すので、1本ずつ計算した結果と一致する
+pub(crate) fn dot_lanes(a: &[Lanes; 3], b: &[Lanes; 3]) -> Lanes {
+    std::array::from_fn(|i| (a[0][i] * b[0][i]) + (a[1][i] * b[1][i]) + (a[2][i] * b[2][i]))
+}
+
+// 各レーンのベクトルから v を引く
+pub(crate) fn sub_lanes(a: &[Lanes; 3], v: Vec3) -> [Lanes; 3] {
+    std::array::from_fn(|axis| a[axis].map(|x| x - v[axis]))
+}
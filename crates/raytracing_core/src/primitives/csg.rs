@@ -1,4 +1,6 @@
-use crate::{CsgOperation, HitRecord, Hittable, Ray};
+use glam::Vec3;
+
+use crate::{Aabb, CsgOperation, HitRecord, Hittable, Ray};
 // CSGオブジェクト
 pub struct CSGObject {
     pub left: Box<dyn Hittable>,
@@ -6,6 +8,8 @@ pub struct CSGObject {
     pub operation: CsgOperation,
 }
 impl Hittable for CSGObject {
+    // 子のヒットを切り詰めると、その先で内外の判定が崩れて手前の結果まで変わる
+    // （積集合で片方の入口だけが残るなど）ので、max_hits での切り詰めは組み合わせた後の結果にだけ行う
     fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
         // 1. 左右の子オブジェクトとの全ての交点を取得
         let hits_left = self
@@ -17,21 +21,29 @@ impl Hittable for CSGObject {
             .intersect_all(ray, t_min, t_max)
             .unwrap_or_default();
 
-        // 2. 全てのヒットを一つのリストにまとめ、tでソート
-        let mut all_hits = hits_left.clone();
-        all_hits.extend(hits_right.clone());
-        all_hits.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
+        // 2. 全てのヒットを、left/rightどちらの物かの印を付けて一つのリストにまとめ、tでソート
+        // t が等しいヒットは入射 (front_face) を先にする（接した面で内外が途切れないように）
+        // それも等しければ安定ソートなので left が先になる
+        let mut all_hits: Vec<(HitRecord, bool)> = hits_left
+            .into_iter()
+            .map(|hit| (hit, true))
+            .chain(hits_right.into_iter().map(|hit| (hit, false)))
+            .collect();
+        all_hits.sort_by(|(a, _), (b, _)| {
+            a.t.total_cmp(&b.t)
+                .then_with(|| b.front_face.cmp(&a.front_face))
+        });
 
         let mut result_hits = Vec::new();
 
         // 3. 演算の種類に応じたフィルタリング処理
-        let mut in_left = false;
-        let mut in_right = false;
-
-        for hit in &all_hits {
-            // このヒットがleft/rightどちらの物か判定
-            let hit_is_on_left = hits_left.iter().any(|h| (h.t - hit.t).abs() < 1e-6);
+        // 平面や無限円柱の子は、始点が内部にあると面を横切らないことがあり偶奇だけでは内外が決まらない
+        // （ウェッジや穴の空いた絞り、光軸に沿ったレンズ）ので、始点での contains を初期状態にする
+        let start = ray.origin + t_min * ray.direction;
+        let mut in_left = self.left.contains(start);
+        let mut in_right = self.right.contains(start);
 
+        for (hit, hit_is_on_left) in all_hits {
             // 演算前の状態を保存
             let was_inside = match self.operation {
                 CsgOperation::Union => in_left || in_right,
@@ -55,15 +67,13 @@ impl Hittable for CSGObject {
 
             // 状態が変化した（＝CSGオブジェクトの表面を通過した）なら、そのヒットは有効
             if was_inside != is_inside {
-                // Differenceの場合、rightオブジェクトの法線は反転させる必要がある
-                if self.operation == CsgOperation::Difference && !hit_is_on_left {
-                    let mut inverted_hit = *hit;
-                    inverted_hit.normal = -hit.normal;
-                    inverted_hit.front_face = !hit.front_face;
-                    result_hits.push(inverted_hit);
-                } else {
-                    result_hits.push(*hit);
-                }
+                // 法線は子の時点でレイと向かい合っているのでそのまま使い、
+                // 表裏だけ合成後の立体に入ったか出たかで決め直す
+                // （Differenceでrightに入るときは、この立体から出ることになる）
+                result_hits.push(HitRecord {
+                    front_face: is_inside,
+                    ..hit
+                });
             }
         }
 
@@ -73,4 +83,34 @@ impl Hittable for CSGObject {
             Some(result_hits)
         }
     }
+
+    fn contains(&self, point: Vec3) -> bool {
+        let in_left = self.left.contains(point);
+        let in_right = self.right.contains(point);
+        match self.operation {
+            CsgOperation::Union => in_left || in_right,
+            CsgOperation::Intersection => in_left && in_right,
+            CsgOperation::Difference => in_left && !in_right,
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let left = self.left.bounding_box();
+        let right = self.right.bounding_box();
+        match self.operation {
+            // 和集合はどちらかが無限なら無限
+            CsgOperation::Union => Some(left?.union(&right?)),
+            // 積集合は有限な方に収まる
+            CsgOperation::Intersection => match (left, right) {
+                (Some(l), Some(r)) => l.intersection(&r),
+                (l, r) => l.or(r),
+            },
+            // 差集合は左側に収まる
+            CsgOperation::Difference => left,
+        }
+    }
+
+    fn children(&self) -> Vec<(&'static str, &dyn Hittable)> {
+        vec![("left", self.left.as_ref()), ("right", self.right.as_ref())]
+    }
 }
@@ -0,0 +1,176 @@
+use crate::validation::{non_finite, nonzero, positive};
+use crate::{HitRecord, Hittable, Material, Ray};
+use glam::Vec3;
+
+// 双曲面の種類
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum HyperboloidSheets {
+    /// 一葉双曲面（くびれのある筒）。くびれの内側が内部
+    One,
+    /// 二葉双曲面（向かい合う2つの椀）。椀の凹側が内部
+    Two,
+}
+
+// 無限に広がる双曲面（カセグレン望遠鏡の副鏡など）。平面とのCSGで切り取って使う
+// 軸方向を z、軸からの距離を r として
+//   二葉: z²/a² - r²/b² = 1（頂点は z = ±a、焦点は z = ±c）
+//   一葉: r²/a² - z²/b² = 1（くびれの半径は a）
+// ただし b² = c² - a²
+#[derive(Debug, Clone)]
+pub struct Hyperboloid {
+    pub vertex: Vec3, // 二葉: +軸側の葉の頂点、一葉: くびれの中心
+    pub axis_dir: Vec3,
+    pub a: f32,
+    pub c: f32, // 焦点までの距離（a より大きいこと）
+    pub sheets: HyperboloidSheets,
+    pub material: Material,
+}
+
+impl Hyperboloid {
+    pub fn new(
+        vertex: Vec3,
+        axis_dir: Vec3,
+        a: f32,
+        c: f32,
+        sheets: HyperboloidSheets,
+        material: Material,
+    ) -> Self {
+        if c <= a {
+            println!(
+                "双曲面の c ({}) が a ({}) 以下のため、形状が正しくありません",
+                c, a
+            );
+        }
+        Self {
+            vertex,
+            axis_dir: axis_dir.normalize(),
+            a,
+            c,
+            sheets,
+            material,
+        }
+    }
+
+    // 双曲面の中心（二葉では2つの頂点の中点）
+    pub fn center(&self) -> Vec3 {
+        match self.sheets {
+            HyperboloidSheets::One => self.vertex,
+            HyperboloidSheets::Two => self.vertex - self.axis_dir * self.a,
+        }
+    }
+
+    // 2つの焦点（+軸側、-軸側の順）
+    pub fn foci(&self) -> (Vec3, Vec3) {
+        let center = self.center();
+        (
+            center + self.axis_dir * self.c,
+            center - self.axis_dir * self.c,
+        )
+    }
+
+    // F(p) = α z² + β r² - 1 の係数 (α, β)。内部では sign·F > 0 になる
+    fn coefficients(&self) -> (f32, f32, f32) {
+        let inv_a_sq = 1.0 / (self.a * self.a);
+        let inv_b_sq = 1.0 / (self.c * self.c - self.a * self.a).max(f32::MIN_POSITIVE);
+        match self.sheets {
+            HyperboloidSheets::One => (-inv_b_sq, inv_a_sq, -1.0),
+            HyperboloidSheets::Two => (inv_a_sq, -inv_b_sq, 1.0),
+        }
+    }
+
+    // 中心からの変位を、軸方向の成分と軸に垂直な成分に分ける
+    fn split(&self, v: Vec3) -> (f32, Vec3) {
+        let z = v.dot(self.axis_dir);
+        (z, v - z * self.axis_dir)
+    }
+}
+
+impl Hittable for Hyperboloid {
+    fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
+        let (alpha, beta, sign) = self.coefficients();
+        let (oz, oq) = self.split(ray.origin - self.center());
+        let (dz, dq) = self.split(ray.direction);
+
+        // F(o + t d) = A t² + B t + C
+        let qa = alpha * dz * dz + beta * dq.length_squared();
+        let qb = 2.0 * (alpha * oz * dz + beta * oq.dot(dq));
+        let qc = alpha * oz * oz + beta * oq.length_squared() - 1.0;
+
+        // (t, 入射か) の組。sign·F が増える向きに横切る解が入射で、
+        // 2つの解では F の傾きの符号が逆なので入射と出射が必ず組になる
+        // （接するレイでも法線の向きから判定して取り違えないように）
+        let roots = if qa.abs() < 1e-12 {
+            // 漸近線と平行なレイは1点でしか交わらない
+            if qb.abs() < 1e-12 {
+                return None;
+            }
+            vec![(-qc / qb, sign * qb > 0.0)]
+        } else {
+            let discriminant = qb * qb - 4.0 * qa * qc;
+            if discriminant < 0.0 {
+                return None;
+            }
+            let sqrtd = discriminant.sqrt();
+            let t1 = (-qb - sqrtd) / (2.0 * qa);
+            let t2 = (-qb + sqrtd) / (2.0 * qa);
+            // A < 0 のとき大小が逆になるので、手前から順に並べる
+            // 手前の解での傾きは -A と同じ符号、奥の解では A と同じ符号
+            let near_enters = sign * qa < 0.0;
+            if t1 <= t2 {
+                vec![(t1, near_enters), (t2, !near_enters)]
+            } else {
+                vec![(t2, near_enters), (t1, !near_enters)]
+            }
+        };
+
+        let hits: Vec<HitRecord> = roots
+            .into_iter()
+            .filter(|&(t, _)| t > t_min && t < t_max)
+            .map(|(t, front_face)| {
+                let point = ray.origin + t * ray.direction;
+                // 勾配 ∇F = 2(α z v + β q) に sign を掛けると内部を向くので、反転して外向きにする
+                let (z, q) = self.split(point - self.center());
+                let outward_normal = (-sign * (alpha * z * self.axis_dir + beta * q)).normalize();
+                let normal = if front_face {
+                    outward_normal
+                } else {
+                    -outward_normal
+                };
+                HitRecord {
+                    t,
+                    point,
+                    normal,
+                    front_face,
+                    incoming: ray.direction,
+                    material: self.material.clone(),
+                }
+            })
+            .collect();
+
+        if hits.is_empty() {
+            None
+        } else {
+            Some(hits)
+        }
+    }
+
+    fn degeneracy(&self) -> Option<String> {
+        non_finite("vertex", self.vertex)
+            .or_else(|| nonzero("axis_dir", self.axis_dir))
+            .or_else(|| positive("a", self.a))
+            .or_else(|| {
+                (self.c.is_nan() || self.c <= self.a)
+                    .then(|| format!("c ({}) は a ({}) より大きくしてください", self.c, self.a))
+            })
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        let (alpha, beta, sign) = self.coefficients();
+        let (z, q) = self.split(point - self.center());
+        sign * (alpha * z * z + beta * q.length_squared() - 1.0) > 0.0
+    }
+
+    fn material(&self) -> Option<&Material> {
+        Some(&self.material)
+    }
+}
@@ -1,7 +1,8 @@
+use crate::validation::{non_finite, unit};
 use crate::{HitRecord, Hittable, Material, Ray};
 use glam::Vec3;
 // 無限円錐
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct InfiniteCone {
     pub vertex: Vec3,      // 円錐の頂点
     pub axis_dir: Vec3,    // 軸の方向（正規化されていること）
@@ -21,6 +22,9 @@ impl InfiniteCone {
     }
 }
 
+// 2次の係数 A がこれより小さければ、レイは母線と平行とみなす
+const PARALLEL_EPSILON: f32 = 1e-6;
+
 // InfiniteCone のための Hittable 実装
 impl Hittable for InfiniteCone {
     fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
@@ -38,19 +42,33 @@ impl Hittable for InfiniteCone {
         let b = 2.0 * (d_dot_v * co_dot_v - ray.direction.dot(co) * self.cos_angle_sq);
         let c = co_dot_v.powi(2) - co.length_squared() * self.cos_angle_sq;
 
-        let discriminant = b * b - 4.0 * a * c;
-        if discriminant < 0.0 {
-            return None; // 実数解なし
-        }
-
-        let sqrtd = discriminant.sqrt();
-        let mut hits = Vec::new();
+        let roots = if a.abs() < PARALLEL_EPSILON {
+            // レイが円錐の母線と平行なら A = 0 となり、B t + C = 0 の1次方程式になる
+            // B も 0 なら、レイは頂点を通る母線上にあるか円錐と交わらないので、当たらないものとする
+            if b.abs() < PARALLEL_EPSILON {
+                return None;
+            }
+            vec![-c / b]
+        } else {
+            let discriminant = b * b - 4.0 * a * c;
+            if discriminant < 0.0 {
+                return None; // 実数解なし
+            }
 
-        // 2つの解を計算
-        let t1 = (-b - sqrtd) / (2.0 * a);
-        let t2 = (-b + sqrtd) / (2.0 * a);
+            // 2つの解を計算
+            let sqrtd = discriminant.sqrt();
+            let t1 = (-b - sqrtd) / (2.0 * a);
+            let t2 = (-b + sqrtd) / (2.0 * a);
+            // A < 0 のとき大小が逆になるので、手前から順に並べる
+            if t1 <= t2 {
+                vec![t1, t2]
+            } else {
+                vec![t2, t1]
+            }
+        };
 
-        for &t in &[t1, t2] {
+        let mut hits = Vec::new();
+        for t in roots {
             if t > t_min && t < t_max {
                 let point = ray.origin + t * ray.direction;
 
@@ -73,7 +91,8 @@ impl Hittable for InfiniteCone {
                     point,
                     normal,
                     front_face,
-                    material: self.material,
+                    incoming: ray.direction,
+                    material: self.material.clone(),
                 });
             }
         }
@@ -84,4 +103,27 @@ impl Hittable for InfiniteCone {
             Some(hits)
         }
     }
+
+    // 軸となす角が開き角より小さければ内部
+    fn degeneracy(&self) -> Option<String> {
+        non_finite("vertex", self.vertex)
+            .or_else(|| unit("axis_dir", self.axis_dir))
+            .or_else(|| {
+                (!(self.cos_angle_sq > 0.0 && self.cos_angle_sq < 1.0)).then(|| {
+                    format!(
+                        "cos_angle_sq は 0 より大きく 1 より小さくしてください ({})",
+                        self.cos_angle_sq
+                    )
+                })
+            })
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        let pv = point - self.vertex;
+        pv.dot(self.axis_dir).powi(2) > pv.length_squared() * self.cos_angle_sq
+    }
+
+    fn material(&self) -> Option<&Material> {
+        Some(&self.material)
+    }
 }
@@ -1,7 +1,8 @@
+use crate::validation::{non_finite, positive, unit};
 use crate::{HitRecord, Hittable, Material, Ray};
 use glam::Vec3;
 // 無限円柱
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct InfiniteCylinder {
     pub axis_point: Vec3, // 軸上の任意の点
     pub axis_dir: Vec3,   // 軸の方向（正規化されていること）
@@ -47,7 +48,8 @@ impl Hittable for InfiniteCylinder {
         let t1 = (-b - sqrtd) / (2.0 * a);
         let t2 = (-b + sqrtd) / (2.0 * a);
 
-        for &t in &[t1, t2] {
+        // a > 0 なので手前の解が入射、奥の解が出射（接するレイでも法線の向きで取り違えないように）
+        for (t, front_face) in [(t1, true), (t2, false)] {
             if t > t_min && t < t_max {
                 let point = ray.origin + t * ray.direction;
 
@@ -59,7 +61,6 @@ impl Hittable for InfiniteCylinder {
                 let point_on_axis = self.axis_point + projection * self.axis_dir;
                 let outward_normal = (point - point_on_axis).normalize();
 
-                let front_face = ray.direction.dot(outward_normal) < 0.0;
                 let normal = if front_face {
                     outward_normal
                 } else {
@@ -71,7 +72,8 @@ impl Hittable for InfiniteCylinder {
                     point,
                     normal,
                     front_face,
-                    material: self.material,
+                    incoming: ray.direction,
+                    material: self.material.clone(),
                 });
             }
         }
@@ -82,4 +84,20 @@ impl Hittable for InfiniteCylinder {
             Some(hits)
         }
     }
+
+    fn degeneracy(&self) -> Option<String> {
+        non_finite("axis_point", self.axis_point)
+            .or_else(|| unit("axis_dir", self.axis_dir))
+            .or_else(|| positive("radius", self.radius))
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        let p_minus_a = point - self.axis_point;
+        let perp = p_minus_a - p_minus_a.dot(self.axis_dir) * self.axis_dir;
+        perp.length_squared() < self.radius * self.radius
+    }
+
+    fn material(&self) -> Option<&Material> {
+        Some(&self.material)
+    }
 }
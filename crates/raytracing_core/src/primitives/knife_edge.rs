@@ -0,0 +1,89 @@
+use crate::validation::{non_finite, nonzero};
+use crate::{HitRecord, Hittable, Material, Ray};
+use glam::Vec3;
+
+// ナイフエッジの刃がどちら側を遮るか
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum KnifeEdgeSide {
+    /// 法線側から見て、エッジ方向の左側（normal × edge_dir の向き）
+    Left,
+    /// 法線側から見て、エッジ方向の右側
+    Right,
+}
+
+// 平面内の直線（エッジ）の片側だけを覆う、厚さのない半平面
+// 吸収体の材質と組み合わせて、ナイフエッジ走査や回折エッジの実験に使う
+#[derive(Debug, Clone)]
+pub struct KnifeEdge {
+    pub plane_point: Vec3, // エッジ上の任意の点
+    pub normal: Vec3,      // 平面の法線
+    pub edge_dir: Vec3,    // エッジの方向
+    pub blocking_side: KnifeEdgeSide,
+    pub material: Material,
+}
+
+impl KnifeEdge {
+    // 平面内でエッジと直交し、遮る側を向くベクトル
+    fn blocking_dir(&self) -> Vec3 {
+        let left = self.normal.cross(self.edge_dir);
+        match self.blocking_side {
+            KnifeEdgeSide::Left => left,
+            KnifeEdgeSide::Right => -left,
+        }
+    }
+}
+
+impl Hittable for KnifeEdge {
+    fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
+        let denom = self.normal.dot(ray.direction);
+
+        // レイが平面と平行な場合は衝突しない
+        if denom.abs() < 1e-6 {
+            return None;
+        }
+
+        let t = (self.plane_point - ray.origin).dot(self.normal) / denom;
+        if t < t_min || t_max < t {
+            return None;
+        }
+
+        // 平面上の交点が遮る側になければ素通りする
+        let point = ray.origin + t * ray.direction;
+        if (point - self.plane_point).dot(self.blocking_dir()) < 0.0 {
+            return None;
+        }
+
+        // 厚さがないので、法線側から当たった場合を表面とする
+        let outward_normal = self.normal.normalize();
+        let front_face = ray.direction.dot(outward_normal) < 0.0;
+        let normal = if front_face {
+            outward_normal
+        } else {
+            -outward_normal
+        };
+
+        Some(vec![HitRecord {
+            t,
+            point,
+            normal,
+            front_face,
+            incoming: ray.direction,
+            material: self.material.clone(),
+        }])
+    }
+
+    // 厚さのない面なので内部は存在しない
+    fn degeneracy(&self) -> Option<String> {
+        non_finite("plane_point", self.plane_point)
+            .or_else(|| nonzero("normal", self.normal))
+            .or_else(|| nonzero("edge_dir", self.edge_dir))
+    }
+
+    fn contains(&self, _point: Vec3) -> bool {
+        false
+    }
+
+    fn material(&self) -> Option<&Material> {
+        Some(&self.material)
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
-    CSGObject, CsgOperation, HitRecord, Hittable, InfiniteCylinder, Material, Plane, Ray, Sphere,
+    Aabb, CSGObject, CsgOperation, HitRecord, Hittable, InfiniteCylinder, Material, Plane, Ray,
+    Sphere,
 };
 use glam::{f32, Vec3};
 //レンズプリミティブ
@@ -21,14 +22,14 @@ impl Lens {
             Box::new(Sphere {
                 center: center1,
                 radius: r1.abs(),
-                material,
+                material: material.clone(),
             }) as Box<dyn Hittable>
         } else {
             // 曲率半径が無限大なら、平面
             Box::new(Plane {
                 point: Vec3::new(0.0, 0.0, -half_thickness),
                 normal: Vec3::Z,
-                material,
+                material: material.clone(),
             }) as Box<dyn Hittable>
         };
 
@@ -38,13 +39,13 @@ impl Lens {
             Box::new(Sphere {
                 center: center2,
                 radius: r2.abs(),
-                material,
+                material: material.clone(),
             }) as Box<dyn Hittable>
         } else {
             Box::new(Plane {
                 point: Vec3::new(0.0, 0.0, half_thickness),
                 normal: Vec3::NEG_Z,
-                material,
+                material: material.clone(),
             }) as Box<dyn Hittable>
         };
 
@@ -80,4 +81,17 @@ impl Hittable for Lens {
     fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
         self.csg_object.intersect_all(ray, t_min, t_max)
     }
+
+    fn contains(&self, point: Vec3) -> bool {
+        self.csg_object.contains(point)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.csg_object.bounding_box()
+    }
+
+    // 中身のCSGはこの形状自身と同じなので、その子を直接返す
+    fn children(&self) -> Vec<(&'static str, &dyn Hittable)> {
+        self.csg_object.children()
+    }
 }
@@ -2,29 +2,57 @@
 // それらの中の公開アイテム（pub）を、このモジュールの外からも使えるようにします。
 
 // 各プリミティブのモジュールを宣言
+mod annulus;
+mod aspheric_surface;
 mod axis_aligned_box;
+mod batch;
 mod csg;
+mod hyperboloid;
 mod infinite_cone;
 mod infinite_cylinder;
+mod knife_edge;
 mod lens;
+mod non_occluding;
+mod perturbed_surface;
 mod plane;
+mod rectangle;
+mod sdf_object;
 mod sphere;
+mod spherical_cap;
 mod transform;
+mod triangle_mesh;
 mod wedge;
 
 // 各モジュール内の公開アイテムを、primitives::* で使えるように再公開（re-export）する
+pub use annulus::Annulus;
+pub use aspheric_surface::AsphericSurface;
 pub use axis_aligned_box::AxisAlignedBox;
 pub use csg::CSGObject;
+pub use hyperboloid::{Hyperboloid, HyperboloidSheets};
 pub use infinite_cone::InfiniteCone;
 pub use infinite_cylinder::InfiniteCylinder;
+pub use knife_edge::{KnifeEdge, KnifeEdgeSide};
 pub use lens::Lens;
+pub use non_occluding::NonOccluding;
+pub use perturbed_surface::{NormalFieldFn, PerturbedSurface};
 pub use plane::Plane;
+pub use rectangle::Rectangle;
+pub use sdf_object::{SdfFn, SdfObject};
 pub use sphere::Sphere;
+pub use spherical_cap::SphericalCap;
 pub use transform::Transform;
+pub use triangle_mesh::TriangleMesh;
 pub use wedge::Wedge;
 
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use glam::Vec3;
+
+use crate::Aabb;
 use crate::HitRecord;
 use crate::Ray;
+use crate::ThinFilmStack;
 // ブーリアン演算の種類
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum CsgOperation {
@@ -36,13 +64,185 @@ pub enum CsgOperation {
     Difference,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+// フラウンホーファー線の波長[nm]
+pub const D_LINE_NM: f32 = 587.6;
+pub const F_LINE_NM: f32 = 486.1;
+pub const C_LINE_NM: f32 = 656.3;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Material {
     Mirror,
+    OneSidedMirror, // 表から当たった光 (front_face) だけを反射し、裏からの光は素通りさせる（平面では法線の向いている側が表）
     Glass { ior: f32 },
-    HalfMirror { reflectance: f32 },
+    GlassByAbbe { nd: f32, vd: f32 }, // d線の屈折率とアッベ数から分散を近似するガラス
+    IdealGlass { ior: f32 }, // 全反射しない理想化したガラス。屈折できない光は向きを変えずに境界を抜ける
+    HalfMirror { reflectance: Reflectance },
+    Retroreflector,                           // 面の向きに関係なく入射方向へ光を返す
+    Absorber,                                 // 当たった光をすべて吸収し、追跡を終える
+    MetalMirror { reflectance: Reflectance }, // 反射のたびに強度を反射率倍する金属鏡（表は波長[nm]ごと）
+    Detector { acceptance_half_angle_deg: f32 }, // 受光角（法線からの半角[度]）の内側の光だけを記録して止め、外側は素通りさせる
+    MultilayerFilter { stack: ThinFilmStack }, // 誘電体多層膜の干渉フィルタ。反射しなかった光は向きを変えずに透過させる（基板での屈折は扱わない）
+}
+
+impl Material {
+    // ビューアで形状を塗る色（sRGBのRGBA、各0.0〜1.0）。ガラスは半透明にする
+    pub fn display_color(&self) -> [f32; 4] {
+        match self {
+            Material::Mirror => [0.85, 0.85, 0.9, 1.0],
+            Material::OneSidedMirror => [0.85, 0.85, 0.9, 0.7],
+            Material::MetalMirror { .. } => [0.75, 0.75, 0.78, 1.0],
+            Material::Glass { .. } | Material::GlassByAbbe { .. } | Material::IdealGlass { .. } => {
+                [0.55, 0.75, 0.95, 0.3]
+            }
+            Material::HalfMirror { .. } => [0.75, 0.8, 0.85, 0.6],
+            Material::Retroreflector => [0.95, 0.85, 0.3, 1.0],
+            Material::Absorber => [0.05, 0.05, 0.05, 1.0],
+            Material::Detector { .. } => [0.3, 0.8, 0.4, 0.8],
+            Material::MultilayerFilter { .. } => [0.7, 0.55, 0.9, 0.5],
+        }
+    }
+}
+
+// d線の屈折率 nd とアッベ数 vd に合うコーシーの式 n = A + B/λ² で、波長[nm]での屈折率を求める
+// vd = (nd - 1) / (nF - nC) より B を、n(λd) = nd より A を決める
+pub fn abbe_refractive_index(nd: f32, vd: f32, wavelength_nm: f32) -> f32 {
+    let b = (nd - 1.0) / (vd * (F_LINE_NM.powi(-2) - C_LINE_NM.powi(-2)));
+    let a = nd - b / D_LINE_NM.powi(2);
+    a + b / wavelength_nm.powi(2)
+}
+
+// 反射率。ハーフミラーでは入射角[度]、金属鏡では波長[nm]の関数として使う
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reflectance {
+    /// 入射角（波長）に依らず一定
+    Constant(f32),
+    /// (入射角[度] または波長[nm], 反射率) の表。昇順に並べ、間は線形補間する
+    Table(Arc<[(f32, f32)]>),
+}
+
+impl Reflectance {
+    // 入射角（度）または波長（nm）に対する反射率を求める。表の範囲外は端の値を使う
+    pub fn at(&self, angle_deg: f32) -> f32 {
+        let table = match self {
+            Reflectance::Constant(reflectance) => return *reflectance,
+            Reflectance::Table(table) => table,
+        };
+        let (Some(&(first_angle, first_value)), Some(&(last_angle, last_value))) =
+            (table.first(), table.last())
+        else {
+            return 0.0;
+        };
+        if angle_deg <= first_angle {
+            return first_value;
+        }
+        if angle_deg >= last_angle {
+            return last_value;
+        }
+        for pair in table.windows(2) {
+            let (a0, r0) = pair[0];
+            let (a1, r1) = pair[1];
+            if angle_deg <= a1 {
+                let s = if a1 > a0 {
+                    (angle_deg - a0) / (a1 - a0)
+                } else {
+                    1.0
+                };
+                return r0 + (r1 - r0) * s;
+            }
+        }
+        last_value
+    }
+}
+
+// 長さの許容誤差の既定値（シーンの大きさが1程度のとき）
+pub const DEFAULT_GEOMETRIC_EPSILON: f32 = 1e-4;
+// 長さの許容誤差（f32のビット列で保持する）。交点の重複判定や始点のずらし幅はこの倍数で決める
+// 向きの平行判定などの無次元の閾値はシーンの大きさに依らないので対象外
+static GEOMETRIC_EPSILON: AtomicU32 = AtomicU32::new(DEFAULT_GEOMETRIC_EPSILON.to_bits());
+
+// マイクロメートル程度の小さなシーンでは小さく、大きなシーンでは大きくする
+pub fn set_geometric_epsilon(epsilon: f32) {
+    if epsilon > 0.0 && epsilon.is_finite() {
+        GEOMETRIC_EPSILON.store(epsilon.to_bits(), Ordering::Relaxed);
+    } else {
+        println!(
+            "長さの許容誤差 {} は正の値ではないため無視しました",
+            epsilon
+        );
+    }
+}
+
+pub fn geometric_epsilon() -> f32 {
+    f32::from_bits(GEOMETRIC_EPSILON.load(Ordering::Relaxed))
 }
 
 pub trait Hittable: Sync + Send {
     fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>>;
+
+    // intersect_all のうち手前から max_hits 個までのヒットを返す（入れ子の深いCSGでヒットの列が長くなり過ぎないように）
+    // 既定では intersect_all の結果を切り詰める。CSGとTransformは中の形状にも上限を渡す
+    fn intersect_nearest(
+        &self,
+        ray: &Ray,
+        t_min: f32,
+        t_max: f32,
+        max_hits: usize,
+    ) -> Option<Vec<HitRecord>> {
+        let mut hits = self.intersect_all(ray, t_min, t_max)?;
+        hits.truncate(max_hits);
+        Some(hits)
+    }
+
+    // 点が形状の内部にあるか。内部を持たない面だけの形状は常にfalse
+    fn contains(&self, _point: Vec3) -> bool {
+        false
+    }
+
+    // 形状の外接ボックス。無限に広がる形状はNoneを返す
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
+
+    // 内部に持つ形状と、その名前（整合性チェックで形状木を辿るのに使う）
+    fn children(&self) -> Vec<(&'static str, &dyn Hittable)> {
+        Vec::new()
+    }
+
+    // 多数のレイそれぞれとの最も近い交点をまとめて求める（origins と directions は同じ長さ）
+    // 既定ではレイごとに intersect_all を呼ぶ。球や平面は複数のレイをまとめて計算する
+    fn intersect_batch(
+        &self,
+        origins: &[Vec3],
+        directions: &[Vec3],
+        t_min: f32,
+        t_max: f32,
+    ) -> Vec<Option<HitRecord>> {
+        origins
+            .iter()
+            .zip(directions)
+            .map(|(&origin, &direction)| {
+                let ray = Ray::new(origin, direction, 1.0);
+                self.intersect_all(&ray, t_min, t_max)
+                    .and_then(|hits| hits.into_iter().next())
+            })
+            .collect()
+    }
+
+    // 後ろの物体を隠さない計測用の面か（NonOccluding で包んだ形状だけが true）
+    // 最も近い衝突がこの面なら、通過を記録してそのまま奥の面を探す
+    fn non_occluding(&self) -> bool {
+        false
+    }
+
+    // 形状自身の寸法や向きの不備（子は Scene::validate が別に辿る）。問題が無ければNone
+    fn degeneracy(&self) -> Option<String> {
+        None
+    }
+
+    // 表示用の代表的な材質。複合形状は最初に見つかった子の材質を使う
+    fn material(&self) -> Option<&Material> {
+        self.children()
+            .into_iter()
+            .find_map(|(_, child)| child.material())
+    }
 }
@@ -0,0 +1,48 @@
+use crate::{Aabb, HitRecord, Hittable, Ray};
+use glam::Vec3;
+
+// 他のHittableオブジェクトを、後ろの物体を隠さない計測用の面（プローブ）にするラッパー
+// 追跡ではレイが通過した点を InteractionKind::PassThrough として記録するだけで、レイの向きも強度も変えない
+// 通過しても進めた回数 (max_bounces) には数えず、同じパスのうちに奥の面まで進む
+pub struct NonOccluding {
+    pub object: Box<dyn Hittable>,
+}
+
+impl NonOccluding {
+    pub fn new(object: Box<dyn Hittable>) -> Self {
+        Self { object }
+    }
+}
+
+impl Hittable for NonOccluding {
+    fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
+        self.object.intersect_all(ray, t_min, t_max)
+    }
+
+    fn intersect_batch(
+        &self,
+        origins: &[Vec3],
+        directions: &[Vec3],
+        t_min: f32,
+        t_max: f32,
+    ) -> Vec<Option<HitRecord>> {
+        self.object
+            .intersect_batch(origins, directions, t_min, t_max)
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        self.object.contains(point)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.object.bounding_box()
+    }
+
+    fn children(&self) -> Vec<(&'static str, &dyn Hittable)> {
+        vec![("object", self.object.as_ref())]
+    }
+
+    fn non_occluding(&self) -> bool {
+        true
+    }
+}
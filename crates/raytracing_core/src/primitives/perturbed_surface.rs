@@ -0,0 +1,90 @@
+use crate::{Aabb, HitRecord, Hittable, Ray};
+use glam::Vec3;
+
+// 当たった点から、法線を傾ける量を返す関数
+// 戻り値の法線に垂直な成分の向きへ、その長さ[rad]だけ法線を傾ける（法線方向の成分は無視する）
+pub type NormalFieldFn = Box<dyn Fn(Vec3) -> Vec3 + Sync + Send>;
+
+// 他のHittableオブジェクトの面の法線を、点ごとに少し傾けるラッパー（粗い面や細かな凹凸のある面）
+// 反射・屈折の向きは傾けた法線で決まる。交点の位置と面の表裏は元の形状のまま
+pub struct PerturbedSurface {
+    pub object: Box<dyn Hittable>,
+    pub field: NormalFieldFn,
+    pub max_angle_deg: f32, // 傾ける角度の上限[度]
+}
+
+impl PerturbedSurface {
+    pub fn new(
+        object: Box<dyn Hittable>,
+        field: impl Fn(Vec3) -> Vec3 + Sync + Send + 'static,
+        max_angle_deg: f32,
+    ) -> Self {
+        Self {
+            object,
+            field: Box::new(field),
+            max_angle_deg,
+        }
+    }
+
+    // 法線を傾ける。傾けた法線がレイと同じ側を向く（面の裏から当たったことになる）なら元の法線のまま
+    fn perturb(&self, mut hit: HitRecord, direction: Vec3) -> HitRecord {
+        let offset = (self.field)(hit.point);
+        let tangent = offset - offset.dot(hit.normal) * hit.normal;
+        let angle = tangent.length().min(self.max_angle_deg.to_radians());
+        if angle <= 0.0 {
+            return hit;
+        }
+        let normal = hit.normal * angle.cos() + tangent.normalize() * angle.sin();
+        if normal.dot(direction) < 0.0 {
+            hit.normal = normal.normalize();
+        }
+        hit
+    }
+}
+
+impl Hittable for PerturbedSurface {
+    fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
+        let hits = self.object.intersect_all(ray, t_min, t_max)?;
+        Some(
+            hits.into_iter()
+                .map(|hit| self.perturb(hit, ray.direction))
+                .collect(),
+        )
+    }
+
+    fn intersect_batch(
+        &self,
+        origins: &[Vec3],
+        directions: &[Vec3],
+        t_min: f32,
+        t_max: f32,
+    ) -> Vec<Option<HitRecord>> {
+        self.object
+            .intersect_batch(origins, directions, t_min, t_max)
+            .into_iter()
+            .zip(directions)
+            .map(|(hit, &direction)| hit.map(|hit| self.perturb(hit, direction)))
+            .collect()
+    }
+
+    fn degeneracy(&self) -> Option<String> {
+        (!(self.max_angle_deg >= 0.0 && self.max_angle_deg.is_finite())).then(|| {
+            format!(
+                "max_angle_deg は 0 以上の有限の値にしてください ({})",
+                self.max_angle_deg
+            )
+        })
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        self.object.contains(point)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.object.bounding_box()
+    }
+
+    fn children(&self) -> Vec<(&'static str, &dyn Hittable)> {
+        vec![("object", self.object.as_ref())]
+    }
+}
@@ -1,12 +1,40 @@
+use super::batch::{dot_lanes, Lanes, RayLanes, BATCH_LANES};
+use crate::validation::{non_finite, nonzero};
 use crate::{HitRecord, Hittable, Material, Ray};
 use glam::Vec3; // main.rsから移動させる共通定義をインポート
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Plane {
     pub point: Vec3,  // 平面上の任意の点
     pub normal: Vec3, // 平面の法線
     pub material: Material,
 }
+
+impl Plane {
+    // 始点 origin・向き direction のレイが t で当たった点の記録
+    fn hit_record(&self, origin: Vec3, direction: Vec3, t: f32) -> HitRecord {
+        // 衝突点の座標を計算
+        let point = origin + t * direction;
+
+        // レイが表面から当たったか、裏面から当たったかを判定
+        let front_face = direction.dot(self.normal) < 0.0;
+        // 法線ベクトルは常にレイと向かい合うように調整
+        let normal = if front_face {
+            self.normal
+        } else {
+            -self.normal
+        };
+
+        HitRecord {
+            t,
+            point,
+            normal,
+            front_face,
+            incoming: direction,
+            material: self.material.clone(),
+        }
+    }
+}
 impl Hittable for Plane {
     // 全ての交点をリストで返すメソッド
     fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
@@ -26,30 +54,59 @@ impl Hittable for Plane {
             return None;
         }
 
-        // --- 有効な交点が見つかった場合の処理 ---
+        // ★★★ 変更点 ★★★
+        // 単一のHitRecordを、要素が1つのVec（ベクタ）に入れてSomeで返す
+        Some(vec![self.hit_record(ray.origin, ray.direction, t)])
+    }
+
+    // BATCH_LANES 本ずつ、分母と t を成分ごとの配列でまとめて求める
+    // 式と計算順は intersect_all と同じなので、1本ずつ求めた交点と一致する
+    fn intersect_batch(
+        &self,
+        origins: &[Vec3],
+        directions: &[Vec3],
+        t_min: f32,
+        t_max: f32,
+    ) -> Vec<Option<HitRecord>> {
+        let normal: [Lanes; 3] = std::array::from_fn(|axis| [self.normal[axis]; BATCH_LANES]);
+        let mut hits = Vec::with_capacity(origins.len());
+        for (origins, directions) in origins
+            .chunks(BATCH_LANES)
+            .zip(directions.chunks(BATCH_LANES))
+        {
+            let lanes = RayLanes::load(origins, directions);
+            let to_plane: [Lanes; 3] =
+                std::array::from_fn(|axis| lanes.origin[axis].map(|o| self.point[axis] - o));
+            let denom = dot_lanes(&normal, &lanes.direction);
+            let numer = dot_lanes(&to_plane, &normal);
 
-        // 衝突点の座標を計算
-        let point = ray.origin + t * ray.direction;
+            // 平行なレーンと範囲外のレーンは NaN にしておく
+            let t: Lanes = std::array::from_fn(|i| {
+                let t = numer[i] / denom[i];
+                if denom[i].abs() < 1e-6 || t < t_min || t_max < t {
+                    f32::NAN
+                } else {
+                    t
+                }
+            });
 
-        // レイが表面から当たったか、裏面から当たったかを判定
-        let front_face = ray.direction.dot(self.normal) < 0.0;
-        // 法線ベクトルは常にレイと向かい合うように調整
-        let normal = if front_face {
-            self.normal
-        } else {
-            -self.normal
-        };
+            for (i, (&origin, &direction)) in origins.iter().zip(directions).enumerate() {
+                hits.push((!t[i].is_nan()).then(|| self.hit_record(origin, direction, t[i])));
+            }
+        }
+        hits
+    }
 
-        let hit_record = HitRecord {
-            t,
-            point,
-            normal,
-            front_face,
-            material: self.material,
-        };
+    fn degeneracy(&self) -> Option<String> {
+        non_finite("point", self.point).or_else(|| nonzero("normal", self.normal))
+    }
 
-        // ★★★ 変更点 ★★★
-        // 単一のHitRecordを、要素が1つのVec（ベクタ）に入れてSomeで返す
-        Some(vec![hit_record])
+    // 法線の向いている側を内部（半空間）とみなす
+    fn contains(&self, point: Vec3) -> bool {
+        (point - self.point).dot(self.normal) > 0.0
+    }
+
+    fn material(&self) -> Option<&Material> {
+        Some(&self.material)
     }
 }
@@ -0,0 +1,97 @@
+use crate::validation::{non_finite, nonzero, positive};
+use crate::{Aabb, HitRecord, Hittable, Material, Ray};
+use glam::Vec3;
+
+// 中心 center を通り normal に垂直な平面上の、幅 2 * half_width・高さ 2 * half_height の長方形
+// 幅の向きは width_dir（normal に垂直でなければ平面に射影する）、高さの向きは normal × width_dir
+// 縁より外は素通りするので、鏡の材質と組み合わせて、無限に広がる Plane では拾ってしまう縁の外の反射が無い折り返し鏡に使う
+#[derive(Debug, Clone)]
+pub struct Rectangle {
+    pub center: Vec3,
+    pub normal: Vec3,
+    pub width_dir: Vec3,
+    pub half_width: f32,
+    pub half_height: f32,
+    pub material: Material,
+}
+
+impl Rectangle {
+    // 平面内の幅と高さの向き（単位ベクトル）
+    fn axes(&self) -> (Vec3, Vec3) {
+        let normal = self.normal.normalize();
+        let width = (self.width_dir - normal * normal.dot(self.width_dir)).normalize();
+        (width, normal.cross(width))
+    }
+}
+
+impl Hittable for Rectangle {
+    fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
+        let outward_normal = self.normal.normalize();
+        let denom = outward_normal.dot(ray.direction);
+
+        // レイが平面と平行な場合は衝突しない
+        if denom.abs() < 1e-6 {
+            return None;
+        }
+
+        let t = (self.center - ray.origin).dot(outward_normal) / denom;
+        if t < t_min || t_max < t {
+            return None;
+        }
+
+        // 平面上の交点が長方形の外なら素通りする
+        let point = ray.origin + t * ray.direction;
+        let (width, height) = self.axes();
+        let offset = point - self.center;
+        if offset.dot(width).abs() > self.half_width || offset.dot(height).abs() > self.half_height
+        {
+            return None;
+        }
+
+        // 厚さがないので、法線側から当たった場合を表面とする
+        let front_face = denom < 0.0;
+        let normal = if front_face {
+            outward_normal
+        } else {
+            -outward_normal
+        };
+
+        Some(vec![HitRecord {
+            t,
+            point,
+            normal,
+            front_face,
+            incoming: ray.direction,
+            material: self.material.clone(),
+        }])
+    }
+
+    fn degeneracy(&self) -> Option<String> {
+        non_finite("center", self.center)
+            .or_else(|| nonzero("normal", self.normal))
+            .or_else(|| nonzero("width_dir", self.width_dir))
+            .or_else(|| positive("half_width", self.half_width))
+            .or_else(|| positive("half_height", self.half_height))
+            .or_else(|| {
+                let cross = self.normal.cross(self.width_dir);
+                (cross.length_squared() == 0.0)
+                    .then(|| "width_dir が normal と平行です".to_string())
+            })
+    }
+
+    // 厚さのない面なので内部は存在しない
+    fn contains(&self, _point: Vec3) -> bool {
+        false
+    }
+
+    // 4つの角を囲むボックス
+    fn bounding_box(&self) -> Option<Aabb> {
+        let (width, height) = self.axes();
+        let extent = (width * self.half_width).abs() + (height * self.half_height).abs();
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+
+    fn material(&self) -> Option<&Material> {
+        Some(&self.material)
+    }
+}
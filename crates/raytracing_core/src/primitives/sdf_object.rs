@@ -0,0 +1,168 @@
+use crate::{geometric_epsilon, Aabb, HitRecord, Hittable, Material, Ray};
+use glam::{Vec2, Vec3};
+
+// 1本のレイで進む回数の上限（面すれすれを進むレイで止まらなくならないように）
+const MAX_MARCH_STEPS: usize = 4096;
+// 境界を挟んだ区間を二分法で縮める回数
+const BISECTION_STEPS: usize = 32;
+// 組み込みの形状で、外接ボックスを形状より少し広げる割合
+const BOUNDS_MARGIN: f32 = 0.01;
+
+// 点から面までの符号付き距離（内部で負）を返す関数
+pub type SdfFn = Box<dyn Fn(Vec3) -> f32 + Sync + Send>;
+
+// 符号付き距離関数 (SDF) で表した形状。sdf が負の点を内部とする
+// bounds の中だけをスフィアトレーシング（|sdf| ずつ進む）で調べ、符号が変わった所を交点とする
+// 形状は bounds の内側に収まっていること。sdf は実際の距離を超えない値を返すこと（超えると面を飛び越える）
+pub struct SdfObject {
+    pub sdf: SdfFn,
+    pub bounds: Aabb,
+    pub material: Material,
+}
+
+impl SdfObject {
+    pub fn new(
+        sdf: impl Fn(Vec3) -> f32 + Sync + Send + 'static,
+        bounds: Aabb,
+        material: Material,
+    ) -> Self {
+        Self {
+            sdf: Box::new(sdf),
+            bounds,
+            material,
+        }
+    }
+
+    // 中心 center・半径 radius の球
+    pub fn sphere(center: Vec3, radius: f32, material: Material) -> Self {
+        let extent = Vec3::splat(radius * (1.0 + BOUNDS_MARGIN));
+        Self::new(
+            move |p| (p - center).length() - radius,
+            Aabb::new(center - extent, center + extent),
+            material,
+        )
+    }
+
+    // 中心 center・各辺の半分の長さ half_size の直方体
+    pub fn cuboid(center: Vec3, half_size: Vec3, material: Material) -> Self {
+        let extent = half_size * (1.0 + BOUNDS_MARGIN);
+        Self::new(
+            move |p| {
+                let q = (p - center).abs() - half_size;
+                q.max(Vec3::ZERO).length() + q.max_element().min(0.0)
+            },
+            Aabb::new(center - extent, center + extent),
+            material,
+        )
+    }
+
+    // 中心 center で Y 軸を囲むトーラス（中心から管の中心までが major_radius、管の半径が minor_radius）
+    pub fn torus(center: Vec3, major_radius: f32, minor_radius: f32, material: Material) -> Self {
+        let outer = major_radius + minor_radius;
+        let extent = Vec3::new(outer, minor_radius, outer) * (1.0 + BOUNDS_MARGIN);
+        Self::new(
+            move |p| {
+                let d = p - center;
+                let q = Vec2::new(Vec2::new(d.x, d.z).length() - major_radius, d.y);
+                q.length() - minor_radius
+            },
+            Aabb::new(center - extent, center + extent),
+            material,
+        )
+    }
+
+    // 中心差分で求めた sdf の勾配の向き（外向きの法線）
+    fn gradient(&self, point: Vec3) -> Vec3 {
+        let h = geometric_epsilon();
+        let sdf = &self.sdf;
+        Vec3::new(
+            sdf(point + Vec3::X * h) - sdf(point - Vec3::X * h),
+            sdf(point + Vec3::Y * h) - sdf(point - Vec3::Y * h),
+            sdf(point + Vec3::Z * h) - sdf(point - Vec3::Z * h),
+        )
+        .normalize_or_zero()
+    }
+
+    // 内外の異なる t_low と t_high の間で、面の位置を二分法で求める（inside_at_low は t_low での内外）
+    fn bisect(&self, ray: &Ray, mut t_low: f32, mut t_high: f32, inside_at_low: bool) -> f32 {
+        for _ in 0..BISECTION_STEPS {
+            let t_mid = 0.5 * (t_low + t_high);
+            if ((self.sdf)(ray.origin + t_mid * ray.direction) < 0.0) == inside_at_low {
+                t_low = t_mid;
+            } else {
+                t_high = t_mid;
+            }
+        }
+        0.5 * (t_low + t_high)
+    }
+
+    fn hit_record(&self, ray: &Ray, t: f32) -> HitRecord {
+        let point = ray.origin + t * ray.direction;
+        let mut outward_normal = self.gradient(point);
+        if outward_normal == Vec3::ZERO {
+            outward_normal = -ray.direction.normalize();
+        }
+        let front_face = ray.direction.dot(outward_normal) < 0.0;
+        let normal = if front_face {
+            outward_normal
+        } else {
+            -outward_normal
+        };
+        HitRecord {
+            t,
+            point,
+            normal,
+            front_face,
+            incoming: ray.direction,
+            material: self.material.clone(),
+        }
+    }
+}
+
+impl Hittable for SdfObject {
+    fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
+        let (t_enter, t_exit) = self.bounds.clip_ray(ray, t_min, t_max)?;
+        let speed = ray.direction.length();
+        // 面の近くでもこれより小さくは進まない（面を確実に越えて符号の変化を捉えるため）
+        let min_step = geometric_epsilon() / speed;
+
+        let mut hits = Vec::new();
+        let mut t = t_enter;
+        let mut distance = (self.sdf)(ray.origin + t * ray.direction);
+        let mut inside = distance < 0.0;
+        for _ in 0..MAX_MARCH_STEPS {
+            if t >= t_exit {
+                break;
+            }
+            let t_next = (t + (distance.abs() / speed).max(min_step)).min(t_exit);
+            let next_distance = (self.sdf)(ray.origin + t_next * ray.direction);
+            if (next_distance < 0.0) != inside {
+                let t_hit = self.bisect(ray, t, t_next, inside);
+                hits.push(self.hit_record(ray, t_hit));
+                inside = !inside;
+            }
+            t = t_next;
+            distance = next_distance;
+        }
+
+        if hits.is_empty() {
+            None
+        } else {
+            Some(hits)
+        }
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        let inside_bounds =
+            point.cmpge(self.bounds.min).all() && point.cmple(self.bounds.max).all();
+        inside_bounds && (self.sdf)(point) < 0.0
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+
+    fn material(&self) -> Option<&Material> {
+        Some(&self.material)
+    }
+}
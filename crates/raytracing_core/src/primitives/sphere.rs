@@ -1,13 +1,38 @@
-use crate::{HitRecord, Hittable, Material, Ray};
+use super::batch::{dot_lanes, sub_lanes, Lanes, RayLanes, BATCH_LANES};
+use crate::validation::{non_finite, positive};
+use crate::{geometric_epsilon, Aabb, HitRecord, Hittable, Material, Ray};
 use glam::Vec3; // main.rsから移動させる共通定義をインポート
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Sphere {
     // ★ pub を追加
     pub center: Vec3,
     pub radius: f32,
     pub material: Material,
 }
+impl Sphere {
+    // 始点 origin・向き direction のレイが t で当たった点の記録
+    fn hit_record(&self, origin: Vec3, direction: Vec3, t: f32) -> HitRecord {
+        let point = origin + t * direction;
+        let outward_normal = (point - self.center) / self.radius;
+        let front_face = direction.dot(outward_normal) < 0.0;
+        let normal = if front_face {
+            outward_normal
+        } else {
+            -outward_normal
+        };
+
+        HitRecord {
+            t,
+            point,
+            normal,
+            front_face,
+            incoming: direction,
+            material: self.material.clone(),
+        }
+    }
+}
+
 impl Hittable for Sphere {
     fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
         let oc = ray.origin - self.center;
@@ -15,7 +40,10 @@ impl Hittable for Sphere {
         let half_b = oc.dot(ray.direction);
         let c = oc.length_squared() - self.radius * self.radius;
         let discriminant = half_b * half_b - a * c;
-        if discriminant < 0.0 {
+        // 弦の半分が許容誤差の10倍以下なら接しているとみなし、当たらなかったことにする
+        // （入射だけ返すと、その先を球の内側と取り違える）
+        let min_half_chord = geometric_epsilon() * 10.0;
+        if discriminant <= min_half_chord * min_half_chord {
             return None;
         }
 
@@ -25,45 +53,13 @@ impl Hittable for Sphere {
         // 1つ目の解
         let t1 = (-half_b - sqrtd) / a;
         if t1 > t_min && t1 < t_max {
-            let point = ray.origin + t1 * ray.direction;
-            let outward_normal = (point - self.center) / self.radius;
-            let front_face = ray.direction.dot(outward_normal) < 0.0;
-            let normal = if front_face {
-                outward_normal
-            } else {
-                -outward_normal
-            };
-
-            hits.push(HitRecord {
-                t: t1,
-                point,
-                normal,
-                front_face,
-                material: self.material,
-            });
+            hits.push(self.hit_record(ray.origin, ray.direction, t1));
         }
 
         // 2つ目の解
-        if discriminant > 1e-6 {
-            let t2 = (-half_b + sqrtd) / a;
-            if t2 > t_min && t2 < t_max {
-                let point = ray.origin + t2 * ray.direction;
-                let outward_normal = (point - self.center) / self.radius;
-                let front_face = ray.direction.dot(outward_normal) < 0.0;
-                let normal = if front_face {
-                    outward_normal
-                } else {
-                    -outward_normal
-                };
-
-                hits.push(HitRecord {
-                    t: t2,
-                    point,
-                    normal,
-                    front_face,
-                    material: self.material,
-                });
-            }
+        let t2 = (-half_b + sqrtd) / a;
+        if t2 > t_min && t2 < t_max {
+            hits.push(self.hit_record(ray.origin, ray.direction, t2));
         }
 
         if hits.is_empty() {
@@ -72,4 +68,67 @@ impl Hittable for Sphere {
             Some(hits)
         }
     }
+
+    // BATCH_LANES 本ずつ、判別式と解を成分ごとの配列でまとめて求める
+    // 式と計算順は intersect_all と同じなので、1本ずつ求めた最初の交点と一致する
+    fn intersect_batch(
+        &self,
+        origins: &[Vec3],
+        directions: &[Vec3],
+        t_min: f32,
+        t_max: f32,
+    ) -> Vec<Option<HitRecord>> {
+        let radius_squared = self.radius * self.radius;
+        let min_half_chord = geometric_epsilon() * 10.0;
+        let mut hits = Vec::with_capacity(origins.len());
+        for (origins, directions) in origins
+            .chunks(BATCH_LANES)
+            .zip(directions.chunks(BATCH_LANES))
+        {
+            let lanes = RayLanes::load(origins, directions);
+            let oc = sub_lanes(&lanes.origin, self.center);
+            let a = dot_lanes(&lanes.direction, &lanes.direction);
+            let half_b = dot_lanes(&oc, &lanes.direction);
+            let c = dot_lanes(&oc, &oc).map(|x| x - radius_squared);
+
+            // 当たらないレーンは NaN にしておく
+            let t: Lanes = std::array::from_fn(|i| {
+                let discriminant = half_b[i] * half_b[i] - a[i] * c[i];
+                let sqrtd = discriminant.sqrt();
+                let t1 = (-half_b[i] - sqrtd) / a[i];
+                let t2 = (-half_b[i] + sqrtd) / a[i];
+                if discriminant <= min_half_chord * min_half_chord {
+                    f32::NAN
+                } else if t1 > t_min && t1 < t_max {
+                    t1
+                } else if t2 > t_min && t2 < t_max {
+                    t2
+                } else {
+                    f32::NAN
+                }
+            });
+
+            for (i, (&origin, &direction)) in origins.iter().zip(directions).enumerate() {
+                hits.push((!t[i].is_nan()).then(|| self.hit_record(origin, direction, t[i])));
+            }
+        }
+        hits
+    }
+
+    fn degeneracy(&self) -> Option<String> {
+        non_finite("center", self.center).or_else(|| positive("radius", self.radius))
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        (point - self.center).length_squared() < self.radius * self.radius
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let r = Vec3::splat(self.radius);
+        Some(Aabb::new(self.center - r, self.center + r))
+    }
+
+    fn material(&self) -> Option<&Material> {
+        Some(&self.material)
+    }
 }
@@ -0,0 +1,97 @@
+use crate::validation::{non_finite, nonzero, positive};
+use crate::{Aabb, HitRecord, Hittable, Material, Ray};
+use glam::Vec3;
+
+// 球面のうち、中心から見て axis_dir との角度が一定以内の部分だけを残した厚さのない曲面（ドーム）
+// ドーム型の窓や半球レンズの球面部分に使う
+#[derive(Debug, Clone)]
+pub struct SphericalCap {
+    pub center: Vec3,
+    pub radius: f32,
+    pub axis_dir: Vec3,     // キャップの頂点の向き
+    pub min_cos_angle: f32, // 頂点からの角度の余弦の下限（0 で半球、-1 で球全体）
+    pub material: Material,
+}
+
+impl SphericalCap {
+    // 球面上の点がキャップの範囲に入っているか
+    fn on_cap(&self, point: Vec3) -> bool {
+        let cos_angle = (point - self.center).dot(self.axis_dir.normalize()) / self.radius;
+        cos_angle >= self.min_cos_angle
+    }
+}
+
+impl Hittable for SphericalCap {
+    fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
+        let oc = ray.origin - self.center;
+        let a = ray.direction.length_squared();
+        let half_b = oc.dot(ray.direction);
+        let c = oc.length_squared() - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        let sqrtd = discriminant.sqrt();
+        let hits: Vec<HitRecord> = [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a]
+            .into_iter()
+            .filter(|&t| t > t_min && t < t_max)
+            .map(|t| (t, ray.origin + t * ray.direction))
+            // 切り取られた部分に当たった解は捨てる
+            .filter(|&(_, point)| self.on_cap(point))
+            .map(|(t, point)| {
+                // 球の外側から当たった場合を表面とする
+                let outward_normal = (point - self.center) / self.radius;
+                let front_face = ray.direction.dot(outward_normal) < 0.0;
+                let normal = if front_face {
+                    outward_normal
+                } else {
+                    -outward_normal
+                };
+                HitRecord {
+                    t,
+                    point,
+                    normal,
+                    front_face,
+                    incoming: ray.direction,
+                    material: self.material.clone(),
+                }
+            })
+            .collect();
+
+        if hits.is_empty() {
+            None
+        } else {
+            Some(hits)
+        }
+    }
+
+    // 厚さのない面なので内部は存在しない
+    fn degeneracy(&self) -> Option<String> {
+        non_finite("center", self.center)
+            .or_else(|| positive("radius", self.radius))
+            .or_else(|| nonzero("axis_dir", self.axis_dir))
+            .or_else(|| {
+                (!(-1.0..=1.0).contains(&self.min_cos_angle)).then(|| {
+                    format!(
+                        "min_cos_angle は -1 以上 1 以下にしてください ({})",
+                        self.min_cos_angle
+                    )
+                })
+            })
+    }
+
+    fn contains(&self, _point: Vec3) -> bool {
+        false
+    }
+
+    // キャップを含む球全体の箱（切り取った分は詰めない）
+    fn bounding_box(&self) -> Option<Aabb> {
+        let r = Vec3::splat(self.radius);
+        Some(Aabb::new(self.center - r, self.center + r))
+    }
+
+    fn material(&self) -> Option<&Material> {
+        Some(&self.material)
+    }
+}
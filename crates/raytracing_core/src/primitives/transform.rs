@@ -1,52 +1,114 @@
-use crate::{HitRecord, Hittable, Ray};
-use glam::Mat4;
+use crate::{Aabb, HitRecord, Hittable, Ray};
+use glam::{Mat4, Vec3};
 // 他のHittableオブジェクトに変換を適用するためのラッパー
 pub struct Transform {
     pub object: Box<dyn Hittable>,
     pub transform: Mat4,         // ローカル空間 -> ワールド空間への変換
     pub inverse_transform: Mat4, // ワールド空間 -> ローカル空間への変換
+    pub normal_matrix: Mat4,     // 法線をワールド空間へ移す行列（逆行列の転置）
 }
 
 impl Transform {
     pub fn new(object: Box<dyn Hittable>, transform: Mat4) -> Self {
+        let inverse_transform = transform.inverse(); // 逆行列も保持
         Self {
             object,
             transform,
-            inverse_transform: transform.inverse(), // 逆行列も保持
+            inverse_transform,
+            // 衝突のたびに転置し直さないよう、ここで求めておく
+            normal_matrix: inverse_transform.transpose(),
         }
     }
+
+    // ローカル空間での衝突の記録をワールド空間へ変換する
+    fn hit_to_world(&self, mut hit: HitRecord) -> HitRecord {
+        // 衝突点と法線、レイの向きをワールド空間に変換
+        hit.point = self.transform.transform_point3(hit.point);
+        // 法線ベクトルの変換は、逆行列の転置行列をかけるのが数学的に正しい
+        hit.normal = self.normal_matrix.transform_vector3(hit.normal).normalize();
+        hit.incoming = self.transform.transform_vector3(hit.incoming);
+        hit
+    }
 }
 impl Hittable for Transform {
     fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
+        self.intersect_nearest(ray, t_min, t_max, usize::MAX)
+    }
+
+    fn intersect_nearest(
+        &self,
+        ray: &Ray,
+        t_min: f32,
+        t_max: f32,
+        max_hits: usize,
+    ) -> Option<Vec<HitRecord>> {
         // 1. レイをワールド空間からオブジェクトのローカル空間へ逆変換
         let local_ray_origin = self.inverse_transform.transform_point3(ray.origin);
         let local_ray_direction = self.inverse_transform.transform_vector3(ray.direction);
         let local_ray = Ray {
             origin: local_ray_origin,
             direction: local_ray_direction,
-            current_ior: ray.current_ior, // IORは空間変換で変化しない
+            ..ray.clone() // IORや強度は空間変換で変化しない
         };
 
         // 2. ローカル空間で、包み込んだオブジェクトとの交差判定を行う
-        if let Some(local_hits) = self.object.intersect_all(&local_ray, t_min, t_max) {
+        if let Some(local_hits) = self
+            .object
+            .intersect_nearest(&local_ray, t_min, t_max, max_hits)
+        {
             // 3. 結果をローカル空間からワールド空間へ変換して返す
             let world_hits = local_hits
                 .into_iter()
-                .map(|mut hit| {
-                    // 衝突点と法線をワールド空間に変換
-                    hit.point = self.transform.transform_point3(hit.point);
-                    // 法線ベクトルの変換は、逆行列の転置行列をかけるのが数学的に正しい
-                    hit.normal = self
-                        .inverse_transform
-                        .transpose()
-                        .transform_vector3(hit.normal)
-                        .normalize();
-                    hit
-                })
+                .map(|hit| self.hit_to_world(hit))
                 .collect();
             Some(world_hits)
         } else {
             None
         }
     }
+
+    // レイをまとめてローカル空間へ移し、包み込んだオブジェクトの intersect_batch に渡す
+    fn intersect_batch(
+        &self,
+        origins: &[Vec3],
+        directions: &[Vec3],
+        t_min: f32,
+        t_max: f32,
+    ) -> Vec<Option<HitRecord>> {
+        let local_origins: Vec<Vec3> = origins
+            .iter()
+            .map(|&origin| self.inverse_transform.transform_point3(origin))
+            .collect();
+        let local_directions: Vec<Vec3> = directions
+            .iter()
+            .map(|&direction| self.inverse_transform.transform_vector3(direction))
+            .collect();
+        self.object
+            .intersect_batch(&local_origins, &local_directions, t_min, t_max)
+            .into_iter()
+            .map(|hit| hit.map(|hit| self.hit_to_world(hit)))
+            .collect()
+    }
+
+    fn degeneracy(&self) -> Option<String> {
+        let invertible = self.transform.is_finite()
+            && self.inverse_transform.is_finite()
+            && self.transform.determinant() != 0.0;
+        (!invertible).then(|| "変換行列が有限でないか、逆行列を持ちません".to_string())
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        self.object
+            .contains(self.inverse_transform.transform_point3(point))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.object
+            .bounding_box()
+            .map(|local_box| local_box.transformed(&self.transform))
+    }
+
+    fn children(&self) -> Vec<(&'static str, &dyn Hittable)> {
+        vec![("object", self.object.as_ref())]
+    }
 }
@@ -0,0 +1,160 @@
+use crate::validation::non_finite;
+use crate::{geometric_epsilon, Aabb, HitRecord, Hittable, Material, Ray};
+use glam::Vec3;
+
+// 三角形メッシュ
+// CSGの演算対象にするには、メッシュが閉じている（水密: 各辺がちょうど2つの三角形に共有される）必要がある。
+// 三角形は外側から見て反時計回りに頂点を並べる（(b - a) × (c - a) が外向き法線になる）。
+// 閉じていないメッシュでは入射/出射が交互にならず、contains の結果も意味を持たない
+#[derive(Debug, Clone)]
+pub struct TriangleMesh {
+    pub vertices: Vec<Vec3>,
+    pub triangles: Vec<[usize; 3]>, // 頂点の添字
+    pub material: Material,
+}
+
+// 同じ交点とみなす t の差（辺や頂点を通るレイが隣の三角形にも当たる場合）。長さの許容誤差に対する倍率
+const DUPLICATE_HIT_FACTOR: f32 = 0.1;
+
+impl TriangleMesh {
+    // 頂点の範囲外を指す三角形は degeneracy で報告し、交差判定では無視する
+    pub fn new(vertices: Vec<Vec3>, triangles: Vec<[usize; 3]>, material: Material) -> Self {
+        Self {
+            vertices,
+            triangles,
+            material,
+        }
+    }
+
+    // レイと交わる三角形の (t, 外向き法線) を手前から順に返す。同じ点での重複は1つにまとめる
+    fn crossings(&self, origin: Vec3, direction: Vec3, t_min: f32, t_max: f32) -> Vec<(f32, Vec3)> {
+        let mut crossings: Vec<(f32, Vec3)> = self
+            .triangles
+            .iter()
+            .filter_map(|&triangle| {
+                let [a, b, c] = triangle.map(|index| self.vertices.get(index).map(|&v| (index, v)));
+                let t = intersect_triangle(origin, direction, [a?, b?, c?])?;
+                let (a, b, c) = (a?.1, b?.1, c?.1);
+                (t > t_min && t < t_max).then(|| (t, (b - a).cross(c - a).normalize()))
+            })
+            .collect();
+        crossings.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+        // 辺や頂点を通ると、同じ向きの交点が隣り合う三角形から重複して見つかるので1つにまとめる
+        // 輪郭の辺に接するレイでは、逆向きの交点（入射と出射）が同じ点で組になるので両方除く
+        let mut merged: Vec<(f32, Vec3)> = Vec::with_capacity(crossings.len());
+        for crossing in crossings {
+            if let Some(prev) = merged.last()
+                && (crossing.0 - prev.0).abs() < geometric_epsilon() * DUPLICATE_HIT_FACTOR
+            {
+                if (crossing.1.dot(direction) < 0.0) != (prev.1.dot(direction) < 0.0) {
+                    merged.pop();
+                }
+                continue;
+            }
+            merged.push(crossing);
+        }
+        merged
+    }
+}
+
+// レイが辺 p → q のどちら側を通るか（プリュッカー座標による判定）
+// 隣り合う三角形で共有する辺は、頂点の添字の小さい方から計算して符号だけ変えるので、
+// 両側の三角形で値がちょうど逆になり、辺の上を通るレイがどちらの三角形からも漏れない
+fn edge_side(origin: Vec3, direction: Vec3, (i, p): (usize, Vec3), (j, q): (usize, Vec3)) -> f32 {
+    let side = |p: Vec3, q: Vec3| direction.dot((p - origin).cross(q - origin));
+    if i < j {
+        side(p, q)
+    } else {
+        -side(q, p)
+    }
+}
+
+// レイと三角形の交差判定。3本の辺の同じ側を通れば当たり、その t を返す
+// （辺の上を通る場合は両側の三角形で当たりとし、重複は crossings でまとめる）
+fn intersect_triangle(origin: Vec3, direction: Vec3, [a, b, c]: [(usize, Vec3); 3]) -> Option<f32> {
+    let sides = [
+        edge_side(origin, direction, a, b),
+        edge_side(origin, direction, b, c),
+        edge_side(origin, direction, c, a),
+    ];
+    if !(sides.iter().all(|&s| s >= 0.0) || sides.iter().all(|&s| s <= 0.0)) {
+        return None;
+    }
+    let normal = (b.1 - a.1).cross(c.1 - a.1);
+    let denominator = normal.dot(direction);
+    if denominator.abs() < 1e-9 {
+        return None; // 三角形と平行
+    }
+    Some(normal.dot(a.1 - origin) / denominator)
+}
+
+impl Hittable for TriangleMesh {
+    fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
+        let hits: Vec<HitRecord> = self
+            .crossings(ray.origin, ray.direction, t_min, t_max)
+            .into_iter()
+            .map(|(t, outward_normal)| {
+                let front_face = ray.direction.dot(outward_normal) < 0.0;
+                let normal = if front_face {
+                    outward_normal
+                } else {
+                    -outward_normal
+                };
+                HitRecord {
+                    t,
+                    point: ray.origin + t * ray.direction,
+                    normal,
+                    front_face,
+                    incoming: ray.direction,
+                    material: self.material.clone(),
+                }
+            })
+            .collect();
+
+        if hits.is_empty() {
+            None
+        } else {
+            Some(hits)
+        }
+    }
+
+    fn degeneracy(&self) -> Option<String> {
+        self.vertices
+            .iter()
+            .find_map(|&vertex| non_finite("頂点", vertex))
+            .or_else(|| {
+                self.triangles
+                    .iter()
+                    .flatten()
+                    .find(|&&index| index >= self.vertices.len())
+                    .map(|index| {
+                        format!(
+                            "頂点の添字 {} が頂点の数 {} を超えています",
+                            index,
+                            self.vertices.len()
+                        )
+                    })
+            })
+    }
+
+    // 点から1方向へ伸ばした半直線が面を横切る回数の偶奇で内外を判定する
+    fn contains(&self, point: Vec3) -> bool {
+        // 辺や頂点をちょうど通りにくい、軸に揃っていない向き
+        let direction = Vec3::new(0.577_215_7, 0.651_247_3, 0.431_468_3).normalize();
+        self.crossings(point, direction, 0.0, f32::INFINITY).len() % 2 == 1
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let first = *self.vertices.first()?;
+        let (min, max) = self
+            .vertices
+            .iter()
+            .fold((first, first), |(min, max), v| (min.min(*v), max.max(*v)));
+        Some(Aabb::new(min, max))
+    }
+
+    fn material(&self) -> Option<&Material> {
+        Some(&self.material)
+    }
+}
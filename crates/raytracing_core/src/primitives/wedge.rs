@@ -1,6 +1,6 @@
 use glam::Vec3;
 
-use crate::{CSGObject, CsgOperation, HitRecord, Hittable, Material, Plane, Ray};
+use crate::{Aabb, CSGObject, CsgOperation, HitRecord, Hittable, Material, Plane, Ray};
 //ウェッジ
 pub struct Wedge {
     pub csg_object: Box<dyn Hittable>,
@@ -8,8 +8,6 @@ pub struct Wedge {
 // Wedge構造体の実装ブロックを追加
 impl Wedge {
     pub fn new(size: Vec3, wedge_angle_rad: f32, material: Material) -> Self {
-        let width = size.x;
-        let height = size.y;
         let half_depth = size.z / 2.0;
 
         // --- 5枚の平面を定義 ---
@@ -17,14 +15,14 @@ impl Wedge {
             // 底面 (y >= 0)
             point: Vec3::ZERO,
             normal: Vec3::Y,
-            material,
+            material: material.clone(),
         }) as Box<dyn Hittable>;
 
         let p2 = Box::new(Plane {
             // 垂直面 (x >= 0)
             point: Vec3::ZERO,
             normal: Vec3::X,
-            material,
+            material: material.clone(),
         }) as Box<dyn Hittable>;
 
         // 傾斜面
@@ -33,14 +31,14 @@ impl Wedge {
         let p3 = Box::new(Plane {
             point: Vec3::ZERO,
             normal: Vec3::new(-angle_sin, angle_cos, 0.0), // 法線で傾きを表現
-            material,
+            material: material.clone(),
         }) as Box<dyn Hittable>;
 
         let p4 = Box::new(Plane {
             // 前面キャップ (z <= half_depth)
             point: Vec3::new(0.0, 0.0, half_depth),
             normal: Vec3::NEG_Z, // 法線を反転させることで、zが小さい側が「内側」になる
-            material,
+            material: material.clone(),
         }) as Box<dyn Hittable>;
 
         let p5 = Box::new(Plane {
@@ -82,4 +80,17 @@ impl Hittable for Wedge {
     fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
         self.csg_object.intersect_all(ray, t_min, t_max)
     }
+
+    fn contains(&self, point: Vec3) -> bool {
+        self.csg_object.contains(point)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.csg_object.bounding_box()
+    }
+
+    // 中身のCSGはこの形状自身と同じなので、その子を直接返す
+    fn children(&self) -> Vec<(&'static str, &dyn Hittable)> {
+        self.csg_object.children()
+    }
 }
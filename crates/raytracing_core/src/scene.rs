@@ -1,9 +1,15 @@
-use std::clone;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::thread;
 
 use glam::Vec3;
 use rand::Rng;
 
-use crate::{Hittable, Material};
+use crate::{
+    abbe_refractive_index, geometric_epsilon, tessellate, Aabb, Bvh, Hittable, Material,
+    SceneError, TriangleData, D_LINE_NM, TESSELLATION_RESOLUTION,
+};
 
 // 反射ベクトルを計算
 fn reflect(incident: Vec3, normal: Vec3) -> Vec3 {
@@ -25,84 +31,1183 @@ fn refract(incident: Vec3, normal: Vec3, ior_ratio: f32) -> Option<Vec3> {
 
     Some((perp + parallel).normalize())
 }
+
+// フレネルの式から無偏光の反射率を計算（全反射なら1.0）
+fn fresnel_reflectance(incident: Vec3, normal: Vec3, n1: f32, n2: f32) -> f32 {
+    let cos_i = (-incident).dot(normal).clamp(0.0, 1.0);
+    let sin_t_squared = (n1 / n2).powi(2) * (1.0 - cos_i * cos_i);
+    if sin_t_squared > 1.0 {
+        return 1.0;
+    }
+    let cos_t = (1.0 - sin_t_squared).sqrt();
+
+    let r_s = ((n1 * cos_i - n2 * cos_t) / (n1 * cos_i + n2 * cos_t)).powi(2);
+    let r_p = ((n1 * cos_t - n2 * cos_i) / (n1 * cos_t + n2 * cos_i)).powi(2);
+    (r_s + r_p) / 2.0
+}
+
+// ガラスの材質の、光線の波長での屈折率（ガラスでなければNone）
+fn glass_ior(material: &Material, wavelength: f32) -> Option<f32> {
+    match *material {
+        Material::Glass { ior } | Material::IdealGlass { ior } => Some(ior),
+        Material::GlassByAbbe { nd, vd } => Some(abbe_refractive_index(nd, vd, wavelength)),
+        _ => None,
+    }
+}
+
+// ガラス面でレイがどうなったか
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GlassScatter {
+    Refracted,
+    PartialReflection, // フレネル反射で反射した（ゴースト像の元になる迷光）
+    TotalInternalReflection,
+    // 屈折した上で、反射率 reflectance の反射光を別の分岐として追跡する
+    Split { reflectance: f32 },
+}
+
+// ガラス面での反射/屈折を行い、レイの向きを更新する
+// n2 は境界の先の媒質の屈折率（屈折したときの媒質の記録は呼び出し側で更新する）
+fn scatter_glass(ray: &mut Ray, normal: Vec3, n2: f32, fresnel_mode: FresnelMode) -> GlassScatter {
+    let n1 = ray.current_ior();
+    let ior_ratio = n1 / n2;
+
+    let reflectance = fresnel_reflectance(ray.direction, normal, n1, n2);
+    let should_reflect = match fresnel_mode {
+        FresnelMode::AlwaysRefract => false,
+        // 屈折する側は透過率で減衰させる（反射した分は呼び出し側で分岐にする）
+        FresnelMode::Split if reflectance < 1.0 => {
+            if let Some(refracted_dir) = refract(ray.direction, normal, ior_ratio) {
+                ray.direction = refracted_dir;
+                ray.intensity *= 1.0 - reflectance;
+                return GlassScatter::Split { reflectance };
+            }
+            true
+        }
+        FresnelMode::Split => true,
+        FresnelMode::Stochastic => rand::thread_rng().r#gen::<f32>() < reflectance,
+        FresnelMode::Deterministic => {
+            // 辿る分岐の確率で強度を減衰させる
+            if reflectance > 0.5 {
+                ray.intensity *= reflectance;
+                true
+            } else {
+                ray.intensity *= 1.0 - reflectance;
+                false
+            }
+        }
+    };
+
+    if should_reflect {
+        ray.direction = reflect(ray.direction, normal);
+        // 反射率1は全反射
+        if reflectance >= 1.0 {
+            GlassScatter::TotalInternalReflection
+        } else {
+            GlassScatter::PartialReflection
+        }
+    } else if let Some(refracted_dir) = refract(ray.direction, normal, ior_ratio) {
+        ray.direction = refracted_dir;
+        GlassScatter::Refracted
+    } else {
+        ray.direction = reflect(ray.direction, normal);
+        GlassScatter::TotalInternalReflection
+    }
+}
+
+// 多層膜フィルタで反射率 reflectance の反射と透過を選び、レイの向きと強度を更新する
+// 透過は向きを変えないので GlassScatter::Refracted として返す。分岐の作り方は fresnel_mode に従う
+// （AlwaysRefract では常に透過し、透過率の分だけ強度を減らす）
+fn scatter_filter(
+    ray: &mut Ray,
+    normal: Vec3,
+    reflectance: f32,
+    fresnel_mode: FresnelMode,
+) -> GlassScatter {
+    let should_reflect = match fresnel_mode {
+        FresnelMode::AlwaysRefract => {
+            ray.intensity *= 1.0 - reflectance;
+            false
+        }
+        FresnelMode::Split if reflectance < 1.0 => {
+            ray.intensity *= 1.0 - reflectance;
+            return GlassScatter::Split { reflectance };
+        }
+        FresnelMode::Split => true,
+        FresnelMode::Stochastic => rand::thread_rng().r#gen::<f32>() < reflectance,
+        FresnelMode::Deterministic => {
+            if reflectance > 0.5 {
+                ray.intensity *= reflectance;
+                true
+            } else {
+                ray.intensity *= 1.0 - reflectance;
+                false
+            }
+        }
+    };
+    if should_reflect {
+        ray.direction = reflect(ray.direction, normal);
+        GlassScatter::PartialReflection
+    } else {
+        GlassScatter::Refracted
+    }
+}
+
+// ガラス面で屈折した光路 path から、反射率 reflectance で反射した分岐（ゴースト）を作る
+// 分岐はそこまでの光路を引き継ぎ、最後の衝突を部分反射に書き換える
+// ray は屈折する前のレイ。弱すぎる反射光や、反射の回数が上限に達する分岐は作らない
+fn spawn_reflected_branch(
+    path: &ActivePath,
+    mut ray: Ray,
+    reflectance: f32,
+    setting: SimulationSettingsConfig,
+    branches: &mut Vec<ActivePath>,
+) {
+    let intensity = ray.intensity * reflectance;
+    if intensity < MIN_BRANCH_INTENSITY * path.initial_intensity
+        || path.reflections + 1 >= setting.max_reflections
+    {
+        return;
+    }
+    let mut branch = path.clone();
+    for interaction in &mut branch.interactions {
+        interaction.inherited = true;
+    }
+    let interaction = branch
+        .interactions
+        .last_mut()
+        .expect("屈折した衝突が記録されている");
+    ray.direction = reflect(ray.direction, interaction.hit.normal);
+    ray.intensity = intensity;
+    ray.origin = interaction.hit.point + ray.direction * hit_t_min();
+    interaction.outgoing_dir = ray.direction;
+    interaction.kind = InteractionKind::Reflection;
+    interaction.ghost = true;
+    interaction.angles = SurfaceAngles::new(
+        interaction.incoming_dir,
+        ray.direction,
+        interaction.hit.normal,
+    );
+    branch.ray = ray;
+    branch.reflections += 1;
+    // 分かれる前の切り詰めは元の光路で数えてあるので、二重に数えない
+    branch.truncated_hits = 0;
+    branches.push(branch);
+}
+
+// 以下の距離は長さの許容誤差 (geometric_epsilon) に対する倍率で、既定値 1e-4 のときの値を括弧内に書く
+// 直前の衝突点とこの距離より近ければ、同じ面への再衝突とみなす (1e-4)
+const REHIT_FACTOR: f32 = 1.0;
+// 再衝突時に始点をずらす距離。通常のずらし幅の10倍 (0.01)
+const REHIT_NUDGE_FACTOR: f32 = 100.0;
+// 直近の OSCILLATION_WINDOW 回の衝突点がすべて最後の点からこの距離の内側にあれば、
+// ほとんど進まずに往復し続けている（薄いガラス板の中で全反射を繰り返すなど）とみなして打ち切る (0.01)
+const OSCILLATION_RADIUS_FACTOR: f32 = 100.0;
+const OSCILLATION_WINDOW: usize = 16;
+
+// 始点のガラスから出た後の媒質（空気）の屈折率
+const AIR_IOR: f32 = 1.0;
+// 媒質の記録に残せる入れ子の深さ
+const MAX_NESTED_MEDIA: usize = 8;
+// 別のオブジェクトの面とこの t の差より近ければ、同じ境界（貼り合わせ面）とみなす (1e-4)
+const COINCIDENT_FACTOR: f32 = 1.0;
+
+// 衝突とみなす t の下限と、衝突後に始点をずらす距離（始点の面に当たり直さないように） (0.001)
+const HIT_T_MIN_FACTOR: f32 = 10.0;
+
+fn hit_t_min() -> f32 {
+    geometric_epsilon() * HIT_T_MIN_FACTOR
+}
+
+// 飛び去るレイの区間の長さの上限（シーンの外接ボックスの対角線に対する倍率）
+const ESCAPE_LENGTH_FACTOR: f32 = 2.0;
+
+// FresnelMode::Split で、これより弱い反射光は分岐として追跡しない（始点での強度に対する割合）
+const MIN_BRANCH_INTENSITY: f32 = 1e-3;
+
+// 全反射した面の外側で、隙間の向こうのガラスを探す距離（gap_decay_length に対する倍率）
+// これより広い隙間の透過率は exp(-10) 未満なので無視する
+const GAP_SEARCH_FACTOR: f32 = 10.0;
+
 pub struct Scene {
     pub objects: Vec<Box<dyn Hittable>>,
     pub rays: Vec<Ray>,
+    pub object_names: HashMap<String, usize>, // 名前を付けたオブジェクトの Scene.objects 内での添字
+}
+
+// ガラス面での反射/屈折の選び方
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FresnelMode {
+    /// フレネル反射率を確率として、反射か屈折かをランダムに選ぶ
+    Stochastic,
+    /// 全反射以外は常に屈折させる（従来の挙動）
+    #[default]
+    AlwaysRefract,
+    /// 確率の高い方の分岐を辿り、その確率で強度を減衰させる
+    Deterministic,
+    /// 屈折光を透過率で減衰させて辿り、反射光も反射率の強度の分岐（ゴースト）として追跡する
+    Split,
 }
 
+// 直前と同じ点にまた衝突した（接線付近で面から抜け出せない）ときの扱い
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RehitMode {
+    /// 衝突を記録せず、始点を進行方向へ大きめにずらして追跡を続ける
+    #[default]
+    Nudge,
+    /// その光路の追跡を打ち切り、DetailedPath.rehit で理由を残す
+    Terminate,
+}
+
+// 1つのオブジェクトとの交差判定で求めるヒット数の上限の既定値
+pub const DEFAULT_MAX_INTERSECTION_HITS: usize = 1024;
+
+#[derive(Debug, Clone, Copy)]
 pub struct SimulationSettingsConfig {
     pub infinity_distance: f32,
     pub max_bounces: u32,
+    pub max_reflections: u32, // 1本の光路で許す反射の回数
+    pub max_refractions: u32, // 1本の光路で許す屈折の回数
+    pub fresnel_mode: FresnelMode,
+    pub rehit_mode: RehitMode,
+    pub adaptive: Option<AdaptiveBounces>, // 指定すると max_bounces の代わりにこちらで打ち切る
+    // 指定すると、全反射した面のすぐ外に別のガラスがあれば隙間を越えて一部を透過させる（光学密着）
+    // 透過率は exp(-隙間 / gap_decay_length)
+    pub gap_decay_length: Option<f32>,
+    pub max_intersection_hits: usize, // 1つのオブジェクトとの交差判定で求めるヒット数の上限（入れ子の深いCSG用）
+}
+
+// max_bounces を決め打ちせず、光が面に当たり続けて強度が残る限り追跡を続ける設定
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveBounces {
+    pub min_intensity: f32, // 強度がこれを下回ったら、その衝突点で打ち切る
+    pub hard_cap: u32,      // 進める回数の上限
+}
+
+// 設定ファイルを使わずに追跡するとき（テストなど）の既定値。変えたい項目だけを書き、残りは ..Default::default() で埋める
+impl Default for SimulationSettingsConfig {
+    fn default() -> Self {
+        Self {
+            infinity_distance: 100.0,
+            max_bounces: 20,
+            max_reflections: 20,
+            max_refractions: 20,
+            fresnel_mode: FresnelMode::default(),
+            rehit_mode: RehitMode::default(),
+            adaptive: None,
+            gap_decay_length: None,
+            max_intersection_hits: DEFAULT_MAX_INTERSECTION_HITS,
+        }
+    }
+}
+
+impl SimulationSettingsConfig {
+    // 1本の光路を進める回数の上限
+    pub fn bounce_limit(&self) -> u32 {
+        self.adaptive
+            .map_or(self.max_bounces, |adaptive| adaptive.hard_cap)
+    }
+}
+
+// 衝突で光がどう振る舞ったか
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InteractionKind {
+    /// 面で反射した（鏡、再帰反射、ガラス面での反射を含む）
+    Reflection,
+    /// ガラス面で屈折して透過した
+    Refraction,
+    /// 向きを変えずに透過した（ハーフミラーの透過）
+    Transmission,
+    /// 吸収されて追跡を終えた（検出器に記録された場合を含む）
+    Absorption,
+    /// 後ろの物体を隠さない面 (NonOccluding) を、向きも強度も変えずに通過した
+    PassThrough,
+}
+
+// 光路上の1回の衝突の記録
+#[derive(Debug, Clone)]
+pub struct Interaction {
+    pub object_index: usize, // Scene.objects 内での添字
+    pub hit: HitRecord,
+    pub incoming_dir: Vec3,      // 衝突前の進行方向
+    pub outgoing_dir: Vec3,      // 衝突後の進行方向
+    pub incoming_intensity: f32, // 衝突直前の強度
+    pub kind: InteractionKind,
+    pub ghost: bool, // ガラス面や多層膜フィルタでの部分反射（全反射は含まない）。ゴースト像の元になる迷光の分岐
+    pub inherited: bool, // 反射光の分岐が分岐元の光路から引き継いだ衝突（分岐点の部分反射を含む）
+    pub tir_suppressed: bool, // 全反射しないガラス (IdealGlass) で、全反射の代わりに向きを変えずに通した衝突
+    pub angles: SurfaceAngles, // incoming_dir・outgoing_dir・hit.normal から求めた角度
+}
+
+// 衝突での入射角・反射角（屈折角）・曲がった角度（度）。スネルの法則などを目で確かめる用
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceAngles {
+    pub incidence_deg: f32, // 入射してきた向きの逆と法線のなす角
+    pub outgoing_deg: f32,  // 出ていく向きと法線のなす角（反射なら反射角、屈折・透過なら屈折角）
+    pub deviation_deg: f32, // 衝突の前後で進行方向が変わった角度
+}
+
+impl SurfaceAngles {
+    // normal は入射側を向いた法線
+    pub fn new(incoming_dir: Vec3, outgoing_dir: Vec3, normal: Vec3) -> Self {
+        let angle = |a: Vec3, b: Vec3| {
+            a.normalize_or_zero()
+                .dot(b.normalize_or_zero())
+                .clamp(-1.0, 1.0)
+                .acos()
+                .to_degrees()
+        };
+        // 反射なら法線の側、屈折なら法線の反対側へ出ていく
+        let outgoing_normal = if outgoing_dir.dot(normal) > 0.0 {
+            normal
+        } else {
+            -normal
+        };
+        Self {
+            incidence_deg: angle(-incoming_dir, normal),
+            outgoing_deg: angle(outgoing_dir, outgoing_normal),
+            deviation_deg: angle(incoming_dir, outgoing_dir),
+        }
+    }
+}
+
+// 光路の分岐の区別。ガラス面での部分反射の回数から決める
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BranchLabel {
+    /// ガラス面で部分反射していない主光線
+    Main,
+    /// ガラス面で reflections 回部分反射したゴースト（2回反射して元の向きに戻った迷光など）
+    Ghost { reflections: u32 },
+}
+
+impl BranchLabel {
+    pub fn is_ghost(&self) -> bool {
+        matches!(self, BranchLabel::Ghost { .. })
+    }
+}
+
+// 追跡の進み具合（各パスの後に通知される）
+#[derive(Debug, Clone, Copy)]
+pub struct SimulationProgress {
+    pub pass: u32,          // 終えたパスの数（1パスで各レイが1回衝突する）
+    pub active_rays: usize, // まだ追跡中のレイの数
+    pub total_rays: usize,
+}
+
+// simulate_rays_streaming で、追跡を終えるたびに送られる光路
+// 届く順序は決まっていないので、(ray_index, branch) の順に並べると simulate_rays_detailed と同じ並びになる
+#[derive(Debug, Clone)]
+pub struct PathResult {
+    pub ray_index: usize, // Scene.rays 内での添字
+    pub branch: usize,    // 同じレイから分かれた光路の中での順番（元の光路は0）
+    pub path: DetailedPath,
+}
+
+// 追跡を始める前の手間の見積もり
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EstimatedCost {
+    pub rays: usize,
+    pub max_intersection_calls: u64, // intersect_all を呼ぶ回数の上限（レイ × max_bounces × オブジェクト）
+}
+
+// 追跡中のレイと、そこまでの光路
+#[derive(Clone)]
+struct ActivePath {
+    index: usize,  // Scene.rays 内での添字
+    branch: usize, // 同じレイから分かれた光路の通し番号（元の光路は0、分岐は作られた順）
+    passes: u32,   // ここまでに進めた回数（bounce_limit と比べる）
+    ray: Ray,
+    points: Vec<Vec3>,
+    optical_lengths: Vec<f32>,
+    interactions: Vec<Interaction>,
+    escaped: bool,
+    stuck: bool,            // 往復し続けて進まないため打ち切ったか
+    rehit: bool,            // 同じ面への再衝突で打ち切ったか
+    truncated_hits: usize,  // 交差判定のヒットの列を max_intersection_hits で切り詰めた回数
+    reflections: u32,       // ここまでの反射の回数
+    refractions: u32,       // ここまでの屈折の回数
+    initial_intensity: f32, // 始点での強度（光源の全パワーを等分した値のこともある）
+}
+
+impl ActivePath {
+    fn new(index: usize, ray: Ray) -> Self {
+        Self {
+            index,
+            branch: 0,
+            passes: 0,
+            points: vec![ray.origin],
+            optical_lengths: vec![0.0],
+            interactions: Vec::new(),
+            escaped: false,
+            stuck: false,
+            rehit: false,
+            truncated_hits: 0,
+            reflections: 0,
+            refractions: 0,
+            initial_intensity: ray.intensity,
+            ray,
+        }
+    }
+
+    // まだ進めるか。max_bounces（adaptive なら hard_cap）に達するか、adaptive で強度が下がりきったら止める
+    fn continues(&self, setting: SimulationSettingsConfig) -> bool {
+        self.passes < setting.bounce_limit()
+            && setting
+                .adaptive
+                .is_none_or(|adaptive| self.ray.intensity >= adaptive.min_intensity)
+    }
+
+    // 直近の OSCILLATION_WINDOW 回の衝突点が、最後の点のごく近くに集まっているか
+    fn is_oscillating(&self) -> bool {
+        let Some(window) = self
+            .points
+            .len()
+            .checked_sub(OSCILLATION_WINDOW + 1)
+            .map(|start| &self.points[start..])
+        else {
+            return false;
+        };
+        let last = window[window.len() - 1];
+        let radius = geometric_epsilon() * OSCILLATION_RADIUS_FACTOR;
+        window.iter().all(|point| point.distance(last) < radius)
+    }
+
+    // 光路に点を追加する。直前の点からの区間は、今のレイの屈折率の媒質を進んだものとして光路長を足す
+    fn push_point(&mut self, point: Vec3) {
+        let last_point = *self.points.last().unwrap();
+        let last_length = *self.optical_lengths.last().unwrap();
+        self.optical_lengths
+            .push(last_length + self.ray.current_ior() * last_point.distance(point));
+        self.points.push(point);
+    }
+
+    // 今のレイが最も近くで当たる衝突。ヒットの列を切り詰めたら数えておく
+    fn closest_hit(&mut self, scene: &Scene, bvh: &Bvh) -> Option<(usize, HitRecord)> {
+        let (closest, truncated) =
+            bvh.closest_hit_capped(&scene.objects, &self.ray, hit_t_min(), f32::INFINITY);
+        if truncated {
+            self.truncated_hits += 1;
+        }
+        closest
+    }
+
+    // 隠さない面を通過した点を記録し、その少し先から進め直す
+    fn pass_through(&mut self, object_index: usize, hit: HitRecord) {
+        self.push_point(hit.point);
+        let direction = self.ray.direction;
+        self.ray.origin = hit.point + direction * hit_t_min();
+        self.interactions.push(Interaction {
+            object_index,
+            incoming_dir: direction,
+            outgoing_dir: direction,
+            incoming_intensity: self.ray.intensity,
+            kind: InteractionKind::PassThrough,
+            ghost: false,
+            inherited: false,
+            tir_suppressed: false,
+            angles: SurfaceAngles::new(direction, direction, hit.normal),
+            hit,
+        });
+    }
+
+    fn finish(self) -> DetailedPath {
+        DetailedPath {
+            points: self.points,
+            optical_lengths: self.optical_lengths,
+            interactions: self.interactions,
+            intensity: self.ray.intensity,
+            escaped: self.escaped,
+            stuck: self.stuck,
+            rehit: self.rehit,
+            truncated_hits: self.truncated_hits,
+            source: self.ray.source,
+        }
+    }
+}
+
+// 1本のレイを追跡した結果
+#[derive(Debug, Clone)]
+pub struct DetailedPath {
+    pub points: Vec<Vec3>,
+    pub optical_lengths: Vec<f32>, // 始点から各点までの光路長（屈折率 × 距離の和）。points と同じ長さ
+    pub interactions: Vec<Interaction>,
+    pub intensity: f32,        // 追跡終了時点での強度
+    pub escaped: bool,         // 最後の区間が何にも当たらずに飛び去った区間か
+    pub stuck: bool,           // ほとんど進まずに往復し続けたため、上限より前に追跡を打ち切ったか
+    pub rehit: bool, // 直前と同じ点に再衝突したため追跡を打ち切ったか（RehitMode::Terminate のとき）
+    pub truncated_hits: usize, // 交差判定のヒットの列を max_intersection_hits で切り詰めた回数（分岐では分かれた後の分だけ）
+    pub source: usize,         // 元のレイの光源の番号（Ray::source）
+}
+
+impl DetailedPath {
+    // 逆向きに辿った光路に並べ替える（点と衝突の順序、進行方向を反転する）
+    // 法線と front_face は、反転後の進行方向に向かい合うように付け直す
+    pub fn reversed(mut self) -> DetailedPath {
+        self.points.reverse();
+        // 光路長は新しい始点（元の終点）から測り直す
+        let total = self.optical_lengths.last().copied().unwrap_or(0.0);
+        self.optical_lengths.reverse();
+        for length in &mut self.optical_lengths {
+            *length = total - *length;
+        }
+        self.interactions.reverse();
+        for interaction in &mut self.interactions {
+            let incoming_dir = -interaction.outgoing_dir;
+            let outgoing_dir = -interaction.incoming_dir;
+            interaction.incoming_dir = incoming_dir;
+            interaction.outgoing_dir = outgoing_dir;
+            interaction.hit.incoming = incoming_dir;
+            if interaction.hit.normal.dot(incoming_dir) > 0.0 {
+                interaction.hit.normal = -interaction.hit.normal;
+                interaction.hit.front_face = !interaction.hit.front_face;
+            }
+            interaction.angles =
+                SurfaceAngles::new(incoming_dir, outgoing_dir, interaction.hit.normal);
+        }
+        DetailedPath {
+            // 飛び去った区間は先頭に来るので、最後の区間ではなくなる
+            escaped: false,
+            ..self
+        }
+    }
+
+    // 衝突の履歴から、主光線かゴーストかを決める
+    pub fn branch_label(&self) -> BranchLabel {
+        let reflections = self
+            .interactions
+            .iter()
+            .filter(|interaction| interaction.ghost)
+            .count() as u32;
+        match reflections {
+            0 => BranchLabel::Main,
+            reflections => BranchLabel::Ghost { reflections },
+        }
+    }
 }
 
 impl Scene {
     pub fn simulate_rays(&self, setting: SimulationSettingsConfig) -> Vec<Vec<Vec3>> {
-        let mut result: Vec<Vec<Vec3>> = Vec::new();
+        self.simulate_rays_detailed(setting)
+            .into_iter()
+            .map(|path| path.points)
+            .collect()
+    }
+
+    // 有限な物体をすべて囲むボックス（無限に広がる物体は除く。有限な物体が無ければNone）
+    pub fn bounding_box(&self) -> Option<Aabb> {
+        self.objects
+            .iter()
+            .filter_map(|object| object.bounding_box())
+            .filter(|bbox| bbox.size().is_finite())
+            .reduce(|a, b| a.union(&b))
+    }
+
+    // 有限な物体とレイの始点をすべて囲むボックス（どちらも無ければNone）
+    pub fn bounding_box_with_rays(&self) -> Option<Aabb> {
+        self.rays
+            .iter()
+            .map(|ray| Aabb::new(ray.origin, ray.origin))
+            .chain(self.bounding_box())
+            .reduce(|a, b| a.union(&b))
+    }
+
+    // 追跡にかかる手間を見積もる（設定の誤りで膨大な追跡を始めてしまうのを防ぐ用）
+    // 各パスで全オブジェクトと交差判定するので、全レイが上限 (bounce_limit) まで進んだ場合が上限になる
+    // FresnelMode::Split の分岐で増えるレイは含まない
+    pub fn estimated_cost(&self, setting: SimulationSettingsConfig) -> EstimatedCost {
+        let rays = self.rays.len();
+        EstimatedCost {
+            rays,
+            max_intersection_calls: (rays as u64)
+                .saturating_mul(setting.bounce_limit() as u64)
+                .saturating_mul(self.objects.len() as u64),
+        }
+    }
+
+    pub fn simulate_rays_detailed(&self, setting: SimulationSettingsConfig) -> Vec<DetailedPath> {
+        self.simulate_rays_detailed_with_progress(setting, |_| {})
+    }
+
+    // 追跡中のレイの集合を1反射ずつ進め、集合が空になった時点で打ち切る
+    // progress は各パスの後に呼ばれ、まだ追跡中のレイの数を受け取る
+    pub fn simulate_rays_detailed_with_progress<F>(
+        &self,
+        setting: SimulationSettingsConfig,
+        mut progress: F,
+    ) -> Vec<DetailedPath>
+    where
+        F: FnMut(SimulationProgress),
+    {
+        let setting = self.clamp_escape_length(setting);
+        let bvh = Bvh::build(&self.objects).with_max_hits(setting.max_intersection_hits);
         // --- 3. 初期光線の設定
-        for ray in &self.rays {
-            let mut ray = ray.clone();
-            // --- 3b. 光路の追跡 ---
-            let mut path_points: Vec<Vec3> = vec![ray.origin];
-            let max_bounces = setting.max_bounces;
-            let infinity_distance = setting.infinity_distance;
-
-            for _ in 0..max_bounces {
-                let mut closest_hit_record: Option<HitRecord> = None;
-                let mut t_closest = f32::INFINITY;
-
-                for object in &self.objects {
-                    if let Some(hits) = object.intersect_all(&ray, 0.001, t_closest) {
-                        if let Some(first_hit) = hits.first() {
-                            if first_hit.t < t_closest {
-                                t_closest = first_hit.t;
-                                closest_hit_record = Some(*first_hit);
-                            }
+        let total_rays = self.rays.len();
+        let mut finished: Vec<(usize, usize, DetailedPath)> = Vec::with_capacity(total_rays);
+        let mut active: Vec<ActivePath> = self
+            .rays
+            .iter()
+            .enumerate()
+            .map(|(index, ray)| ActivePath::new(index, ray.clone()))
+            .collect();
+
+        let mut pass = 0;
+        let mut branch_count = 0;
+        while !active.is_empty() {
+            let mut still_active = Vec::with_capacity(active.len());
+            let mut branches = Vec::new();
+            let mut batched_hits = self.batched_hits(&active).map(Vec::into_iter);
+            for mut path in active {
+                let hit = match &mut batched_hits {
+                    Some(hits) => hits.next().flatten().map(|hit| (0, hit)),
+                    None => path.closest_hit(self, &bvh),
+                };
+                if self.advance_with_hit(&bvh, &mut path, hit, setting, &mut branches)
+                    && path.continues(setting)
+                {
+                    still_active.push(path);
+                } else {
+                    // 上限に達したレイや、adaptive で強度が下がりきったレイもそこで打ち切る
+                    finished.push((path.index, path.branch, path.finish()));
+                }
+            }
+            // 分岐は次のパスから追跡する
+            for mut branch in branches {
+                branch_count += 1;
+                branch.branch = branch_count;
+                if branch.continues(setting) {
+                    still_active.push(branch);
+                } else {
+                    finished.push((branch.index, branch.branch, branch.finish()));
+                }
+            }
+            active = still_active;
+            pass += 1;
+            progress(SimulationProgress {
+                pass,
+                active_rays: active.len(),
+                total_rays,
+            });
+        }
+
+        // レイの順に並べ、同じレイから分かれた光路は元の光路、分岐の順にする
+        finished.sort_by_key(|&(index, branch, _)| (index, branch));
+        finished.into_iter().map(|(_, _, path)| path).collect()
+    }
+
+    // 全レイを並列に追跡し、レイごとに追跡を終えた光路から順に sender へ送る
+    // 全ての光路を手元に溜めずに、書き出しや表示の更新をしながら処理する用
+    // 受け取り側が閉じられたら、残りのレイは追跡せずに戻る
+    pub fn simulate_rays_streaming(
+        &self,
+        setting: SimulationSettingsConfig,
+        sender: Sender<PathResult>,
+    ) {
+        let setting = self.clamp_escape_length(setting);
+        let bvh = Bvh::build(&self.objects).with_max_hits(setting.max_intersection_hits);
+        let next_ray = AtomicUsize::new(0);
+        let workers = thread::available_parallelism()
+            .map_or(1, |count| count.get())
+            .min(self.rays.len().max(1));
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                let sender = sender.clone();
+                let (bvh, next_ray) = (&bvh, &next_ray);
+                scope.spawn(move || loop {
+                    // 空いたスレッドから次のレイを取る
+                    let ray_index = next_ray.fetch_add(1, Ordering::Relaxed);
+                    let Some(ray) = self.rays.get(ray_index) else {
+                        break;
+                    };
+                    let paths =
+                        self.trace_active(bvh, ActivePath::new(ray_index, ray.clone()), setting);
+                    for (branch, path) in paths.into_iter().enumerate() {
+                        let result = PathResult {
+                            ray_index,
+                            branch,
+                            path,
+                        };
+                        if sender.send(result).is_err() {
+                            // 残りのレイも送れないので、他のスレッドにも取らせない
+                            next_ray.store(self.rays.len(), Ordering::Relaxed);
+                            return;
                         }
                     }
+                });
+            }
+        });
+    }
+
+    // Scene.rays を検出器などの目標側から光源側へ向かうレイとして通常どおり追跡し、
+    // 得られた光路を DetailedPath::reversed で光源側から目標側へ進む順に並べ替えて返す
+    // 反射も屈折も光の向きを逆にすると同じ折れ線を辿るので、屈折率の入れ替えなど逆向き専用の処理はしない。
+    // そのため各レイの ior には目標側の媒質の屈折率を与えること。
+    // 強度は目標側から辿った値のままで、光源の分布による重み付け（随伴追跡）はしていない
+    pub fn simulate_rays_from_targets(
+        &self,
+        setting: SimulationSettingsConfig,
+    ) -> Vec<DetailedPath> {
+        self.simulate_rays_detailed(setting)
+            .into_iter()
+            .map(DetailedPath::reversed)
+            .collect()
+    }
+
+    // 全てのレイを追跡し、名前で指定したオブジェクトに当たった光路だけを返す（センサーに届いた光の調査用）
+    // 名前が見つからなければ追跡せずに UnknownObjectName を返す
+    pub fn simulate_rays_reaching(
+        &self,
+        object_name: &str,
+        setting: SimulationSettingsConfig,
+    ) -> Result<Vec<DetailedPath>, SceneError> {
+        let Some(&object_index) = self.object_names.get(object_name) else {
+            return Err(SceneError::UnknownObjectName {
+                name: object_name.to_string(),
+            });
+        };
+        Ok(self
+            .simulate_rays_detailed(setting)
+            .into_iter()
+            .filter(|path| {
+                path.interactions
+                    .iter()
+                    .any(|interaction| interaction.object_index == object_index)
+            })
+            .collect())
+    }
+
+    // 各オブジェクトの表面を三角形に分けたもの（Bevy以外の描画エンジンに渡す用）
+    // 並びは Scene.objects と同じで、無限に広がる形状や厚さのない面は空のメッシュになる
+    pub fn renderable_triangles(&self) -> Vec<TriangleData> {
+        self.objects
+            .iter()
+            .map(|object| tessellate(object.as_ref(), TESSELLATION_RESOLUTION).unwrap_or_default())
+            .collect()
+    }
+
+    // 各レイの最初の衝突だけを求める（プレビューや光源の向きの確認用）
+    // 戻り値は (レイの始点, 衝突情報)。何にも当たらなければNone
+    pub fn simulate_first_hits(&self) -> Vec<Option<(Vec3, HitRecord)>> {
+        let bvh = Bvh::build(&self.objects);
+        self.rays
+            .iter()
+            .map(|ray| {
+                bvh.closest_hit(&self.objects, ray, hit_t_min(), f32::INFINITY)
+                    .map(|(_, hit)| (ray.origin, hit))
+            })
+            .collect()
+    }
+
+    // 指定した番号のレイだけを追跡する（特定のレイの不具合を調べる用）
+    // 結果は indices の順に並ぶ（分岐があれば元の光路の後に続く）。範囲外の番号があれば何も追跡せずにエラーを返す
+    pub fn simulate_ray_indices(
+        &self,
+        indices: &[usize],
+        setting: SimulationSettingsConfig,
+    ) -> Result<Vec<DetailedPath>, SceneError> {
+        if let Some(&ray_index) = indices.iter().find(|&&index| index >= self.rays.len()) {
+            return Err(SceneError::RayIndexOutOfRange {
+                ray_index,
+                ray_count: self.rays.len(),
+            });
+        }
+        let setting = self.clamp_escape_length(setting);
+        let bvh = Bvh::build(&self.objects).with_max_hits(setting.max_intersection_hits);
+        Ok(indices
+            .iter()
+            .flat_map(|&index| {
+                self.trace_active(
+                    &bvh,
+                    ActivePath::new(index, self.rays[index].clone()),
+                    setting,
+                )
+            })
+            .collect())
+    }
+
+    // --- 3b. 光路の追跡 ---
+    // 元の光路だけを返す（FresnelMode::Split の分岐は捨てる）
+    pub(crate) fn trace_path(&self, ray: Ray, setting: SimulationSettingsConfig) -> DetailedPath {
+        let setting = self.clamp_escape_length(setting);
+        let bvh = Bvh::build(&self.objects).with_max_hits(setting.max_intersection_hits);
+        self.trace_active(&bvh, ActivePath::new(0, ray), setting)
+            .into_iter()
+            .next()
+            .expect("元の光路は必ず返る")
+    }
+
+    // 1本の光路を、吸収されるか飛び去るか max_bounces に達するまで進める
+    // 分かれた光路も同じように進め、元の光路の後に並べて返す
+    fn trace_active(
+        &self,
+        bvh: &Bvh,
+        path: ActivePath,
+        setting: SimulationSettingsConfig,
+    ) -> Vec<DetailedPath> {
+        // simulate_rays_detailed と同じく1パスずつ進め、分岐に同じ順で番号を付ける
+        // （レイを分けて追跡しても、まとめて追跡したときと同じ順に光路が並ぶように）
+        let mut active = vec![path];
+        let mut finished = Vec::new();
+        let mut branch_count = 0;
+        while !active.is_empty() {
+            let mut still_active = Vec::with_capacity(active.len());
+            let mut branches = Vec::new();
+            for mut path in active {
+                if self.advance(bvh, &mut path, setting, &mut branches) && path.continues(setting) {
+                    still_active.push(path);
+                } else {
+                    finished.push((path.branch, path.finish()));
                 }
-                if let Some(hit) = closest_hit_record {
-                    path_points.push(hit.point);
+            }
+            for mut branch in branches {
+                branch_count += 1;
+                branch.branch = branch_count;
+                if branch.continues(setting) {
+                    still_active.push(branch);
+                } else {
+                    finished.push((branch.branch, branch.finish()));
+                }
+            }
+            active = still_active;
+        }
+        finished.sort_by_key(|&(branch, _)| branch);
+        finished.into_iter().map(|(_, path)| path).collect()
+    }
 
-                    let material = hit.material; // HitRecordから直接マテリアルを取得！
+    // 飛び去るレイの最後の区間が、シーンの広がりに比べて長くなりすぎないようにする
+    // （有限な物体が無ければ infinity_distance をそのまま使う）
+    fn clamp_escape_length(&self, setting: SimulationSettingsConfig) -> SimulationSettingsConfig {
+        let Some(bounds) = self.bounding_box() else {
+            return setting;
+        };
+        let bounds = self
+            .rays
+            .iter()
+            .fold(bounds, |b, ray| b.union(&Aabb::new(ray.origin, ray.origin)));
+        let limit = bounds.size().length().max(1e-3) * ESCAPE_LENGTH_FACTOR;
+        SimulationSettingsConfig {
+            infinity_distance: setting.infinity_distance.min(limit),
+            ..setting
+        }
+    }
 
-                    match material {
-                        Material::Mirror => {
-                            ray.direction = reflect(ray.direction, hit.normal);
-                        }
-                        Material::Glass { ior: material_ior } => {
-                            let n1 = ray.current_ior;
-                            let n2 = if hit.front_face { material_ior } else { 1.0 };
-                            let ior_ratio = n1 / n2;
-
-                            if let Some(refracted_dir) =
-                                refract(ray.direction, hit.normal, ior_ratio)
-                            {
-                                ray.direction = refracted_dir;
-                                ray.current_ior = n2;
-                            } else {
-                                ray.direction = reflect(ray.direction, hit.normal);
+    // 物体が1つだけのシーンでは、追跡中の全レイとの交差判定を intersect_batch でまとめて行う
+    // （平行光の格子などで大量のレイが1つの形状に当たる場合に速い）。それ以外は None
+    fn batched_hits(&self, active: &[ActivePath]) -> Option<Vec<Option<HitRecord>>> {
+        let [object] = self.objects.as_slice() else {
+            return None;
+        };
+        let origins: Vec<Vec3> = active.iter().map(|path| path.ray.origin).collect();
+        let directions: Vec<Vec3> = active.iter().map(|path| path.ray.direction).collect();
+        Some(object.intersect_batch(&origins, &directions, hit_t_min(), f32::INFINITY))
+    }
+
+    // レイを1回分の衝突だけ進める。まだ追跡を続けるならtrueを返す
+    // 反射光の分岐ができれば branches に加える
+    fn advance(
+        &self,
+        bvh: &Bvh,
+        path: &mut ActivePath,
+        setting: SimulationSettingsConfig,
+        branches: &mut Vec<ActivePath>,
+    ) -> bool {
+        let hit = path.closest_hit(self, bvh);
+        self.advance_with_hit(bvh, path, hit, setting, branches)
+    }
+
+    // 求めておいた最も近い衝突 closest で、レイを1回分進める
+    fn advance_with_hit(
+        &self,
+        bvh: &Bvh,
+        path: &mut ActivePath,
+        closest: Option<(usize, HitRecord)>,
+        setting: SimulationSettingsConfig,
+        branches: &mut Vec<ActivePath>,
+    ) -> bool {
+        path.passes += 1;
+        // 隠さない面への衝突は通過を記録するだけにして、同じパスのうちに奥の面を探し直す
+        let mut closest = closest;
+        while let Some((object_index, hit)) =
+            closest.take_if(|(index, _)| self.objects[*index].non_occluding())
+        {
+            path.pass_through(object_index, hit);
+            closest = path.closest_hit(self, bvh);
+        }
+        let ray = &mut path.ray;
+        let Some((object_index, hit)) = closest else {
+            // 何にも当たらなければ遠方まで伸ばして終了
+            let end = ray.origin + ray.direction * setting.infinity_distance;
+            path.push_point(end);
+            path.escaped = true;
+            return false;
+        };
+
+        // 直前の衝突点とほぼ同じ点に当たったら、同じ面から抜け出せていない
+        if let Some(last) = path.interactions.last()
+            && last.hit.point.distance(hit.point) < geometric_epsilon() * REHIT_FACTOR
+        {
+            match setting.rehit_mode {
+                RehitMode::Nudge => {
+                    ray.origin += ray.direction * geometric_epsilon() * REHIT_NUDGE_FACTOR;
+                    return true;
+                }
+                RehitMode::Terminate => {
+                    path.rehit = true;
+                    return false;
+                }
+            }
+        }
+
+        path.push_point(hit.point);
+        let ray = &mut path.ray;
+        let incoming_dir = ray.direction;
+        let incoming_intensity = ray.intensity;
+
+        let material = &hit.material; // HitRecordから直接マテリアルを取得！
+
+        // 検出器は、法線と入射方向のなす角が受光角の内側の光だけを記録する
+        let detected = match material {
+            &Material::Detector {
+                acceptance_half_angle_deg,
+            } => {
+                let cos_i = (-ray.direction).dot(hit.normal).clamp(-1.0, 1.0);
+                cos_i.acos().to_degrees() <= acceptance_half_angle_deg
+            }
+            _ => false,
+        };
+
+        let mut ghost = false;
+        let mut tir_suppressed = false;
+        let mut split = None; // 反射光の分岐を作る場合の (屈折前のレイ, 反射率)
+        let mut exit_point = hit.point; // 衝突後のレイの始点（隙間を越えたときは向こうの面の点）
+        match material {
+            Material::Mirror => {
+                ray.direction = reflect(ray.direction, hit.normal);
+            }
+            Material::OneSidedMirror => {
+                // 裏から当たった光は向きを変えずに透過する
+                if hit.front_face {
+                    ray.direction = reflect(ray.direction, hit.normal);
+                }
+            }
+            Material::MetalMirror { reflectance } => {
+                // 反射されなかった分は金属に吸収される
+                ray.direction = reflect(ray.direction, hit.normal);
+                ray.intensity *= reflectance.at(ray.wavelength);
+            }
+            Material::Glass { .. } | Material::GlassByAbbe { .. } | Material::IdealGlass { .. } => {
+                // 入っているガラスの記録から境界の先の媒質を決める（入れ子や貼り合わせのガラスに対応）
+                // 同じ点で接する他のガラスの面も、同じ1つの境界として一緒に通過する
+                let mut media = ray.media;
+                let own = (object_index, hit.front_face, material);
+                for (index, front_face, material) in std::iter::once(own).chain(
+                    self.coincident_glass_hits(ray, object_index, hit.t)
+                        .iter()
+                        .map(|(index, other)| (*index, other.front_face, &other.material)),
+                ) {
+                    // 光線の波長での屈折率を使う
+                    match glass_ior(material, ray.wavelength) {
+                        Some(ior) if front_face => media.enter(index, ior),
+                        Some(_) => media.exit(index),
+                        None => {}
+                    }
+                }
+                let incoming_ray = ray.clone();
+                match scatter_glass(ray, hit.normal, media.current_ior(), setting.fresnel_mode) {
+                    GlassScatter::Refracted => ray.media = media,
+                    GlassScatter::Split { reflectance } => {
+                        ray.media = media;
+                        split = Some((incoming_ray, reflectance));
+                    }
+                    GlassScatter::PartialReflection => ghost = true,
+                    GlassScatter::TotalInternalReflection
+                        if matches!(material, Material::IdealGlass { .. }) =>
+                    {
+                        // 全反射させずに、向きも強度も変えないまま境界の先の媒質へ進める
+                        tir_suppressed = true;
+                        *ray = Ray {
+                            media,
+                            ..incoming_ray
+                        };
+                    }
+                    GlassScatter::TotalInternalReflection => {
+                        // 狭い隙間の向こうにガラスがあれば、その面へ抜ける
+                        if let Some(decay_length) = setting.gap_decay_length
+                            && let Some((index, other, transmittance)) =
+                                self.gap_partner(object_index, &hit, ray.wavelength, decay_length)
+                            && let Some(ior) = glass_ior(&other.material, ray.wavelength)
+                            && let Some(tunneled_dir) = refract(
+                                incoming_ray.direction,
+                                hit.normal,
+                                incoming_ray.current_ior() / ior,
+                            )
+                        {
+                            // 全反射が基本なので、AlwaysRefract でも確率の高い方を辿る
+                            let fresnel_mode = match setting.fresnel_mode {
+                                FresnelMode::AlwaysRefract => FresnelMode::Deterministic,
+                                mode => mode,
+                            };
+                            *ray = incoming_ray.clone();
+                            let scatter =
+                                scatter_filter(ray, hit.normal, 1.0 - transmittance, fresnel_mode);
+                            if let GlassScatter::Refracted | GlassScatter::Split { .. } = scatter {
+                                media.enter(index, ior);
+                                ray.media = media;
+                                ray.direction = tunneled_dir;
+                                exit_point = other.point;
                             }
-                        }
-                        Material::HalfMirror { reflectance } => {
-                            // 0.0から1.0までの一様な乱数を生成
-                            if rand::thread_rng().r#gen::<f32>() < reflectance {
-                                // 反射する場合
-                                ray.direction = reflect(ray.direction, hit.normal);
-                            } else {
-                                // 透過する場合（方向は変わらない）
-                                // ray.direction はそのまま
+                            if let GlassScatter::Split { reflectance } = scatter {
+                                split = Some((incoming_ray, reflectance));
                             }
                         }
                     }
-                    ray.origin = hit.point + ray.direction * 0.001;
+                }
+            }
+            Material::Retroreflector => {
+                // 法線に依らず、来た方向へそのまま送り返す
+                ray.direction = -ray.direction;
+            }
+            Material::Absorber => {
+                // 光はここで吸収され、先へは進まない
+                ray.intensity = 0.0;
+            }
+            Material::MultilayerFilter { stack } => {
+                let cos_i = (-ray.direction.normalize()).dot(hit.normal);
+                let reflectance = stack.reflectance(ray.wavelength, cos_i, hit.front_face);
+                let incoming_ray = ray.clone();
+                match scatter_filter(ray, hit.normal, reflectance, setting.fresnel_mode) {
+                    GlassScatter::Split { reflectance } => {
+                        split = Some((incoming_ray, reflectance))
+                    }
+                    GlassScatter::PartialReflection => ghost = true,
+                    _ => {}
+                }
+            }
+            Material::Detector { .. } => {
+                // 記録した光は強度を残したまま追跡を終える。受光角の外の光は向きを変えずに素通りする
+            }
+            Material::HalfMirror { reflectance } => {
+                // 入射角（度）から反射率を求める
+                let cos_i = (-ray.direction).dot(hit.normal).abs().min(1.0);
+                let reflectance = reflectance.at(cos_i.acos().to_degrees());
+                // 0.0から1.0までの一様な乱数を生成
+                if rand::thread_rng().r#gen::<f32>() < reflectance {
+                    // 反射する場合
+                    ray.direction = reflect(ray.direction, hit.normal);
                 } else {
-                    path_points.push(ray.origin + ray.direction * infinity_distance);
-                    break;
+                    // 透過する場合（方向は変わらない）
+                    // ray.direction はそのまま
                 }
             }
-            result.push(path_points);
         }
-        result
+        // 法線は入射側を向いているので、出ていく向きが法線側なら反射
+        let kind = match material {
+            Material::Absorber => InteractionKind::Absorption,
+            _ if detected => InteractionKind::Absorption,
+            _ if ray.direction.dot(hit.normal) > 0.0 => InteractionKind::Reflection,
+            Material::Glass { .. } | Material::GlassByAbbe { .. } | Material::IdealGlass { .. } => {
+                InteractionKind::Refraction
+            }
+            _ => InteractionKind::Transmission,
+        };
+        ray.origin = exit_point + ray.direction * hit_t_min();
+        path.interactions.push(Interaction {
+            object_index,
+            incoming_dir,
+            outgoing_dir: ray.direction,
+            incoming_intensity,
+            kind,
+            ghost,
+            inherited: false,
+            tir_suppressed,
+            angles: SurfaceAngles::new(incoming_dir, ray.direction, hit.normal),
+            hit,
+        });
+        if let Some((incoming_ray, reflectance)) = split {
+            spawn_reflected_branch(path, incoming_ray, reflectance, setting, branches);
+        }
+        // 反射・屈折の回数が上限に達したら、max_bounces と同じくこの衝突点で打ち切る
+        let within_limits = match kind {
+            InteractionKind::Reflection => {
+                path.reflections += 1;
+                path.reflections < setting.max_reflections
+            }
+            InteractionKind::Refraction => {
+                path.refractions += 1;
+                path.refractions < setting.max_refractions
+            }
+            InteractionKind::Transmission | InteractionKind::PassThrough => true,
+            InteractionKind::Absorption => false,
+        };
+        // 残りの回数を使い切るまで同じ所を往復し続けないよう、進んでいなければここで打ち切る
+        if within_limits && path.is_oscillating() {
+            path.stuck = true;
+            return false;
+        }
+        within_limits
+    }
+
+    // object_index 以外のオブジェクトで、t とほぼ同じ位置にあるガラスの面への衝突
+    fn coincident_glass_hits(
+        &self,
+        ray: &Ray,
+        object_index: usize,
+        t: f32,
+    ) -> Vec<(usize, HitRecord)> {
+        let epsilon = geometric_epsilon() * COINCIDENT_FACTOR;
+        self.objects
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| index != object_index)
+            .filter_map(|(index, object)| {
+                let hits = object.intersect_all(ray, t - epsilon, t + epsilon)?;
+                let hit = hits.into_iter().next()?;
+                glass_ior(&hit.material, ray.wavelength).map(|_| (index, hit))
+            })
+            .collect()
+    }
+
+    // object_index の面で全反射した衝突 hit の外側（隙間）を法線に沿って探し、近くにある別のガラスの面を返す
+    // (オブジェクトの添字, その面への衝突, 隙間を越える透過率) の組。ガラスの外側の面でなければ None
+    fn gap_partner(
+        &self,
+        object_index: usize,
+        hit: &HitRecord,
+        wavelength: f32,
+        decay_length: f32,
+    ) -> Option<(usize, HitRecord, f32)> {
+        let probe = Ray::new(hit.point, -hit.normal, AIR_IOR);
+        let (index, other) = self
+            .objects
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| index != object_index)
+            .filter_map(|(index, object)| {
+                let hits = object.intersect_all(&probe, 0.0, decay_length * GAP_SEARCH_FACTOR)?;
+                hits.into_iter().next().map(|hit| (index, hit))
+            })
+            .min_by(|(_, a), (_, b)| a.t.total_cmp(&b.t))?;
+        if !other.front_face || glass_ior(&other.material, wavelength).is_none() {
+            return None;
+        }
+        let transmittance = (-other.t / decay_length).exp().clamp(0.0, 1.0);
+        Some((index, other, transmittance))
+    }
+
+    // レイに最も近い衝突を、衝突したオブジェクトの添字と共に返す
+    // 全てのオブジェクトを順に調べる（追跡では同じ結果を Bvh::closest_hit で早く求める）
+    pub fn closest_hit(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<(usize, HitRecord)> {
+        let mut closest: Option<(usize, HitRecord)> = None;
+        let mut t_closest = t_max;
+
+        for (index, object) in self.objects.iter().enumerate() {
+            if let Some(hits) = object.intersect_all(ray, t_min, t_closest)
+                && let Some(first_hit) = hits.first()
+                && first_hit.t < t_closest
+            {
+                t_closest = first_hit.t;
+                closest = Some((index, first_hit.clone()));
+            }
+        }
+        closest
     }
 }
 // 光線を表す構造体
@@ -111,15 +1216,87 @@ impl Scene {
 pub struct Ray {
     pub origin: Vec3,
     pub direction: Vec3,
-    pub current_ior: f32,
+    pub media: MediumStack, // 今いる媒質（入れ子のガラスの記録）
+    pub intensity: f32,     // 光線の強度（初期値1.0）
+    pub wavelength: f32,    // 波長[nm]（初期値はd線）
+    pub source: usize,      // 光源の番号（同じ光源から出たレイは同じ値。初期値0）
 }
 
-// 衝突（ヒット）に関する情報をまとめる構造体
+impl Ray {
+    // current_ior は始点の媒質の屈折率
+    pub fn new(origin: Vec3, direction: Vec3, current_ior: f32) -> Self {
+        Self {
+            origin,
+            direction,
+            media: MediumStack::new(current_ior),
+            intensity: 1.0,
+            wavelength: D_LINE_NM,
+            source: 0,
+        }
+    }
+
+    // 今いる媒質の屈折率
+    pub fn current_ior(&self) -> f32 {
+        self.media.current_ior()
+    }
+}
+
+// レイが入ったガラスの記録（オブジェクトの添字と屈折率）。最後に入ったものが今いる媒質
+// 重なったガラスから出るときは、入った順に関係なくそのガラスの記録だけを取り除く
 #[derive(Debug, Clone, Copy)]
+pub struct MediumStack {
+    initial_ior: f32, // どのガラスにも入っていないときの屈折率（始点の媒質）
+    entries: [(usize, f32); MAX_NESTED_MEDIA],
+    len: usize,
+}
+
+impl MediumStack {
+    pub fn new(initial_ior: f32) -> Self {
+        Self {
+            initial_ior,
+            entries: [(0, 0.0); MAX_NESTED_MEDIA],
+            len: 0,
+        }
+    }
+
+    pub fn current_ior(&self) -> f32 {
+        match self.len {
+            0 => self.initial_ior,
+            len => self.entries[len - 1].1,
+        }
+    }
+
+    // object_index のガラスに入った。深さの上限を超えた入れ子は記録せず、外側の媒質のままとする
+    pub fn enter(&mut self, object_index: usize, ior: f32) {
+        if self.len < MAX_NESTED_MEDIA {
+            self.entries[self.len] = (object_index, ior);
+            self.len += 1;
+        }
+    }
+
+    // object_index のガラスから出た
+    // 記録に無いガラスから出たのは始点がそのガラスの中にあった場合なので、外は空気とみなす
+    pub fn exit(&mut self, object_index: usize) {
+        match self.entries[..self.len]
+            .iter()
+            .rposition(|&(index, _)| index == object_index)
+        {
+            Some(position) => {
+                self.entries.copy_within(position + 1..self.len, position);
+                self.len -= 1;
+            }
+            None => self.initial_ior = AIR_IOR,
+        }
+    }
+}
+
+// 衝突（ヒット）に関する情報をまとめる構造体
+#[derive(Debug, Clone)]
 pub struct HitRecord {
     pub t: f32,
     pub point: Vec3,
     pub normal: Vec3,
     pub front_face: bool,
+    pub incoming: Vec3, // 当たったレイの進行方向（正規化しない）
     pub material: Material,
 }
@@ -0,0 +1,140 @@
+// 形状の三角形分割（描画エンジンに依らない表示用のメッシュ）
+// 外接ボックスを格子に区切って内外判定 (contains) を調べ、マーチングテトラヘドラで境界面を取り出す
+use std::collections::HashMap;
+
+use glam::Vec3;
+
+use crate::Hittable;
+
+// 外接ボックスの最も長い辺の分割数
+pub const TESSELLATION_RESOLUTION: usize = 48;
+// 格子の頂点で交点を絞り込む二分法の回数
+const BISECTION_STEPS: usize = 10;
+// 立方体を主対角線 (0 -> 7) を共有する6つの四面体に分ける（隣の立方体と面が食い違わない）
+// 頂点番号のビットは x = 1, y = 2, z = 4
+const CUBE_TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 1, 3, 7],
+    [0, 1, 5, 7],
+    [0, 2, 3, 7],
+    [0, 2, 6, 7],
+    [0, 4, 5, 7],
+    [0, 4, 6, 7],
+];
+
+// 三角形メッシュ（頂点と、外側から見て反時計回りの頂点番号の組）
+pub type TriangleData = (Vec<Vec3>, Vec<[u32; 3]>);
+
+// 有限で閉じた形状の表面を三角形に分ける。無限に広がる形状や厚さのない面は None
+pub fn tessellate(object: &dyn Hittable, resolution: usize) -> Option<TriangleData> {
+    let bbox = object.bounding_box()?;
+    let size = bbox.size();
+    if !size.is_finite() || size.max_element() <= 0.0 {
+        return None;
+    }
+
+    // 外側を一回り広げ、格子点が面にちょうど乗らないよう半端にずらす
+    let cell = size.max_element() / resolution.max(1) as f32;
+    let origin = bbox.min - Vec3::splat(cell * 1.37);
+    let counts = ((size + Vec3::splat(cell * 2.74)) / cell).ceil().as_uvec3() + 1;
+    let (nx, ny, nz) = (counts.x as usize, counts.y as usize, counts.z as usize);
+    let point = |index: usize| {
+        let (x, y, z) = (index % nx, index / nx % ny, index / (nx * ny));
+        origin + Vec3::new(x as f32, y as f32, z as f32) * cell
+    };
+    let inside: Vec<bool> = (0..nx * ny * nz)
+        .map(|index| object.contains(point(index)))
+        .collect();
+
+    let mut mesh = MeshBuilder::default();
+    for z in 0..nz - 1 {
+        for y in 0..ny - 1 {
+            for x in 0..nx - 1 {
+                let base = x + nx * (y + ny * z);
+                let corner = |bits: usize| {
+                    base + (bits & 1) + nx * ((bits >> 1) & 1) + nx * ny * ((bits >> 2) & 1)
+                };
+                for tetrahedron in CUBE_TETRAHEDRA {
+                    mesh.add_tetrahedron(tetrahedron.map(corner), &inside, point, |a, b| {
+                        refine(object, point(a), point(b))
+                    });
+                }
+            }
+        }
+    }
+
+    if mesh.triangles.is_empty() {
+        None
+    } else {
+        Some((mesh.vertices, mesh.triangles))
+    }
+}
+
+// 内側の点 inside と外側の点 outside を結ぶ線分上の境界を二分法で求める
+fn refine(object: &dyn Hittable, mut inside: Vec3, mut outside: Vec3) -> Vec3 {
+    for _ in 0..BISECTION_STEPS {
+        let mid = (inside + outside) / 2.0;
+        if object.contains(mid) {
+            inside = mid;
+        } else {
+            outside = mid;
+        }
+    }
+    (inside + outside) / 2.0
+}
+
+#[derive(Default)]
+struct MeshBuilder {
+    vertices: Vec<Vec3>,
+    triangles: Vec<[u32; 3]>,
+    edge_vertices: HashMap<(usize, usize), u32>, // 格子の辺 (内側の点, 外側の点) ごとの頂点
+}
+
+impl MeshBuilder {
+    // point は格子点の位置、crossing は内側と外側の格子点を結ぶ辺上の境界の位置
+    fn add_tetrahedron<P, F>(
+        &mut self,
+        vertices: [usize; 4],
+        inside: &[bool],
+        point: P,
+        mut crossing: F,
+    ) where
+        P: Fn(usize) -> Vec3,
+        F: FnMut(usize, usize) -> Vec3,
+    {
+        let (ins, outs): (Vec<usize>, Vec<usize>) = vertices.into_iter().partition(|&v| inside[v]);
+        // 内側の頂点の重心から外側の頂点の重心へ向かう向き
+        let centroid = |points: &[usize]| {
+            points.iter().map(|&p| point(p)).sum::<Vec3>() / points.len().max(1) as f32
+        };
+        let outward = centroid(&outs) - centroid(&ins);
+        let mut edge = |a: usize, b: usize| {
+            *self.edge_vertices.entry((a, b)).or_insert_with(|| {
+                self.vertices.push(crossing(a, b));
+                (self.vertices.len() - 1) as u32
+            })
+        };
+        let polygon = match (ins.as_slice(), outs.as_slice()) {
+            ([i], [o0, o1, o2]) => vec![edge(*i, *o0), edge(*i, *o1), edge(*i, *o2)],
+            ([i0, i1, i2], [o]) => vec![edge(*i0, *o), edge(*i1, *o), edge(*i2, *o)],
+            // 四角形は隣り合う辺が頂点を共有する順に並べる
+            ([i0, i1], [o0, o1]) => vec![
+                edge(*i0, *o0),
+                edge(*i0, *o1),
+                edge(*i1, *o1),
+                edge(*i1, *o0),
+            ],
+            _ => return,
+        };
+
+        // 面が内側から外側へ向く（外側から見て反時計回りになる）ように頂点の順序をそろえる
+        for k in 1..polygon.len() - 1 {
+            let [a, b, c] = [polygon[0], polygon[k], polygon[k + 1]];
+            let [pa, pb, pc] = [a, b, c].map(|v| self.vertices[v as usize]);
+            if (pb - pa).cross(pc - pa).dot(outward) >= 0.0 {
+                self.triangles.push([a, b, c]);
+            } else {
+                self.triangles.push([a, c, b]);
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+// 誘電体多層膜（干渉フィルタ）の反射率を特性行列（転送行列）法で求める
+//
+// 各層の特性行列 [[cos δ, i sin δ / η], [i η sin δ, cos δ]] を入射側から掛け合わせ、
+// [B, C] = M [1, η_基板] から振幅反射率 r = (η_入射 B - C) / (η_入射 B + C) を得る
+// δ = 2π n d cosθ / λ、η は s 偏光で n cosθ、p 偏光で n / cosθ。無偏光は両者の平均とする
+// 臨界角を超える層では cosθ が虚数になるので、複素数のまま計算する
+use std::f64::consts::PI;
+use std::ops::{Add, Div, Mul, Sub};
+use std::sync::Arc;
+
+// 干渉フィルタの膜。表側の媒質（屈折率 incident_ior）と裏側の基板（屈折率 substrate_ior）の間に layers を重ねる
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThinFilmStack {
+    pub layers: Arc<[(f32, f32)]>, // 表側から順に (屈折率, 厚さ[nm])
+    pub incident_ior: f32,
+    pub substrate_ior: f32,
+}
+
+impl ThinFilmStack {
+    // 波長 wavelength_nm の光が入射角の余弦 cos_incidence で当たったときの反射率
+    // 裏側から当たった光 (front_face = false) には、層の順序と両側の媒質を入れ替えて求める
+    pub fn reflectance(&self, wavelength_nm: f32, cos_incidence: f32, front_face: bool) -> f32 {
+        if front_face {
+            multilayer_reflectance(
+                &self.layers,
+                self.incident_ior,
+                self.substrate_ior,
+                wavelength_nm,
+                cos_incidence,
+            )
+        } else {
+            let reversed: Vec<_> = self.layers.iter().rev().copied().collect();
+            multilayer_reflectance(
+                &reversed,
+                self.substrate_ior,
+                self.incident_ior,
+                wavelength_nm,
+                cos_incidence,
+            )
+        }
+    }
+}
+
+// 計算用の最小限の複素数
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    const ZERO: Complex = Complex { re: 0.0, im: 0.0 };
+    const ONE: Complex = Complex { re: 1.0, im: 0.0 };
+    const I: Complex = Complex { re: 0.0, im: 1.0 };
+
+    fn real(re: f64) -> Complex {
+        Complex { re, im: 0.0 }
+    }
+
+    fn norm_squared(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    // 虚部が 0 以上になる側の平方根（減衰する向きのエバネッセント波を選ぶ）
+    fn sqrt(self) -> Complex {
+        let r = self.norm_squared().sqrt();
+        let re = ((r + self.re) / 2.0).max(0.0).sqrt();
+        let im = ((r - self.re) / 2.0).max(0.0).sqrt();
+        if self.im < 0.0 {
+            Complex { re: -re, im }
+        } else {
+            Complex { re, im }
+        }
+    }
+
+    fn cos(self) -> Complex {
+        Complex {
+            re: self.re.cos() * self.im.cosh(),
+            im: -self.re.sin() * self.im.sinh(),
+        }
+    }
+
+    fn sin(self) -> Complex {
+        Complex {
+            re: self.re.sin() * self.im.cosh(),
+            im: self.re.cos() * self.im.sinh(),
+        }
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, other: Complex) -> Complex {
+        Complex {
+            re: self.re + other.re,
+            im: self.im + other.im,
+        }
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, other: Complex) -> Complex {
+        Complex {
+            re: self.re - other.re,
+            im: self.im - other.im,
+        }
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, other: Complex) -> Complex {
+        Complex {
+            re: self.re * other.re - self.im * other.im,
+            im: self.re * other.im + self.im * other.re,
+        }
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+    fn div(self, other: Complex) -> Complex {
+        let denominator = other.norm_squared();
+        Complex {
+            re: (self.re * other.re + self.im * other.im) / denominator,
+            im: (self.im * other.re - self.re * other.im) / denominator,
+        }
+    }
+}
+
+// 2×2 の特性行列 [[a, b], [c, d]]
+type Matrix = [[Complex; 2]; 2];
+
+fn mat_mul(m: Matrix, n: Matrix) -> Matrix {
+    [
+        [
+            m[0][0] * n[0][0] + m[0][1] * n[1][0],
+            m[0][0] * n[0][1] + m[0][1] * n[1][1],
+        ],
+        [
+            m[1][0] * n[0][0] + m[1][1] * n[1][0],
+            m[1][0] * n[0][1] + m[1][1] * n[1][1],
+        ],
+    ]
+}
+
+#[derive(Clone, Copy)]
+enum Polarization {
+    S,
+    P,
+}
+
+// 屈折率 n の媒質での n cosθ（スネルの法則で n sinθ = n0 sinθ0 が保たれる）
+fn n_cos(n: f64, n0_sin0: f64) -> Complex {
+    Complex::real(n * n - n0_sin0 * n0_sin0).sqrt()
+}
+
+// 光学アドミッタンス（s 偏光で n cosθ、p 偏光で n / cosθ = n² / (n cosθ)）
+fn admittance(n: f64, n_cos: Complex, polarization: Polarization) -> Complex {
+    match polarization {
+        Polarization::S => n_cos,
+        Polarization::P => Complex::real(n * n) / n_cos,
+    }
+}
+
+fn reflectance_for(
+    layers: &[(f32, f32)],
+    incident_ior: f64,
+    substrate_ior: f64,
+    wavelength_nm: f64,
+    sin_incidence: f64,
+    polarization: Polarization,
+) -> f64 {
+    let n0_sin0 = incident_ior * sin_incidence;
+    let mut m: Matrix = [[Complex::ONE, Complex::ZERO], [Complex::ZERO, Complex::ONE]];
+    for &(ior, thickness_nm) in layers {
+        let (n, d) = (ior as f64, thickness_nm as f64);
+        let n_cos = n_cos(n, n0_sin0);
+        let eta = admittance(n, n_cos, polarization);
+        let delta = Complex::real(2.0 * PI * d / wavelength_nm) * n_cos;
+        let (cos, sin) = (delta.cos(), delta.sin());
+        m = mat_mul(
+            m,
+            [[cos, Complex::I * sin / eta], [Complex::I * eta * sin, cos]],
+        );
+    }
+    let eta_0 = admittance(incident_ior, n_cos(incident_ior, n0_sin0), polarization);
+    let eta_s = admittance(substrate_ior, n_cos(substrate_ior, n0_sin0), polarization);
+    let b = m[0][0] + m[0][1] * eta_s;
+    let c = m[1][0] + m[1][1] * eta_s;
+    let r = (eta_0 * b - c) / (eta_0 * b + c);
+    r.norm_squared().clamp(0.0, 1.0)
+}
+
+// 入射側の屈折率 incident_ior の媒質から、layers（入射側から順に (屈折率, 厚さ[nm])）を経て
+// 屈折率 substrate_ior の基板へ向かう光の無偏光の反射率。cos_incidence は入射角の余弦
+// 吸収のない膜なので、透過率は 1 - 反射率
+pub fn multilayer_reflectance(
+    layers: &[(f32, f32)],
+    incident_ior: f32,
+    substrate_ior: f32,
+    wavelength_nm: f32,
+    cos_incidence: f32,
+) -> f32 {
+    let cos_i = (cos_incidence as f64).clamp(0.0, 1.0);
+    let sin_i = (1.0 - cos_i * cos_i).sqrt();
+    let (n0, ns, wavelength) = (
+        incident_ior as f64,
+        substrate_ior as f64,
+        wavelength_nm as f64,
+    );
+    let r_s = reflectance_for(layers, n0, ns, wavelength, sin_i, Polarization::S);
+    let r_p = reflectance_for(layers, n0, ns, wavelength, sin_i, Polarization::P);
+    ((r_s + r_p) / 2.0) as f32
+}
+
+// 入射角 0 で波長 design_wavelength_nm の 1/4 波長の厚さになる高・低屈折率の層を pairs 組重ねた膜
+// （最後に高屈折率の層をもう1枚置く）。反射帯の中心が design_wavelength_nm になる
+pub fn quarter_wave_stack(
+    high_ior: f32,
+    low_ior: f32,
+    pairs: usize,
+    design_wavelength_nm: f32,
+) -> Vec<(f32, f32)> {
+    let quarter = |ior: f32| (ior, design_wavelength_nm / (4.0 * ior));
+    let mut layers = Vec::with_capacity(2 * pairs + 1);
+    for _ in 0..pairs {
+        layers.push(quarter(high_ior));
+        layers.push(quarter(low_ior));
+    }
+    layers.push(quarter(high_ior));
+    layers
+}
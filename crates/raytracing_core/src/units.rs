@@ -0,0 +1,42 @@
+// シーンの長さの単位（表示とビューアの既定値の調整に使う。幾何計算には影響しない）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LengthUnit {
+    /// ナノメートル
+    Nanometer,
+    /// マイクロメートル
+    Micrometer,
+    /// ミリメートル
+    Millimeter,
+    /// センチメートル
+    Centimeter,
+    /// メートル
+    Meter,
+    /// インチ
+    Inch,
+}
+
+impl LengthUnit {
+    // 1単位あたりのメートル数
+    pub fn meters(&self) -> f32 {
+        match self {
+            LengthUnit::Nanometer => 1e-9,
+            LengthUnit::Micrometer => 1e-6,
+            LengthUnit::Millimeter => 1e-3,
+            LengthUnit::Centimeter => 1e-2,
+            LengthUnit::Meter => 1.0,
+            LengthUnit::Inch => 0.0254,
+        }
+    }
+
+    // 表示用の記号
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            LengthUnit::Nanometer => "nm",
+            LengthUnit::Micrometer => "um",
+            LengthUnit::Millimeter => "mm",
+            LengthUnit::Centimeter => "cm",
+            LengthUnit::Meter => "m",
+            LengthUnit::Inch => "in",
+        }
+    }
+}
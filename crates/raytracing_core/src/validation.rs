@@ -0,0 +1,120 @@
+use std::fmt;
+
+use glam::Vec3;
+
+use crate::{Hittable, Scene};
+
+// 向きが単位ベクトルとみなせる長さのずれ
+const UNIT_LENGTH_TOLERANCE: f32 = 1e-3;
+
+// 追跡の前に見つかったシーンの不備
+#[derive(Debug, Clone, PartialEq)]
+pub enum SceneError {
+    /// 形状が退化している（半径が0、NaNを含む変換など）
+    DegenerateObject {
+        object_index: usize, // Scene.objects 内での添字
+        path: String,        // 形状木の中での位置（例: "root.object"）
+        reason: String,
+    },
+    /// レイの始点が有限でないか、向きが単位ベクトルでない
+    InvalidRay { ray_index: usize, reason: String },
+    /// 追跡するレイの番号が Scene.rays の範囲外
+    RayIndexOutOfRange { ray_index: usize, ray_count: usize },
+    /// 名前で指定したオブジェクトが Scene.object_names に無い
+    UnknownObjectName { name: String },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::DegenerateObject {
+                object_index,
+                path,
+                reason,
+            } => write!(
+                f,
+                "オブジェクト {} の {} が正しくありません: {}",
+                object_index, path, reason
+            ),
+            SceneError::InvalidRay { ray_index, reason } => {
+                write!(f, "レイ {} が正しくありません: {}", ray_index, reason)
+            }
+            SceneError::RayIndexOutOfRange {
+                ray_index,
+                ray_count,
+            } => write!(
+                f,
+                "レイの番号 {} は範囲外です（レイは {} 本）",
+                ray_index, ray_count
+            ),
+            SceneError::UnknownObjectName { name } => {
+                write!(f, "名前が {} のオブジェクトはありません", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl Scene {
+    // 各オブジェクトの形状木（CSGの子を含む）と各レイを調べ、最初に見つかった不備を返す
+    // 形状は Hittable::degeneracy で分かる範囲と、外接ボックスが有限かどうかだけを調べる
+    pub fn validate(&self) -> Result<(), SceneError> {
+        for (object_index, object) in self.objects.iter().enumerate() {
+            validate_node(object.as_ref(), object_index, "root".to_string())?;
+        }
+        for (ray_index, ray) in self.rays.iter().enumerate() {
+            let reason =
+                non_finite("origin", ray.origin).or_else(|| unit("direction", ray.direction));
+            if let Some(reason) = reason {
+                return Err(SceneError::InvalidRay { ray_index, reason });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate_node(node: &dyn Hittable, object_index: usize, path: String) -> Result<(), SceneError> {
+    let reason = node.degeneracy().or_else(|| {
+        let bounds = node.bounding_box()?;
+        non_finite("外接ボックスの min", bounds.min)
+            .or_else(|| non_finite("外接ボックスの max", bounds.max))
+    });
+    if let Some(reason) = reason {
+        return Err(SceneError::DegenerateObject {
+            object_index,
+            path,
+            reason,
+        });
+    }
+    for (name, child) in node.children() {
+        validate_node(child, object_index, format!("{}.{}", path, name))?;
+    }
+    Ok(())
+}
+
+// 以下は Hittable::degeneracy の実装で使う。問題があれば理由を返す
+pub(crate) fn non_finite(name: &str, v: Vec3) -> Option<String> {
+    (!v.is_finite()).then(|| format!("{} が有限の値ではありません ({})", name, v))
+}
+
+pub(crate) fn nonzero(name: &str, v: Vec3) -> Option<String> {
+    non_finite(name, v).or_else(|| (v == Vec3::ZERO).then(|| format!("{} が零ベクトルです", name)))
+}
+
+pub(crate) fn unit(name: &str, v: Vec3) -> Option<String> {
+    non_finite(name, v).or_else(|| {
+        ((v.length() - 1.0).abs() > UNIT_LENGTH_TOLERANCE).then(|| {
+            format!(
+                "{} が単位ベクトルではありません（長さ {}）",
+                name,
+                v.length()
+            )
+        })
+    })
+}
+
+pub(crate) fn positive(name: &str, value: f32) -> Option<String> {
+    (!(value > 0.0 && value.is_finite()))
+        .then(|| format!("{} は正の有限の値にしてください ({})", name, value))
+}
@@ -0,0 +1,25 @@
+// アッベ数から求めた屈折率が、d線で nd に一致し、F線とC線の差からアッベ数が戻ることの確認
+use raytracing_core::{abbe_refractive_index, C_LINE_NM, D_LINE_NM, F_LINE_NM};
+
+// BK7 と SF11 相当
+const GLASSES: [(f32, f32); 2] = [(1.5168, 64.17), (1.7847, 25.68)];
+
+#[test]
+fn d_line_index_equals_nd() {
+    for (nd, vd) in GLASSES {
+        let n = abbe_refractive_index(nd, vd, D_LINE_NM);
+        assert!((n - nd).abs() < 1e-5, "{} != {}", n, nd);
+    }
+}
+
+#[test]
+fn f_and_c_dispersion_matches_vd() {
+    for (nd, vd) in GLASSES {
+        let nf = abbe_refractive_index(nd, vd, F_LINE_NM);
+        let nc = abbe_refractive_index(nd, vd, C_LINE_NM);
+        // 短い波長ほど屈折率が大きい（正常分散）
+        assert!(nf > nd && nd > nc);
+        let computed = (nd - 1.0) / (nf - nc);
+        assert!((computed - vd).abs() / vd < 1e-3, "{} != {}", computed, vd);
+    }
+}
@@ -0,0 +1,61 @@
+// 追跡中のレイの集合が空になった時点で追跡が終わり、progress に残りの本数が渡ることの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{Material, Plane, Ray, Scene, SimulationSettingsConfig};
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        infinity_distance: 100.0,
+        max_bounces: 50,
+        max_reflections: 50,
+        max_refractions: 50,
+        ..Default::default()
+    }
+}
+
+// 各パスの後に渡された追跡中のレイの数
+fn active_counts(scene: &Scene) -> Vec<usize> {
+    let mut counts = Vec::new();
+    let paths = scene.simulate_rays_detailed_with_progress(setting(), |progress| {
+        assert_eq!(progress.pass as usize, counts.len() + 1);
+        assert_eq!(progress.total_rays, scene.rays.len());
+        counts.push(progress.active_rays);
+    });
+    assert_eq!(paths.len(), scene.rays.len());
+    counts
+}
+
+#[test]
+fn pure_escapes_finish_in_one_pass() {
+    let scene = Scene {
+        objects: Vec::new(),
+        rays: (0..10)
+            .map(|i| Ray::new(Vec3::new(i as f32, 0.0, 0.0), Vec3::Z, 1.0))
+            .collect(),
+        object_names: HashMap::new(),
+    };
+    assert_eq!(active_counts(&scene), vec![0]);
+}
+
+#[test]
+fn active_count_decreases_monotonically() {
+    // z = 0 の鏡。下向きのレイは1回反射してから抜け、上向きのレイはすぐ抜ける
+    let scene = Scene {
+        objects: vec![Box::new(Plane {
+            point: Vec3::ZERO,
+            normal: Vec3::Z,
+            material: Material::Mirror,
+        })],
+        rays: vec![
+            Ray::new(Vec3::new(0.0, 0.0, 1.0), Vec3::Z, 1.0),
+            Ray::new(Vec3::new(1.0, 0.0, 1.0), Vec3::new(0.3, 0.0, -1.0), 1.0),
+            Ray::new(Vec3::new(2.0, 0.0, 1.0), Vec3::new(0.3, 0.0, 1.0), 1.0),
+            Ray::new(Vec3::new(3.0, 0.0, 1.0), Vec3::NEG_Z, 1.0),
+        ],
+        object_names: HashMap::new(),
+    };
+    let counts = active_counts(&scene);
+    assert_eq!(counts, vec![2, 0]);
+    assert!(counts.windows(2).all(|pair| pair[1] <= pair[0]));
+}
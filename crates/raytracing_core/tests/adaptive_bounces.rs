@@ -0,0 +1,67 @@
+// adaptive を指定すると、max_bounces ではなく強度と hard_cap で打ち切られることの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    AdaptiveBounces, DetailedPath, Hittable, Material, Plane, Ray, Reflectance, Scene,
+    SimulationSettingsConfig,
+};
+
+// y = 0 と y = 1 の向かい合った金属鏡の間で、レイを斜めに反射させ続ける
+fn trace(reflectance: f32, max_bounces: u32, adaptive: Option<AdaptiveBounces>) -> DetailedPath {
+    let mirror = |y: f32, normal: Vec3| -> Box<dyn Hittable> {
+        Box::new(Plane {
+            point: Vec3::new(0.0, y, 0.0),
+            normal,
+            material: Material::MetalMirror {
+                reflectance: Reflectance::Constant(reflectance),
+            },
+        })
+    };
+    let scene = Scene {
+        objects: vec![mirror(0.0, Vec3::Y), mirror(1.0, Vec3::NEG_Y)],
+        rays: vec![Ray::new(
+            Vec3::new(0.0, 0.5, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            1.0,
+        )],
+        object_names: HashMap::new(),
+    };
+    let setting = SimulationSettingsConfig {
+        infinity_distance: 1000.0,
+        max_bounces,
+        max_reflections: 1000,
+        max_refractions: 1000,
+        adaptive,
+        ..Default::default()
+    };
+    scene.simulate_rays_detailed(setting).remove(0)
+}
+
+#[test]
+fn hard_cap_replaces_max_bounces() {
+    let adaptive = AdaptiveBounces {
+        min_intensity: 0.0,
+        hard_cap: 7,
+    };
+    let path = trace(1.0, 2, Some(adaptive));
+    assert_eq!(path.interactions.len(), 7);
+}
+
+#[test]
+fn stops_once_intensity_falls_below_threshold() {
+    // 1回ごとに強度が半分になるので、4回目の反射で 0.0625 < 0.1 となってそこで止まる
+    let adaptive = AdaptiveBounces {
+        min_intensity: 0.1,
+        hard_cap: 100,
+    };
+    let path = trace(0.5, 2, Some(adaptive));
+    assert_eq!(path.interactions.len(), 4);
+    assert!((path.intensity - 0.0625).abs() < 1e-6);
+}
+
+#[test]
+fn without_adaptive_max_bounces_applies() {
+    let path = trace(0.5, 3, None);
+    assert_eq!(path.interactions.len(), 3);
+}
@@ -0,0 +1,59 @@
+// 輪 (Annulus) が、中心の穴と外周より外では当たらず輪の部分でだけ当たることの確認
+use glam::Vec3;
+use raytracing_core::{Annulus, Hittable, Material, Ray};
+
+// z = 0 の平面上の、半径 1〜2 の輪
+fn washer() -> Annulus {
+    Annulus {
+        center: Vec3::ZERO,
+        normal: Vec3::NEG_Z,
+        inner_radius: 1.0,
+        outer_radius: 2.0,
+        material: Material::Absorber,
+    }
+}
+
+fn shoot(x: f32) -> Option<Vec<raytracing_core::HitRecord>> {
+    let ray = Ray::new(Vec3::new(x, 0.0, -5.0), Vec3::Z, 1.0);
+    washer().intersect_all(&ray, 1e-4, f32::INFINITY)
+}
+
+#[test]
+fn ray_through_central_hole_misses() {
+    assert!(shoot(0.0).is_none());
+    assert!(shoot(0.9).is_none());
+}
+
+#[test]
+fn ray_outside_rim_misses() {
+    assert!(shoot(2.1).is_none());
+    assert!(shoot(-3.0).is_none());
+}
+
+#[test]
+fn ray_through_ring_hits() {
+    let hits = shoot(1.5).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert!((hits[0].point - Vec3::new(1.5, 0.0, 0.0)).length() < 1e-5);
+    assert!((hits[0].t - 5.0).abs() < 1e-5);
+    // 法線側 (-Z) から当たるので表面
+    assert!(hits[0].front_face);
+    assert!((hits[0].normal - Vec3::NEG_Z).length() < 1e-5);
+}
+
+#[test]
+fn bounding_box_is_flat_disk() {
+    let bbox = washer().bounding_box().unwrap();
+    assert!((bbox.min - Vec3::new(-2.0, -2.0, 0.0)).length() < 1e-5);
+    assert!((bbox.max - Vec3::new(2.0, 2.0, 0.0)).length() < 1e-5);
+}
+
+#[test]
+fn inner_radius_beyond_outer_is_degenerate() {
+    let annulus = Annulus {
+        inner_radius: 3.0,
+        ..washer()
+    };
+    assert!(annulus.degeneracy().is_some());
+    assert!(washer().degeneracy().is_none());
+}
@@ -0,0 +1,90 @@
+// 円錐係数 0・多項式の項なしの非球面が、同じ曲率半径の球面と同じ点・同じ法線で当たることの確認
+use glam::Vec3;
+use raytracing_core::{AsphericSurface, HitRecord, Hittable, Material, Ray, Sphere};
+
+const RADIUS: f32 = 5.0;
+
+// 頂点が原点で +z 側に凹む面と、それを下半分に持つ球
+fn asphere() -> AsphericSurface {
+    AsphericSurface {
+        vertex: Vec3::ZERO,
+        axis: Vec3::Z,
+        curvature: 1.0 / RADIUS,
+        conic: 0.0,
+        coeffs: vec![],
+        material: Material::Mirror,
+    }
+}
+
+fn sphere() -> Sphere {
+    Sphere {
+        center: Vec3::new(0.0, 0.0, RADIUS),
+        radius: RADIUS,
+        material: Material::Mirror,
+    }
+}
+
+fn first_hit(object: &dyn Hittable, ray: &Ray) -> HitRecord {
+    object
+        .intersect_all(ray, 1e-4, f32::INFINITY)
+        .and_then(|hits| hits.into_iter().next())
+        .expect("面に当たりません")
+}
+
+fn assert_same_hit(ray: Ray) {
+    let a = first_hit(&asphere(), &ray);
+    let s = first_hit(&sphere(), &ray);
+    assert!(
+        (a.t - s.t).abs() < 1e-3,
+        "{} 方向 {}: 非球面の t = {}、球面の t = {}",
+        ray.origin,
+        ray.direction,
+        a.t,
+        s.t
+    );
+    assert!(
+        a.point.abs_diff_eq(s.point, 1e-3),
+        "{} != {}",
+        a.point,
+        s.point
+    );
+    assert!(
+        a.normal.abs_diff_eq(s.normal, 1e-3),
+        "{} != {}",
+        a.normal,
+        s.normal
+    );
+    assert_eq!(a.front_face, s.front_face);
+}
+
+#[test]
+fn axial_rays_match_sphere() {
+    // 軸から 0〜4.5 離れた、軸に平行なレイ
+    for i in 0..10 {
+        let r = i as f32 * 0.5;
+        assert_same_hit(Ray::new(Vec3::new(r, 0.0, -10.0), Vec3::Z, 1.0));
+        assert_same_hit(Ray::new(Vec3::new(0.0, -r, -10.0), Vec3::Z, 1.0));
+    }
+}
+
+#[test]
+fn oblique_rays_match_sphere() {
+    let rays = [
+        (Vec3::new(-6.0, 0.0, -4.0), Vec3::new(0.5, 0.0, 0.4)),
+        (Vec3::new(2.0, 3.0, -8.0), Vec3::new(-0.2, -0.3, 1.0)),
+        (Vec3::new(0.0, 0.0, -3.0), Vec3::new(0.3, 0.2, 1.0)),
+    ];
+    for (origin, direction) in rays {
+        assert_same_hit(Ray::new(origin, direction.normalize(), 1.0));
+    }
+}
+
+#[test]
+fn ray_from_inside_matches_sphere() {
+    // 球の中心から下向きに出るレイは、どちらも裏から当たる
+    assert_same_hit(Ray::new(
+        Vec3::new(0.5, 0.0, RADIUS),
+        Vec3::new(0.1, 0.0, -1.0).normalize(),
+        1.0,
+    ));
+}
@@ -0,0 +1,81 @@
+// 直方体の角や辺にちょうど当たっても、法線が有限の単位ベクトルになることの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, HitRecord, Hittable, Material, Ray, Scene, SimulationSettingsConfig,
+};
+
+fn unit_box(material: Material) -> AxisAlignedBox {
+    AxisAlignedBox {
+        min: Vec3::splat(-1.0),
+        max: Vec3::splat(1.0),
+        material,
+    }
+}
+
+fn assert_unit_normal(hit: &HitRecord) {
+    assert!(
+        hit.normal.is_finite(),
+        "法線 {} が有限ではありません",
+        hit.normal
+    );
+    assert!(
+        (hit.normal.length() - 1.0).abs() < 1e-6,
+        "法線 {} が単位ベクトルではありません",
+        hit.normal
+    );
+}
+
+#[test]
+fn corner_hit_has_unit_normal() {
+    // (-1, -1, -1) の角を通って (1, 1, 1) の角から出る対角線
+    let direction = Vec3::ONE.normalize();
+    let ray = Ray::new(Vec3::splat(-5.0), direction, 1.0);
+    let hits = unit_box(Material::Mirror)
+        .intersect_all(&ray, 1e-4, f32::INFINITY)
+        .expect("角に当たりません");
+    assert_eq!(hits.len(), 2);
+    assert!(hits[0].point.abs_diff_eq(Vec3::splat(-1.0), 1e-4));
+    assert!(hits[1].point.abs_diff_eq(Vec3::splat(1.0), 1e-4));
+    for hit in &hits {
+        assert_unit_normal(hit);
+        // 入口でも出口でも、法線はレイに向かい合う
+        assert!(hit.normal.dot(direction) < 0.0);
+    }
+    assert!(hits[0].front_face);
+    assert!(!hits[1].front_face);
+}
+
+#[test]
+fn edge_hit_has_unit_normal() {
+    // z 軸に平行な (-1, -1) の辺に斜めに当たる
+    let direction = Vec3::new(1.0, 1.0, 0.0).normalize();
+    let ray = Ray::new(Vec3::new(-5.0, -5.0, 0.3), direction, 1.0);
+    let hits = unit_box(Material::Mirror)
+        .intersect_all(&ray, 1e-4, f32::INFINITY)
+        .expect("辺に当たりません");
+    assert!(hits[0].point.abs_diff_eq(Vec3::new(-1.0, -1.0, 0.3), 1e-4));
+    for hit in &hits {
+        assert_unit_normal(hit);
+    }
+}
+
+#[test]
+fn corner_reflection_stays_finite() {
+    let scene = Scene {
+        objects: vec![Box::new(unit_box(Material::Mirror))],
+        rays: vec![Ray::new(Vec3::splat(-5.0), Vec3::ONE.normalize(), 1.0)],
+        object_names: HashMap::new(),
+    };
+    let path = scene
+        .simulate_rays_detailed(SimulationSettingsConfig {
+            infinity_distance: 100.0,
+            max_bounces: 10,
+            ..Default::default()
+        })
+        .remove(0);
+    assert!(!path.interactions.is_empty());
+    assert!(path.points.iter().all(|point| point.is_finite()));
+    assert!(path.escaped);
+}
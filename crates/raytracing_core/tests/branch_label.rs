@@ -0,0 +1,100 @@
+// ガラス面での部分反射の回数から、光路が主光線かゴーストかを区別できるか確かめる
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, BranchLabel, DetailedPath, FresnelMode, Hittable, InteractionKind, Material,
+    Plane, Ray, Scene, SimulationSettingsConfig,
+};
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        // 確率の高い方の分岐を辿るので、かすめる角度ではガラス面で反射する
+        fresnel_mode: FresnelMode::Deterministic,
+        ..Default::default()
+    }
+}
+
+fn glass_box(min: Vec3, max: Vec3) -> Box<dyn Hittable> {
+    Box::new(AxisAlignedBox {
+        min,
+        max,
+        material: Material::Glass { ior: 1.5 },
+    })
+}
+
+// 隙間 0 < y < 2 を挟んで向かい合う2枚のガラス板
+fn trace(rays: Vec<Ray>) -> Vec<DetailedPath> {
+    let scene = Scene {
+        objects: vec![
+            glass_box(Vec3::new(-30.0, -5.0, -5.0), Vec3::new(30.0, 0.0, 5.0)),
+            glass_box(Vec3::new(-30.0, 2.0, -5.0), Vec3::new(30.0, 7.0, 5.0)),
+        ],
+        rays,
+        object_names: HashMap::new(),
+    };
+    scene.simulate_rays_detailed(setting())
+}
+
+#[test]
+fn double_reflection_ghost_is_labeled_apart_from_direct_transmission() {
+    // 隙間を面から5度でかすめるレイは、下の板と上の板で1回ずつ反射して抜けていく
+    let grazing = 5.0_f32.to_radians();
+    let paths = trace(vec![
+        Ray::new(Vec3::new(0.0, 20.0, 0.0), Vec3::NEG_Y, 1.0),
+        Ray::new(
+            Vec3::new(-20.0, 1.0, 0.0),
+            Vec3::new(grazing.cos(), -grazing.sin(), 0.0),
+            1.0,
+        ),
+    ]);
+
+    let direct = &paths[0];
+    assert_eq!(direct.interactions.len(), 4);
+    assert!(direct
+        .interactions
+        .iter()
+        .all(|interaction| interaction.kind == InteractionKind::Refraction));
+    assert_eq!(direct.branch_label(), BranchLabel::Main);
+
+    let ghost = &paths[1];
+    assert_eq!(ghost.interactions.len(), 2);
+    assert!(ghost
+        .interactions
+        .iter()
+        .all(|interaction| interaction.ghost));
+    assert_eq!(ghost.branch_label(), BranchLabel::Ghost { reflections: 2 });
+    assert!(ghost.branch_label().is_ghost());
+    assert!(ghost.intensity < direct.intensity);
+}
+
+#[test]
+fn total_internal_reflection_is_not_a_ghost() {
+    // 下の板の中から面に60度で当たるレイは全反射する
+    let angle = 60.0_f32.to_radians();
+    let paths = trace(vec![Ray::new(
+        Vec3::new(20.0, -2.5, 0.0),
+        Vec3::new(angle.sin(), angle.cos(), 0.0),
+        1.5,
+    )]);
+    let path = &paths[0];
+    assert_eq!(path.interactions[0].kind, InteractionKind::Reflection);
+    assert!(!path.interactions[0].ghost);
+    assert_eq!(path.branch_label(), BranchLabel::Main);
+}
+
+#[test]
+fn mirror_reflection_is_not_a_ghost() {
+    let scene = Scene {
+        objects: vec![Box::new(Plane {
+            point: Vec3::ZERO,
+            normal: Vec3::NEG_Y,
+            material: Material::Mirror,
+        })],
+        rays: vec![Ray::new(Vec3::new(0.0, 5.0, 0.0), Vec3::NEG_Y, 1.0)],
+        object_names: HashMap::new(),
+    };
+    let path = &scene.simulate_rays_detailed(setting())[0];
+    assert_eq!(path.interactions[0].kind, InteractionKind::Reflection);
+    assert_eq!(path.branch_label(), BranchLabel::Main);
+}
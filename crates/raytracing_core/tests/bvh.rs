@@ -0,0 +1,122 @@
+// Bvh::closest_hit（近い順に辿って打ち切る探索）が、全オブジェクトを調べる Scene::closest_hit と同じ衝突を返すことの確認
+use glam::{Mat4, Vec3};
+use raytracing_core::{
+    AxisAlignedBox, Bvh, Hittable, Material, Plane, Ray, Scene, Sphere, Transform,
+};
+
+// 格子状に並べた球と箱、回転した箱、無限に広がる床と、同じ位置に重ねた2つの箱
+fn scene() -> Scene {
+    let mut objects: Vec<Box<dyn Hittable>> = Vec::new();
+    for i in 0..5 {
+        for j in 0..5 {
+            let center = Vec3::new(i as f32 * 3.0 - 6.0, j as f32 * 3.0 - 6.0, (i + j) as f32);
+            if (i + j) % 2 == 0 {
+                objects.push(Box::new(Sphere {
+                    center,
+                    radius: 1.0,
+                    material: Material::Glass { ior: 1.5 },
+                }));
+            } else {
+                objects.push(Box::new(AxisAlignedBox {
+                    min: center - Vec3::splat(0.8),
+                    max: center + Vec3::splat(0.8),
+                    material: Material::Mirror,
+                }));
+            }
+        }
+    }
+    let cube = AxisAlignedBox {
+        min: Vec3::splat(-1.0),
+        max: Vec3::splat(1.0),
+        material: Material::Absorber,
+    };
+    let matrix = Mat4::from_translation(Vec3::new(1.5, 1.5, 12.0)) * Mat4::from_rotation_y(0.7);
+    objects.push(Box::new(Transform::new(Box::new(cube), matrix)));
+    objects.push(Box::new(Plane {
+        point: Vec3::new(0.0, -9.0, 0.0),
+        normal: Vec3::Y,
+        material: Material::Mirror,
+    }));
+    for _ in 0..2 {
+        objects.push(Box::new(AxisAlignedBox {
+            min: Vec3::new(-2.0, -2.0, 15.0),
+            max: Vec3::new(2.0, 2.0, 16.0),
+            material: Material::Glass { ior: 1.5 },
+        }));
+    }
+    Scene {
+        objects,
+        rays: Vec::new(),
+        object_names: Default::default(),
+    }
+}
+
+// 乱数を使わずに、球面上にほぼ一様に散らばる向き（フィボナッチ格子）
+fn directions(count: usize) -> impl Iterator<Item = Vec3> {
+    let golden_angle = std::f32::consts::PI * (3.0 - 5f32.sqrt());
+    (0..count).map(move |i| {
+        let z = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+        let r = (1.0 - z * z).sqrt();
+        let phi = golden_angle * i as f32;
+        Vec3::new(r * phi.cos(), r * phi.sin(), z)
+    })
+}
+
+#[test]
+fn bvh_matches_exhaustive_scan() {
+    let scene = scene();
+    let bvh = Bvh::build(&scene.objects);
+    let origins = [
+        Vec3::new(0.0, 0.0, -10.0),
+        Vec3::new(-7.0, 3.0, 2.0),
+        Vec3::new(0.5, 0.5, 4.0),
+        Vec3::new(0.0, 0.0, 20.0),
+    ];
+    let mut hits = 0;
+    for origin in origins {
+        for direction in directions(2000) {
+            let ray = Ray::new(origin, direction, 1.0);
+            let expected = scene.closest_hit(&ray, 1e-3, f32::INFINITY);
+            let actual = bvh.closest_hit(&scene.objects, &ray, 1e-3, f32::INFINITY);
+            match (expected, actual) {
+                (Some((expected_index, expected_hit)), Some((index, hit))) => {
+                    assert_eq!(index, expected_index, "{:?}", ray);
+                    assert_eq!(hit.t, expected_hit.t);
+                    assert_eq!(hit.point, expected_hit.point);
+                    assert_eq!(hit.normal, expected_hit.normal);
+                    hits += 1;
+                }
+                (None, None) => {}
+                (expected, actual) => panic!(
+                    "{:?}: expected {:?}, got {:?}",
+                    ray,
+                    expected.map(|(index, _)| index),
+                    actual.map(|(index, _)| index)
+                ),
+            }
+        }
+    }
+    // 大半のレイが何かに当たる（床があるので下向きは必ず当たる）
+    assert!(hits > 4000, "{hits}");
+}
+
+#[test]
+fn coincident_objects_resolve_to_lower_index() {
+    let scene = scene();
+    let bvh = Bvh::build(&scene.objects);
+    let last = scene.objects.len() - 1;
+    let ray = Ray::new(Vec3::new(0.0, 0.0, 14.0), Vec3::Z, 1.0);
+    let (index, _) = bvh
+        .closest_hit(&scene.objects, &ray, 1e-3, f32::INFINITY)
+        .unwrap();
+    assert_eq!(index, last - 1);
+}
+
+#[test]
+fn respects_t_max() {
+    let scene = scene();
+    let bvh = Bvh::build(&scene.objects);
+    let ray = Ray::new(Vec3::new(0.0, 0.0, 14.0), Vec3::Z, 1.0);
+    assert!(bvh.closest_hit(&scene.objects, &ray, 1e-3, 0.5).is_none());
+    assert!(scene.closest_hit(&ray, 1e-3, 0.5).is_none());
+}
@@ -0,0 +1,95 @@
+// 屈折率が 1 よりわずかに大きい容器（加圧した空気）をガラスの箱として置いたとき、
+// 壁でわずかに曲がり、中に置いたガラスとの境界では容器の屈折率が n1 / n2 に使われることの確認
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, DetailedPath, Hittable, Material, Ray, Scene, SimulationSettingsConfig,
+};
+
+const N_CHAMBER: f32 = 1.0003;
+const N_GLASS: f32 = 1.5;
+// 入射光の向き（+Z から +X 側へ60度）
+const SIN_INCIDENCE: f32 = 0.866_025_4;
+
+fn slab(z_min: f32, z_max: f32, half_width: f32, ior: f32) -> Box<dyn Hittable> {
+    Box::new(AxisAlignedBox {
+        min: Vec3::new(-half_width, -half_width, z_min),
+        max: Vec3::new(half_width, half_width, z_max),
+        material: Material::Glass { ior },
+    })
+}
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        infinity_distance: 1000.0,
+        max_bounces: 10,
+        max_reflections: 10,
+        max_refractions: 10,
+        ..Default::default()
+    }
+}
+
+fn trace(objects: Vec<Box<dyn Hittable>>) -> DetailedPath {
+    let cos = (1.0 - SIN_INCIDENCE * SIN_INCIDENCE).sqrt();
+    let scene = Scene {
+        objects,
+        rays: vec![Ray::new(
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(SIN_INCIDENCE, 0.0, cos),
+            1.0,
+        )],
+        object_names: Default::default(),
+    };
+    scene.simulate_rays_detailed(setting()).remove(0)
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+}
+
+#[test]
+fn entering_the_chamber_bends_by_small_angle() {
+    let path = trace(vec![slab(0.0, 100.0, 500.0, N_CHAMBER)]);
+    let inside = path.interactions[0].outgoing_dir;
+    // スネルの法則: sinθ = sin 60° / 1.0003
+    assert_close(inside.x, SIN_INCIDENCE / N_CHAMBER);
+    // 曲がる角度は (n - 1) tanθ ≈ 5.2e-4 rad
+    let bend = SIN_INCIDENCE.asin() - inside.x.asin();
+    let expected = (N_CHAMBER - 1.0) * 3f32.sqrt();
+    assert!((bend - expected).abs() < 2e-5, "{bend} != {expected}");
+    // 反対側の壁から出ると元の向きに戻る
+    assert_close(path.interactions[1].outgoing_dir.x, SIN_INCIDENCE);
+}
+
+#[test]
+fn glass_inside_the_chamber_refracts_from_chamber_ior() {
+    let path = trace(vec![
+        slab(0.0, 100.0, 500.0, N_CHAMBER),
+        slab(10.0, 20.0, 400.0, N_GLASS),
+    ]);
+    assert_eq!(path.interactions.len(), 4);
+    // 容器の中のガラスへ: n1 = 1.0003
+    assert_close(path.interactions[1].outgoing_dir.x, SIN_INCIDENCE / N_GLASS);
+    // ガラスから出た先は空気ではなく容器の中
+    assert_close(
+        path.interactions[2].outgoing_dir.x,
+        SIN_INCIDENCE / N_CHAMBER,
+    );
+    assert_close(path.interactions[3].outgoing_dir.x, SIN_INCIDENCE);
+}
+
+#[test]
+fn ray_starting_inside_the_chamber_uses_its_ior() {
+    let scene = Scene {
+        objects: vec![slab(-100.0, 100.0, 500.0, N_CHAMBER)],
+        rays: vec![Ray::new(
+            Vec3::ZERO,
+            Vec3::new(0.5, 0.0, 0.75f32.sqrt()),
+            N_CHAMBER,
+        )],
+        object_names: Default::default(),
+    };
+    let path = scene.simulate_rays_detailed(setting()).remove(0);
+    // 容器から空気へ出ると sinθ が 1.0003 倍になる
+    assert_eq!(path.interactions.len(), 1);
+    assert_close(path.interactions[0].outgoing_dir.x, 0.5 * N_CHAMBER);
+}
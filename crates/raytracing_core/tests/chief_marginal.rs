@@ -0,0 +1,88 @@
+// 絞りを通る主光線・周辺光線 (analysis::chief_and_marginal) の確認
+use std::collections::HashMap;
+
+use glam::{Mat4, Vec3};
+use raytracing_core::analysis::chief_and_marginal;
+use raytracing_core::{
+    AxisAlignedBox, CSGObject, CsgOperation, DetailedPath, Hittable, InfiniteCylinder, Lens,
+    Material, Scene, Transform,
+};
+
+const STOP_RADIUS: f32 = 2.0;
+
+// z = -10 に両凸レンズ、z = 0 に半径 2 の穴の空いた薄い吸収板（絞り）を置く
+// 絞りはレンズの後ろにあるので、絞りの中心を通すには入射位置の調整が要る
+fn scene() -> Scene {
+    let lens = Lens::new(2.0, 10.0, 20.0, -20.0, Material::Glass { ior: 1.5 });
+    let plate = AxisAlignedBox {
+        min: Vec3::new(-8.0, -8.0, -0.001),
+        max: Vec3::new(8.0, 8.0, 0.001),
+        material: Material::Absorber,
+    };
+    let hole = InfiniteCylinder {
+        axis_point: Vec3::ZERO,
+        axis_dir: Vec3::Z,
+        radius: STOP_RADIUS,
+        material: Material::Absorber,
+    };
+    let stop = CSGObject {
+        left: Box::new(plate),
+        right: Box::new(hole),
+        operation: CsgOperation::Difference,
+    };
+    let objects: Vec<Box<dyn Hittable>> = vec![
+        Box::new(Transform::new(
+            Box::new(lens),
+            Mat4::from_translation(Vec3::new(0.0, 0.0, -10.0)),
+        )),
+        Box::new(stop),
+    ];
+    Scene {
+        objects,
+        rays: Vec::new(),
+        object_names: HashMap::new(),
+    }
+}
+
+// 光路が z = 0 の面を横切る点
+fn stop_crossing(path: &DetailedPath) -> Vec3 {
+    path.points
+        .windows(2)
+        .find_map(|segment| {
+            let (z0, z1) = (segment[0].z, segment[1].z);
+            (z0 < 0.0 && z1 >= 0.0).then(|| segment[0].lerp(segment[1], -z0 / (z1 - z0)))
+        })
+        .expect("光路が絞りの面に届いていない")
+}
+
+#[test]
+fn chief_ray_passes_through_stop_center() {
+    let field_deg = 5.0;
+    let (chief, marginal) = chief_and_marginal(&scene(), 1, field_deg).unwrap();
+
+    let crossing = stop_crossing(&chief);
+    assert!(
+        crossing.truncate().length() < 1e-3,
+        "chief ray crosses the stop at {crossing}"
+    );
+    // 入射する向きは画角どおり
+    let incoming = chief.interactions[0].incoming_dir;
+    let angle = incoming.angle_between(Vec3::Z).to_degrees();
+    assert!((angle - field_deg).abs() < 1e-3, "field angle {angle}");
+    // レンズの前で光軸から外れた位置から入射する（直進していれば y = tan 5° × 距離 だけ下）
+    assert!(chief.points[0].y < 0.0);
+
+    // 周辺光線は絞りの +Y 側の縁を通り、吸収されずに抜ける
+    let crossing = stop_crossing(&marginal);
+    assert!(
+        (crossing.y - STOP_RADIUS).abs() < 0.01,
+        "marginal ray crosses the stop at {crossing}"
+    );
+    assert!(crossing.x.abs() < 1e-4);
+    assert!(marginal.escaped);
+}
+
+#[test]
+fn missing_aperture_returns_none() {
+    assert!(chief_and_marginal(&scene(), 5, 0.0).is_none());
+}
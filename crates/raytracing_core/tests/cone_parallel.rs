@@ -0,0 +1,46 @@
+// 円錐の母線と平行なレイ（2次の係数 A = 0）の交差判定の確認
+use std::f32::consts::FRAC_PI_4;
+
+use glam::Vec3;
+use raytracing_core::{Hittable, InfiniteCone, Material, Ray};
+
+// 頂点が原点、軸が +Y、半頂角 45° の円錐（x² + z² = y²）
+fn cone() -> InfiniteCone {
+    InfiniteCone::new(Vec3::ZERO, Vec3::Y, FRAC_PI_4, Material::Mirror)
+}
+
+#[test]
+fn ray_parallel_to_slant_hits_once() {
+    let direction = Vec3::new(1.0, 1.0, 0.0).normalize();
+    let ray = Ray::new(Vec3::new(-2.0, 0.0, 0.0), direction, 1.0);
+    let hits = cone().intersect_all(&ray, 1e-4, f32::INFINITY).unwrap();
+
+    assert_eq!(hits.len(), 1);
+    let hit = &hits[0];
+    assert!(hit.t.is_finite());
+    assert!((hit.point - Vec3::new(-1.0, 1.0, 0.0)).length() < 1e-4);
+    assert!((hit.normal.length() - 1.0).abs() < 1e-4);
+    assert!(hit.normal.dot(direction) < 0.0);
+}
+
+#[test]
+fn ray_along_slant_through_vertex_misses_cleanly() {
+    let direction = Vec3::new(1.0, 1.0, 0.0).normalize();
+    let ray = Ray::new(Vec3::new(-1.0, -1.0, 0.0), direction, 1.0);
+    assert!(cone().intersect_all(&ray, 1e-4, f32::INFINITY).is_none());
+}
+
+#[test]
+fn nearly_parallel_rays_give_finite_hits() {
+    for i in 1..100 {
+        let tilt = i as f32 * 1e-7;
+        let direction = Vec3::new(1.0, 1.0 + tilt, 0.0).normalize();
+        let ray = Ray::new(Vec3::new(-2.0, 0.0, 0.0), direction, 1.0);
+        let hits = cone().intersect_all(&ray, 1e-4, 1e4).unwrap_or_default();
+        assert!(
+            hits.iter()
+                .all(|hit| hit.t.is_finite() && hit.point.is_finite()),
+            "tilt {tilt}"
+        );
+    }
+}
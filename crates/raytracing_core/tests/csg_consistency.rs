@@ -0,0 +1,64 @@
+// check_csg_consistency が、平面を組み合わせて閉じたウェッジを通し、
+// 法線を逆向きにして閉じなくなったウェッジを見つけることの確認
+use glam::Vec3;
+use raytracing_core::{CSGObject, CsgOperation, Hittable, Material, Plane, Scene};
+
+fn plane(point: Vec3, normal: Vec3) -> Box<dyn Hittable> {
+    Box::new(Plane {
+        point,
+        normal: normal.normalize(),
+        material: Material::Absorber,
+    })
+}
+
+fn intersect(left: Box<dyn Hittable>, right: Box<dyn Hittable>) -> Box<dyn Hittable> {
+    Box::new(CSGObject {
+        left,
+        right,
+        operation: CsgOperation::Intersection,
+    })
+}
+
+// 底面 y >= 0、奥の面 x <= 1、傾斜面 y <= x tan(30°)、奥行き |z| <= 0.5 の三角柱。
+// 各平面の法線は内部を向く。slope_sign を -1 にすると傾斜面の法線が外を向く
+fn wedge(slope_sign: f32) -> Box<dyn Hittable> {
+    let angle = 30.0_f32.to_radians();
+    let slope_normal = Vec3::new(angle.sin(), -angle.cos(), 0.0) * slope_sign;
+    let planes = [
+        plane(Vec3::ZERO, Vec3::Y),
+        plane(Vec3::new(1.0, 0.0, 0.0), Vec3::NEG_X),
+        plane(Vec3::ZERO, slope_normal),
+        plane(Vec3::new(0.0, 0.0, 0.5), Vec3::NEG_Z),
+        plane(Vec3::new(0.0, 0.0, -0.5), Vec3::Z),
+    ];
+    planes.into_iter().reduce(intersect).unwrap()
+}
+
+fn scene(objects: Vec<Box<dyn Hittable>>) -> Scene {
+    Scene {
+        objects,
+        rays: Vec::new(),
+        object_names: Default::default(),
+    }
+}
+
+#[test]
+fn closed_wedge_passes() {
+    let issues = scene(vec![wedge(1.0)]).check_csg_consistency();
+    assert!(issues.is_empty(), "{:?}", issues);
+}
+
+#[test]
+fn wedge_with_flipped_normal_is_flagged() {
+    let issues = scene(vec![wedge(1.0), wedge(-1.0)]).check_csg_consistency();
+    assert_eq!(issues.len(), 1, "{:?}", issues);
+    assert_eq!(issues[0].object_index, 1);
+    assert_eq!(issues[0].path, "root");
+    assert!(issues[0].failed_rays > 0);
+}
+
+#[test]
+fn standalone_plane_is_not_flagged() {
+    let issues = scene(vec![plane(Vec3::ZERO, Vec3::Y)]).check_csg_consistency();
+    assert!(issues.is_empty(), "{:?}", issues);
+}
@@ -0,0 +1,59 @@
+// CSG で子の面が重なり、同じ t のヒットが並んだときの結果の確認
+use glam::Vec3;
+use raytracing_core::{AxisAlignedBox, CSGObject, CsgOperation, Hittable, Material, Ray};
+
+fn aabb(min: [f32; 3], max: [f32; 3]) -> Box<dyn Hittable> {
+    Box::new(AxisAlignedBox {
+        min: Vec3::from_array(min),
+        max: Vec3::from_array(max),
+        material: Material::Glass { ior: 1.5 },
+    })
+}
+
+// x = 0 の面で接した2つの箱
+fn touching(operation: CsgOperation) -> CSGObject {
+    CSGObject {
+        left: aabb([-1.0, -1.0, -1.0], [0.0, 1.0, 1.0]),
+        right: aabb([0.0, -1.0, -1.0], [1.0, 1.0, 1.0]),
+        operation,
+    }
+}
+
+fn x_ray() -> Ray {
+    Ray::new(Vec3::new(-5.0, 0.2, 0.3), Vec3::X, 1.0)
+}
+
+#[test]
+fn union_of_touching_boxes_has_no_inner_surface() {
+    let hits = touching(CsgOperation::Union)
+        .intersect_all(&x_ray(), 1e-4, f32::INFINITY)
+        .unwrap();
+    let ts: Vec<f32> = hits.iter().map(|hit| hit.t).collect();
+    assert_eq!(ts, vec![4.0, 6.0]);
+    assert!(hits[0].front_face);
+    assert!(!hits[1].front_face);
+}
+
+#[test]
+fn difference_with_touching_box_keeps_shared_face() {
+    // left から right を引いても、接した面は left の出口として残る
+    let hits = touching(CsgOperation::Difference)
+        .intersect_all(&x_ray(), 1e-4, f32::INFINITY)
+        .unwrap();
+    let ts: Vec<f32> = hits.iter().map(|hit| hit.t).collect();
+    assert_eq!(ts, vec![4.0, 5.0]);
+    assert!(hits[0].front_face);
+    assert!(!hits[1].front_face);
+    // 法線はレイと向かい合う
+    assert!(hits.iter().all(|hit| hit.normal.dot(Vec3::X) < 0.0));
+}
+
+#[test]
+fn reversed_ray_gives_the_same_surfaces() {
+    let ray = Ray::new(Vec3::new(5.0, 0.2, 0.3), Vec3::NEG_X, 1.0);
+    let hits = touching(CsgOperation::Union)
+        .intersect_all(&ray, 1e-4, f32::INFINITY)
+        .unwrap();
+    let ts: Vec<f32> = hits.iter().map(|hit| hit.t).collect();
+    assert_eq!(ts, vec![4.0, 6.0]);
+}
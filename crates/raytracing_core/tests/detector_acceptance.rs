@@ -0,0 +1,86 @@
+// 検出器の受光角 (Material::Detector) の内側から来た光だけが記録されるか確かめる
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{InteractionKind, Material, Plane, Ray, Scene, SimulationSettingsConfig};
+
+const HALF_ANGLE_DEG: f32 = 12.0;
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        max_bounces: 8,
+        max_reflections: 8,
+        max_refractions: 8,
+        ..Default::default()
+    }
+}
+
+// z = 10 の検出面に、光軸から angles_deg だけ傾けたレイを当てる
+fn scene(angles_deg: &[f32]) -> Scene {
+    Scene {
+        objects: vec![Box::new(Plane {
+            point: Vec3::new(0.0, 0.0, 10.0),
+            normal: Vec3::Z,
+            material: Material::Detector {
+                acceptance_half_angle_deg: HALF_ANGLE_DEG,
+            },
+        })],
+        rays: angles_deg
+            .iter()
+            .map(|angle| {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                Ray::new(Vec3::ZERO, Vec3::new(sin, 0.0, cos), 1.0)
+            })
+            .collect(),
+        object_names: HashMap::new(),
+    }
+}
+
+#[test]
+fn rays_inside_the_acceptance_cone_are_recorded() {
+    let paths = scene(&[0.0, 5.0, -11.0]).simulate_rays_detailed(setting());
+    for path in &paths {
+        assert_eq!(path.interactions.len(), 1);
+        let interaction = &path.interactions[0];
+        assert_eq!(interaction.kind, InteractionKind::Absorption);
+        assert_eq!(interaction.object_index, 0);
+        // 記録した光の強度はそのまま残る
+        assert_eq!(path.intensity, 1.0);
+        assert!(!path.escaped);
+        assert!((path.points.last().unwrap().z - 10.0).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn rays_outside_the_acceptance_cone_pass_through() {
+    let paths = scene(&[13.0, -30.0, 60.0]).simulate_rays_detailed(setting());
+    for path in &paths {
+        assert_eq!(path.interactions.len(), 1);
+        assert_eq!(path.interactions[0].kind, InteractionKind::Transmission);
+        assert_eq!(
+            path.interactions[0].outgoing_dir,
+            path.interactions[0].incoming_dir
+        );
+        assert!(path.escaped);
+        assert!(path.points.last().unwrap().z > 10.0);
+    }
+}
+
+#[test]
+fn acceptance_does_not_depend_on_which_side_is_hit() {
+    let mut scene = scene(&[5.0, 20.0]);
+    // 検出面の裏側（+Z 側）から -Z 向きに当てる
+    for ray in &mut scene.rays {
+        ray.origin.z = 20.0;
+        ray.direction.z = -ray.direction.z;
+    }
+    let kinds: Vec<InteractionKind> = scene
+        .simulate_rays_detailed(setting())
+        .iter()
+        .map(|path| path.interactions[0].kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        [InteractionKind::Absorption, InteractionKind::Transmission]
+    );
+}
@@ -0,0 +1,90 @@
+// 検出器の平面に強度を足し込む analysis::detector_image の確認
+use glam::Vec3;
+use raytracing_core::analysis::{detector_image, exit_focus, DetectorShape};
+use raytracing_core::{Lens, Material, Plane, Ray, Scene, SimulationSettingsConfig};
+
+const RESOLUTION: usize = 21;
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        infinity_distance: 200.0,
+        max_bounces: 10,
+        max_reflections: 10,
+        max_refractions: 10,
+        ..Default::default()
+    }
+}
+
+fn detector(z: f32) -> Plane {
+    Plane {
+        point: Vec3::new(0.0, 0.0, z),
+        normal: Vec3::Z,
+        material: Material::Mirror, // 判定には使わない
+    }
+}
+
+#[test]
+fn converging_bundle_peaks_at_the_focus() {
+    // 原点の両凸レンズに、格子状に並べた平行光を当てて集光させる
+    let rays = (-4..=4)
+        .flat_map(|i| (-4..=4).map(move |j| (i as f32 * 0.5, j as f32 * 0.5)))
+        .filter(|(x, y)| x * x + y * y <= 4.0)
+        .map(|(x, y)| Ray::new(Vec3::new(x, y, -10.0), Vec3::Z, 1.0))
+        .collect();
+    let scene = Scene {
+        objects: vec![Box::new(Lens::new(
+            2.0,
+            10.0,
+            40.0,
+            -40.0,
+            Material::Glass { ior: 1.5 },
+        ))],
+        rays,
+        object_names: Default::default(),
+    };
+    let paths = scene.simulate_rays_detailed(setting());
+    let focus = exit_focus(&paths).unwrap();
+    assert!(focus.z > 10.0, "focus {focus}");
+
+    let image = detector_image(
+        &paths,
+        &detector(focus.z),
+        DetectorShape::Disk { radius: 2.0 },
+        [RESOLUTION, RESOLUTION],
+    );
+    let (brightest, _) = image
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .unwrap();
+    let center = RESOLUTION / 2;
+    assert_eq!(brightest, center + RESOLUTION * center, "{image:?}");
+    // 光路はすべて焦点の近くに集まり、検出器から外れない
+    let total: f32 = image.iter().sum();
+    let expected: f32 = paths.iter().map(|path| path.intensity).sum();
+    assert!((total - expected).abs() < 1e-3, "{total} vs {expected}");
+}
+
+#[test]
+fn rectangle_bins_in_the_plane_frame() {
+    // レンズの無い場で、z = 5 の検出器を u（= X）の正の側だけで横切る光路
+    let rays = vec![
+        Ray::new(Vec3::new(1.5, 0.5, 0.0), Vec3::Z, 1.0),
+        Ray::new(Vec3::new(1.5, 0.5, 0.0), Vec3::Z, 1.0),
+        Ray::new(Vec3::new(-1.5, -0.5, 0.0), Vec3::Z, 1.0),
+        Ray::new(Vec3::new(5.0, 0.0, 0.0), Vec3::Z, 1.0), // 検出器の外
+    ];
+    let scene = Scene {
+        objects: Vec::new(),
+        rays,
+        object_names: Default::default(),
+    };
+    let paths = scene.simulate_rays_detailed(setting());
+    let shape = DetectorShape::Rectangle {
+        half_width: 2.0,
+        half_height: 1.0,
+    };
+    // u を 4 分割、v を 2 分割（各マスは 1 × 1）
+    let image = detector_image(&paths, &detector(5.0), shape, [4, 2]);
+    assert_eq!(image, vec![1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0]);
+}
@@ -0,0 +1,94 @@
+// 絞りから求める近軸の入射瞳 (analysis::entrance_pupil) を、厚肉レンズの近軸理論と比べる
+use std::collections::HashMap;
+
+use glam::{Mat4, Vec3};
+use raytracing_core::analysis::entrance_pupil;
+use raytracing_core::{
+    AxisAlignedBox, CSGObject, CsgOperation, Hittable, InfiniteCylinder, Lens, Material, Scene,
+    Transform,
+};
+
+const STOP_RADIUS: f32 = 2.0;
+const IOR: f32 = 1.5;
+const RADIUS: f32 = 20.0; // 両凸レンズの曲率半径（r1 = RADIUS, r2 = -RADIUS）
+const THICKNESS: f32 = 2.0;
+const LENS_Z: f32 = -10.0;
+
+// 半径 STOP_RADIUS の穴の空いた薄い吸収板（絞り）を z = 0 に置き、lens_z に両凸レンズを置く
+fn scene(with_lens: bool) -> Scene {
+    let plate = AxisAlignedBox {
+        min: Vec3::new(-8.0, -8.0, -0.001),
+        max: Vec3::new(8.0, 8.0, 0.001),
+        material: Material::Absorber,
+    };
+    let hole = InfiniteCylinder {
+        axis_point: Vec3::ZERO,
+        axis_dir: Vec3::Z,
+        radius: STOP_RADIUS,
+        material: Material::Absorber,
+    };
+    let mut objects: Vec<Box<dyn Hittable>> = vec![Box::new(CSGObject {
+        left: Box::new(plate),
+        right: Box::new(hole),
+        operation: CsgOperation::Difference,
+    })];
+    if with_lens {
+        let lens = Lens::new(
+            THICKNESS,
+            10.0,
+            RADIUS,
+            -RADIUS,
+            Material::Glass { ior: IOR },
+        );
+        objects.push(Box::new(Transform::new(
+            Box::new(lens),
+            Mat4::from_translation(Vec3::new(0.0, 0.0, LENS_Z)),
+        )));
+    }
+    Scene {
+        objects,
+        rays: Vec::new(),
+        object_names: HashMap::new(),
+    }
+}
+
+#[test]
+fn stop_behind_lens_matches_paraxial_theory() {
+    // 厚肉レンズの焦点距離と主点（レンズメーカーの式）
+    let power = (IOR - 1.0)
+        * (1.0 / RADIUS + 1.0 / RADIUS - (IOR - 1.0) * THICKNESS / (IOR * RADIUS * RADIUS));
+    let focal = 1.0 / power;
+    let principal_shift = focal * (IOR - 1.0) * THICKNESS / (IOR * RADIUS);
+    let front_principal = LENS_Z - THICKNESS / 2.0 + principal_shift;
+    let rear_principal = LENS_Z + THICKNESS / 2.0 - principal_shift;
+    // 後側主点から d の距離にある絞りを、レンズを通して前から見た虚像
+    // 前側主点から d f / (f - d) だけ絞りの側にでき、倍率は f / (f - d)
+    let d = -rear_principal;
+    let expected_z = front_principal + d * focal / (focal - d);
+    let expected_diameter = 2.0 * STOP_RADIUS * focal / (focal - d);
+
+    let pupil = entrance_pupil(&scene(true), 0).expect("入射瞳が求まらない");
+    assert!(
+        (pupil.position.z - expected_z).abs() < 0.02,
+        "{} != {}",
+        pupil.position.z,
+        expected_z
+    );
+    assert!(pupil.position.truncate().length() < 1e-4);
+    assert!(
+        (pupil.diameter - expected_diameter).abs() < 0.01,
+        "{} != {}",
+        pupil.diameter,
+        expected_diameter
+    );
+    // 絞りの後ろにできる拡大された虚像
+    assert!(pupil.position.z > 0.0);
+    assert!(pupil.diameter > 2.0 * STOP_RADIUS);
+}
+
+#[test]
+fn stop_without_front_optics_is_its_own_pupil() {
+    let pupil = entrance_pupil(&scene(false), 0).unwrap();
+    assert!(pupil.position.length() < 1e-3);
+    assert!((pupil.diameter - 2.0 * STOP_RADIUS).abs() < 1e-3);
+}
@@ -0,0 +1,76 @@
+// 飛び去るレイの最後の区間が、シーンの対角線の2倍までに切り詰められることの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    DetailedPath, Material, Plane, Ray, Scene, SimulationSettingsConfig, Sphere,
+};
+
+fn trace(objects: Vec<Box<dyn raytracing_core::Hittable>>, infinity_distance: f32) -> DetailedPath {
+    let scene = Scene {
+        objects,
+        rays: vec![Ray::new(
+            Vec3::new(0.0, 0.0, -5.0),
+            Vec3::new(0.0, 0.001, 1.0),
+            1.0,
+        )],
+        object_names: HashMap::new(),
+    };
+    let setting = SimulationSettingsConfig {
+        infinity_distance,
+        max_bounces: 10,
+        ..Default::default()
+    };
+    scene.simulate_rays_detailed(setting).remove(0)
+}
+
+fn last_segment_length(path: &DetailedPath) -> f32 {
+    let [.., a, b] = path.points.as_slice() else {
+        panic!("区間がありません");
+    };
+    a.distance(*b)
+}
+
+fn glass_sphere() -> Box<dyn raytracing_core::Hittable> {
+    Box::new(Sphere {
+        center: Vec3::ZERO,
+        radius: 1.0,
+        material: Material::Glass { ior: 1.5 },
+    })
+}
+
+#[test]
+fn escaped_segment_is_clamped_to_twice_the_scene_diagonal() {
+    let path = trace(vec![glass_sphere()], 1.0e6);
+    assert!(path.escaped);
+    // 球の外接ボックスとレイの始点 (0, 0, -5) を囲む箱の対角線（外接ボックスのわずかな余白は許す）
+    let diagonal = Vec3::new(2.0, 2.0, 6.0).length();
+    let length = last_segment_length(&path);
+    assert!(
+        length <= 2.0 * diagonal * 1.001,
+        "{} > {}",
+        length,
+        2.0 * diagonal
+    );
+    assert!(length > diagonal);
+}
+
+#[test]
+fn short_infinity_distance_is_kept() {
+    let path = trace(vec![glass_sphere()], 3.0);
+    assert!(path.escaped);
+    assert!((last_segment_length(&path) - 3.0).abs() < 1e-2);
+}
+
+#[test]
+fn scene_without_finite_objects_uses_infinity_distance() {
+    // 無限に広がる平面だけでは大きさが決まらない
+    let plane = Box::new(Plane {
+        point: Vec3::new(0.0, 0.0, 100.0),
+        normal: Vec3::NEG_Y,
+        material: Material::Absorber,
+    });
+    let path = trace(vec![plane], 1000.0);
+    assert!(path.escaped);
+    assert!((last_segment_length(&path) - 1000.0).abs() < 1e-2);
+}
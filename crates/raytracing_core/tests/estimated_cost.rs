@@ -0,0 +1,68 @@
+// 追跡の手間の見積もり (Scene::estimated_cost) がレイとオブジェクトの数に比例することの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    EstimatedCost, Hittable, Material, Ray, Scene, SimulationSettingsConfig, Sphere,
+};
+
+fn setting(max_bounces: u32) -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        max_bounces,
+        max_reflections: max_bounces,
+        max_refractions: max_bounces,
+        ..Default::default()
+    }
+}
+
+fn scene(rays: usize, objects: usize) -> Scene {
+    Scene {
+        objects: (0..objects)
+            .map(|i| {
+                Box::new(Sphere {
+                    center: Vec3::new(0.0, 0.0, 5.0 * (i + 1) as f32),
+                    radius: 1.0,
+                    material: Material::Mirror,
+                }) as Box<dyn Hittable>
+            })
+            .collect(),
+        rays: (0..rays)
+            .map(|i| Ray::new(Vec3::new(i as f32, 0.0, 0.0), Vec3::Z, 1.0))
+            .collect(),
+        object_names: HashMap::new(),
+    }
+}
+
+#[test]
+fn estimate_scales_with_rays_objects_and_bounces() {
+    assert_eq!(
+        scene(10, 3).estimated_cost(setting(8)),
+        EstimatedCost {
+            rays: 10,
+            max_intersection_calls: 240,
+        }
+    );
+
+    let base = scene(10, 3)
+        .estimated_cost(setting(8))
+        .max_intersection_calls;
+    let more_rays = scene(40, 3)
+        .estimated_cost(setting(8))
+        .max_intersection_calls;
+    let more_objects = scene(10, 6)
+        .estimated_cost(setting(8))
+        .max_intersection_calls;
+    let more_bounces = scene(10, 3)
+        .estimated_cost(setting(16))
+        .max_intersection_calls;
+    assert_eq!(more_rays, base * 4);
+    assert_eq!(more_objects, base * 2);
+    assert_eq!(more_bounces, base * 2);
+
+    assert_eq!(
+        scene(0, 3)
+            .estimated_cost(setting(8))
+            .max_intersection_calls,
+        0
+    );
+}
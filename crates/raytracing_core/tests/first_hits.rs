@@ -0,0 +1,54 @@
+// 最初の衝突だけを求める simulate_first_hits が、光路全体の追跡の最初の点と一致することの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{Material, Ray, Scene, SimulationSettingsConfig, Sphere};
+
+#[test]
+fn first_hits_match_the_first_point_of_full_simulation() {
+    let scene = Scene {
+        objects: vec![
+            Box::new(Sphere {
+                center: Vec3::new(0.0, 0.0, 10.0),
+                radius: 2.0,
+                material: Material::Glass { ior: 1.5 },
+            }),
+            Box::new(Sphere {
+                center: Vec3::new(5.0, 0.0, 20.0),
+                radius: 1.0,
+                material: Material::Mirror,
+            }),
+        ],
+        rays: vec![
+            Ray::new(Vec3::ZERO, Vec3::Z, 1.0),
+            Ray::new(Vec3::new(1.0, 0.5, 0.0), Vec3::Z, 1.0),
+            Ray::new(Vec3::ZERO, Vec3::new(5.0, 0.0, 20.0), 1.0),
+            // どこにも当たらない
+            Ray::new(Vec3::ZERO, Vec3::NEG_Z, 1.0),
+        ],
+        object_names: HashMap::new(),
+    };
+    let first_hits = scene.simulate_first_hits();
+    let paths = scene.simulate_rays_detailed(SimulationSettingsConfig::default());
+    assert_eq!(first_hits.len(), scene.rays.len());
+
+    for (index, (first_hit, path)) in first_hits.iter().zip(&paths).enumerate() {
+        match first_hit {
+            Some((origin, hit)) => {
+                assert_eq!(*origin, scene.rays[index].origin);
+                assert!(
+                    (hit.point - path.points[1]).length() < 1e-5,
+                    "{index}: {} vs {}",
+                    hit.point,
+                    path.points[1]
+                );
+                assert_eq!(hit.point, path.interactions[0].hit.point);
+            }
+            None => {
+                assert!(path.interactions.is_empty(), "{index}");
+                assert!(path.escaped, "{index}");
+            }
+        }
+    }
+    assert!(first_hits[3].is_none());
+}
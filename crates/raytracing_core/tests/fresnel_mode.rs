@@ -0,0 +1,34 @@
+// FresnelMode::Deterministic で、確率の高い分岐を辿って強度がその確率倍になることの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, FresnelMode, InteractionKind, Material, Ray, Scene, SimulationSettingsConfig,
+};
+
+#[test]
+fn deterministic_normal_incidence_follows_refraction() {
+    // z = 0〜2 のガラス板 (n = 1.5) に垂直に当てる。反射率は 0.04 なので屈折を辿る
+    let scene = Scene {
+        objects: vec![Box::new(AxisAlignedBox {
+            min: Vec3::new(-5.0, -5.0, 0.0),
+            max: Vec3::new(5.0, 5.0, 2.0),
+            material: Material::Glass { ior: 1.5 },
+        })],
+        rays: vec![Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::Z, 1.0)],
+        object_names: HashMap::new(),
+    };
+    let setting = SimulationSettingsConfig {
+        fresnel_mode: FresnelMode::Deterministic,
+        ..Default::default()
+    };
+    let paths = scene.simulate_rays_detailed(setting);
+    assert_eq!(paths.len(), 1);
+    let interactions = &paths[0].interactions;
+    assert_eq!(interactions.len(), 2);
+    assert_eq!(interactions[0].kind, InteractionKind::Refraction);
+    assert_eq!(interactions[0].outgoing_dir, Vec3::Z);
+    // 入射面を抜けた直後（裏面に当たる直前）の強度は透過率 0.96
+    assert!((interactions[1].incoming_intensity - 0.96).abs() < 1e-5);
+    assert!((paths[0].intensity - 0.96 * 0.96).abs() < 1e-5);
+}
@@ -0,0 +1,125 @@
+// 目標側から追跡した光路を並べ替えたものが、光源側から追跡した光路と同じ折れ線になることの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, DetailedPath, Hittable, Material, Plane, Ray, Scene, SimulationSettingsConfig,
+};
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        infinity_distance: 100.0,
+        max_bounces: 10,
+        ..Default::default()
+    }
+}
+
+fn absorber(point: Vec3, normal: Vec3) -> Box<dyn Hittable> {
+    Box::new(Plane {
+        point,
+        normal: normal.normalize(),
+        material: Material::Absorber,
+    })
+}
+
+fn trace(objects: Vec<Box<dyn Hittable>>, ray: Ray, from_target: bool) -> DetailedPath {
+    let scene = Scene {
+        objects,
+        rays: vec![ray],
+        object_names: HashMap::new(),
+    };
+    let mut paths = if from_target {
+        scene.simulate_rays_from_targets(setting())
+    } else {
+        scene.simulate_rays_detailed(setting())
+    };
+    paths.remove(0)
+}
+
+// 順方向の光路の終点から、最後の区間を逆にたどるレイを撃つ
+fn trace_back(objects: Vec<Box<dyn Hittable>>, forward: &DetailedPath) -> DetailedPath {
+    let [.., before_last, last] = forward.points.as_slice() else {
+        panic!("区間がありません");
+    };
+    trace(
+        objects,
+        Ray::new(*last, (*before_last - *last).normalize(), 1.0),
+        true,
+    )
+}
+
+fn assert_same_polyline(forward: &DetailedPath, reversed: &DetailedPath) {
+    assert_eq!(
+        forward.points.len(),
+        reversed.points.len(),
+        "{:?}\n{:?}",
+        forward.points,
+        reversed.points
+    );
+    for (a, b) in forward.points.iter().zip(&reversed.points) {
+        assert!(
+            a.distance(*b) < 1e-3,
+            "{:?}\n{:?}",
+            forward.points,
+            reversed.points
+        );
+    }
+}
+
+#[test]
+fn mirror_path_reversed_matches_forward() {
+    // 光源 S = (-4, 4, 0) から y = 0 の鏡の原点で反射し、目標 T = (4, 4, 0) の吸収面に届く
+    // S と T を通る吸収面は、それぞれ光源側と目標側の区間に平行ではないので、逆向きのレイは S で止まる
+    let objects = || {
+        vec![
+            Box::new(Plane {
+                point: Vec3::ZERO,
+                normal: Vec3::Y,
+                material: Material::Mirror,
+            }) as Box<dyn Hittable>,
+            absorber(Vec3::new(-4.0, 4.0, 0.0), Vec3::new(-1.0, 1.0, 0.0)),
+            absorber(Vec3::new(4.0, 4.0, 0.0), Vec3::new(1.0, 1.0, 0.0)),
+        ]
+    };
+    let forward = trace(
+        objects(),
+        Ray::new(
+            Vec3::new(-4.0, 4.0, 0.0),
+            Vec3::new(1.0, -1.0, 0.0).normalize(),
+            1.0,
+        ),
+        false,
+    );
+    assert_eq!(forward.points.len(), 3);
+    assert!(forward.points[2].distance(Vec3::new(4.0, 4.0, 0.0)) < 1e-4);
+
+    let reversed = trace_back(objects(), &forward);
+    assert_same_polyline(&forward, &reversed);
+}
+
+#[test]
+fn glass_slab_path_reversed_matches_forward() {
+    // z = 0 の光源面から斜めに出て、屈折率 1.5 の板を通り抜け、z = 10 の目標面に届く
+    let objects = || {
+        vec![
+            Box::new(AxisAlignedBox {
+                min: Vec3::new(-50.0, -50.0, 3.0),
+                max: Vec3::new(50.0, 50.0, 5.0),
+                material: Material::Glass { ior: 1.5 },
+            }) as Box<dyn Hittable>,
+            absorber(Vec3::ZERO, Vec3::Z),
+            absorber(Vec3::new(0.0, 0.0, 10.0), Vec3::NEG_Z),
+        ]
+    };
+    let forward = trace(
+        objects(),
+        Ray::new(Vec3::ZERO, Vec3::new(0.6, 0.2, 1.0).normalize(), 1.0),
+        false,
+    );
+    // 光源、板の入口と出口、目標
+    assert_eq!(forward.points.len(), 4);
+    assert!((forward.points[3].z - 10.0).abs() < 1e-4);
+
+    let reversed = trace_back(objects(), &forward);
+    assert_same_polyline(&forward, &reversed);
+}
@@ -0,0 +1,86 @@
+// gap_decay_length を指定すると、全反射した面の狭い隙間の向こうにあるガラスへ光が一部透過することの確認
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, DetailedPath, FresnelMode, Material, Ray, Scene, SimulationSettingsConfig,
+};
+
+const IOR: f32 = 1.5;
+const DECAY_LENGTH: f32 = 1e-3;
+
+fn glass(min: Vec3, max: Vec3) -> AxisAlignedBox {
+    AxisAlignedBox {
+        min,
+        max,
+        material: Material::Glass { ior: IOR },
+    }
+}
+
+// x = 0 の面に内側から45度で当たって全反射するレイと、幅 gap の隙間を挟んだもう1つのガラス
+fn trace(gap: f32, gap_decay_length: Option<f32>) -> Vec<DetailedPath> {
+    let scene = Scene {
+        objects: vec![
+            Box::new(glass(Vec3::new(-1.0, -1.0, -1.0), Vec3::new(0.0, 1.0, 1.0))),
+            Box::new(glass(
+                Vec3::new(gap, -1.0, -1.0),
+                Vec3::new(1.0 + gap, 5.0, 1.0),
+            )),
+        ],
+        rays: vec![Ray::new(
+            Vec3::new(-0.5, -0.5, 0.0),
+            Vec3::new(1.0, 1.0, 0.0).normalize(),
+            IOR,
+        )],
+        object_names: Default::default(),
+    };
+    let setting = SimulationSettingsConfig {
+        max_bounces: 4,
+        max_reflections: 4,
+        max_refractions: 4,
+        fresnel_mode: FresnelMode::Split,
+        gap_decay_length,
+        ..Default::default()
+    };
+    scene.simulate_rays_detailed(setting)
+}
+
+// 元の光路で、最初の面を越えた後の強度（全反射したら None）
+fn transmitted(gap: f32) -> Option<f32> {
+    let paths = trace(gap, Some(DECAY_LENGTH));
+    let main = &paths[0];
+    (main.interactions[0].outgoing_dir.x > 0.0).then(|| main.interactions[1].incoming_intensity)
+}
+
+#[test]
+fn narrower_gap_transmits_more() {
+    let wide = transmitted(2e-3).expect("隙間を越える");
+    let narrow = transmitted(5e-4).expect("隙間を越える");
+    assert!(narrow > wide, "narrow {narrow}, wide {wide}");
+    assert!((wide - (-2.0f32).exp()).abs() < 1e-3, "{wide}");
+    assert!((narrow - (-0.5f32).exp()).abs() < 1e-3, "{narrow}");
+}
+
+#[test]
+fn tunneled_ray_continues_from_far_surface() {
+    let gap = 5e-4;
+    let paths = trace(gap, Some(DECAY_LENGTH));
+    let main = &paths[0];
+    // 向きは変わらず（同じ屈折率）、次の衝突は向こうのガラスの裏側の面
+    let outgoing = main.interactions[0].outgoing_dir;
+    assert!(outgoing.distance(Vec3::new(1.0, 1.0, 0.0).normalize()) < 1e-5);
+    assert!((main.interactions[1].hit.point.x - (1.0 + gap)).abs() < 1e-4);
+    assert_eq!(main.interactions[1].object_index, 1);
+    // 反射した分は別の分岐として追跡する
+    let reflected = &paths[1];
+    assert!(reflected.interactions[0].outgoing_dir.x < 0.0);
+}
+
+#[test]
+fn total_reflection_without_setting_or_beyond_search_range() {
+    for (gap, decay) in [(5e-4, None), (DECAY_LENGTH * 20.0, Some(DECAY_LENGTH))] {
+        let paths = trace(gap, decay);
+        assert_eq!(paths.len(), 1);
+        let first = &paths[0].interactions[0];
+        assert!(first.outgoing_dir.x < 0.0);
+        assert_eq!(paths[0].interactions[1].incoming_intensity, 1.0);
+    }
+}
@@ -0,0 +1,58 @@
+// 長さの許容誤差 (geometric_epsilon) をシーンの大きさに合わせられることの確認
+// 許容誤差はプロセス全体で共有するので、このファイルのテストは1つにまとめる
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    geometric_epsilon, set_geometric_epsilon, InteractionKind, Material, Ray, Scene,
+    SimulationSettingsConfig, Sphere, DEFAULT_GEOMETRIC_EPSILON,
+};
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        infinity_distance: 1e-3,
+        max_bounces: 8,
+        max_reflections: 8,
+        max_refractions: 8,
+        ..Default::default()
+    }
+}
+
+// 半径 1e-4 のガラス球を 5e-4 先に置き、中心を通るレイを飛ばす（0.1mm 程度のシーンを m 単位で書いた場合）
+fn micro_scene() -> Scene {
+    Scene {
+        objects: vec![Box::new(Sphere {
+            center: Vec3::new(0.0, 0.0, 5e-4),
+            radius: 1e-4,
+            material: Material::Glass { ior: 1.5 },
+        })],
+        rays: vec![Ray::new(Vec3::ZERO, Vec3::Z, 1.0)],
+        object_names: HashMap::new(),
+    }
+}
+
+#[test]
+fn scaled_epsilon_finds_hits_in_micro_scene() {
+    assert_eq!(geometric_epsilon(), DEFAULT_GEOMETRIC_EPSILON);
+
+    // 既定の許容誤差では、衝突とみなす t の下限 (0.001) が球までの距離より大きく、球を素通りする
+    let paths = micro_scene().simulate_rays_detailed(setting());
+    assert!(paths[0].interactions.is_empty());
+
+    // シーンの大きさに合わせて許容誤差を小さくすれば、球の入口と出口で屈折する
+    set_geometric_epsilon(1e-8);
+    let paths = micro_scene().simulate_rays_detailed(setting());
+    set_geometric_epsilon(DEFAULT_GEOMETRIC_EPSILON);
+
+    let interactions = &paths[0].interactions;
+    assert_eq!(interactions.len(), 2);
+    assert!(interactions
+        .iter()
+        .all(|interaction| interaction.kind == InteractionKind::Refraction));
+    assert!((interactions[0].hit.t - 4e-4).abs() < 1e-8);
+    assert!((interactions[1].hit.point.z - 6e-4).abs() < 1e-8);
+
+    // 正でない値は無視する
+    set_geometric_epsilon(0.0);
+    assert_eq!(geometric_epsilon(), DEFAULT_GEOMETRIC_EPSILON);
+}
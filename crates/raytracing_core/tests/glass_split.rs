@@ -0,0 +1,81 @@
+// FresnelMode::Split で、ガラス面ごとに反射光の分岐（ゴースト）が作られることの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, BranchLabel, DetailedPath, FresnelMode, InteractionKind, Material, Ray, Scene,
+    SimulationSettingsConfig,
+};
+
+// 垂直入射での空気とガラス (n = 1.5) の境界の反射率 ((1.5 - 1) / (1.5 + 1))²
+const REFLECTANCE: f32 = 0.04;
+
+fn setting(fresnel_mode: FresnelMode) -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        fresnel_mode,
+        ..Default::default()
+    }
+}
+
+// z = 0〜2 のガラス板に、-Z 側から垂直にレイを1本当てる
+fn trace(fresnel_mode: FresnelMode) -> Vec<DetailedPath> {
+    let scene = Scene {
+        objects: vec![Box::new(AxisAlignedBox {
+            min: Vec3::new(-5.0, -5.0, 0.0),
+            max: Vec3::new(5.0, 5.0, 2.0),
+            material: Material::Glass { ior: 1.5 },
+        })],
+        rays: vec![Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::Z, 1.0)],
+        object_names: HashMap::new(),
+    };
+    scene.simulate_rays_detailed(setting(fresnel_mode))
+}
+
+#[test]
+fn slab_at_normal_incidence_reflects_four_percent() {
+    let paths = trace(FresnelMode::Split);
+    assert!(paths.len() >= 2, "paths: {}", paths.len());
+
+    // 元の光路は2回屈折して抜け、両面の透過率の分だけ弱くなる
+    let main = &paths[0];
+    assert_eq!(main.branch_label(), BranchLabel::Main);
+    assert!(main.escaped);
+    assert!(outgoing_z(main) > 0.0);
+    let transmitted = (1.0 - REFLECTANCE) * (1.0 - REFLECTANCE);
+    assert!(
+        (main.intensity - transmitted).abs() < 1e-4,
+        "{}",
+        main.intensity
+    );
+
+    // 入射面で反射した分岐は、約4%の強度で来た向きへ戻る
+    let front = paths
+        .iter()
+        .find(|path| path.interactions.len() == 1)
+        .expect("入射面での反射光が無い");
+    assert_eq!(front.branch_label(), BranchLabel::Ghost { reflections: 1 });
+    assert_eq!(front.interactions[0].kind, InteractionKind::Reflection);
+    assert!(
+        (front.intensity - REFLECTANCE).abs() < 1e-4,
+        "{}",
+        front.intensity
+    );
+    assert!(outgoing_z(front) < 0.0);
+}
+
+#[test]
+fn other_modes_do_not_spawn_ghosts() {
+    for mode in [FresnelMode::AlwaysRefract, FresnelMode::Deterministic] {
+        let paths = trace(mode);
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].branch_label(), BranchLabel::Main);
+    }
+}
+
+// 最後の区間の z 方向の向き
+fn outgoing_z(path: &DetailedPath) -> f32 {
+    let [.., before, last] = path.points.as_slice() else {
+        return 0.0;
+    };
+    last.z - before.z
+}
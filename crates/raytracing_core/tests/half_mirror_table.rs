@@ -0,0 +1,52 @@
+// ハーフミラーの反射率を入射角の表で与えたとき、角度に応じた割合で反射することの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    InteractionKind, Material, Plane, Ray, Reflectance, Scene, SimulationSettingsConfig,
+};
+
+fn table() -> Reflectance {
+    Reflectance::Table([(0.0, 0.1), (60.0, 0.4), (90.0, 1.0)].into())
+}
+
+#[test]
+fn table_reflectance_is_interpolated() {
+    let reflectance = table();
+    assert!((reflectance.at(0.0) - 0.1).abs() < 1e-6);
+    assert!((reflectance.at(30.0) - 0.25).abs() < 1e-6);
+    assert!((reflectance.at(75.0) - 0.7).abs() < 1e-6);
+    // 範囲外は端の値
+    assert!((reflectance.at(-5.0) - 0.1).abs() < 1e-6);
+}
+
+// 入射角 angle_deg で count 本のレイを当て、反射した割合を返す
+fn reflected_fraction(angle_deg: f32, count: usize) -> f32 {
+    let angle = angle_deg.to_radians();
+    let direction = Vec3::new(angle.sin(), 0.0, angle.cos());
+    let scene = Scene {
+        objects: vec![Box::new(Plane {
+            point: Vec3::new(0.0, 0.0, 1.0),
+            normal: Vec3::NEG_Z,
+            material: Material::HalfMirror {
+                reflectance: table(),
+            },
+        })],
+        rays: vec![Ray::new(Vec3::ZERO, direction, 1.0); count],
+        object_names: HashMap::new(),
+    };
+    let paths = scene.simulate_rays_detailed(SimulationSettingsConfig::default());
+    let reflected = paths
+        .iter()
+        .filter(|path| path.interactions[0].kind == InteractionKind::Reflection)
+        .count();
+    reflected as f32 / count as f32
+}
+
+#[test]
+fn near_normal_and_grazing_split_by_table_values() {
+    let near_normal = reflected_fraction(0.0, 4000);
+    let grazing = reflected_fraction(75.0, 4000);
+    assert!((near_normal - 0.1).abs() < 0.04, "{near_normal}");
+    assert!((grazing - 0.7).abs() < 0.04, "{grazing}");
+}
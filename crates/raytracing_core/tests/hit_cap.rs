@@ -0,0 +1,160 @@
+// 層の多い玉ねぎ状のCSGでも、交差判定で求めるヒット数が上限で抑えられることの確認
+use std::collections::HashMap;
+
+use glam::{Mat4, Vec3};
+use raytracing_core::{
+    AxisAlignedBox, CSGObject, CsgOperation, Hittable, Material, Ray, Scene,
+    SimulationSettingsConfig, Sphere, Transform,
+};
+
+const LAYERS: usize = 20;
+
+fn sphere(radius: f32) -> Box<dyn Hittable> {
+    Box::new(Sphere {
+        center: Vec3::ZERO,
+        radius,
+        material: Material::Absorber,
+    })
+}
+
+// 半径 i から i + 0.5 までの殻を LAYERS 枚重ねた和集合。中心を通るレイは殻ごとに4回当たる
+fn onion() -> Box<dyn Hittable> {
+    (1..=LAYERS)
+        .map(|i| {
+            Box::new(CSGObject {
+                left: sphere(i as f32 + 0.5),
+                right: sphere(i as f32),
+                operation: CsgOperation::Difference,
+            }) as Box<dyn Hittable>
+        })
+        .reduce(|left, right| {
+            Box::new(CSGObject {
+                left,
+                right,
+                operation: CsgOperation::Union,
+            })
+        })
+        .unwrap()
+}
+
+fn ray() -> Ray {
+    Ray::new(Vec3::new(0.0, 0.0, -50.0), Vec3::Z, 1.0)
+}
+
+#[test]
+fn onion_returns_at_most_max_hits() {
+    let onion = onion();
+    let all = onion.intersect_all(&ray(), 1e-4, 1000.0).unwrap();
+    assert_eq!(all.len(), 4 * LAYERS);
+
+    for max_hits in [1, 7, 16] {
+        let nearest = onion
+            .intersect_nearest(&ray(), 1e-4, 1000.0, max_hits)
+            .unwrap();
+        assert_eq!(nearest.len(), max_hits);
+        // 切り詰めても手前のヒットは変わらない
+        for (a, b) in nearest.iter().zip(&all) {
+            assert!((a.t - b.t).abs() < 1e-4);
+            assert_eq!(a.front_face, b.front_face);
+        }
+    }
+}
+
+#[test]
+fn transform_passes_the_cap_to_the_wrapped_object() {
+    let transformed = Transform::new(onion(), Mat4::from_translation(Vec3::X * 0.1));
+    let nearest = transformed
+        .intersect_nearest(&ray(), 1e-4, 1000.0, 5)
+        .unwrap();
+    assert_eq!(nearest.len(), 5);
+}
+
+#[test]
+fn capped_scene_still_finds_the_nearest_hit() {
+    // 物体が1つだけだと intersect_batch でまとめて判定するので、離れた所に球を置いて Bvh を通す
+    let scene = Scene {
+        objects: vec![
+            onion(),
+            Box::new(Sphere {
+                center: Vec3::new(100.0, 0.0, 0.0),
+                radius: 1.0,
+                material: Material::Absorber,
+            }),
+        ],
+        rays: vec![ray()],
+        object_names: HashMap::new(),
+    };
+    let setting = SimulationSettingsConfig {
+        max_intersection_hits: 2,
+        ..Default::default()
+    };
+    let path = scene.simulate_rays_detailed(setting).remove(0);
+    // 一番外側の殻の表面で吸収される
+    assert_eq!(path.points.len(), 2);
+    assert!((path.points[1].z + LAYERS as f32 + 0.5).abs() < 1e-3);
+    // 上限を超えたヒットの列を切り詰めたことが光路に記録される
+    assert_eq!(path.truncated_hits, 1);
+
+    let uncapped = scene
+        .simulate_rays_detailed(SimulationSettingsConfig::default())
+        .remove(0);
+    assert_eq!(uncapped.points, path.points);
+    assert_eq!(uncapped.truncated_hits, 0);
+}
+
+// z = z0〜z1 の板。+Z に進むレイ ray_z() では t = z で当たる
+fn slab(z0: f32, z1: f32) -> Box<dyn Hittable> {
+    Box::new(AxisAlignedBox {
+        min: Vec3::new(-1.0, -1.0, z0),
+        max: Vec3::new(1.0, 1.0, z1),
+        material: Material::Absorber,
+    })
+}
+
+fn ray_z() -> Ray {
+    Ray::new(Vec3::ZERO, Vec3::Z, 1.0)
+}
+
+// 左は t = 1, 2, 3, 4 で当たる2枚の板、右は t = 3.5, 10 で当たる1枚の板
+fn two_slabs() -> Box<dyn Hittable> {
+    Box::new(CSGObject {
+        left: slab(1.0, 2.0),
+        right: slab(3.0, 4.0),
+        operation: CsgOperation::Union,
+    })
+}
+
+fn t_and_front_faces(object: &dyn Hittable, max_hits: usize) -> Vec<(f32, bool)> {
+    object
+        .intersect_nearest(&ray_z(), 1e-4, 100.0, max_hits)
+        .unwrap_or_default()
+        .iter()
+        .map(|hit| ((hit.t * 1e3).round() / 1e3, hit.front_face))
+        .collect()
+}
+
+#[test]
+fn cap_applies_after_combining_children() {
+    // 子を先に2個へ切り詰めると、左の [1, 2] だけでは右と重ならず積集合を見失う
+    let intersection = CSGObject {
+        left: two_slabs(),
+        right: slab(3.5, 10.0),
+        operation: CsgOperation::Intersection,
+    };
+    assert_eq!(
+        t_and_front_faces(&intersection, 2),
+        [(3.5, true), (4.0, false)]
+    );
+
+    // 同じく、右の板から左の2枚を除くと 4 から 10 の区間が残る
+    let difference = CSGObject {
+        left: slab(3.5, 10.0),
+        right: two_slabs(),
+        operation: CsgOperation::Difference,
+    };
+    assert_eq!(
+        t_and_front_faces(&difference, 2),
+        [(4.0, true), (10.0, false)]
+    );
+    assert_eq!(t_and_front_faces(&difference, 1), [(4.0, true)]);
+}
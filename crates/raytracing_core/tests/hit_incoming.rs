@@ -0,0 +1,66 @@
+// HitRecord.incoming が、その衝突の直前のレイの進行方向になっていることの確認
+use glam::{Mat4, Vec3};
+use raytracing_core::{
+    Hittable, Material, Ray, Scene, SimulationSettingsConfig, Sphere, Transform,
+};
+
+fn glass_sphere() -> Sphere {
+    Sphere {
+        center: Vec3::ZERO,
+        radius: 1.0,
+        material: Material::Glass { ior: 1.5 },
+    }
+}
+
+#[test]
+fn incoming_is_the_ray_direction_without_normalizing() {
+    let ray = Ray::new(Vec3::new(0.3, 0.0, -5.0), Vec3::new(0.0, 0.0, 2.0), 1.0);
+    let hits = glass_sphere().intersect_all(&ray, 1e-4, 100.0).unwrap();
+    assert_eq!(hits.len(), 2);
+    assert!(hits.iter().all(|hit| hit.incoming == ray.direction));
+}
+
+#[test]
+fn transform_reports_incoming_in_world_space() {
+    let object = Transform::new(
+        Box::new(glass_sphere()),
+        Mat4::from_scale_rotation_translation(
+            Vec3::new(2.0, 1.0, 0.5),
+            glam::Quat::from_rotation_x(0.4),
+            Vec3::new(0.0, 0.2, 1.0),
+        ),
+    );
+    let ray = Ray::new(Vec3::new(0.1, 0.0, -5.0), Vec3::new(0.0, 0.1, 1.0), 1.0);
+    let hits = object.intersect_all(&ray, 1e-4, 100.0).unwrap();
+    assert!(!hits.is_empty());
+    for hit in hits {
+        assert!(
+            hit.incoming.distance(ray.direction) < 1e-5,
+            "{:?}",
+            hit.incoming
+        );
+    }
+}
+
+// 屈折した後の衝突では、屈折後の向きが incoming になる
+#[test]
+fn traced_interactions_record_the_direction_at_each_hit() {
+    let scene = Scene {
+        objects: vec![Box::new(glass_sphere())],
+        rays: vec![Ray::new(Vec3::new(0.0, 0.4, -5.0), Vec3::Z, 1.0)],
+        object_names: Default::default(),
+    };
+    let setting = SimulationSettingsConfig {
+        infinity_distance: 20.0,
+        max_bounces: 10,
+        max_reflections: 10,
+        max_refractions: 10,
+        ..Default::default()
+    };
+    let path = &scene.simulate_rays_detailed(setting)[0];
+    assert_eq!(path.interactions.len(), 2);
+    assert_ne!(path.interactions[1].incoming_dir, Vec3::Z);
+    for interaction in &path.interactions {
+        assert_eq!(interaction.hit.incoming, interaction.incoming_dir);
+    }
+}
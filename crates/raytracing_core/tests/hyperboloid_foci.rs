@@ -0,0 +1,73 @@
+// 双曲面鏡の焦点の性質の確認: 一方の焦点へ向かうレイは、凸面で反射してもう一方の焦点を通る
+// （カセグレン望遠鏡の副鏡と同じ使い方）
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    Hyperboloid, HyperboloidSheets, InteractionKind, Material, Ray, Scene, SimulationSettingsConfig,
+};
+
+// z²/1 - r²/3 = 1 の二葉双曲面。頂点は z = ±1、焦点は z = ±2
+fn mirror() -> Hyperboloid {
+    Hyperboloid::new(
+        Vec3::new(0.0, 0.0, 1.0),
+        Vec3::Z,
+        1.0,
+        2.0,
+        HyperboloidSheets::Two,
+        Material::Mirror,
+    )
+}
+
+// 点 point から、origin を通り direction に進む直線までの距離
+fn distance_to_line(point: Vec3, origin: Vec3, direction: Vec3) -> f32 {
+    (point - origin).cross(direction.normalize()).length()
+}
+
+#[test]
+fn foci_are_on_axis() {
+    let (near, far) = mirror().foci();
+    assert!(near.abs_diff_eq(Vec3::new(0.0, 0.0, 2.0), 1e-6));
+    assert!(far.abs_diff_eq(Vec3::new(0.0, 0.0, -2.0), 1e-6));
+}
+
+#[test]
+fn ray_toward_one_focus_reflects_through_other() {
+    let (focus, other_focus) = mirror().foci();
+    // +z 側の葉の凸面（中心側）から、その葉の内側にある焦点へ向かうレイ
+    let starts = [
+        Vec3::new(1.5, 0.0, 0.0),
+        Vec3::new(0.0, -0.8, 0.2),
+        Vec3::new(0.3, 0.4, -0.5),
+        Vec3::new(0.05, 0.0, 0.0), // 近軸
+    ];
+    let rays: Vec<Ray> = starts
+        .iter()
+        .map(|&start| Ray::new(start, (focus - start).normalize(), 1.0))
+        .collect();
+    let scene = Scene {
+        objects: vec![Box::new(mirror())],
+        rays,
+        object_names: HashMap::new(),
+    };
+    let paths = scene.simulate_rays_detailed(SimulationSettingsConfig {
+        infinity_distance: 100.0,
+        max_bounces: 10,
+        ..Default::default()
+    });
+    for (path, start) in paths.iter().zip(starts) {
+        // 反射後のレイはもう一方の葉にも当たるので、最初の反射だけを見る
+        assert_eq!(path.interactions[0].kind, InteractionKind::Reflection);
+        let hit = path.points[1];
+        // 反射点は +z 側の葉の上（頂点 z = 1 より焦点側）
+        assert!(hit.z >= 1.0 && hit.z < focus.z, "{start} の反射点 {hit}");
+        let reflected = path.points[2] - hit;
+        // 反射後は -z 側へ進み、もう一方の焦点を通る（points[2] は -z 側の葉との交点）
+        assert!(reflected.z < 0.0);
+        let miss = distance_to_line(other_focus, hit, reflected);
+        assert!(
+            miss < 1e-3,
+            "{start} からのレイが焦点 {other_focus} から {miss} 離れて通ります"
+        );
+    }
+}
@@ -0,0 +1,78 @@
+// 全反射しないガラス (Material::IdealGlass) と通常のガラスで、臨界角を超えた光の振る舞いを比べる
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, DetailedPath, FresnelMode, InteractionKind, Material, Ray, Scene,
+    SimulationSettingsConfig,
+};
+
+fn setting(max_bounces: u32) -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        max_bounces,
+        fresnel_mode: FresnelMode::Deterministic,
+        ..Default::default()
+    }
+}
+
+// z = -10〜0 のガラス板 (n = 1.5、臨界角は約 41.8°) に ray を当てる
+fn trace(material: Material, ray: Ray, max_bounces: u32) -> DetailedPath {
+    let scene = Scene {
+        objects: vec![Box::new(AxisAlignedBox {
+            min: Vec3::new(-100.0, -100.0, -10.0),
+            max: Vec3::new(100.0, 100.0, 0.0),
+            material,
+        })],
+        rays: vec![ray],
+        object_names: HashMap::new(),
+    };
+    let mut paths = scene.simulate_rays_detailed(setting(max_bounces));
+    assert_eq!(paths.len(), 1);
+    paths.remove(0)
+}
+
+// ガラスの中から z = 0 の面へ、臨界角を超える 60° で当てる
+fn trace_steep(material: Material) -> DetailedPath {
+    let direction = Vec3::new(60f32.to_radians().sin(), 0.0, 60f32.to_radians().cos());
+    trace(
+        material,
+        Ray::new(Vec3::new(0.0, 0.0, -1.0), direction, 1.5),
+        1,
+    )
+}
+
+#[test]
+fn steep_ray_reflects_in_physical_glass() {
+    let path = trace_steep(Material::Glass { ior: 1.5 });
+    let interaction = &path.interactions[0];
+    assert_eq!(interaction.kind, InteractionKind::Reflection);
+    assert!(!interaction.ghost);
+    assert!(!interaction.tir_suppressed);
+    assert!(interaction.outgoing_dir.z < 0.0);
+    assert_eq!(path.intensity, 1.0);
+}
+
+#[test]
+fn steep_ray_passes_straight_through_ideal_glass() {
+    let path = trace_steep(Material::IdealGlass { ior: 1.5 });
+    let interaction = &path.interactions[0];
+    assert_eq!(interaction.kind, InteractionKind::Refraction);
+    assert_eq!(interaction.outgoing_dir, interaction.incoming_dir);
+    // 全反射の代わりに直進させたことが衝突に記録される
+    assert!(interaction.tir_suppressed);
+    assert_eq!(path.intensity, 1.0);
+}
+
+#[test]
+fn ideal_glass_refracts_like_glass_below_the_critical_angle() {
+    // 板に垂直に入って抜けるレイは、どちらのガラスでも同じ光路になる
+    let ray = Ray::new(Vec3::new(0.0, 0.0, -20.0), Vec3::Z, 1.0);
+    let glass = trace(Material::Glass { ior: 1.5 }, ray.clone(), 5);
+    let ideal = trace(Material::IdealGlass { ior: 1.5 }, ray, 5);
+    assert_eq!(glass.points, ideal.points);
+    assert_eq!(glass.intensity, ideal.intensity);
+    assert!(ideal
+        .interactions
+        .iter()
+        .all(|interaction| !interaction.tir_suppressed));
+}
@@ -0,0 +1,28 @@
+// 入射角ヒストグラム (analysis::incidence_histogram) のビン分けの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::analysis::incidence_histogram;
+use raytracing_core::{Material, Plane, Ray, Scene, SimulationSettingsConfig};
+
+#[test]
+fn normal_incidence_lands_in_zero_degree_bin() {
+    // z = 0 の吸収面に、垂直なレイと 45° のレイを当てる
+    let scene = Scene {
+        objects: vec![Box::new(Plane {
+            point: Vec3::ZERO,
+            normal: Vec3::NEG_Z,
+            material: Material::Absorber,
+        })],
+        rays: vec![
+            Ray::new(Vec3::new(0.0, 0.0, -1.0), Vec3::Z, 1.0),
+            Ray::new(Vec3::new(0.0, 0.0, -1.0), Vec3::new(1.0, 0.0, 1.0), 1.0),
+        ],
+        object_names: HashMap::new(),
+    };
+    let paths = scene.simulate_rays_detailed(SimulationSettingsConfig::default());
+
+    // 10° 刻みの 9 ビン
+    let histogram = incidence_histogram(&paths, 0, 9);
+    assert_eq!(histogram, vec![1, 0, 0, 0, 1, 0, 0, 0, 0]);
+}
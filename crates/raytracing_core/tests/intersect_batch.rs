@@ -0,0 +1,127 @@
+// intersect_batch でまとめて求めた交点が、1本ずつの intersect_all の最初の交点と一致するか確かめる
+use std::collections::HashMap;
+
+use glam::{Mat4, Vec3};
+use raytracing_core::{
+    AxisAlignedBox, FresnelMode, HitRecord, Hittable, Material, Plane, Ray, Scene,
+    SimulationSettingsConfig, Sphere, Transform,
+};
+
+// 8本単位で割り切れない本数にして、端数のレーンも確かめる
+// 球の内側から出るレイ、外れるレイ、平面に平行なレイを含む
+fn rays() -> Vec<Ray> {
+    let mut rays = Vec::new();
+    for i in 0..11 {
+        for j in 0..9 {
+            let u = i as f32 / 10.0 * 2.0 - 1.0;
+            let v = j as f32 / 8.0 * 2.0 - 1.0;
+            let origin = Vec3::new(u * 3.0, v * 3.0, -6.0 + (i + j) as f32 * 0.4);
+            let direction = Vec3::new(u * 0.3, v * 0.2 - 0.05, 1.0).normalize();
+            rays.push(Ray::new(origin, direction, 1.0));
+        }
+    }
+    rays.push(Ray::new(Vec3::new(0.0, 2.0, 0.0), Vec3::X, 1.0));
+    rays.push(Ray::new(Vec3::ZERO, Vec3::NEG_Z, 1.0));
+    rays
+}
+
+fn same_hit(batched: &Option<HitRecord>, scalar: &Option<HitRecord>) -> bool {
+    match (batched, scalar) {
+        (None, None) => true,
+        (Some(a), Some(b)) => {
+            a.t == b.t && a.point == b.point && a.normal == b.normal && a.front_face == b.front_face
+        }
+        _ => false,
+    }
+}
+
+fn assert_batch_matches_scalar(shape: &dyn Hittable) {
+    let rays = rays();
+    let origins: Vec<Vec3> = rays.iter().map(|ray| ray.origin).collect();
+    let directions: Vec<Vec3> = rays.iter().map(|ray| ray.direction).collect();
+    let batched = shape.intersect_batch(&origins, &directions, 0.001, 50.0);
+    assert_eq!(batched.len(), rays.len());
+
+    let mut hit_count = 0;
+    for (i, ray) in rays.iter().enumerate() {
+        let scalar = shape
+            .intersect_all(ray, 0.001, 50.0)
+            .and_then(|hits| hits.into_iter().next());
+        assert!(
+            same_hit(&batched[i], &scalar),
+            "レイ {i}: {:?} != {:?}",
+            batched[i],
+            scalar
+        );
+        hit_count += scalar.is_some() as usize;
+    }
+    // 当たるレイと外れるレイの両方がある
+    assert!(hit_count > 0 && hit_count < rays.len(), "hits: {hit_count}");
+}
+
+fn sphere() -> Sphere {
+    Sphere {
+        center: Vec3::new(0.3, -0.2, 1.0),
+        radius: 2.0,
+        material: Material::Glass { ior: 1.5 },
+    }
+}
+
+fn plane() -> Plane {
+    Plane {
+        point: Vec3::new(0.0, 2.0, 0.0),
+        normal: Vec3::new(0.0, 1.0, 0.2).normalize(),
+        material: Material::Mirror,
+    }
+}
+
+#[test]
+fn sphere_batch_matches_intersect_all() {
+    assert_batch_matches_scalar(&sphere());
+}
+
+#[test]
+fn plane_batch_matches_intersect_all() {
+    assert_batch_matches_scalar(&plane());
+}
+
+#[test]
+fn transformed_sphere_batch_matches_intersect_all() {
+    let matrix = Mat4::from_translation(Vec3::new(0.5, 0.0, 2.0)) * Mat4::from_rotation_y(0.7);
+    assert_batch_matches_scalar(&Transform::new(Box::new(sphere()), matrix));
+}
+
+#[test]
+fn default_batch_falls_back_to_intersect_all() {
+    assert_batch_matches_scalar(&AxisAlignedBox {
+        min: Vec3::new(-1.0, -1.0, 0.0),
+        max: Vec3::new(1.0, 1.0, 2.0),
+        material: Material::Mirror,
+    });
+}
+
+// 物体が1つのシーンではまとめた交差判定で追跡するので、1本ずつ追跡した結果と比べる
+#[test]
+fn single_object_scene_traces_the_same_paths() {
+    let scene = Scene {
+        objects: vec![Box::new(sphere())],
+        rays: rays(),
+        object_names: HashMap::new(),
+    };
+    let setting = SimulationSettingsConfig {
+        infinity_distance: 50.0,
+        max_bounces: 10,
+        max_reflections: 10,
+        max_refractions: 10,
+        fresnel_mode: FresnelMode::Deterministic,
+        ..Default::default()
+    };
+    let indices: Vec<usize> = (0..scene.rays.len()).collect();
+    let batched = scene.simulate_rays_detailed(setting);
+    let scalar = scene.simulate_ray_indices(&indices, setting).unwrap();
+    assert_eq!(batched.len(), scalar.len());
+    for (a, b) in batched.iter().zip(&scalar) {
+        assert_eq!(a.points, b.points);
+        assert_eq!(a.intensity, b.intensity);
+    }
+}
@@ -0,0 +1,360 @@
+// 各プリミティブが intersect_all の暗黙の約束を守っているかを、ランダムなレイで確かめる
+// - ヒットは t の昇順に並び、[t_min, t_max] に収まる
+// - 閉じた立体では、始点の内外状態から入射 (front_face = true) と出射が交互に現れる
+//   （平面と無限円錐の front_face は法線の向きで決まるので、この項目は調べない）
+// - 輪郭に接するレイでも、入射と出射が組で現れる（入射だけ返して内側と取り違えない）
+// - 隣り合うヒットの間の点の内外判定 (contains) が、その区間の状態と一致する
+// - 法線は単位ベクトルで、レイと向かい合う
+// - incoming は当たったレイの進行方向そのもの
+use glam::{Mat4, Vec3};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use raytracing_core::{
+    AsphericSurface, AxisAlignedBox, CSGObject, CsgOperation, Hittable, Hyperboloid,
+    HyperboloidSheets, InfiniteCone, InfiniteCylinder, Lens, Material, Plane, Ray, Sphere,
+    Transform, TriangleMesh, Wedge,
+};
+
+const RAY_COUNT: usize = 4000;
+const T_MIN: f32 = 1e-4;
+const T_MAX: f32 = 1e4;
+// レイと法線がほぼ直交する（接する）ヒットの前後では contains が不安定なので、間の点の内外は調べない
+const GRAZING_COS: f32 = 1e-2;
+// ヒット同士がこれより近いと間の点の内外判定が不安定なので、間の点の内外は調べない
+const MIN_GAP: f32 = 1e-3;
+
+fn mirror() -> Material {
+    Material::Mirror
+}
+
+fn sphere() -> Sphere {
+    Sphere {
+        center: Vec3::new(0.2, -0.1, 0.3),
+        radius: 1.0,
+        material: mirror(),
+    }
+}
+
+fn aabb() -> AxisAlignedBox {
+    AxisAlignedBox {
+        min: Vec3::new(-1.0, -0.5, -0.8),
+        max: Vec3::new(1.0, 0.5, 0.8),
+        material: mirror(),
+    }
+}
+
+fn tetrahedron() -> TriangleMesh {
+    TriangleMesh::new(
+        vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.5, 0.0, 0.0),
+            Vec3::new(0.0, 1.5, 0.0),
+            Vec3::new(0.0, 0.0, 1.5),
+        ],
+        vec![[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]],
+        mirror(),
+    )
+}
+
+// front_face が内外判定ではなく法線の向きで決まる、開いた面
+const OPEN_SURFACES: [&str; 2] = ["InfiniteCone", "Plane"];
+
+// 試験する形状と、レイの始点をばらまく範囲の半径
+fn primitives() -> Vec<(&'static str, Box<dyn Hittable>, f32)> {
+    vec![
+        ("Sphere", Box::new(sphere()), 2.5),
+        ("AxisAlignedBox", Box::new(aabb()), 2.5),
+        (
+            "InfiniteCylinder",
+            Box::new(InfiniteCylinder {
+                axis_point: Vec3::ZERO,
+                axis_dir: Vec3::new(0.3, 1.0, -0.2).normalize(),
+                radius: 0.8,
+                material: mirror(),
+            }),
+            2.5,
+        ),
+        (
+            "InfiniteCone",
+            Box::new(InfiniteCone::new(
+                Vec3::ZERO,
+                Vec3::new(0.0, 1.0, 0.4),
+                0.5,
+                mirror(),
+            )),
+            2.5,
+        ),
+        (
+            "Plane",
+            Box::new(Plane {
+                point: Vec3::new(0.0, 0.3, 0.0),
+                normal: Vec3::new(0.2, 1.0, 0.1).normalize(),
+                material: mirror(),
+            }),
+            2.5,
+        ),
+        (
+            "Hyperboloid (One)",
+            Box::new(Hyperboloid::new(
+                Vec3::ZERO,
+                Vec3::new(0.2, 1.0, 0.0),
+                0.6,
+                1.0,
+                HyperboloidSheets::One,
+                mirror(),
+            )),
+            2.5,
+        ),
+        (
+            "Hyperboloid (Two)",
+            Box::new(Hyperboloid::new(
+                Vec3::new(0.0, 0.5, 0.0),
+                Vec3::new(0.0, 1.0, 0.3),
+                0.5,
+                0.9,
+                HyperboloidSheets::Two,
+                mirror(),
+            )),
+            2.5,
+        ),
+        (
+            "AsphericSurface",
+            Box::new(AsphericSurface {
+                vertex: Vec3::new(0.0, 0.0, -0.5),
+                axis: Vec3::new(0.0, 0.3, 1.0).normalize(),
+                curvature: 0.8,
+                conic: -0.5,
+                coeffs: vec![0.02],
+                material: mirror(),
+            }),
+            2.5,
+        ),
+        ("TriangleMesh", Box::new(tetrahedron()), 2.5),
+        (
+            "Lens",
+            Box::new(Lens::new(0.6, 2.0, 3.0, -4.0, mirror())),
+            2.5,
+        ),
+        (
+            "Wedge",
+            Box::new(Wedge::new(Vec3::new(2.0, 1.5, 1.0), 0.4, mirror())),
+            2.5,
+        ),
+        (
+            "CSG Difference",
+            Box::new(CSGObject {
+                left: Box::new(aabb()),
+                right: Box::new(sphere()),
+                operation: CsgOperation::Difference,
+            }),
+            2.5,
+        ),
+        (
+            "Transform",
+            Box::new(Transform::new(
+                Box::new(aabb()),
+                Mat4::from_scale_rotation_translation(
+                    Vec3::new(1.0, 2.0, 0.5),
+                    glam::Quat::from_rotation_y(0.7),
+                    Vec3::new(0.3, 0.0, -0.2),
+                ),
+            )),
+            3.0,
+        ),
+    ]
+}
+
+// 約束を破っていれば、その内容を返す
+fn check_ray(shape: &dyn Hittable, ray: &Ray, check_front_face: bool) -> Result<(), String> {
+    let hits = shape.intersect_all(ray, T_MIN, T_MAX).unwrap_or_default();
+
+    for hit in &hits {
+        if !(T_MIN..=T_MAX).contains(&hit.t) {
+            return Err(format!("t = {} が範囲外", hit.t));
+        }
+        if (hit.normal.length() - 1.0).abs() > 1e-3 {
+            return Err(format!("法線 {:?} が単位ベクトルでない", hit.normal));
+        }
+        if hit.normal.dot(ray.direction) > 1e-4 {
+            return Err(format!("法線 {:?} がレイと向かい合っていない", hit.normal));
+        }
+        if hit.incoming.distance(ray.direction) > 1e-4 {
+            return Err(format!(
+                "incoming {:?} がレイの向き {:?} と一致しない",
+                hit.incoming, ray.direction
+            ));
+        }
+    }
+    for pair in hits.windows(2) {
+        if pair[1].t < pair[0].t {
+            return Err(format!(
+                "t が昇順でない: {} の後に {}",
+                pair[0].t, pair[1].t
+            ));
+        }
+    }
+
+    // 接するヒットや近すぎるヒットの間の点では contains が不安定なので、その点の内外は調べない
+    // （入射と出射が交互に現れることは、どのレイでも調べる）
+    let grazing = hits
+        .iter()
+        .any(|hit| ray.direction.dot(hit.normal).abs() < GRAZING_COS);
+    let crowded = hits.windows(2).any(|pair| pair[1].t - pair[0].t < MIN_GAP)
+        || hits.first().is_some_and(|hit| hit.t < T_MIN + MIN_GAP);
+    let check_contains = !(grazing || crowded);
+
+    let at = |t: f32| ray.origin + ray.direction * t;
+    let mut inside = shape.contains(at(T_MIN));
+    for (i, hit) in hits.iter().enumerate() {
+        if check_front_face && hit.front_face == inside {
+            return Err(format!(
+                "{} 番目のヒット (t = {}) の front_face = {} が内外状態と合わない",
+                i, hit.t, hit.front_face
+            ));
+        }
+        inside = !inside;
+        let next_t = hits.get(i + 1).map_or(hit.t + 1.0, |next| next.t);
+        let mid = (hit.t + next_t) / 2.0;
+        if check_contains && shape.contains(at(mid)) != inside {
+            return Err(format!(
+                "{} 番目のヒットの後 (t = {}) の contains が {} でない",
+                i, mid, inside
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn random_unit(rng: &mut StdRng) -> Vec3 {
+    loop {
+        let v = Vec3::new(
+            rng.gen_range(-1.0..1.0),
+            rng.gen_range(-1.0..1.0),
+            rng.gen_range(-1.0..1.0),
+        );
+        let length = v.length();
+        if length > 1e-3 && length <= 1.0 {
+            return v / length;
+        }
+    }
+}
+
+#[test]
+fn primitives_satisfy_intersect_all_contract() {
+    let mut failures = Vec::new();
+    for (name, shape, spread) in primitives() {
+        let mut rng = StdRng::seed_from_u64(1405);
+        let check_front_face = !OPEN_SURFACES.contains(&name);
+        let mut failed = 0;
+        let mut first_error = None;
+        for _ in 0..RAY_COUNT {
+            let origin = random_unit(&mut rng) * rng.gen_range(0.0..spread);
+            let ray = Ray::new(origin, random_unit(&mut rng), 1.0);
+            if let Err(error) = check_ray(shape.as_ref(), &ray, check_front_face) {
+                failed += 1;
+                first_error.get_or_insert(format!(
+                    "{:?} 方向 {:?}: {}",
+                    ray.origin, ray.direction, error
+                ));
+            }
+        }
+        if let Some(error) = first_error {
+            failures.push(format!(
+                "{}: {}/{} 本で不整合。例: {}",
+                name, failed, RAY_COUNT, error
+            ));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+// 輪郭に接するレイを探す向き（どの形状の軸とも平行でないもの）
+const TANGENT_DIRECTIONS: [Vec3; 3] = [
+    Vec3::new(1.0, 0.3, 0.2),
+    Vec3::new(-0.4, 0.1, 1.0),
+    Vec3::new(0.1, -1.0, 0.5),
+];
+// 輪郭の位置から横にずらす量。接する・わずかにかすめる・わずかに外れるレイを作る
+const TANGENT_OFFSETS: [f32; 9] = [-1e-3, -1e-4, -1e-5, -1e-6, 0.0, 1e-6, 1e-5, 1e-4, 1e-3];
+
+// 始点の内外状態から入射と出射が交互に現れ、通り抜けた先の内外状態とも合うこと
+fn check_crossings(shape: &dyn Hittable, ray: &Ray, far: f32) -> Result<(), String> {
+    let hits = shape.intersect_all(ray, T_MIN, T_MAX).unwrap_or_default();
+    let at = |t: f32| ray.origin + ray.direction * t;
+    let mut inside = shape.contains(at(T_MIN));
+    for (i, hit) in hits.iter().enumerate() {
+        if hit.front_face == inside {
+            return Err(format!(
+                "{} 番目のヒット (t = {}) の front_face = {} が内外状態と合わない",
+                i, hit.t, hit.front_face
+            ));
+        }
+        inside = !inside;
+    }
+    if shape.contains(at(far)) != inside {
+        return Err(format!(
+            "{} 個のヒットを通り抜けた先 (t = {}) の contains が {} でない",
+            hits.len(),
+            far,
+            inside
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn tangent_rays_enter_and_exit_in_pairs() {
+    let mut failures = Vec::new();
+    for (name, shape, spread) in primitives() {
+        if OPEN_SURFACES.contains(&name) {
+            continue;
+        }
+        // 形状の内側の点を通るレイから横にずらしていく（無限に広がる形状もあるので内側の点は探す）
+        let mut rng = StdRng::seed_from_u64(1405);
+        let center = (0..RAY_COUNT)
+            .map(|_| random_unit(&mut rng) * rng.gen_range(0.0..spread))
+            .find(|&point| shape.contains(point))
+            .expect("内側の点が見つからない");
+        let back = 4.0 * spread;
+        let mut tangents = 0;
+        for direction in TANGENT_DIRECTIONS {
+            let direction = direction.normalize();
+            let normal = direction.any_orthonormal_vector();
+            let binormal = direction.cross(normal);
+            for side in [normal, -normal, binormal, -binormal] {
+                let ray_at = |offset: f32| {
+                    Ray::new(center - direction * back + side * offset, direction, 1.0)
+                };
+                let hits =
+                    |offset: f32| shape.intersect_all(&ray_at(offset), T_MIN, T_MAX).is_some();
+                // 内側を通るレイは当たり、離れたレイは外れる。その境目を二分法で輪郭に寄せる
+                let (mut near, mut far) = (0.0, 2.0 * spread);
+                if !hits(near) || hits(far) {
+                    continue;
+                }
+                for _ in 0..60 {
+                    let mid = (near + far) / 2.0;
+                    if hits(mid) {
+                        near = mid;
+                    } else {
+                        far = mid;
+                    }
+                }
+                tangents += 1;
+                for delta in TANGENT_OFFSETS {
+                    let ray = ray_at(near + delta);
+                    if let Err(error) = check_crossings(shape.as_ref(), &ray, 2.0 * back) {
+                        failures.push(format!(
+                            "{}: 始点 {:?} 方向 {:?}: {}",
+                            name, ray.origin, ray.direction, error
+                        ));
+                    }
+                }
+            }
+        }
+        if tangents == 0 {
+            failures.push(format!("{}: 輪郭に接するレイが見つからない", name));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
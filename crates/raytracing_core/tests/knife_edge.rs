@@ -0,0 +1,70 @@
+// 光束をナイフエッジに横切らせたとき、遮られる割合がエッジの位置に比例することの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    InteractionKind, KnifeEdge, KnifeEdgeSide, Material, Ray, Scene, SimulationSettingsConfig,
+};
+
+const RAY_COUNT: usize = 200;
+
+// x = -1 から 1 まで等間隔に並んだ +Z 向きの平行光のうち、z = 0 のナイフエッジで吸収された割合
+fn blocked_fraction(edge_x: f32, blocking_side: KnifeEdgeSide) -> f32 {
+    let rays = (0..RAY_COUNT)
+        .map(|i| {
+            let x = -1.0 + (i as f32 + 0.5) * 2.0 / RAY_COUNT as f32;
+            Ray::new(Vec3::new(x, 0.0, -1.0), Vec3::Z, 1.0)
+        })
+        .collect();
+    let scene = Scene {
+        // エッジは Y 方向。法線 +Z から見た左側 (+Z × +Y = -X) は x < edge_x
+        objects: vec![Box::new(KnifeEdge {
+            plane_point: Vec3::new(edge_x, 0.0, 0.0),
+            normal: Vec3::Z,
+            edge_dir: Vec3::Y,
+            blocking_side,
+            material: Material::Absorber,
+        })],
+        rays,
+        object_names: HashMap::new(),
+    };
+    let paths = scene.simulate_rays_detailed(SimulationSettingsConfig::default());
+    let blocked = paths
+        .iter()
+        .filter(|path| {
+            path.interactions
+                .iter()
+                .any(|interaction| interaction.kind == InteractionKind::Absorption)
+        })
+        .count();
+    blocked as f32 / RAY_COUNT as f32
+}
+
+#[test]
+fn blocked_fraction_follows_the_edge_position() {
+    for edge_x in [-0.8, -0.5, 0.0, 0.3, 0.9] {
+        let expected = (edge_x + 1.0) / 2.0;
+        let left = blocked_fraction(edge_x, KnifeEdgeSide::Left);
+        let right = blocked_fraction(edge_x, KnifeEdgeSide::Right);
+        assert!(
+            (left - expected).abs() < 1e-6,
+            "{}: {} != {}",
+            edge_x,
+            left,
+            expected
+        );
+        assert!(
+            (right - (1.0 - expected)).abs() < 1e-6,
+            "{}: {}",
+            edge_x,
+            right
+        );
+    }
+}
+
+#[test]
+fn edge_outside_the_bundle_blocks_all_or_nothing() {
+    // エッジを光束の外へ出すと、どちらかに寄せた側に応じて何も遮らないか全て遮る
+    assert_eq!(blocked_fraction(-2.0, KnifeEdgeSide::Left), 0.0);
+    assert_eq!(blocked_fraction(2.0, KnifeEdgeSide::Left), 1.0);
+}
@@ -0,0 +1,45 @@
+// 有効焦点距離の測定 (analysis::measure_efl) をレンズメーカーの式と比べる
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::analysis::measure_efl;
+use raytracing_core::{Lens, Material, Scene};
+
+fn lens_scene(thickness: f32, r1: f32, r2: f32, ior: f32) -> Scene {
+    Scene {
+        objects: vec![Box::new(Lens::new(
+            thickness,
+            10.0,
+            r1,
+            r2,
+            Material::Glass { ior },
+        ))],
+        rays: Vec::new(),
+        object_names: HashMap::new(),
+    }
+}
+
+// 厚肉レンズのレンズメーカーの式
+fn lensmaker_efl(thickness: f32, r1: f32, r2: f32, ior: f32) -> f32 {
+    let power = (ior - 1.0) * (1.0 / r1 - 1.0 / r2 + (ior - 1.0) * thickness / (ior * r1 * r2));
+    1.0 / power
+}
+
+#[test]
+fn biconvex_lens_matches_lensmaker_equation() {
+    let (thickness, r1, r2, ior) = (1.0, 50.0, -50.0, 1.5);
+    let scene = lens_scene(thickness, r1, r2, ior);
+    let efl = measure_efl(&scene, &[0], Vec3::Z);
+    let expected = lensmaker_efl(thickness, r1, r2, ior);
+    // 後側主平面を最終面で近似しているので、厚みの分だけずれる
+    assert!(
+        (efl - expected).abs() < expected * 0.02,
+        "{efl} (期待値 {expected})"
+    );
+}
+
+#[test]
+fn missing_lens_gives_nan() {
+    let scene = lens_scene(1.0, 50.0, -50.0, 1.5);
+    assert!(measure_efl(&scene, &[3], Vec3::Z).is_nan());
+}
@@ -0,0 +1,88 @@
+// 重なったガラスの球を通るレイの媒質の記録 (Ray::media) を、区間ごとの屈折率で確かめる
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{Material, MediumStack, Ray, Scene, SimulationSettingsConfig, Sphere};
+
+const N_OUTER: f32 = 1.5;
+const N_INNER: f32 = 1.7;
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        max_bounces: 10,
+        max_reflections: 10,
+        max_refractions: 10,
+        ..Default::default()
+    }
+}
+
+fn glass_sphere(z: f32, radius: f32, ior: f32) -> Sphere {
+    Sphere {
+        center: Vec3::new(0.0, 0.0, z),
+        radius,
+        material: Material::Glass { ior },
+    }
+}
+
+// z 軸に沿って +Z へ進むレイの、各区間を進んだ媒質の屈折率（光路長 / 距離）
+fn segment_iors(spheres: Vec<Sphere>) -> Vec<f32> {
+    let scene = Scene {
+        objects: spheres
+            .into_iter()
+            .map(|sphere| Box::new(sphere) as _)
+            .collect(),
+        rays: vec![Ray::new(Vec3::new(0.0, 0.0, -10.0), Vec3::Z, 1.0)],
+        object_names: HashMap::new(),
+    };
+    let path = scene.simulate_rays_detailed(setting()).remove(0);
+    path.points
+        .windows(2)
+        .zip(path.optical_lengths.windows(2))
+        .map(|(points, lengths)| (lengths[1] - lengths[0]) / points[0].distance(points[1]))
+        .collect()
+}
+
+fn assert_iors(actual: &[f32], expected: &[f32]) {
+    assert_eq!(actual.len(), expected.len(), "{actual:?}");
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-4, "{actual:?} != {expected:?}");
+    }
+}
+
+#[test]
+fn exiting_the_inner_sphere_returns_to_the_outer_ior() {
+    // 半径4の球 (1.5) の中に、半径1.5の球 (1.7) が入っている
+    let iors = segment_iors(vec![
+        glass_sphere(0.0, 4.0, N_OUTER),
+        glass_sphere(0.5, 1.5, N_INNER),
+    ]);
+    assert_iors(&iors, &[1.0, N_OUTER, N_INNER, N_OUTER, 1.0]);
+}
+
+#[test]
+fn exiting_the_first_of_two_overlapping_spheres_keeps_the_second() {
+    // z = -1〜3 で重なる2つの球。先に入った球から先に出る
+    let iors = segment_iors(vec![
+        glass_sphere(0.0, 3.0, N_OUTER),
+        glass_sphere(2.0, 3.0, N_INNER),
+    ]);
+    assert_iors(&iors, &[1.0, N_OUTER, N_INNER, N_INNER, 1.0]);
+}
+
+#[test]
+fn medium_stack_reports_the_innermost_glass() {
+    let mut media = MediumStack::new(1.0);
+    media.enter(0, N_OUTER);
+    media.enter(1, N_INNER);
+    assert_eq!(media.current_ior(), N_INNER);
+    media.exit(1);
+    assert_eq!(media.current_ior(), N_OUTER);
+    media.exit(0);
+    assert_eq!(media.current_ior(), 1.0);
+
+    // 始点がガラスの中にあったレイは、そのガラスから出ると空気に出る
+    let mut ray = Ray::new(Vec3::ZERO, Vec3::Z, N_OUTER);
+    assert_eq!(ray.current_ior(), N_OUTER);
+    ray.media.exit(3);
+    assert_eq!(ray.current_ior(), 1.0);
+}
@@ -0,0 +1,54 @@
+// 閉じた三角形メッシュ（四面体）を箱から差し引くと、箱の中に四面体の形の空洞ができることの確認
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, CSGObject, CsgOperation, Hittable, Material, Ray, TriangleMesh,
+};
+
+// 原点の角から各軸に 1 の四面体。三角形は外から見て反時計回り
+fn tetrahedron() -> TriangleMesh {
+    TriangleMesh::new(
+        vec![Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::Z],
+        vec![[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]],
+        Material::Absorber,
+    )
+}
+
+fn box_minus_tetrahedron() -> CSGObject {
+    CSGObject {
+        left: Box::new(AxisAlignedBox {
+            min: Vec3::splat(-2.0),
+            max: Vec3::splat(2.0),
+            material: Material::Absorber,
+        }),
+        right: Box::new(tetrahedron()),
+        operation: CsgOperation::Difference,
+    }
+}
+
+#[test]
+fn tetrahedron_is_carved_out_of_the_box() {
+    let solid = box_minus_tetrahedron();
+    assert!(solid.contains(Vec3::new(-1.0, -1.0, -1.0)));
+    assert!(solid.contains(Vec3::new(0.5, 0.5, 0.5)));
+    assert!(!solid.contains(Vec3::splat(0.2)));
+    assert!(!solid.contains(Vec3::splat(3.0)));
+}
+
+#[test]
+fn ray_through_the_cavity_enters_and_exits_twice() {
+    let solid = box_minus_tetrahedron();
+    // y = z = 0.2 で X 方向に進むと、四面体の中を x = 0 から x = 0.6 まで通る
+    let ray = Ray::new(Vec3::new(-5.0, 0.2, 0.2), Vec3::X, 1.0);
+    let hits = solid.intersect_all(&ray, 1e-4, 100.0).unwrap();
+    let xs: Vec<f32> = hits.iter().map(|hit| hit.point.x).collect();
+    let expected = [-2.0, 0.0, 0.6, 2.0];
+    assert_eq!(xs.len(), expected.len(), "{:?}", xs);
+    for (x, e) in xs.iter().zip(expected) {
+        assert!((x - e).abs() < 1e-4, "{:?}", xs);
+    }
+    // 箱に入り、空洞へ出て、また入り、箱から出る
+    let front_faces: Vec<bool> = hits.iter().map(|hit| hit.front_face).collect();
+    assert_eq!(front_faces, [true, false, true, false]);
+    // 法線はレイと向かい合う
+    assert!(hits.iter().all(|hit| hit.normal.dot(ray.direction) < 0.0));
+}
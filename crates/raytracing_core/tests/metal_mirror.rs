@@ -0,0 +1,56 @@
+// 金属鏡 (Material::MetalMirror) で反射するたびに強度が反射率倍になることの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    Hittable, Material, Plane, Ray, Reflectance, Scene, SimulationSettingsConfig,
+};
+
+// y = 0 と y = 1 の向かい合った鏡の間で、レイを斜めに N 回反射させる
+fn bounce(reflectance: Reflectance, bounces: u32, wavelength: f32) -> f32 {
+    let mirror = |y: f32, normal: Vec3| -> Box<dyn Hittable> {
+        Box::new(Plane {
+            point: Vec3::new(0.0, y, 0.0),
+            normal,
+            material: Material::MetalMirror {
+                reflectance: reflectance.clone(),
+            },
+        })
+    };
+    let mut ray = Ray::new(Vec3::new(0.0, 0.5, 0.0), Vec3::new(1.0, 1.0, 0.0), 1.0);
+    ray.wavelength = wavelength;
+    let scene = Scene {
+        objects: vec![mirror(0.0, Vec3::Y), mirror(1.0, Vec3::NEG_Y)],
+        rays: vec![ray],
+        object_names: HashMap::new(),
+    };
+    let setting = SimulationSettingsConfig {
+        max_bounces: bounces,
+        max_reflections: bounces,
+        max_refractions: bounces,
+        ..Default::default()
+    };
+    let path = &scene.simulate_rays_detailed(setting)[0];
+    assert_eq!(path.interactions.len(), bounces as usize);
+    path.intensity
+}
+
+#[test]
+fn intensity_after_n_bounces_is_reflectance_to_the_n() {
+    for bounces in [1, 5, 12] {
+        let intensity = bounce(Reflectance::Constant(0.9), bounces, 550.0);
+        let expected = 0.9f32.powi(bounces as i32);
+        assert!(
+            (intensity - expected).abs() < 1e-5,
+            "{bounces} 回: {intensity} (期待値 {expected})"
+        );
+    }
+}
+
+#[test]
+fn reflectance_table_depends_on_wavelength() {
+    let table = Reflectance::Table([(400.0, 0.5), (700.0, 0.9)].into());
+    assert!((bounce(table.clone(), 2, 400.0) - 0.25).abs() < 1e-5);
+    assert!((bounce(table.clone(), 2, 550.0) - 0.49).abs() < 1e-5);
+    assert!((bounce(table, 2, 700.0) - 0.81).abs() < 1e-5);
+}
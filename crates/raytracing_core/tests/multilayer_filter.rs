@@ -0,0 +1,96 @@
+// 誘電体多層膜の反射率が、1/4 波長膜で設計波長に強い反射帯を持つことの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    multilayer_reflectance, quarter_wave_stack, FresnelMode, Material, Plane, Ray, Scene,
+    SimulationSettingsConfig, ThinFilmStack,
+};
+
+const DESIGN_NM: f32 = 550.0;
+const HIGH_IOR: f32 = 2.35; // TiO2 程度
+const LOW_IOR: f32 = 1.38; // MgF2 程度
+const SUBSTRATE_IOR: f32 = 1.52;
+
+#[test]
+fn quarter_wave_stack_reflects_strongly_at_design_wavelength() {
+    let layers = quarter_wave_stack(HIGH_IOR, LOW_IOR, 6, DESIGN_NM);
+    let at_design = multilayer_reflectance(&layers, 1.0, SUBSTRATE_IOR, DESIGN_NM, 1.0);
+    // 垂直入射では R = ((1 - Y) / (1 + Y))²、Y = (nH/nL)^2N nH² / ns
+    let y = (HIGH_IOR / LOW_IOR).powi(12) * HIGH_IOR * HIGH_IOR / SUBSTRATE_IOR;
+    let expected = ((1.0 - y) / (1.0 + y)).powi(2);
+    assert!(
+        (at_design - expected).abs() < 1e-4,
+        "{at_design} (期待値 {expected})"
+    );
+    assert!(at_design > 0.99);
+
+    // 反射帯の外では反射が弱い
+    for off_band in [400.0, 800.0] {
+        let reflectance = multilayer_reflectance(&layers, 1.0, SUBSTRATE_IOR, off_band, 1.0);
+        assert!(reflectance < 0.5, "{off_band} nm: {reflectance}");
+    }
+}
+
+#[test]
+fn tilting_shifts_band_to_shorter_wavelengths() {
+    let layers = quarter_wave_stack(HIGH_IOR, LOW_IOR, 6, DESIGN_NM);
+    let cos_45 = 45f32.to_radians().cos();
+    // 傾けると膜の位相厚さが減るので、反射帯の長波長側の端に近い波長では反射が弱まる
+    let edge = 600.0;
+    let normal = multilayer_reflectance(&layers, 1.0, SUBSTRATE_IOR, edge, 1.0);
+    let tilted = multilayer_reflectance(&layers, 1.0, SUBSTRATE_IOR, edge, cos_45);
+    assert!(tilted < normal, "垂直 {normal}, 45° {tilted}");
+}
+
+#[test]
+fn without_layers_matches_bare_interface() {
+    let reflectance = multilayer_reflectance(&[], 1.0, 1.5, DESIGN_NM, 1.0);
+    assert!((reflectance - 0.04).abs() < 1e-6);
+}
+
+// Split では透過光と反射光の両方を追跡し、強度の和が 1 になる
+#[test]
+fn filter_splits_rays_by_reflectance() {
+    let layers = quarter_wave_stack(HIGH_IOR, LOW_IOR, 2, DESIGN_NM);
+    let mut ray = Ray::new(Vec3::new(0.0, 0.0, -1.0), Vec3::Z, 1.0);
+    ray.wavelength = DESIGN_NM;
+    let scene = Scene {
+        objects: vec![Box::new(Plane {
+            point: Vec3::ZERO,
+            normal: Vec3::Z,
+            material: Material::MultilayerFilter {
+                stack: ThinFilmStack {
+                    layers: layers.clone().into(),
+                    incident_ior: 1.0,
+                    substrate_ior: SUBSTRATE_IOR,
+                },
+            },
+        })],
+        rays: vec![ray],
+        object_names: HashMap::new(),
+    };
+    let setting = SimulationSettingsConfig {
+        infinity_distance: 10.0,
+        max_bounces: 5,
+        max_reflections: 5,
+        max_refractions: 5,
+        fresnel_mode: FresnelMode::Split,
+        ..Default::default()
+    };
+    let paths = scene.simulate_rays_detailed(setting);
+    assert_eq!(paths.len(), 2);
+    let expected = multilayer_reflectance(&layers, 1.0, SUBSTRATE_IOR, DESIGN_NM, 1.0);
+    let reflected = paths
+        .iter()
+        .find(|path| path.branch_label().is_ghost())
+        .unwrap();
+    let transmitted = paths
+        .iter()
+        .find(|path| !path.branch_label().is_ghost())
+        .unwrap();
+    assert!((reflected.intensity - expected).abs() < 1e-5);
+    assert!((transmitted.intensity - (1.0 - expected)).abs() < 1e-5);
+    assert!(reflected.points.last().unwrap().z < 0.0);
+    assert!(transmitted.points.last().unwrap().z > 0.0);
+}
@@ -0,0 +1,100 @@
+// 入ったガラスの記録（媒質のスタック）で、貼り合わせ面や入れ子のガラスの n1/n2 が正しく決まるか確かめる
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, DetailedPath, InteractionKind, Material, Ray, Scene, SimulationSettingsConfig,
+};
+
+const N_CROWN: f32 = 1.5;
+const N_FLINT: f32 = 1.7;
+// 入射光の向きの正弦（+Z から +X 側へ30度）
+const SIN_INCIDENCE: f32 = 0.5;
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        max_bounces: 10,
+        max_reflections: 10,
+        max_refractions: 10,
+        ..Default::default()
+    }
+}
+
+fn slab(z_min: f32, z_max: f32, half_width: f32, ior: f32) -> AxisAlignedBox {
+    AxisAlignedBox {
+        min: Vec3::new(-half_width, -half_width, z_min),
+        max: Vec3::new(half_width, half_width, z_max),
+        material: Material::Glass { ior },
+    }
+}
+
+fn trace(scene_objects: Vec<AxisAlignedBox>) -> DetailedPath {
+    let cos = (1.0 - SIN_INCIDENCE * SIN_INCIDENCE).sqrt();
+    let scene = Scene {
+        objects: scene_objects
+            .into_iter()
+            .map(|object| Box::new(object) as _)
+            .collect(),
+        rays: vec![Ray::new(
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(SIN_INCIDENCE, 0.0, cos),
+            1.0,
+        )],
+        object_names: HashMap::new(),
+    };
+    scene.simulate_rays_detailed(setting()).remove(0)
+}
+
+// スネルの法則 n sinθ = 一定 より、媒質 n の中での進行方向の x 成分
+fn sin_in(n: f32) -> f32 {
+    SIN_INCIDENCE / n
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-5, "{actual} != {expected}");
+}
+
+#[test]
+fn cemented_interface_refracts_from_first_glass_into_second() {
+    // z = 2 で接する2枚のガラス（空気 | 1.5 | 1.7 | 空気）
+    let path = trace(vec![
+        slab(0.0, 2.0, 10.0, N_CROWN),
+        slab(2.0, 4.0, 10.0, N_FLINT),
+    ]);
+
+    // 貼り合わせ面は1つの境界として通過する
+    assert_eq!(path.interactions.len(), 3);
+    assert!(path
+        .interactions
+        .iter()
+        .all(|interaction| interaction.kind == InteractionKind::Refraction));
+    assert_close(path.interactions[0].outgoing_dir.x, sin_in(N_CROWN));
+    // 貼り合わせ面: n1 = 1.5, n2 = 1.7（空気を挟まない）
+    assert_close(path.interactions[1].hit.point.z, 2.0);
+    assert_close(path.interactions[1].outgoing_dir.x, sin_in(N_FLINT));
+    assert_close(path.interactions[2].outgoing_dir.x, SIN_INCIDENCE);
+
+    // 光路長も各ガラスの屈折率で数える
+    let thickness = 2.0;
+    let expected = 1.0 / (1.0 - SIN_INCIDENCE * SIN_INCIDENCE).sqrt()
+        + N_CROWN * thickness / (1.0 - sin_in(N_CROWN).powi(2)).sqrt()
+        + N_FLINT * thickness / (1.0 - sin_in(N_FLINT).powi(2)).sqrt();
+    let at_exit = path.optical_lengths[3];
+    assert!((at_exit - expected).abs() < 1e-3, "{at_exit} != {expected}");
+}
+
+#[test]
+fn leaving_an_inner_glass_returns_to_the_outer_glass() {
+    // 1.5 のガラスの中に 1.7 のガラスが埋め込まれている
+    let path = trace(vec![
+        slab(0.0, 6.0, 10.0, N_CROWN),
+        slab(2.0, 4.0, 5.0, N_FLINT),
+    ]);
+
+    assert_eq!(path.interactions.len(), 4);
+    assert_close(path.interactions[1].outgoing_dir.x, sin_in(N_FLINT));
+    // 内側のガラスから出た先は空気ではなく外側のガラス
+    assert_close(path.interactions[2].hit.point.z, 4.0);
+    assert_close(path.interactions[2].outgoing_dir.x, sin_in(N_CROWN));
+    assert_close(path.interactions[3].outgoing_dir.x, SIN_INCIDENCE);
+}
@@ -0,0 +1,91 @@
+// 隠さない面 (NonOccluding) の後ろにある物体に、レイが正しく当たることの確認
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, Hittable, InteractionKind, Material, NonOccluding, Plane, Ray, Scene,
+    SimulationSettingsConfig,
+};
+
+fn setting(max_bounces: u32) -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        infinity_distance: 10.0,
+        max_bounces,
+        max_reflections: max_bounces,
+        max_refractions: max_bounces,
+        ..Default::default()
+    }
+}
+
+// z = 0 の吸収体の面をプローブにし、その奥の z = 2 に鏡の箱を置く
+fn probe() -> Box<dyn Hittable> {
+    Box::new(NonOccluding::new(Box::new(Plane {
+        point: Vec3::ZERO,
+        normal: Vec3::Z,
+        material: Material::Absorber,
+    })))
+}
+
+fn scene() -> Scene {
+    Scene {
+        objects: vec![
+            probe(),
+            Box::new(AxisAlignedBox {
+                min: Vec3::new(-1.0, -1.0, 2.0),
+                max: Vec3::new(1.0, 1.0, 3.0),
+                material: Material::Mirror,
+            }),
+        ],
+        rays: vec![Ray::new(Vec3::new(0.0, 0.0, -1.0), Vec3::Z, 1.0)],
+        object_names: Default::default(),
+    }
+}
+
+#[test]
+fn opaque_object_behind_probe_is_hit() {
+    let path = &scene().simulate_rays_detailed(setting(10))[0];
+    let kinds: Vec<_> = path
+        .interactions
+        .iter()
+        .map(|interaction| (interaction.object_index, interaction.kind))
+        .collect();
+    // 行きと帰りにプローブを通過し、間で鏡に反射する
+    assert_eq!(
+        kinds,
+        [
+            (0, InteractionKind::PassThrough),
+            (1, InteractionKind::Reflection),
+            (0, InteractionKind::PassThrough),
+        ]
+    );
+    assert!(
+        path.interactions[1]
+            .hit
+            .point
+            .distance(Vec3::new(0.0, 0.0, 2.0))
+            < 1e-4
+    );
+    assert!(path.escaped);
+    assert_eq!(path.intensity, 1.0);
+    assert!(path.points.last().unwrap().z < -1.0);
+}
+
+// プローブの通過は進めた回数に数えない
+#[test]
+fn pass_through_does_not_use_up_bounces() {
+    let path = &scene().simulate_rays_detailed(setting(1))[0];
+    assert_eq!(path.interactions.len(), 2);
+    assert_eq!(path.interactions[1].kind, InteractionKind::Reflection);
+}
+
+// プローブだけのシーン（まとめて交差判定する経路）でも、通過して飛び去る
+#[test]
+fn probe_alone_does_not_stop_rays() {
+    let scene = Scene {
+        objects: vec![probe()],
+        ..scene()
+    };
+    let path = &scene.simulate_rays_detailed(setting(10))[0];
+    assert_eq!(path.interactions.len(), 1);
+    assert_eq!(path.interactions[0].kind, InteractionKind::PassThrough);
+    assert!(path.escaped);
+    assert!(path.points.last().unwrap().z > 1.0);
+}
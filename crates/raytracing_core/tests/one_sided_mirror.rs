@@ -0,0 +1,48 @@
+// 片面鏡 (Material::OneSidedMirror) が表からの光だけを反射し、裏からの光を素通りさせることの確認
+use glam::Vec3;
+use raytracing_core::{InteractionKind, Material, Plane, Ray, Scene, SimulationSettingsConfig};
+
+// z = 0 の平面。法線 +Z の向いている z > 0 の側が表になる
+fn trace(ray: Ray) -> raytracing_core::DetailedPath {
+    let scene = Scene {
+        objects: vec![Box::new(Plane {
+            point: Vec3::ZERO,
+            normal: Vec3::Z,
+            material: Material::OneSidedMirror,
+        })],
+        rays: vec![ray],
+        object_names: Default::default(),
+    };
+    let setting = SimulationSettingsConfig {
+        infinity_distance: 10.0,
+        max_bounces: 5,
+        max_reflections: 5,
+        max_refractions: 5,
+        ..Default::default()
+    };
+    scene.simulate_rays_detailed(setting).remove(0)
+}
+
+#[test]
+fn front_side_reflects() {
+    let direction = Vec3::new(1.0, 0.0, -1.0).normalize();
+    let path = trace(Ray::new(Vec3::new(-1.0, 0.0, 1.0), direction, 1.0));
+    let interaction = &path.interactions[0];
+    assert!(interaction.hit.front_face);
+    assert_eq!(interaction.kind, InteractionKind::Reflection);
+    assert!((interaction.outgoing_dir - Vec3::new(direction.x, 0.0, -direction.z)).length() < 1e-6);
+}
+
+#[test]
+fn back_side_passes_through_unchanged() {
+    let direction = Vec3::new(0.3, 0.2, 1.0).normalize();
+    let path = trace(Ray::new(Vec3::new(0.0, 0.0, -1.0), direction, 1.0));
+    assert_eq!(path.interactions.len(), 1);
+    let interaction = &path.interactions[0];
+    assert!(!interaction.hit.front_face);
+    assert_eq!(interaction.kind, InteractionKind::Transmission);
+    assert_eq!(interaction.outgoing_dir, direction);
+    assert_eq!(path.intensity, 1.0);
+    assert!(path.escaped);
+    assert!(path.points.last().unwrap().z > 0.0);
+}
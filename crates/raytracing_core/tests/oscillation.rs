@@ -0,0 +1,57 @@
+// 薄いガラス板の中で全反射を繰り返し、ほとんど進まない光路が途中で打ち切られることの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, DetailedPath, Material, Ray, Scene, SimulationSettingsConfig,
+};
+
+const MAX_BOUNCES: u32 = 1000;
+
+// 屈折率 5 の板（臨界角は約 11.5°）の中から、法線に対して 13° の急な角度でレイを出す
+// 板の面に当たるたびに全反射し、1回ごとに厚さ × tan 13° だけ横に進む
+fn trace_in_slab(thickness: f32) -> DetailedPath {
+    let scene = Scene {
+        objects: vec![Box::new(AxisAlignedBox {
+            min: Vec3::new(-1000.0, -1000.0, 0.0),
+            max: Vec3::new(1000.0, 1000.0, thickness),
+            material: Material::Glass { ior: 5.0 },
+        })],
+        rays: vec![Ray::new(
+            Vec3::new(0.0, 0.0, thickness * 0.1),
+            Vec3::new(13f32.to_radians().sin(), 0.0, 13f32.to_radians().cos()),
+            5.0,
+        )],
+        object_names: HashMap::new(),
+    };
+    let setting = SimulationSettingsConfig {
+        max_bounces: MAX_BOUNCES,
+        max_reflections: MAX_BOUNCES,
+        max_refractions: MAX_BOUNCES,
+        ..Default::default()
+    };
+    let mut paths = scene.simulate_rays_detailed(setting);
+    assert_eq!(paths.len(), 1);
+    paths.remove(0)
+}
+
+#[test]
+fn oscillation_in_thin_slab_is_terminated_early() {
+    // 厚さ 0.002 では1回に 0.00046 ほどしか進まない
+    let path = trace_in_slab(0.002);
+    assert!(path.stuck);
+    assert!(!path.escaped);
+    assert!(
+        path.interactions.len() < 50,
+        "interactions: {}",
+        path.interactions.len()
+    );
+}
+
+#[test]
+fn light_guide_that_makes_progress_is_not_terminated() {
+    // 厚さ 1 なら1回に 0.23 ずつ進むので、max_bounces まで追跡する
+    let path = trace_in_slab(1.0);
+    assert!(!path.stuck);
+    assert_eq!(path.interactions.len(), MAX_BOUNCES as usize);
+}
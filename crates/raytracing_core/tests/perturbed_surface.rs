@@ -0,0 +1,85 @@
+// 法線を傾ける PerturbedSurface の確認
+use glam::Vec3;
+use raytracing_core::{
+    Hittable, Material, PerturbedSurface, Plane, Ray, Scene, SimulationSettingsConfig, Sphere,
+};
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        infinity_distance: 50.0,
+        ..Default::default()
+    }
+}
+
+fn glass_sphere() -> Box<dyn Hittable> {
+    Box::new(Sphere {
+        center: Vec3::new(0.0, 0.0, 5.0),
+        radius: 2.0,
+        material: Material::Glass { ior: 1.5 },
+    })
+}
+
+fn mirror() -> Box<dyn Hittable> {
+    Box::new(Plane {
+        point: Vec3::new(0.0, 0.0, 12.0),
+        normal: Vec3::new(0.0, 0.3, -1.0).normalize(),
+        material: Material::Mirror,
+    })
+}
+
+fn scene(objects: Vec<Box<dyn Hittable>>) -> Scene {
+    let rays = (0..7)
+        .map(|i| Ray::new(Vec3::new(0.0, -1.5 + 0.5 * i as f32, 0.0), Vec3::Z, 1.0))
+        .collect();
+    Scene {
+        objects,
+        rays,
+        object_names: Default::default(),
+    }
+}
+
+#[test]
+fn zero_perturbation_matches_smooth_surface() {
+    let smooth = scene(vec![glass_sphere(), mirror()]);
+    let perturbed = scene(vec![
+        Box::new(PerturbedSurface::new(glass_sphere(), |_| Vec3::ZERO, 5.0)),
+        Box::new(PerturbedSurface::new(mirror(), |_| Vec3::ZERO, 5.0)),
+    ]);
+    assert_eq!(
+        perturbed.simulate_rays(setting()),
+        smooth.simulate_rays(setting())
+    );
+}
+
+#[test]
+fn tilt_is_limited_to_max_angle() {
+    // z = 0 の鏡に真上から当てる。0.05 rad 傾けるよう指定しても 1° までしか傾かない
+    let plane = Plane {
+        point: Vec3::ZERO,
+        normal: Vec3::Z,
+        material: Material::Mirror,
+    };
+    let surface = PerturbedSurface::new(Box::new(plane), |_| Vec3::new(0.05, 0.0, 0.3), 1.0);
+    let ray = Ray::new(Vec3::new(0.0, 0.0, 5.0), -Vec3::Z, 1.0);
+    let hit = surface
+        .intersect_all(&ray, 1e-3, f32::INFINITY)
+        .unwrap()
+        .remove(0);
+    assert!((hit.t - 5.0).abs() < 1e-5);
+    assert!((hit.normal.angle_between(Vec3::Z).to_degrees() - 1.0).abs() < 1e-3);
+    assert!(hit.normal.x > 0.0 && hit.normal.y.abs() < 1e-6);
+
+    // 反射光は法線の傾きの2倍だけ曲がる
+    let scene = scene_with(surface);
+    let points = &scene.simulate_rays(setting())[0];
+    let outgoing = (points[2] - points[1]).normalize();
+    assert!((outgoing.angle_between(Vec3::Z).to_degrees() - 2.0).abs() < 1e-2);
+}
+
+fn scene_with(surface: PerturbedSurface) -> Scene {
+    Scene {
+        objects: vec![Box::new(surface)],
+        rays: vec![Ray::new(Vec3::new(0.0, 0.0, 5.0), -Vec3::Z, 1.0)],
+        object_names: Default::default(),
+    }
+}
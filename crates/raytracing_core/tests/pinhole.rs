@@ -0,0 +1,62 @@
+// ピンホールカメラの撮像で、正面の球が材質の色の円として写ることの確認
+use glam::Vec3;
+use raytracing_core::{render_pinhole, CameraPose, Material, Scene, Sphere, BACKGROUND_COLOR};
+
+const SIZE: usize = 41;
+
+fn render(center: Vec3) -> raytracing_core::Image {
+    let scene = Scene {
+        objects: vec![Box::new(Sphere {
+            center,
+            radius: 1.0,
+            material: Material::Retroreflector,
+        })],
+        rays: Vec::new(),
+        object_names: Default::default(),
+    };
+    let camera = CameraPose {
+        position: Vec3::ZERO,
+        forward: Vec3::NEG_Z,
+        up: Vec3::Y,
+        vertical_fov_deg: 60.0,
+    };
+    render_pinhole(&scene, &camera, [SIZE, SIZE])
+}
+
+#[test]
+fn sphere_in_front_is_a_disk_of_its_color() {
+    let image = render(Vec3::new(0.0, 0.0, -5.0));
+    let [r, g, b, _] = Material::Retroreflector.display_color();
+    let color = [r, g, b];
+    assert_eq!(image.pixels.len(), SIZE * SIZE);
+
+    // 球の見かけの半径（視線と中心のなす角）は asin(1/5)。画像の半分の高さが tan(30°)
+    let radius_px = (0.2f32.asin().tan() / 30f32.to_radians().tan()) * SIZE as f32 / 2.0;
+    let center = (SIZE as f32 - 1.0) / 2.0;
+    let mut inside = 0;
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let distance = (x as f32 - center).hypot(y as f32 - center);
+            // 縁の画素は、どちらになるかを問わない
+            if distance < radius_px - 1.0 {
+                assert_eq!(image.pixel(x, y), color, "({x}, {y})");
+                inside += 1;
+            } else if distance > radius_px + 1.0 {
+                assert_eq!(image.pixel(x, y), BACKGROUND_COLOR, "({x}, {y})");
+            }
+        }
+    }
+    assert!(inside > 50, "{inside}");
+}
+
+#[test]
+fn image_is_not_mirrored() {
+    // 右上にずらした球は、画像の右上（x が大きく y が小さい側）に写る
+    let image = render(Vec3::new(2.0, 2.0, -5.0));
+    let colored: Vec<(usize, usize)> = (0..SIZE)
+        .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+        .filter(|&(x, y)| image.pixel(x, y) != BACKGROUND_COLOR)
+        .collect();
+    assert!(!colored.is_empty());
+    assert!(colored.iter().all(|&(x, y)| x > SIZE / 2 && y < SIZE / 2));
+}
@@ -0,0 +1,43 @@
+// 番号で指定したレイだけを追跡する simulate_ray_indices の確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{Ray, Scene, SceneError, SimulationSettingsConfig};
+
+// 何も無いシーンに、始点の x がレイの番号になる3本のレイ
+fn scene() -> Scene {
+    Scene {
+        objects: Vec::new(),
+        rays: (0..3)
+            .map(|i| Ray::new(Vec3::new(i as f32, 0.0, 0.0), Vec3::Z, 1.0))
+            .collect(),
+        object_names: HashMap::new(),
+    }
+}
+
+#[test]
+fn traces_exactly_the_requested_rays() {
+    let scene = scene();
+    let setting = SimulationSettingsConfig::default();
+    let paths = scene.simulate_ray_indices(&[0, 2], setting).unwrap();
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[0].points[0], Vec3::new(0.0, 0.0, 0.0));
+    assert_eq!(paths[1].points[0], Vec3::new(2.0, 0.0, 0.0));
+
+    // 全てのレイを追跡したときの同じ番号の光路と一致する
+    let all = scene.simulate_rays_detailed(setting);
+    assert_eq!(paths[0].points, all[0].points);
+    assert_eq!(paths[1].points, all[2].points);
+}
+
+#[test]
+fn out_of_range_index_is_returned_to_the_caller() {
+    let result = scene().simulate_ray_indices(&[1, 5], SimulationSettingsConfig::default());
+    assert_eq!(
+        result.unwrap_err(),
+        SceneError::RayIndexOutOfRange {
+            ray_index: 5,
+            ray_count: 3
+        }
+    );
+}
@@ -0,0 +1,82 @@
+// 有限の長方形 (Rectangle) が縁の内側でだけ当たり、鏡にしても縁の外のレイは反射しないことの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{Hittable, Material, Ray, Rectangle, Scene, SimulationSettingsConfig};
+
+// z = 0 の平面上の、幅 4（X 方向）・高さ 2（Y 方向）の鏡
+fn mirror() -> Rectangle {
+    Rectangle {
+        center: Vec3::ZERO,
+        normal: Vec3::NEG_Z,
+        width_dir: Vec3::X,
+        half_width: 2.0,
+        half_height: 1.0,
+        material: Material::Mirror,
+    }
+}
+
+fn shoot(x: f32, y: f32) -> Option<Vec<raytracing_core::HitRecord>> {
+    let ray = Ray::new(Vec3::new(x, y, -5.0), Vec3::Z, 1.0);
+    mirror().intersect_all(&ray, 1e-4, f32::INFINITY)
+}
+
+#[test]
+fn hits_only_inside_edges() {
+    assert!(shoot(0.0, 0.0).is_some());
+    assert!(shoot(1.9, 0.9).is_some());
+    // 幅の外と高さの外
+    assert!(shoot(2.1, 0.0).is_none());
+    assert!(shoot(0.0, 1.1).is_none());
+    assert!(shoot(-2.1, -1.1).is_none());
+}
+
+#[test]
+fn ray_past_edge_is_not_reflected() {
+    let scene = Scene {
+        objects: vec![Box::new(mirror())],
+        rays: vec![
+            Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::Z, 1.0),
+            Ray::new(Vec3::new(2.5, 0.0, -5.0), Vec3::Z, 1.0),
+        ],
+        object_names: HashMap::new(),
+    };
+    let setting = SimulationSettingsConfig {
+        infinity_distance: 20.0,
+        max_bounces: 10,
+        max_reflections: 10,
+        max_refractions: 10,
+        ..Default::default()
+    };
+    let paths = scene.simulate_rays_detailed(setting);
+    // 縁の内側のレイは鏡で折り返す
+    assert_eq!(paths[0].interactions.len(), 1);
+    assert!(paths[0].points.last().unwrap().z < -5.0);
+    // 縁の外を狙ったレイは何にも当たらずに直進する
+    assert!(paths[1].interactions.is_empty());
+    assert!(paths[1].escaped);
+    assert!(paths[1].points.last().unwrap().z > 0.0);
+}
+
+#[test]
+fn bounding_box_covers_tilted_corners() {
+    // Y 軸まわりに 45° 傾けた鏡
+    let tilted = Rectangle {
+        normal: Vec3::new(1.0, 0.0, -1.0),
+        ..mirror()
+    };
+    let bbox = tilted.bounding_box().unwrap();
+    let half = 2.0 / 2f32.sqrt();
+    assert!((bbox.max - Vec3::new(half, 1.0, half)).length() < 1e-5);
+    assert!((bbox.min + Vec3::new(half, 1.0, half)).length() < 1e-5);
+}
+
+#[test]
+fn width_dir_parallel_to_normal_is_degenerate() {
+    let rectangle = Rectangle {
+        width_dir: Vec3::Z,
+        ..mirror()
+    };
+    assert!(rectangle.degeneracy().is_some());
+    assert!(mirror().degeneracy().is_none());
+}
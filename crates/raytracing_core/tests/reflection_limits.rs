@@ -0,0 +1,109 @@
+// 反射と屈折の回数の上限が、進める回数の上限 (max_bounces) より先に光路を打ち切ることの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, DetailedPath, Hittable, InteractionKind, Material, Plane, Ray, Scene,
+    SimulationSettingsConfig,
+};
+
+fn trace(
+    objects: Vec<Box<dyn Hittable>>,
+    ray: Ray,
+    setting: SimulationSettingsConfig,
+) -> DetailedPath {
+    let scene = Scene {
+        objects,
+        rays: vec![ray],
+        object_names: HashMap::new(),
+    };
+    scene.simulate_rays_detailed(setting).remove(0)
+}
+
+fn count(path: &DetailedPath, kind: InteractionKind) -> usize {
+    path.interactions
+        .iter()
+        .filter(|interaction| interaction.kind == kind)
+        .count()
+}
+
+// z = 0 と z = 1 の向かい合った鏡の間を、斜めに往復し続けるレイ
+fn between_mirrors(setting: SimulationSettingsConfig) -> DetailedPath {
+    let mirror = |z: f32, normal: Vec3| {
+        Box::new(Plane {
+            point: Vec3::new(0.0, 0.0, z),
+            normal,
+            material: Material::Mirror,
+        }) as Box<dyn Hittable>
+    };
+    trace(
+        vec![mirror(0.0, Vec3::Z), mirror(1.0, Vec3::NEG_Z)],
+        Ray::new(
+            Vec3::new(0.0, 0.0, 0.5),
+            Vec3::new(1.0, 0.0, 1.0).normalize(),
+            1.0,
+        ),
+        setting,
+    )
+}
+
+#[test]
+fn max_reflections_terminates_before_max_bounces() {
+    let path = between_mirrors(SimulationSettingsConfig {
+        max_bounces: 100,
+        max_reflections: 3,
+        max_refractions: 100,
+        ..Default::default()
+    });
+    assert_eq!(count(&path, InteractionKind::Reflection), 3);
+    assert_eq!(path.interactions.len(), 3);
+    assert!(!path.escaped);
+}
+
+#[test]
+fn max_bounces_still_applies_when_reflections_remain() {
+    let path = between_mirrors(SimulationSettingsConfig {
+        max_bounces: 5,
+        max_reflections: 100,
+        max_refractions: 100,
+        ..Default::default()
+    });
+    assert_eq!(path.interactions.len(), 5);
+}
+
+// z 方向に並んだ3枚のガラス板。まっすぐ通り抜けると6回屈折する
+fn plates() -> Vec<Box<dyn Hittable>> {
+    (0..3)
+        .map(|i| {
+            let z = i as f32 * 2.0;
+            Box::new(AxisAlignedBox {
+                min: Vec3::new(-5.0, -5.0, z),
+                max: Vec3::new(5.0, 5.0, z + 1.0),
+                material: Material::Glass { ior: 1.5 },
+            }) as Box<dyn Hittable>
+        })
+        .collect()
+}
+
+#[test]
+fn max_refractions_terminates_inside_a_plate_stack() {
+    let ray = Ray::new(Vec3::new(0.0, 0.0, -1.0), Vec3::Z, 1.0);
+    let unlimited = trace(plates(), ray.clone(), SimulationSettingsConfig::default());
+    assert_eq!(count(&unlimited, InteractionKind::Refraction), 6);
+    assert!(unlimited.escaped);
+
+    let limited = trace(
+        plates(),
+        ray,
+        SimulationSettingsConfig {
+            max_bounces: 100,
+            max_reflections: 100,
+            max_refractions: 3,
+            ..Default::default()
+        },
+    );
+    assert_eq!(count(&limited, InteractionKind::Refraction), 3);
+    assert!(!limited.escaped);
+    // 2枚目の板の中で止まる
+    assert!((limited.points.last().unwrap().z - 2.0).abs() < 1e-4);
+}
@@ -0,0 +1,104 @@
+// 接線付近で同じ点に当たり直す光路が、その点で止まり続けないことの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    DetailedPath, HitRecord, Hittable, Material, Ray, RehitMode, Scene, SimulationSettingsConfig,
+    Sphere,
+};
+
+const MAX_BOUNCES: u32 = 50;
+
+fn trace(object: Box<dyn Hittable>, ray: Ray, rehit_mode: RehitMode) -> DetailedPath {
+    let scene = Scene {
+        objects: vec![object],
+        rays: vec![ray],
+        object_names: HashMap::new(),
+    };
+    let setting = SimulationSettingsConfig {
+        infinity_distance: 100.0,
+        max_bounces: MAX_BOUNCES,
+        max_reflections: MAX_BOUNCES,
+        max_refractions: MAX_BOUNCES,
+        rehit_mode,
+        ..Default::default()
+    };
+    scene.simulate_rays_detailed(setting).remove(0)
+}
+
+// 接点の近くから出たレイには、数値誤差で接点そのものに当たったと報告し続ける面
+// （接線方向のレイが面から抜け出せなくなる状況を作る）
+struct SnappingSurface {
+    point: Vec3,
+}
+
+impl Hittable for SnappingSurface {
+    fn intersect_all(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<Vec<HitRecord>> {
+        let t = if ray.origin.distance(self.point) < 0.1 {
+            t_min * 1.5
+        } else {
+            (self.point - ray.origin).dot(ray.direction)
+        };
+        if t <= t_min || t >= t_max {
+            return None;
+        }
+        Some(vec![HitRecord {
+            t,
+            point: self.point,
+            normal: -ray.direction,
+            // 裏から当たった片面鏡は素通りする
+            front_face: false,
+            incoming: ray.direction,
+            material: Material::OneSidedMirror,
+        }])
+    }
+}
+
+fn snapping_ray() -> Ray {
+    Ray::new(Vec3::new(0.0, 0.0, -1.0), Vec3::Z, 1.0)
+}
+
+#[test]
+fn grazing_sphere_does_not_stall() {
+    // 半径 1 のガラス球の縁をかすめるレイ
+    let sphere = Box::new(Sphere {
+        center: Vec3::ZERO,
+        radius: 1.0,
+        material: Material::Glass { ior: 1.5 },
+    });
+    let ray = Ray::new(Vec3::new(-5.0, 1.0 - 1e-6, 0.0), Vec3::X, 1.0);
+    let path = trace(sphere, ray, RehitMode::Nudge);
+    assert!(path.escaped);
+    assert!(!path.rehit && !path.stuck);
+    assert!(path.interactions.len() < 5);
+    for pair in path.points.windows(2) {
+        assert!(pair[0].distance(pair[1]) > 1e-4, "{:?}", path.points);
+    }
+}
+
+#[test]
+fn nudge_moves_past_the_rehit_point() {
+    let path = trace(
+        Box::new(SnappingSurface { point: Vec3::ZERO }),
+        snapping_ray(),
+        RehitMode::Nudge,
+    );
+    // 接点での衝突は1回だけ記録され、その先へ抜ける
+    assert_eq!(path.interactions.len(), 1);
+    assert!(path.escaped);
+    assert!(!path.rehit);
+    assert!(path.points.last().unwrap().z > 1.0);
+}
+
+#[test]
+fn terminate_records_the_reason() {
+    let path = trace(
+        Box::new(SnappingSurface { point: Vec3::ZERO }),
+        snapping_ray(),
+        RehitMode::Terminate,
+    );
+    assert!(path.rehit);
+    assert!(!path.escaped);
+    assert_eq!(path.interactions.len(), 1);
+    assert_eq!(path.points.len(), 2);
+}
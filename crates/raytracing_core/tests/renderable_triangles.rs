@@ -0,0 +1,82 @@
+// Scene::renderable_triangles が閉じた三角形メッシュを返すことの確認
+use std::collections::{HashMap, HashSet};
+use std::f32::consts::PI;
+
+use glam::{Mat4, Vec3};
+use raytracing_core::{Hittable, Lens, Material, Plane, Scene, Sphere, Transform};
+
+fn scene(objects: Vec<Box<dyn Hittable>>) -> Scene {
+    Scene {
+        objects,
+        rays: Vec::new(),
+        object_names: HashMap::new(),
+    }
+}
+
+// 有向辺がちょうど1回ずつ現れ、その逆向きの辺もある（穴が無く、向きがそろっている）
+fn assert_closed(triangles: &[[u32; 3]]) {
+    let mut edges = HashSet::new();
+    for &[a, b, c] in triangles {
+        for edge in [(a, b), (b, c), (c, a)] {
+            assert!(edges.insert(edge), "辺 {edge:?} が重複している");
+        }
+    }
+    for &(a, b) in &edges {
+        assert!(edges.contains(&(b, a)), "辺 {:?} の相手が無い", (a, b));
+    }
+}
+
+// 閉じたメッシュの符号付き体積（外向きなら正）
+fn volume(vertices: &[Vec3], triangles: &[[u32; 3]]) -> f32 {
+    triangles
+        .iter()
+        .map(|t| {
+            let [a, b, c] = t.map(|i| vertices[i as usize]);
+            a.dot(b.cross(c)) / 6.0
+        })
+        .sum()
+}
+
+#[test]
+fn sphere_yields_closed_outward_mesh() {
+    let center = Vec3::new(1.0, -2.0, 3.0);
+    let sphere = Sphere {
+        center,
+        radius: 2.0,
+        material: Material::Mirror,
+    };
+    let meshes = scene(vec![Box::new(sphere)]).renderable_triangles();
+    assert_eq!(meshes.len(), 1);
+    let (vertices, triangles) = &meshes[0];
+    assert!(!triangles.is_empty());
+    assert_closed(triangles);
+
+    // 頂点は球面上にあり、体積は球の体積に近い
+    assert!(vertices
+        .iter()
+        .all(|v| (v.distance(center) - 2.0).abs() < 1e-2));
+    let expected = 4.0 / 3.0 * PI * 8.0;
+    let measured = volume(vertices, triangles);
+    assert!((measured - expected).abs() / expected < 0.02, "{measured}");
+}
+
+#[test]
+fn transformed_lens_is_closed_and_unbounded_plane_is_empty() {
+    let lens = Lens::new(4.0, 10.0, 20.0, -20.0, Material::Glass { ior: 1.5 });
+    let matrix = Mat4::from_rotation_y(0.7) * Mat4::from_translation(Vec3::new(5.0, 0.0, 0.0));
+    let plane = Plane {
+        point: Vec3::ZERO,
+        normal: Vec3::Y,
+        material: Material::Mirror,
+    };
+    let meshes = scene(vec![
+        Box::new(Transform::new(Box::new(lens), matrix)),
+        Box::new(plane),
+    ])
+    .renderable_triangles();
+    assert_eq!(meshes.len(), 2);
+    assert!(!meshes[0].1.is_empty());
+    assert_closed(&meshes[0].1);
+    assert!(volume(&meshes[0].0, &meshes[0].1) > 0.0);
+    assert!(meshes[1].0.is_empty() && meshes[1].1.is_empty());
+}
@@ -0,0 +1,32 @@
+// 再帰反射材 (Material::Retroreflector) が入射角によらずレイを逆向きに返すことの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{Material, Plane, Ray, Scene, SimulationSettingsConfig};
+
+#[test]
+fn retroreflector_returns_negated_direction() {
+    for angle_deg in [0.0f32, 20.0, 45.0, 70.0] {
+        let angle = angle_deg.to_radians();
+        let direction = Vec3::new(angle.sin(), 0.0, angle.cos());
+        let scene = Scene {
+            objects: vec![Box::new(Plane {
+                point: Vec3::new(0.0, 0.0, 5.0),
+                normal: Vec3::NEG_Z,
+                material: Material::Retroreflector,
+            })],
+            rays: vec![Ray::new(Vec3::ZERO, direction, 1.0)],
+            object_names: HashMap::new(),
+        };
+        let path = &scene.simulate_rays_detailed(SimulationSettingsConfig::default())[0];
+        assert_eq!(path.interactions.len(), 1);
+        let outgoing = path.interactions[0].outgoing_dir;
+        assert!(
+            (outgoing + direction).length() < 1e-5,
+            "{angle_deg}°: {outgoing}"
+        );
+        // 戻ったレイは原点を通って抜ける
+        let last = *path.points.last().unwrap();
+        assert!(last.z < 0.0, "{angle_deg}°: {last}");
+    }
+}
@@ -0,0 +1,65 @@
+// Scene::bounding_box が有限な物体をすべて囲み、無限に広がる物体を除くことの確認
+use glam::Vec3;
+use raytracing_core::{Aabb, Material, Plane, Ray, Scene, Sphere};
+
+fn sphere(center: Vec3, radius: f32) -> Sphere {
+    Sphere {
+        center,
+        radius,
+        material: Material::Mirror,
+    }
+}
+
+#[test]
+fn union_encloses_both_spheres_and_ignores_infinite_objects() {
+    let scene = Scene {
+        objects: vec![
+            Box::new(sphere(Vec3::new(-2.0, 0.0, 0.0), 1.0)),
+            Box::new(Plane {
+                point: Vec3::ZERO,
+                normal: Vec3::Y,
+                material: Material::Mirror,
+            }),
+            Box::new(sphere(Vec3::new(3.0, 1.0, -1.0), 0.5)),
+        ],
+        rays: vec![Ray::new(Vec3::new(0.0, 0.0, 10.0), Vec3::NEG_Z, 1.0)],
+        object_names: Default::default(),
+    };
+
+    let bounds = scene.bounding_box().unwrap();
+    assert_eq!(bounds.min, Vec3::new(-3.0, -1.0, -1.5));
+    assert_eq!(bounds.max, Vec3::new(3.5, 1.5, 1.0));
+    for object in &scene.objects {
+        if let Some(bbox) = object.bounding_box().filter(|b| b.size().is_finite()) {
+            assert_eq!(bounds.union(&bbox), bounds);
+        }
+    }
+
+    let with_rays = scene.bounding_box_with_rays().unwrap();
+    assert_eq!(with_rays.min, bounds.min);
+    assert_eq!(with_rays.max, Vec3::new(3.5, 1.5, 10.0));
+}
+
+#[test]
+fn none_only_when_nothing_finite_exists() {
+    let plane_only = Scene {
+        objects: vec![Box::new(Plane {
+            point: Vec3::ZERO,
+            normal: Vec3::Y,
+            material: Material::Mirror,
+        })],
+        rays: vec![],
+        object_names: Default::default(),
+    };
+    assert_eq!(plane_only.bounding_box(), None);
+    assert_eq!(plane_only.bounding_box_with_rays(), None);
+
+    let rays_only = Scene {
+        rays: vec![Ray::new(Vec3::ONE, Vec3::Z, 1.0)],
+        ..plane_only
+    };
+    assert_eq!(
+        rays_only.bounding_box_with_rays(),
+        Some(Aabb::new(Vec3::ONE, Vec3::ONE))
+    );
+}
@@ -0,0 +1,85 @@
+// 追跡前のシーンの検査 (Scene::validate) の確認
+use glam::{Mat4, Vec3};
+use raytracing_core::{Hittable, Material, Plane, Ray, Scene, SceneError, Sphere, Transform};
+
+fn scene(objects: Vec<Box<dyn Hittable>>, rays: Vec<Ray>) -> Scene {
+    Scene {
+        objects,
+        rays,
+        object_names: Default::default(),
+    }
+}
+
+fn sphere(radius: f32) -> Box<dyn Hittable> {
+    Box::new(Sphere {
+        center: Vec3::ZERO,
+        radius,
+        material: Material::Mirror,
+    })
+}
+
+fn ray() -> Ray {
+    Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::Z, 1.0)
+}
+
+#[test]
+fn valid_scene_passes() {
+    let plane: Box<dyn Hittable> = Box::new(Plane {
+        point: Vec3::ZERO,
+        normal: Vec3::Y,
+        material: Material::Absorber,
+    });
+    assert_eq!(
+        scene(vec![sphere(1.0), plane], vec![ray()]).validate(),
+        Ok(())
+    );
+}
+
+#[test]
+fn zero_radius_sphere_is_reported() {
+    match scene(vec![sphere(1.0), sphere(0.0)], vec![ray()]).validate() {
+        Err(SceneError::DegenerateObject {
+            object_index,
+            path,
+            reason,
+        }) => {
+            assert_eq!(object_index, 1);
+            assert_eq!(path, "root");
+            assert!(reason.contains("radius"), "{reason}");
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn degenerate_child_is_reported_with_its_path() {
+    let moved = Transform::new(sphere(-1.0), Mat4::from_translation(Vec3::X));
+    match scene(vec![Box::new(moved)], vec![ray()]).validate() {
+        Err(SceneError::DegenerateObject { path, .. }) => assert_eq!(path, "root.object"),
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn nan_transform_is_reported() {
+    let broken = Transform::new(sphere(1.0), Mat4::from_translation(Vec3::splat(f32::NAN)));
+    assert!(matches!(
+        scene(vec![Box::new(broken)], vec![ray()]).validate(),
+        Err(SceneError::DegenerateObject {
+            object_index: 0,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn nan_ray_is_reported() {
+    let nan_ray = Ray::new(Vec3::new(f32::NAN, 0.0, 0.0), Vec3::Z, 1.0);
+    match scene(vec![sphere(1.0)], vec![ray(), nan_ray]).validate() {
+        Err(SceneError::InvalidRay { ray_index, reason }) => {
+            assert_eq!(ray_index, 1);
+            assert!(reason.contains("origin"), "{reason}");
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
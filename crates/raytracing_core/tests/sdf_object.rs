@@ -0,0 +1,82 @@
+// 符号付き距離関数で表した形状 (SdfObject) の交点が、解析的な形状と一致することの確認
+use glam::Vec3;
+use raytracing_core::{Hittable, Material, Ray, SdfObject, Sphere};
+
+const TOLERANCE: f32 = 1e-3;
+
+#[test]
+fn sdf_sphere_matches_analytic_sphere() {
+    let center = Vec3::new(0.5, -0.3, 4.0);
+    let sdf_sphere = SdfObject::sphere(center, 1.5, Material::Mirror);
+    let sphere = Sphere {
+        center,
+        radius: 1.5,
+        material: Material::Mirror,
+    };
+
+    // 中心付近を通るレイと、縁に近いところを通るレイ
+    for offset in [0.0, 0.4, 1.0, 1.4] {
+        let ray = Ray::new(
+            Vec3::new(offset, 0.0, -2.0),
+            Vec3::new(0.1, -0.05, 1.0),
+            1.0,
+        );
+        let expected = sphere.intersect_all(&ray, 1e-3, f32::INFINITY).unwrap();
+        let actual = sdf_sphere.intersect_all(&ray, 1e-3, f32::INFINITY).unwrap();
+        assert_eq!(actual.len(), expected.len(), "offset {offset}");
+        for (a, e) in actual.iter().zip(&expected) {
+            assert!((a.t - e.t).abs() < TOLERANCE, "t {} vs {}", a.t, e.t);
+            assert!(
+                a.normal.abs_diff_eq(e.normal, TOLERANCE),
+                "{} vs {}",
+                a.normal,
+                e.normal
+            );
+            assert_eq!(a.front_face, e.front_face);
+        }
+    }
+
+    // 外れるレイはどちらも当たらない
+    let miss = Ray::new(Vec3::new(3.0, 0.0, -2.0), Vec3::Z, 1.0);
+    assert!(sphere.intersect_all(&miss, 1e-3, f32::INFINITY).is_none());
+    assert!(sdf_sphere
+        .intersect_all(&miss, 1e-3, f32::INFINITY)
+        .is_none());
+}
+
+#[test]
+fn ray_from_inside_hits_the_exit_only() {
+    let sdf_sphere = SdfObject::sphere(Vec3::ZERO, 2.0, Material::Glass { ior: 1.5 });
+    let ray = Ray::new(Vec3::ZERO, Vec3::X, 1.0);
+    let hits = sdf_sphere.intersect_all(&ray, 1e-3, f32::INFINITY).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert!((hits[0].t - 2.0).abs() < TOLERANCE);
+    assert!(!hits[0].front_face);
+}
+
+#[test]
+fn ray_through_torus_hole_crosses_the_tube_twice_per_side() {
+    // Y 軸を囲むトーラスを X 方向に貫くと、左右の管で2回ずつ面を横切る
+    let torus = SdfObject::torus(Vec3::ZERO, 3.0, 1.0, Material::Absorber);
+    let ray = Ray::new(Vec3::new(-10.0, 0.0, 0.0), Vec3::X, 1.0);
+    let hits = torus.intersect_all(&ray, 1e-3, f32::INFINITY).unwrap();
+    let ts: Vec<f32> = hits.iter().map(|hit| hit.t).collect();
+    let expected = [6.0, 8.0, 12.0, 14.0];
+    assert_eq!(ts.len(), expected.len(), "{ts:?}");
+    for (t, e) in ts.iter().zip(expected) {
+        assert!((t - e).abs() < TOLERANCE, "{ts:?}");
+    }
+    assert!(torus.contains(Vec3::new(3.0, 0.0, 0.0)));
+    assert!(!torus.contains(Vec3::ZERO));
+}
+
+#[test]
+fn cuboid_matches_its_faces() {
+    let cuboid = SdfObject::cuboid(Vec3::ZERO, Vec3::new(1.0, 2.0, 3.0), Material::Mirror);
+    let ray = Ray::new(Vec3::new(0.2, 0.3, -10.0), Vec3::Z, 1.0);
+    let hits = cuboid.intersect_all(&ray, 1e-3, f32::INFINITY).unwrap();
+    assert_eq!(hits.len(), 2);
+    assert!((hits[0].t - 7.0).abs() < TOLERANCE);
+    assert!((hits[1].t - 13.0).abs() < TOLERANCE);
+    assert!(hits[0].normal.abs_diff_eq(-Vec3::Z, TOLERANCE));
+}
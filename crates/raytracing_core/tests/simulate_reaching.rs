@@ -0,0 +1,58 @@
+// 名前で指定したオブジェクトに届いた光路だけを返す (Scene::simulate_rays_reaching) の確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, Hittable, Material, Ray, Scene, SceneError, SimulationSettingsConfig,
+};
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        max_bounces: 8,
+        max_reflections: 8,
+        max_refractions: 8,
+        ..Default::default()
+    }
+}
+
+fn block(center: Vec3) -> Box<dyn Hittable> {
+    Box::new(AxisAlignedBox {
+        min: center - Vec3::splat(0.5),
+        max: center + Vec3::splat(0.5),
+        material: Material::Absorber,
+    })
+}
+
+// +Z 側に "sensor"、+X 側に名前の無い箱を置き、それぞれに向けてレイを1本ずつ飛ばす
+fn scene() -> Scene {
+    Scene {
+        objects: vec![
+            block(Vec3::new(0.0, 0.0, 5.0)),
+            block(Vec3::new(5.0, 0.0, 0.0)),
+        ],
+        rays: vec![
+            Ray::new(Vec3::ZERO, Vec3::X, 1.0),
+            Ray::new(Vec3::ZERO, Vec3::Z, 1.0),
+        ],
+        object_names: HashMap::from([("sensor".to_string(), 0)]),
+    }
+}
+
+#[test]
+fn only_paths_reaching_the_named_object_are_returned() {
+    let paths = scene().simulate_rays_reaching("sensor", setting()).unwrap();
+    assert_eq!(paths.len(), 1);
+    let path = &paths[0];
+    assert_eq!(path.interactions.len(), 1);
+    assert_eq!(path.interactions[0].object_index, 0);
+    assert!((path.points[1] - Vec3::new(0.0, 0.0, 4.5)).length() < 1e-5);
+}
+
+#[test]
+fn unknown_name_is_an_error() {
+    match scene().simulate_rays_reaching("detector", setting()) {
+        Err(SceneError::UnknownObjectName { name }) => assert_eq!(name, "detector"),
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("unknown object name was accepted"),
+    }
+}
@@ -0,0 +1,41 @@
+// 球面キャップ (SphericalCap) が、切り取られた部分では当たらずキャップの部分でだけ当たることの確認
+use glam::Vec3;
+use raytracing_core::{Hittable, Material, Ray, SphericalCap};
+
+// 原点を中心とする半径1の、+Y 側の半球
+fn dome() -> SphericalCap {
+    SphericalCap {
+        center: Vec3::ZERO,
+        radius: 1.0,
+        axis_dir: Vec3::Y,
+        min_cos_angle: 0.0,
+        material: Material::Glass { ior: 1.5 },
+    }
+}
+
+#[test]
+fn ray_through_removed_part_misses() {
+    // -Y 側の切り取られた部分を横切るだけのレイ
+    let ray = Ray::new(Vec3::new(-5.0, -0.5, 0.0), Vec3::X, 1.0);
+    assert!(dome().intersect_all(&ray, 1e-4, f32::INFINITY).is_none());
+}
+
+#[test]
+fn ray_through_cap_hits() {
+    let ray = Ray::new(Vec3::new(-5.0, 0.5, 0.0), Vec3::X, 1.0);
+    let hits = dome().intersect_all(&ray, 1e-4, f32::INFINITY).unwrap();
+    assert_eq!(hits.len(), 2);
+    assert!(hits.iter().all(|hit| hit.point.y > 0.0));
+}
+
+#[test]
+fn ray_along_axis_hits_only_cap() {
+    // 下から軸に沿って進むレイは、切り取られた底を素通りして頂点にだけ当たる
+    let ray = Ray::new(Vec3::new(0.0, -5.0, 0.0), Vec3::Y, 1.0);
+    let hits = dome().intersect_all(&ray, 1e-4, f32::INFINITY).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert!((hits[0].point - Vec3::Y).length() < 1e-5);
+    // 内側から当たるので、法線は -Y（レイと向かい合う向き）
+    assert!(!hits[0].front_face);
+    assert!((hits[0].normal + Vec3::Y).length() < 1e-5);
+}
@@ -0,0 +1,143 @@
+// stray_light_budget で、迷光の強度が衝突の種類ごとに1回ずつ数えられることの確認
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::analysis::{stray_light_budget, StrayLightBudget};
+use raytracing_core::{
+    AxisAlignedBox, DetailedPath, FresnelMode, Hittable, Material, Plane, Ray, Reflectance, Scene,
+    SimulationSettingsConfig,
+};
+
+// 垂直入射での空気とガラス (n = 1.5) の境界の反射率 ((1.5 - 1) / (1.5 + 1))²
+const REFLECTANCE: f32 = 0.04;
+
+fn setting(fresnel_mode: FresnelMode) -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        fresnel_mode,
+        ..Default::default()
+    }
+}
+
+fn trace(objects: Vec<Box<dyn Hittable>>, fresnel_mode: FresnelMode) -> Vec<DetailedPath> {
+    let scene = Scene {
+        objects,
+        rays: vec![Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::Z, 1.0)],
+        object_names: HashMap::new(),
+    };
+    scene.simulate_rays_detailed(setting(fresnel_mode))
+}
+
+// z = 0〜2 のガラス板
+fn slab() -> Vec<Box<dyn Hittable>> {
+    vec![Box::new(AxisAlignedBox {
+        min: Vec3::new(-5.0, -5.0, 0.0),
+        max: Vec3::new(5.0, 5.0, 2.0),
+        material: Material::Glass { ior: 1.5 },
+    })]
+}
+
+fn final_direction_z(path: &DetailedPath) -> f32 {
+    let n = path.points.len();
+    path.points[n - 1].z - path.points[n - 2].z
+}
+
+#[test]
+fn split_slab_conserves_incident_intensity() {
+    let paths = trace(slab(), FresnelMode::Split);
+    assert!(paths.len() >= 3, "paths: {}", paths.len());
+    assert!(paths.iter().all(|path| path.escaped));
+
+    // 抜けた光路を透過側 (+Z) と反射側 (-Z) に分けて足すと、入射した強度に戻る
+    let (mut transmitted, mut reflected) = (0.0, 0.0);
+    for path in &paths {
+        if final_direction_z(path) > 0.0 {
+            transmitted += path.intensity;
+        } else {
+            reflected += path.intensity;
+        }
+    }
+    assert!(
+        (transmitted + reflected - 1.0).abs() < 1e-4,
+        "transmitted: {transmitted}, reflected: {reflected}"
+    );
+
+    // 板の中の多重反射をすべて足すと、反射光へ分かれた強度は 2R になる
+    // 分岐が引き継いだ衝突まで数えると、表面での R がもう一度足されてしまう
+    let budget = stray_light_budget(&paths);
+    assert!(
+        (budget.fresnel_reflection - 2.0 * REFLECTANCE).abs() < 1e-4,
+        "budget: {budget:?}"
+    );
+    assert_eq!(budget.mirror_absorption, 0.0);
+    assert_eq!(budget.total_internal_reflection, 0.0);
+    assert_eq!(budget.absorbed, 0.0);
+}
+
+#[test]
+fn deterministic_slab_budget_is_what_the_main_path_lost() {
+    let paths = trace(slab(), FresnelMode::Deterministic);
+    assert_eq!(paths.len(), 1);
+    let transmitted = paths[0].intensity;
+    let expected = (1.0 - REFLECTANCE) * (1.0 - REFLECTANCE);
+    assert!((transmitted - expected).abs() < 1e-5, "{transmitted}");
+
+    let budget = stray_light_budget(&paths);
+    assert!(
+        (transmitted + budget.fresnel_reflection - 1.0).abs() < 1e-5,
+        "budget: {budget:?}"
+    );
+}
+
+#[test]
+fn metal_mirror_and_absorber_are_counted_separately() {
+    // z = 0 の金属鏡で反射した光が、z = -10 の吸収体で止まる
+    let objects: Vec<Box<dyn Hittable>> = vec![
+        Box::new(Plane {
+            point: Vec3::ZERO,
+            normal: Vec3::NEG_Z,
+            material: Material::MetalMirror {
+                reflectance: Reflectance::Constant(0.9),
+            },
+        }),
+        Box::new(Plane {
+            point: Vec3::new(0.0, 0.0, -10.0),
+            normal: Vec3::Z,
+            material: Material::Absorber,
+        }),
+    ];
+    let paths = trace(objects, FresnelMode::AlwaysRefract);
+    let budget = stray_light_budget(&paths);
+    assert_eq!(
+        budget,
+        StrayLightBudget {
+            mirror_absorption: 1.0 - 0.9,
+            absorbed: 0.9,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn total_internal_reflection_is_counted_once_per_reflection() {
+    // 始点をガラスの中に置き、臨界角 (約 41.8°) より浅い 60° で z = 2 の面に当たり、全反射する
+    let objects: Vec<Box<dyn Hittable>> = vec![Box::new(AxisAlignedBox {
+        min: Vec3::new(-100.0, -5.0, -10.0),
+        max: Vec3::new(100.0, 5.0, 2.0),
+        material: Material::Glass { ior: 1.5 },
+    })];
+    let scene = Scene {
+        objects,
+        rays: vec![Ray::new(
+            Vec3::ZERO,
+            Vec3::new(60f32.to_radians().sin(), 0.0, 60f32.to_radians().cos()),
+            1.5,
+        )],
+        object_names: HashMap::new(),
+    };
+    let mut setting = setting(FresnelMode::AlwaysRefract);
+    setting.max_reflections = 1;
+    let paths = scene.simulate_rays_detailed(setting);
+    let budget = stray_light_budget(&paths);
+    assert_eq!(budget.total_internal_reflection, 1.0, "budget: {budget:?}");
+    assert_eq!(budget.fresnel_reflection, 0.0);
+}
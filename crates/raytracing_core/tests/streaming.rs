@@ -0,0 +1,86 @@
+// simulate_rays_streaming で送られた光路を集めて並べると、まとめて追跡した結果と一致することの確認
+use std::collections::HashMap;
+use std::sync::mpsc;
+
+use glam::Vec3;
+use raytracing_core::{
+    FresnelMode, Material, PathResult, Plane, Ray, Scene, SimulationSettingsConfig, Sphere,
+};
+
+fn settings(fresnel_mode: FresnelMode) -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        fresnel_mode,
+        ..Default::default()
+    }
+}
+
+// ガラス球と鏡の床に、少しずつずらしたレイを当てる
+fn scene() -> Scene {
+    let rays = (0..40)
+        .map(|i| {
+            let y = -1.5 + 3.0 * i as f32 / 39.0;
+            Ray::new(Vec3::new(-10.0, y, 0.1), Vec3::new(1.0, -0.05, 0.0), 1.0)
+        })
+        .collect();
+    Scene {
+        objects: vec![
+            Box::new(Sphere {
+                center: Vec3::ZERO,
+                radius: 1.0,
+                material: Material::Glass { ior: 1.5 },
+            }),
+            Box::new(Plane {
+                point: Vec3::new(0.0, -3.0, 0.0),
+                normal: Vec3::Y,
+                material: Material::Mirror,
+            }),
+        ],
+        rays,
+        object_names: HashMap::new(),
+    }
+}
+
+fn streamed(scene: &Scene, setting: SimulationSettingsConfig) -> Vec<PathResult> {
+    let (sender, receiver) = mpsc::channel();
+    scene.simulate_rays_streaming(setting, sender);
+    let mut results: Vec<PathResult> = receiver.into_iter().collect();
+    results.sort_by_key(|result| (result.ray_index, result.branch));
+    results
+}
+
+#[test]
+fn collected_stream_equals_batch() {
+    let scene = scene();
+    let setting = settings(FresnelMode::Deterministic);
+    let results = streamed(&scene, setting);
+    assert_eq!(results.len(), scene.rays.len());
+    let points: Vec<Vec<Vec3>> = results
+        .into_iter()
+        .map(|result| result.path.points)
+        .collect();
+    assert_eq!(points, scene.simulate_rays(setting));
+}
+
+#[test]
+fn split_branches_keep_batch_order() {
+    // 分岐のある追跡でも、(レイ, 分岐) の順に並べればまとめて追跡した結果と同じ並びになる
+    let scene = scene();
+    let setting = settings(FresnelMode::Split);
+    let results = streamed(&scene, setting);
+    let batch = scene.simulate_rays(setting);
+    assert!(batch.len() > scene.rays.len());
+    let points: Vec<Vec<Vec3>> = results
+        .into_iter()
+        .map(|result| result.path.points)
+        .collect();
+    assert_eq!(points, batch);
+}
+
+#[test]
+fn stops_when_receiver_is_dropped() {
+    let scene = scene();
+    let (sender, receiver) = mpsc::channel();
+    drop(receiver);
+    // 送れなくなっても戻ってくる
+    scene.simulate_rays_streaming(settings(FresnelMode::Deterministic), sender);
+}
@@ -0,0 +1,111 @@
+// Interaction.angles の入射角・屈折角（反射角）・曲がった角度が、スネルの法則と反射の法則を満たすことの確認
+use glam::Vec3;
+use raytracing_core::{
+    AxisAlignedBox, Hittable, Material, Plane, Ray, Scene, SimulationSettingsConfig,
+};
+
+const IOR: f32 = 1.5;
+const INCIDENCE_DEG: f32 = 40.0;
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        max_bounces: 10,
+        max_reflections: 10,
+        max_refractions: 10,
+        ..Default::default()
+    }
+}
+
+// +Z から +X 側へ INCIDENCE_DEG 傾いたレイを z = 0 の面に当てる
+fn scene(object: Box<dyn Hittable>) -> Scene {
+    let angle = INCIDENCE_DEG.to_radians();
+    Scene {
+        objects: vec![object],
+        rays: vec![Ray::new(
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(angle.sin(), 0.0, angle.cos()),
+            1.0,
+        )],
+        object_names: Default::default(),
+    }
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-3, "{actual} != {expected}");
+}
+
+fn sin_deg(degrees: f32) -> f32 {
+    degrees.to_radians().sin()
+}
+
+#[test]
+fn refraction_angles_satisfy_snells_law() {
+    let slab = AxisAlignedBox {
+        min: Vec3::new(-10.0, -10.0, 0.0),
+        max: Vec3::new(10.0, 10.0, 2.0),
+        material: Material::Glass { ior: IOR },
+    };
+    let path = scene(Box::new(slab))
+        .simulate_ray_indices(&[0], setting())
+        .unwrap()
+        .remove(0);
+    assert_eq!(path.interactions.len(), 2);
+
+    // 空気からガラスへ: 1.0 sinθ1 = 1.5 sinθ2
+    let entry = path.interactions[0].angles;
+    assert_close(entry.incidence_deg, INCIDENCE_DEG);
+    assert_close(
+        sin_deg(entry.incidence_deg),
+        IOR * sin_deg(entry.outgoing_deg),
+    );
+    assert_close(
+        entry.deviation_deg,
+        entry.incidence_deg - entry.outgoing_deg,
+    );
+
+    // ガラスから空気へ: 1.5 sinθ1 = 1.0 sinθ2。平行な面なので元の向きに戻る
+    let exit = path.interactions[1].angles;
+    assert_close(exit.incidence_deg, entry.outgoing_deg);
+    assert_close(
+        IOR * sin_deg(exit.incidence_deg),
+        sin_deg(exit.outgoing_deg),
+    );
+    assert_close(exit.outgoing_deg, INCIDENCE_DEG);
+}
+
+#[test]
+fn reflection_angle_equals_incidence() {
+    let mirror = Plane {
+        point: Vec3::ZERO,
+        normal: Vec3::NEG_Z,
+        material: Material::Mirror,
+    };
+    let path = scene(Box::new(mirror))
+        .simulate_ray_indices(&[0], setting())
+        .unwrap()
+        .remove(0);
+    let angles = path.interactions[0].angles;
+    assert_close(angles.incidence_deg, INCIDENCE_DEG);
+    assert_close(angles.outgoing_deg, INCIDENCE_DEG);
+    assert_close(angles.deviation_deg, 180.0 - 2.0 * INCIDENCE_DEG);
+}
+
+#[test]
+fn reversed_path_swaps_incidence_and_refraction() {
+    let slab = AxisAlignedBox {
+        min: Vec3::new(-10.0, -10.0, 0.0),
+        max: Vec3::new(10.0, 10.0, 2.0),
+        material: Material::Glass { ior: IOR },
+    };
+    let path = scene(Box::new(slab))
+        .simulate_ray_indices(&[0], setting())
+        .unwrap()
+        .remove(0);
+    let forward = path.interactions[0].angles;
+    let reversed = path.reversed();
+    // 逆向きでは最初の面が最後の衝突になり、ガラス側から入射する
+    let backward = reversed.interactions[1].angles;
+    assert_close(backward.incidence_deg, forward.outgoing_deg);
+    assert_close(backward.outgoing_deg, forward.incidence_deg);
+    assert_close(backward.deviation_deg, forward.deviation_deg);
+}
@@ -0,0 +1,59 @@
+// 1点に集まる光束のうち、目標の近くに届く光路の数を数えられることの確認
+use std::collections::HashMap;
+
+use glam::{Vec2, Vec3};
+use raytracing_core::analysis::{count_hits_near, hits_on_plane_within};
+use raytracing_core::{Material, Plane, Ray, Scene, SimulationSettingsConfig};
+
+const FOCUS: Vec3 = Vec3::new(0.0, 0.0, 10.0);
+
+// z = 0 の円周上の 12 本は FOCUS へ、4 本は FOCUS から 1 ずれた点へ向かい、z = 10 の吸収面で止まる
+fn convergent_bundle() -> Scene {
+    let mut rays = Vec::new();
+    for i in 0..16 {
+        let angle = i as f32 / 16.0 * std::f32::consts::TAU;
+        let origin = Vec3::new(angle.cos(), angle.sin(), 0.0) * 2.0;
+        let target = if i % 4 == 0 { FOCUS + Vec3::X } else { FOCUS };
+        rays.push(Ray::new(origin, (target - origin).normalize(), 1.0));
+    }
+    Scene {
+        objects: vec![Box::new(Plane {
+            point: FOCUS,
+            normal: Vec3::NEG_Z,
+            material: Material::Absorber,
+        })],
+        rays,
+        object_names: HashMap::new(),
+    }
+}
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        infinity_distance: 100.0,
+        max_bounces: 10,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn count_hits_near_counts_the_converging_rays() {
+    let paths = convergent_bundle().simulate_rays_detailed(setting());
+    assert_eq!(count_hits_near(&paths, FOCUS, 0.01), 12);
+    assert_eq!(count_hits_near(&paths, FOCUS, 1.5), 16);
+    assert_eq!(count_hits_near(&paths, FOCUS + Vec3::X, 0.01), 4);
+}
+
+#[test]
+fn circular_detector_counts_the_converging_rays() {
+    let paths = convergent_bundle().simulate_rays_detailed(setting());
+    let detector = Plane {
+        point: FOCUS,
+        normal: Vec3::Z,
+        material: Material::Absorber,
+    };
+    assert_eq!(
+        hits_on_plane_within(&paths, &detector, Vec2::ZERO, 0.01),
+        12
+    );
+    assert_eq!(hits_on_plane_within(&paths, &detector, Vec2::ZERO, 1.5), 16);
+}
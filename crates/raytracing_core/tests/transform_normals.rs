@@ -0,0 +1,58 @@
+// Transform が保持する normal_matrix で変換した法線が、逆行列の転置をその場で求めた場合と一致することの確認
+use glam::{Mat4, Quat, Vec3};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use raytracing_core::{Hittable, Material, Ray, Sphere, Transform};
+
+#[test]
+fn normals_match_inverse_transpose_per_hit() {
+    let sphere = || Sphere {
+        center: Vec3::new(0.1, -0.2, 0.3),
+        radius: 1.0,
+        material: Material::Mirror,
+    };
+    let matrix = Mat4::from_scale_rotation_translation(
+        Vec3::new(2.0, 0.5, 1.5),
+        Quat::from_euler(glam::EulerRot::XYZ, 0.3, -0.8, 1.1),
+        Vec3::new(1.0, 2.0, -3.0),
+    );
+    let transform = Transform::new(Box::new(sphere()), matrix);
+    assert_eq!(transform.normal_matrix, matrix.inverse().transpose());
+
+    let mut rng = StdRng::seed_from_u64(1456);
+    let mut checked = 0;
+    for _ in 0..500 {
+        let target = Vec3::new(1.0, 2.0, -3.0)
+            + Vec3::new(
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-0.3..0.3),
+                rng.gen_range(-1.0..1.0),
+            );
+        let origin = target + Vec3::new(0.0, 0.0, -10.0);
+        let ray = Ray::new(origin, target - origin, 1.0);
+        let Some(hits) = transform.intersect_all(&ray, 1e-4, f32::INFINITY) else {
+            continue;
+        };
+
+        // 以前の実装と同じく、ローカル空間で求めた法線に逆行列の転置をその場で掛ける
+        let inverse = matrix.inverse();
+        let local_ray = Ray {
+            origin: inverse.transform_point3(ray.origin),
+            direction: inverse.transform_vector3(ray.direction),
+            ..ray.clone()
+        };
+        let local_hits = sphere()
+            .intersect_all(&local_ray, 1e-4, f32::INFINITY)
+            .unwrap();
+        assert_eq!(hits.len(), local_hits.len());
+        for (hit, local) in hits.iter().zip(&local_hits) {
+            let expected = inverse
+                .transpose()
+                .transform_vector3(local.normal)
+                .normalize();
+            assert_eq!(hit.normal, expected);
+            checked += 1;
+        }
+    }
+    assert!(checked > 100);
+}
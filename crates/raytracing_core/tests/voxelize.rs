@@ -0,0 +1,66 @@
+// 光路の長さをボクセル格子に足し込む analysis::voxelize の確認
+use glam::Vec3;
+use raytracing_core::analysis::voxelize;
+use raytracing_core::{Aabb, DetailedPath};
+
+fn path(points: Vec<Vec3>) -> DetailedPath {
+    DetailedPath {
+        optical_lengths: vec![0.0; points.len()],
+        points,
+        interactions: Vec::new(),
+        intensity: 1.0,
+        escaped: false,
+        stuck: false,
+        rehit: false,
+        truncated_hits: 0,
+        source: 0,
+    }
+}
+
+fn index(resolution: [usize; 3], [x, y, z]: [usize; 3]) -> usize {
+    x + resolution[0] * (y + resolution[1] * z)
+}
+
+#[test]
+fn straight_path_deposits_its_length() {
+    let bounds = Aabb::new(Vec3::ZERO, Vec3::new(10.0, 4.0, 3.0));
+    let resolution = [10, 4, 3];
+    let start = Vec3::new(0.3, 0.2, 0.1);
+    let end = Vec3::new(9.6, 3.7, 2.8);
+    let grid = voxelize(&[path(vec![start, end])], bounds, resolution);
+
+    assert_eq!(grid.len(), 10 * 4 * 3);
+    let total: f32 = grid.iter().sum();
+    assert!(
+        (total - (end - start).length()).abs() < 1e-4,
+        "total {total}, length {}",
+        (end - start).length()
+    );
+    // 始点と終点のボクセルには長さが入り、通らない隅には入らない
+    assert!(grid[index(resolution, [0, 0, 0])] > 0.0);
+    assert!(grid[index(resolution, [9, 3, 2])] > 0.0);
+    assert_eq!(grid[index(resolution, [9, 0, 0])], 0.0);
+    assert_eq!(grid[index(resolution, [0, 3, 2])], 0.0);
+}
+
+#[test]
+fn axis_aligned_path_fills_one_row() {
+    // y = 1.5, z = 0.5 を x 方向に貫く光路は、1 行のボクセルに 1 ずつ入る
+    let bounds = Aabb::new(Vec3::ZERO, Vec3::new(5.0, 2.0, 1.0));
+    let resolution = [5, 2, 1];
+    let points = vec![Vec3::new(-3.0, 1.5, 0.5), Vec3::new(8.0, 1.5, 0.5)];
+    let grid = voxelize(&[path(points)], bounds, resolution);
+
+    for x in 0..5 {
+        assert!((grid[index(resolution, [x, 1, 0])] - 1.0).abs() < 1e-5);
+        assert_eq!(grid[index(resolution, [x, 0, 0])], 0.0);
+    }
+}
+
+#[test]
+fn path_outside_bounds_deposits_nothing() {
+    let bounds = Aabb::new(Vec3::ZERO, Vec3::ONE);
+    let points = vec![Vec3::new(2.0, 0.0, 0.0), Vec3::new(2.0, 5.0, 0.0)];
+    let grid = voxelize(&[path(points)], bounds, [2, 2, 2]);
+    assert!(grid.iter().all(|&value| value == 0.0));
+}
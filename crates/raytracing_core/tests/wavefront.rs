@@ -0,0 +1,102 @@
+// 光路長から求める波面収差 (analysis::wavefront_rms) の確認
+// - 放物面鏡は平行光を焦点に無収差で集めるので、焦点を基準にした RMS はほぼ 0
+// - 同じ曲率半径の球面鏡は球面収差を持つので、RMS がはっきり大きくなる
+// - 平行平板ガラスを通った平行光は平面波のままなので、平面を基準にした RMS はほぼ 0
+use std::collections::HashMap;
+
+use glam::Vec3;
+use raytracing_core::analysis::{wavefront_rms, WavefrontReference};
+use raytracing_core::{
+    AsphericSurface, AxisAlignedBox, Hittable, Material, Plane, Ray, Scene,
+    SimulationSettingsConfig,
+};
+
+const RADIUS_OF_CURVATURE: f32 = 20.0;
+const APERTURE: f32 = 6.0;
+
+fn setting() -> SimulationSettingsConfig {
+    SimulationSettingsConfig {
+        max_bounces: 8,
+        max_reflections: 8,
+        max_refractions: 8,
+        ..Default::default()
+    }
+}
+
+// 光軸 (-Z 向き) に平行な光線を開口内の格子状に並べる
+fn collimated_rays(start_z: f32) -> Vec<Ray> {
+    let steps = 12;
+    let mut rays = Vec::new();
+    for i in -steps..=steps {
+        for j in -steps..=steps {
+            let x = APERTURE * i as f32 / steps as f32;
+            let y = APERTURE * j as f32 / steps as f32;
+            if x * x + y * y <= APERTURE * APERTURE {
+                rays.push(Ray::new(Vec3::new(x, y, start_z), Vec3::NEG_Z, 1.0));
+            }
+        }
+    }
+    rays
+}
+
+fn mirror_rms(conic: f32) -> f32 {
+    let mirror = AsphericSurface {
+        vertex: Vec3::ZERO,
+        axis: Vec3::Z,
+        curvature: 1.0 / RADIUS_OF_CURVATURE,
+        conic,
+        coeffs: Vec::new(),
+        material: Material::Mirror,
+    };
+    // 放物面は閉じていないので、反射光が再び鏡に当たらないよう焦点の先で吸収する
+    let stop = Plane {
+        point: Vec3::new(0.0, 0.0, RADIUS_OF_CURVATURE / 2.0 + 2.0),
+        normal: Vec3::NEG_Z,
+        material: Material::Absorber,
+    };
+    let scene = Scene {
+        objects: vec![Box::new(mirror), Box::new(stop)],
+        rays: collimated_rays(RADIUS_OF_CURVATURE / 2.0 + 1.0),
+        object_names: HashMap::new(),
+    };
+    let paths = scene.simulate_rays_detailed(setting());
+    let focus = Vec3::new(0.0, 0.0, RADIUS_OF_CURVATURE / 2.0);
+    wavefront_rms(&paths, WavefrontReference::Point(focus))
+}
+
+#[test]
+fn parabolic_mirror_has_no_wavefront_error() {
+    let parabolic = mirror_rms(-1.0);
+    let spherical = mirror_rms(0.0);
+    assert!(parabolic < 1e-4, "放物面鏡の RMS が大きすぎる: {parabolic}");
+    assert!(spherical > 1e-3, "球面鏡の RMS が小さすぎる: {spherical}");
+    assert!(spherical > parabolic * 10.0);
+}
+
+#[test]
+fn flat_window_keeps_plane_wave() {
+    let window = AxisAlignedBox {
+        min: Vec3::new(-10.0, -10.0, -1.0),
+        max: Vec3::new(10.0, 10.0, 1.0),
+        material: Material::Glass { ior: 1.5 },
+    };
+    let scene = Scene {
+        objects: vec![Box::new(window) as Box<dyn Hittable>],
+        rays: collimated_rays(5.0),
+        object_names: HashMap::new(),
+    };
+    let paths = scene.simulate_rays_detailed(setting());
+    let plane = Plane {
+        point: Vec3::new(0.0, 0.0, -5.0),
+        normal: Vec3::Z,
+        material: Material::Absorber,
+    };
+    let rms = wavefront_rms(&paths, WavefrontReference::Plane(&plane));
+    assert!(
+        rms < 1e-5,
+        "平行平板を通った平面波の RMS が大きすぎる: {rms}"
+    );
+    // 平板の中ではガラスの屈折率の分だけ光路長が伸びる
+    let expected = 4.0 + 2.0 * 1.5;
+    assert!((paths[0].optical_lengths[2] - expected).abs() < 1e-3);
+}